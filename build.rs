@@ -0,0 +1,30 @@
+//! 构建脚本
+//!
+//! 预先执行脚本脚手架并生成 V8 启动快照，避免每个请求重复初始化。
+//! 未启用 `scripting` 特性时不做任何事。
+
+fn main() {
+    #[cfg(feature = "scripting")]
+    snapshot::build();
+}
+
+#[cfg(feature = "scripting")]
+mod snapshot {
+    use deno_core::{JsRuntimeForSnapshot, RuntimeOptions};
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    pub fn build() {
+        println!("cargo:rerun-if-changed=src/js/bootstrap.js");
+
+        let mut runtime = JsRuntimeForSnapshot::new(RuntimeOptions::default());
+        runtime
+            .execute_script("<bootstrap>", include_str!("src/js/bootstrap.js"))
+            .expect("Failed to execute bootstrap script");
+        let snapshot = runtime.snapshot();
+
+        let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+        fs::write(out_dir.join("HTTPIE_SNAPSHOT.bin"), snapshot).expect("Failed to write snapshot");
+    }
+}
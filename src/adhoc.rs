@@ -0,0 +1,114 @@
+//! 命令行请求模块
+//!
+//! 把 `httpie get URL 名称:值 字段=值 参数==值` 形式的命令行参数转换为HTTP请求，
+//! 无需编写.http文件即可发送单个请求。
+
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use reqwest::Method;
+use serde_json::{Map, Value};
+
+/// 支持的命令行请求方法（小写，作为子命令名称）
+pub const ADHOC_METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// 请求项分隔符，同一位置上较长的优先
+const SEPARATORS: &[&str] = &["==", ":=", "=", ":"];
+
+/// 命令行中的单个请求项
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestItem {
+    /// `名称:值` 请求头
+    Header(String, String),
+    /// `名称==值` 查询参数
+    Query(String, String),
+    /// `名称=值` JSON字符串字段
+    Field(String, String),
+    /// `名称:=JSON` 原始JSON字段
+    JsonField(String, Value),
+}
+
+impl RequestItem {
+    /// 按最先出现的分隔符解析请求项
+    pub fn parse(item: &str) -> Result<Self> {
+        let (position, separator) = SEPARATORS
+            .iter()
+            .filter_map(|separator| item.find(separator).map(|pos| (pos, *separator)))
+            .min_by_key(|(pos, separator)| (*pos, usize::MAX - separator.len()))
+            .ok_or_else(|| {
+                HttpieError::Parse(format!(
+                    "Invalid request item '{item}': expected Name:value, field=value, field:=json or param==value"
+                ))
+            })?;
+        let name = item[..position].to_string();
+        let value = item[position + separator.len()..].to_string();
+        if name.is_empty() {
+            return Err(HttpieError::Parse(format!(
+                "Invalid request item '{item}': missing name"
+            )));
+        }
+
+        Ok(match separator {
+            "==" => Self::Query(name, value),
+            ":=" => {
+                let json = serde_json::from_str(&value).map_err(|e| {
+                    HttpieError::Parse(format!("Invalid JSON in request item '{item}': {e}"))
+                })?;
+                Self::JsonField(name, json)
+            }
+            "=" => Self::Field(name, value),
+            _ => Self::Header(name, value.trim().to_string()),
+        })
+    }
+}
+
+/// 把方法、URL和请求项转换为HTTP请求
+///
+/// 有字段时请求体为JSON对象，未指定 `Content-Type` 时自动设为 `application/json`。
+pub fn from_args(method: &str, url: &str, items: &[String]) -> Result<HttpRequest> {
+    let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| HttpieError::InvalidMethod(method.to_string()))?;
+    let url = normalize_url(url);
+    let mut request = HttpRequest::new(format!("{method} {url}"), method, url);
+    let mut fields = Map::new();
+
+    for item in items {
+        match RequestItem::parse(item)? {
+            RequestItem::Header(name, value) => {
+                request.headers.insert(name, value);
+            }
+            RequestItem::Query(name, value) => request.query.push((name, value)),
+            RequestItem::Field(name, value) => {
+                fields.insert(name, Value::String(value));
+            }
+            RequestItem::JsonField(name, value) => {
+                fields.insert(name, value);
+            }
+        }
+    }
+
+    if !fields.is_empty() {
+        if !request
+            .headers
+            .keys()
+            .any(|name| name.eq_ignore_ascii_case("content-type"))
+        {
+            request
+                .headers
+                .insert("Content-Type".to_string(), "application/json".to_string());
+        }
+        request.body = Some(serde_json::to_string(&Value::Object(fields))?);
+    }
+    Ok(request)
+}
+
+/// 补全URL：省略scheme时使用 `http://`，`:3000/path` 表示localhost
+fn normalize_url(url: &str) -> String {
+    if url.contains("://") {
+        return url.to_string();
+    }
+    match url.strip_prefix(':') {
+        Some(rest) if rest.starts_with('/') || rest.is_empty() => format!("http://localhost{rest}"),
+        Some(rest) => format!("http://localhost:{rest}"),
+        None => format!("http://{url}"),
+    }
+}
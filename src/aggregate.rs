@@ -0,0 +1,183 @@
+//! 聚合断言模块
+//!
+//! `?? p95 < 300ms`、`?? error_rate < 1%` 这类断言针对一个请求的全部执行结果求值：
+//! `# @repeat N` 的请求在所有迭代完成后检查一次（结果附在最后完成的迭代上），
+//! `bench` 子命令在压测结束后检查。未重复的请求按单次执行求值。
+//!
+//! 指标有延迟分位数 `pN`（如 `p50`、`p95`、`p99.9`）、`mean`、`min`、`max`
+//! （期望值为毫秒，可带 `ms`/`s` 单位）和 `error_rate`（传输失败或4xx/5xx的比例，
+//! 期望值可写作 `1%` 或 `0.01`）。
+
+use crate::assertion::{self, ASSERTION_ERROR_KIND, Operator};
+use crate::bench::{self, BenchReport};
+use crate::error::{HttpieError, Result};
+use crate::script::TestResult;
+use std::time::Duration;
+
+/// 聚合指标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    /// 延迟分位数（0 ~ 100）
+    Percentile(f64),
+    Mean,
+    Min,
+    Max,
+    /// 错误率（0.0 ~ 1.0）
+    ErrorRate,
+}
+
+impl Metric {
+    fn parse(token: &str) -> Option<Self> {
+        Some(match token {
+            "mean" => Self::Mean,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "error_rate" => Self::ErrorRate,
+            _ => {
+                let percentile: f64 = token.strip_prefix('p')?.parse().ok()?;
+                if !(percentile > 0.0 && percentile <= 100.0) {
+                    return None;
+                }
+                Self::Percentile(percentile)
+            }
+        })
+    }
+
+    /// 在压测结果中的取值：延迟为毫秒，错误率为比例
+    fn value(self, report: &BenchReport) -> Option<f64> {
+        let ms = |duration: Option<Duration>| duration.map(|d| d.as_secs_f64() * 1000.0);
+        match self {
+            Self::Percentile(percentile) => ms(report.percentile(percentile)),
+            Self::Mean => ms(report.mean()),
+            Self::Min => ms(report.latencies.first().copied()),
+            Self::Max => ms(report.latencies.last().copied()),
+            Self::ErrorRate => (report.total() > 0).then(|| report.error_rate()),
+        }
+    }
+}
+
+/// 跨多次执行求值的断言
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateAssertion {
+    pub metric: Metric,
+    pub operator: Operator,
+    /// 期望值：延迟为毫秒，错误率为比例
+    pub expected: f64,
+    /// 原始断言文本，用作测试名称
+    pub source: String,
+}
+
+impl AggregateAssertion {
+    /// 断言文本（可带 `??` 前缀）的检查对象是否为聚合指标
+    pub fn is_aggregate(line: &str) -> bool {
+        let source = strip_prefix(line);
+        let subject = source.split_whitespace().next().unwrap_or_default();
+        Metric::parse(subject).is_some()
+    }
+
+    /// 解析聚合断言，检查对象不是聚合指标时返回 None
+    pub fn parse(line: &str) -> Result<Option<Self>> {
+        let source = strip_prefix(line).to_string();
+        let mut tokens = source.split_whitespace();
+        let Some(metric) = tokens.next().and_then(Metric::parse) else {
+            return Ok(None);
+        };
+        let invalid = |reason: &str| {
+            HttpieError::InvalidRequest(format!("Invalid assertion '{source}': {reason}"))
+        };
+
+        let operator = match tokens.next().and_then(Operator::parse) {
+            Some(
+                operator @ (Operator::Eq
+                | Operator::Ne
+                | Operator::Lt
+                | Operator::Le
+                | Operator::Gt
+                | Operator::Ge),
+            ) => operator,
+            _ => return Err(invalid("expected a comparison operator")),
+        };
+        let expected = tokens.collect::<Vec<_>>().join(" ");
+        if expected.is_empty() {
+            return Err(invalid("missing expected value"));
+        }
+        let expected = match metric {
+            Metric::ErrorRate => {
+                bench::parse_ratio(&expected).map_err(|_| invalid("invalid rate"))?
+            }
+            _ => assertion::duration_millis(&expected)
+                .and_then(|millis| millis.parse().ok())
+                .ok_or_else(|| invalid("invalid duration"))?,
+        };
+
+        Ok(Some(Self {
+            metric,
+            operator,
+            expected,
+            source,
+        }))
+    }
+
+    /// 对压测结果（或重复请求的全部迭代）求值
+    pub fn check(&self, report: &BenchReport) -> TestResult {
+        let Some(actual) = self.metric.value(report) else {
+            return TestResult::failure(
+                &self.source,
+                "no completed requests",
+                ASSERTION_ERROR_KIND,
+            );
+        };
+        let passed = match self.operator {
+            Operator::Eq => actual == self.expected,
+            Operator::Ne => actual != self.expected,
+            Operator::Lt => actual < self.expected,
+            Operator::Le => actual <= self.expected,
+            Operator::Gt => actual > self.expected,
+            _ => actual >= self.expected,
+        };
+        if passed {
+            return TestResult::success(&self.source);
+        }
+        let actual = match self.metric {
+            Metric::ErrorRate => format!("error rate was {:.2}%", actual * 100.0),
+            _ => format!("{} was {actual:.2} ms", self.subject()),
+        };
+        let message = format!("{actual} over {} request(s)", report.total());
+        TestResult::failure(&self.source, message, ASSERTION_ERROR_KIND)
+    }
+
+    fn subject(&self) -> &str {
+        self.source.split_whitespace().next().unwrap_or_default()
+    }
+}
+
+/// 对压测结果求值断言列表中的聚合断言，其余断言忽略
+///
+/// 无法解析的聚合断言（例如替换变量后的期望值无效）记为失败的测试。
+pub fn check_all(assertions: &[String], report: &BenchReport) -> Vec<TestResult> {
+    assertions
+        .iter()
+        .filter_map(|assertion| match AggregateAssertion::parse(assertion) {
+            Ok(parsed) => parsed.map(|parsed| parsed.check(report)),
+            Err(e) => Some(TestResult::failure(
+                strip_prefix(assertion),
+                e.to_string(),
+                ASSERTION_ERROR_KIND,
+            )),
+        })
+        .collect()
+}
+
+/// 断言列表中是否有聚合断言
+pub fn has_aggregate(assertions: &[String]) -> bool {
+    assertions
+        .iter()
+        .any(|a| AggregateAssertion::is_aggregate(a))
+}
+
+fn strip_prefix(line: &str) -> &str {
+    let line = line.trim();
+    line.strip_prefix(assertion::ASSERTION_PREFIX)
+        .unwrap_or(line)
+        .trim()
+}
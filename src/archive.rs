@@ -0,0 +1,122 @@
+//! 响应存档模块
+//!
+//! 把每个响应体保存到目录中的 `<请求名称>.<扩展名>`（扩展名由Content-Type推断），
+//! 并在旁边写入 `<请求名称>.meta.json`（状态码、响应头、耗时），便于存档和比较多次运行。
+//! 同名文件被覆盖，同一次运行中重名的请求依次加 `-2`、`-3` 后缀。
+
+use crate::binary;
+use crate::error::Result;
+use crate::models::{Headers, HttpRequest};
+use crate::observer::ExecutionObserver;
+use crate::script::ResponseObject;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// 响应体旁的元数据文件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseMetadata {
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub duration_ms: u64,
+    pub content_type: String,
+    /// 响应体文件名（与元数据文件在同一目录）
+    pub body: String,
+    pub headers: Headers,
+}
+
+/// 把响应保存到目录的观察者
+#[derive(Debug)]
+pub struct ResponseArchive {
+    dir: PathBuf,
+    /// 本次运行中每个文件名已使用的次数
+    used: HashMap<String, usize>,
+}
+
+impl ResponseArchive {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            used: HashMap::new(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 保存响应体和元数据，返回响应体文件路径
+    pub fn save(
+        &mut self,
+        request: &HttpRequest,
+        response: &ResponseObject,
+        duration: Duration,
+    ) -> Result<PathBuf> {
+        let stem = self.file_stem(&request.name);
+        let body_name = format!("{stem}.{}", binary::extension_for(&response.content_type));
+        let body_path = self.dir.join(&body_name);
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&body_path, body_bytes(response))?;
+
+        let metadata = ResponseMetadata {
+            name: request.name.clone(),
+            method: request.method.to_string(),
+            url: request
+                .target_url()
+                .map_or_else(|_| request.url.clone(), String::from),
+            status: response.status,
+            duration_ms: duration.as_millis() as u64,
+            content_type: response.content_type.clone(),
+            body: body_name,
+            headers: response.headers.clone(),
+        };
+        fs::write(
+            self.dir.join(format!("{stem}.meta.json")),
+            serde_json::to_string_pretty(&metadata)?,
+        )?;
+        Ok(body_path)
+    }
+
+    /// 请求名称对应的文件名（不含扩展名），本次运行中重名时加序号
+    fn file_stem(&mut self, name: &str) -> String {
+        let stem = binary::sanitize_file_name(name);
+        let count = self.used.entry(stem.clone()).or_default();
+        *count += 1;
+        match *count {
+            1 => stem,
+            n => format!("{stem}-{n}"),
+        }
+    }
+}
+
+/// 响应体的原始字节；手工构建、没有原始字节的响应对象使用已解析的body
+fn body_bytes(response: &ResponseObject) -> Vec<u8> {
+    if !response.raw_body.is_empty() {
+        return response.raw_body.to_vec();
+    }
+    match &response.body {
+        Value::Null => Vec::new(),
+        Value::String(text) => text.clone().into_bytes(),
+        body => serde_json::to_vec_pretty(body).unwrap_or_default(),
+    }
+}
+
+impl ExecutionObserver for ResponseArchive {
+    fn on_response(
+        &mut self,
+        request: &HttpRequest,
+        response: &ResponseObject,
+        duration: Duration,
+    ) {
+        if let Err(e) = self.save(request, response, duration) {
+            warn!("Failed to save response of '{}': {}", request.name, e);
+        }
+    }
+}
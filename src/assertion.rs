@@ -0,0 +1,262 @@
+//! 内联断言模块
+//!
+//! 解析 `?? status == 200` 形式的轻量断言，在Rust中直接求值并生成 [`TestResult`]，
+//! 无需脚本引擎。支持的检查对象：`status`、`header 名称`、JSONPath（`$.a.b`）、
+//! `body`、`duration`（毫秒，期望值可带 `ms`/`s` 单位，如 `duration < 500ms`）和
+//! `redirects`（跟随的重定向次数）。跨多次执行求值的 `p95`、`error_rate` 等见 [`crate::aggregate`]。
+
+use crate::bench::parse_duration;
+use crate::error::{HttpieError, Result};
+use crate::jsonpath;
+use crate::script::{ResponseObject, TestResult};
+use serde_json::Value;
+use std::time::Duration;
+
+/// 断言行前缀
+pub const ASSERTION_PREFIX: &str = "??";
+
+/// 断言失败时的错误类型
+pub(crate) const ASSERTION_ERROR_KIND: &str = "AssertionError";
+
+/// 断言的检查对象
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subject {
+    Status,
+    Header(String),
+    JsonPath(String),
+    Body,
+    Duration,
+    Redirects,
+}
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    Exists,
+}
+
+impl Operator {
+    pub(crate) fn parse(token: &str) -> Option<Self> {
+        Some(match token {
+            "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "contains" => Self::Contains,
+            "exists" => Self::Exists,
+            _ => return None,
+        })
+    }
+}
+
+/// 单条内联断言
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assertion {
+    pub subject: Subject,
+    pub operator: Operator,
+    pub expected: Option<String>,
+    /// 原始断言文本，用作测试名称
+    pub source: String,
+}
+
+impl Assertion {
+    /// 解析断言文本（可带 `??` 前缀）
+    pub fn parse(line: &str) -> Result<Self> {
+        let source = line
+            .trim()
+            .strip_prefix(ASSERTION_PREFIX)
+            .unwrap_or(line)
+            .trim()
+            .to_string();
+        let invalid = |reason: &str| {
+            HttpieError::InvalidRequest(format!("Invalid assertion '{source}': {reason}"))
+        };
+
+        let (subject, rest) = match source.split_once(char::is_whitespace) {
+            Some((first, rest)) => (first, rest.trim_start()),
+            None => (source.as_str(), ""),
+        };
+        let (subject, rest) = match subject {
+            "status" => (Subject::Status, rest),
+            "body" => (Subject::Body, rest),
+            "duration" => (Subject::Duration, rest),
+            "redirects" => (Subject::Redirects, rest),
+            "header" => {
+                let (name, rest) = rest
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| invalid("expected 'header NAME OP VALUE'"))?;
+                (Subject::Header(name.to_string()), rest.trim_start())
+            }
+            path if path.starts_with('$') => {
+                jsonpath::parse(path).map_err(|e| invalid(&e.to_string()))?;
+                (Subject::JsonPath(path.to_string()), rest)
+            }
+            _ => return Err(invalid("unknown subject")),
+        };
+
+        let (operator, expected) = match rest.split_once(char::is_whitespace) {
+            Some((op, expected)) => (op, expected.trim()),
+            None => (rest, ""),
+        };
+        let operator = Operator::parse(operator).ok_or_else(|| invalid("unknown operator"))?;
+        let expected = match (operator, expected.is_empty()) {
+            (Operator::Exists, true) => None,
+            (Operator::Exists, false) => return Err(invalid("'exists' takes no value")),
+            (_, true) => return Err(invalid("missing expected value")),
+            (_, false) if subject == Subject::Duration => {
+                Some(duration_millis(expected).ok_or_else(|| invalid("invalid duration"))?)
+            }
+            (_, false) => Some(expected.to_string()),
+        };
+
+        Ok(Self {
+            subject,
+            operator,
+            expected,
+            source,
+        })
+    }
+
+    /// 对响应求值
+    ///
+    /// `duration` 断言的结果耗时记录为实际的请求耗时。
+    pub fn check(&self, response: &ResponseObject, duration: Duration) -> TestResult {
+        let actual = self.actual(response, duration);
+        let mut result = match self.evaluate(actual.as_ref()) {
+            Ok(()) => TestResult::success(&self.source),
+            Err(message) => TestResult::failure(&self.source, message, ASSERTION_ERROR_KIND),
+        };
+        if self.subject == Subject::Duration {
+            result.duration_ms = duration.as_millis() as u64;
+        }
+        result
+    }
+
+    /// 取出检查对象的实际值，不存在时返回 None
+    fn actual(&self, response: &ResponseObject, duration: Duration) -> Option<Value> {
+        match &self.subject {
+            Subject::Status => Some(Value::from(response.status)),
+            Subject::Duration => Some(Value::from(duration.as_millis() as u64)),
+            Subject::Redirects => Some(Value::from(response.redirects.len())),
+            Subject::Header(name) => response
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| Value::String(value.clone())),
+            Subject::Body => Some(Value::String(match &response.body {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })),
+            Subject::JsonPath(path) => {
+                let parsed;
+                let body = match &response.body {
+                    Value::String(text) => {
+                        parsed = serde_json::from_str(text).ok()?;
+                        &parsed
+                    }
+                    body => body,
+                };
+                jsonpath::select(body, path).ok()?.cloned()
+            }
+        }
+    }
+
+    fn evaluate(&self, actual: Option<&Value>) -> std::result::Result<(), String> {
+        let Some(actual) = actual else {
+            return Err("value does not exist".to_string());
+        };
+        let Some(expected) = self.expected.as_deref() else {
+            return Ok(());
+        };
+
+        let passed = match self.operator {
+            Operator::Exists => true,
+            Operator::Eq => equals(actual, expected),
+            Operator::Ne => !equals(actual, expected),
+            Operator::Contains => contains(actual, expected),
+            Operator::Lt | Operator::Le | Operator::Gt | Operator::Ge => {
+                let (Some(left), Ok(right)) = (as_number(actual), expected.parse::<f64>()) else {
+                    return Err(format!(
+                        "cannot compare {} with {expected} numerically",
+                        display(actual)
+                    ));
+                };
+                match self.operator {
+                    Operator::Lt => left < right,
+                    Operator::Le => left <= right,
+                    Operator::Gt => left > right,
+                    _ => left >= right,
+                }
+            }
+        };
+
+        if passed {
+            Ok(())
+        } else if self.subject == Subject::Duration {
+            Err(format!("request took {actual} ms"))
+        } else {
+            Err(format!("actual value was {}", display(actual)))
+        }
+    }
+}
+
+/// 期望值优先按JSON解析，否则按去掉引号的字符串比较
+fn equals(actual: &Value, expected: &str) -> bool {
+    if serde_json::from_str::<Value>(expected).is_ok_and(|expected| &expected == actual) {
+        return true;
+    }
+    match actual {
+        Value::String(text) => text == unquote(expected),
+        Value::Number(number) => expected.parse::<f64>().ok() == number.as_f64(),
+        _ => false,
+    }
+}
+
+fn contains(actual: &Value, expected: &str) -> bool {
+    match actual {
+        Value::String(text) => text.contains(unquote(expected)),
+        Value::Array(items) => items.iter().any(|item| equals(item, expected)),
+        Value::Object(map) => map.contains_key(unquote(expected)),
+        other => other.to_string().contains(unquote(expected)),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 耗时期望值换算为毫秒：不带单位时即为毫秒，否则按 `ms`、`s`、`m` 解析
+pub(crate) fn duration_millis(expected: &str) -> Option<String> {
+    if expected.parse::<f64>().is_ok() {
+        return Some(expected.to_string());
+    }
+    let duration = parse_duration(expected).ok()?;
+    Some((duration.as_secs_f64() * 1000.0).to_string())
+}
+
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .or_else(|| text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+        .unwrap_or(text)
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => format!("'{text}'"),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,581 @@
+//! 压测模块
+//!
+//! 以多个并发worker重复发送同一个请求（按次数或按时长），
+//! 统计延迟分位数、吞吐量和错误率。结果可以与保存的基线（`--json` 写出的汇总）比较，
+//! 延迟或错误率的退化超过阈值时视为失败。
+
+use crate::aggregate;
+use crate::client::{self, TransportConfig};
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use crate::runner::CaseSelector;
+use crate::script::TestResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// 压测的结束条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchLimit {
+    /// 总共发送的请求数
+    Iterations(usize),
+    /// 持续时长
+    Duration(Duration),
+}
+
+/// 单次请求的采样
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub latency: Duration,
+    /// 传输失败时为 None
+    pub status: Option<u16>,
+}
+
+impl Sample {
+    /// 传输失败或状态码为4xx/5xx时视为错误
+    pub fn is_error(&self) -> bool {
+        self.status.is_none_or(|status| status >= 400)
+    }
+}
+
+/// 压测运行器
+pub struct Bench {
+    request: HttpRequest,
+    transport_config: TransportConfig,
+    concurrency: usize,
+    limit: BenchLimit,
+    cancellation: CancellationToken,
+    baseline: Option<BenchSummary>,
+    thresholds: BenchThresholds,
+}
+
+impl Bench {
+    /// 创建压测运行器（默认1个worker、100次请求）
+    pub fn new(request: HttpRequest) -> Self {
+        Self {
+            request,
+            transport_config: TransportConfig::default(),
+            concurrency: 1,
+            limit: BenchLimit::Iterations(100),
+            cancellation: CancellationToken::new(),
+            baseline: None,
+            thresholds: BenchThresholds::default(),
+        }
+    }
+
+    /// 设置传输层配置
+    pub fn with_transport_config(mut self, transport_config: TransportConfig) -> Self {
+        self.transport_config = transport_config;
+        self
+    }
+
+    /// 设置并发worker数量（至少为1）
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// 设置结束条件
+    pub fn with_limit(mut self, limit: BenchLimit) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// 设置取消令牌，取消后提前结束并返回已采集的结果
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// 设置比较的基线，[`Bench::evaluate`] 按阈值判断是否退化
+    pub fn with_baseline(mut self, baseline: Option<BenchSummary>) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// 设置与基线比较时允许的退化
+    pub fn with_thresholds(mut self, thresholds: BenchThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// 执行压测，与基线比较并对请求中的聚合断言（`?? p95 < 300ms`）求值
+    pub async fn evaluate(&self) -> Result<BenchOutcome> {
+        let report = self.run().await?;
+        let comparison = self
+            .baseline
+            .as_ref()
+            .map(|baseline| report.summary().compare(baseline, self.thresholds));
+        let assertions = aggregate::check_all(&self.request.assertions, &report);
+        Ok(BenchOutcome {
+            report,
+            comparison,
+            assertions,
+        })
+    }
+
+    /// 执行压测
+    pub async fn run(&self) -> Result<BenchReport> {
+        let config = self
+            .transport_config
+            .for_request(&self.request)?
+            .unwrap_or_else(|| self.transport_config.clone());
+        let transport = config.build()?;
+        let request = Arc::new(self.request.clone());
+        let remaining = Arc::new(AtomicUsize::new(match self.limit {
+            BenchLimit::Iterations(count) => count,
+            BenchLimit::Duration(_) => usize::MAX,
+        }));
+
+        let started = Instant::now();
+        let deadline = match self.limit {
+            BenchLimit::Duration(duration) => Some(started + duration),
+            BenchLimit::Iterations(_) => None,
+        };
+
+        let workers: Vec<_> = (0..self.concurrency)
+            .map(|_| {
+                let transport = transport.clone();
+                let request = request.clone();
+                let remaining = remaining.clone();
+                let cancellation = self.cancellation.clone();
+                let config = config.clone();
+
+                tokio::spawn(async move {
+                    let mut samples = Vec::new();
+                    loop {
+                        if deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                            || cancellation.is_cancelled()
+                            || remaining
+                                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                                    n.checked_sub(1)
+                                })
+                                .is_err()
+                        {
+                            break;
+                        }
+
+                        let sent = Instant::now();
+                        let fetched = match deadline {
+                            Some(deadline) => tokio::time::timeout_at(
+                                deadline.into(),
+                                client::fetch_with_cancellation(
+                                    &transport,
+                                    &request,
+                                    &config,
+                                    &cancellation,
+                                ),
+                            )
+                            .await
+                            .unwrap_or(Err(HttpieError::Cancelled)),
+                            None => {
+                                client::fetch_with_cancellation(
+                                    &transport,
+                                    &request,
+                                    &config,
+                                    &cancellation,
+                                )
+                                .await
+                            }
+                        };
+
+                        // 被取消或超出时长的请求不计入统计
+                        let status = match fetched {
+                            Ok(raw) => Some(raw.status),
+                            Err(HttpieError::Cancelled) => break,
+                            Err(_) => None,
+                        };
+                        samples.push(Sample {
+                            latency: sent.elapsed(),
+                            status,
+                        });
+                    }
+                    samples
+                })
+            })
+            .collect();
+
+        let mut samples = Vec::new();
+        for worker in futures::future::join_all(workers).await {
+            samples.extend(worker.expect("benchmark worker panicked"));
+        }
+
+        let mut report = BenchReport::new(&self.request.name, samples, started.elapsed());
+        report.cancelled = self.cancellation.is_cancelled();
+        Ok(report)
+    }
+}
+
+/// 选择压测的请求：按选择方式选中的第一个请求，未指定时文件中必须只有一个请求
+pub fn select_request<'a>(
+    requests: &'a [HttpRequest],
+    selector: Option<&CaseSelector>,
+) -> Result<&'a HttpRequest> {
+    match selector {
+        Some(selector) => {
+            let requests: Vec<&HttpRequest> = requests.iter().collect();
+            selector
+                .position(&requests)
+                .map(|position| requests[position])
+                .ok_or_else(|| HttpieError::InvalidRequest(format!("Case {selector} not found")))
+        }
+        None if requests.len() == 1 => Ok(&requests[0]),
+        None => Err(HttpieError::InvalidRequest(
+            "bench requires --case when the file contains multiple requests".to_string(),
+        )),
+    }
+}
+
+/// 压测结果、与基线的比较和聚合断言的结果
+#[derive(Debug, Clone)]
+pub struct BenchOutcome {
+    pub report: BenchReport,
+    /// 设置了基线时的比较结果
+    pub comparison: Option<BenchComparison>,
+    pub assertions: Vec<TestResult>,
+}
+
+impl BenchOutcome {
+    /// 有聚合断言失败或指标相对基线退化时返回错误
+    pub fn check(&self) -> Result<()> {
+        let failed = self.assertions.iter().filter(|r| r.is_failure()).count();
+        if failed > 0 {
+            return Err(HttpieError::RequestFailed(
+                self.report.name.clone(),
+                format!("{failed} aggregate assertion(s) failed"),
+            ));
+        }
+        let regressions = self
+            .comparison
+            .as_ref()
+            .map_or(0, |comparison| comparison.regressions().len());
+        if regressions > 0 {
+            return Err(HttpieError::RequestFailed(
+                self.report.name.clone(),
+                format!("{regressions} metric(s) regressed against the baseline"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 压测结果
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub name: String,
+    /// 按升序排列的延迟
+    pub latencies: Vec<Duration>,
+    pub errors: usize,
+    pub status_codes: BTreeMap<u16, usize>,
+    pub elapsed: Duration,
+    pub cancelled: bool,
+}
+
+impl BenchReport {
+    /// 根据采样生成结果
+    pub fn new(name: &str, samples: Vec<Sample>, elapsed: Duration) -> Self {
+        let mut latencies: Vec<Duration> = samples.iter().map(|s| s.latency).collect();
+        latencies.sort();
+
+        let mut status_codes = BTreeMap::new();
+        for status in samples.iter().filter_map(|s| s.status) {
+            *status_codes.entry(status).or_insert(0) += 1;
+        }
+
+        Self {
+            name: name.to_string(),
+            latencies,
+            errors: samples.iter().filter(|s| s.is_error()).count(),
+            status_codes,
+            elapsed,
+            cancelled: false,
+        }
+    }
+
+    /// 完成的请求总数
+    pub fn total(&self) -> usize {
+        self.latencies.len()
+    }
+
+    /// 错误率（0.0 ~ 1.0）
+    pub fn error_rate(&self) -> f64 {
+        if self.total() == 0 {
+            return 0.0;
+        }
+        self.errors as f64 / self.total() as f64
+    }
+
+    /// 吞吐量（请求/秒）
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.total() as f64 / seconds
+    }
+
+    /// 延迟分位数（最近秩法，`percentile` 取值 0 ~ 100）
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let rank = (percentile / 100.0 * self.latencies.len() as f64).ceil() as usize;
+        Some(self.latencies[rank.clamp(1, self.latencies.len()) - 1])
+    }
+
+    /// 平均延迟
+    pub fn mean(&self) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        Some(self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32)
+    }
+
+    /// 生成可序列化的汇总
+    pub fn summary(&self) -> BenchSummary {
+        let ms = |d: Option<Duration>| d.map_or(0.0, |d| d.as_secs_f64() * 1000.0);
+        BenchSummary {
+            name: self.name.clone(),
+            requests: self.total(),
+            errors: self.errors,
+            error_rate: self.error_rate(),
+            duration_ms: self.elapsed.as_secs_f64() * 1000.0,
+            throughput: self.throughput(),
+            latency_ms: LatencySummary {
+                min: ms(self.latencies.first().copied()),
+                mean: ms(self.mean()),
+                p50: ms(self.percentile(50.0)),
+                p95: ms(self.percentile(95.0)),
+                p99: ms(self.percentile(99.0)),
+                max: ms(self.latencies.last().copied()),
+            },
+            status_codes: self.status_codes.clone(),
+            cancelled: self.cancelled,
+        }
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let summary = self.summary();
+        let latency = &summary.latency_ms;
+
+        writeln!(f, "=== Benchmark: {} ===", self.name)?;
+        writeln!(f, "{:<12} {}", "Requests", summary.requests)?;
+        writeln!(
+            f,
+            "{:<12} {} ({:.2}%)",
+            "Errors",
+            summary.errors,
+            summary.error_rate * 100.0
+        )?;
+        writeln!(f, "{:<12} {:.2}s", "Duration", self.elapsed.as_secs_f64())?;
+        writeln!(f, "{:<12} {:.1} req/s", "Throughput", summary.throughput)?;
+        writeln!(f, "{:<12} {:.2}ms", "Latency min", latency.min)?;
+        writeln!(f, "{:<12} {:.2}ms", "Latency mean", latency.mean)?;
+        writeln!(f, "{:<12} {:.2}ms", "Latency p50", latency.p50)?;
+        writeln!(f, "{:<12} {:.2}ms", "Latency p95", latency.p95)?;
+        writeln!(f, "{:<12} {:.2}ms", "Latency p99", latency.p99)?;
+        writeln!(f, "{:<12} {:.2}ms", "Latency max", latency.max)?;
+        for (status, count) in &self.status_codes {
+            writeln!(f, "{:<12} {}", format!("Status {status}"), count)?;
+        }
+        if self.cancelled {
+            writeln!(f, "(cancelled before completion)")?;
+        }
+        Ok(())
+    }
+}
+
+/// 压测汇总（用于JSON输出，也作为比较的基线）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchSummary {
+    pub name: String,
+    pub requests: usize,
+    pub errors: usize,
+    pub error_rate: f64,
+    pub duration_ms: f64,
+    pub throughput: f64,
+    pub latency_ms: LatencySummary,
+    pub status_codes: BTreeMap<u16, usize>,
+    pub cancelled: bool,
+}
+
+impl BenchSummary {
+    /// 读取 `--json` 写出的汇总作为基线
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| {
+            HttpieError::InvalidRequest(format!("Failed to read baseline {}: {e}", path.display()))
+        })?;
+        serde_json::from_str(&content)
+            .map_err(|e| HttpieError::Parse(format!("Invalid baseline {}: {e}", path.display())))
+    }
+
+    /// 与基线比较延迟分位数和错误率
+    pub fn compare(&self, baseline: &BenchSummary, thresholds: BenchThresholds) -> BenchComparison {
+        let (current, base) = (&self.latency_ms, &baseline.latency_ms);
+        let latency = |metric: &'static str, current: f64, baseline: f64| {
+            // 基线为0时无法计算相对变化，不视为退化
+            let change = (baseline > 0.0).then(|| (current - baseline) / baseline);
+            MetricDiff {
+                metric,
+                baseline,
+                current,
+                change,
+                regressed: change.is_some_and(|change| change > thresholds.latency),
+            }
+        };
+        let error_rate = MetricDiff {
+            metric: "error_rate",
+            baseline: baseline.error_rate,
+            current: self.error_rate,
+            change: Some(self.error_rate - baseline.error_rate),
+            regressed: self.error_rate - baseline.error_rate > thresholds.error_rate,
+        };
+
+        BenchComparison {
+            name: self.name.clone(),
+            thresholds,
+            metrics: vec![
+                latency("mean", current.mean, base.mean),
+                latency("p50", current.p50, base.p50),
+                latency("p95", current.p95, base.p95),
+                latency("p99", current.p99, base.p99),
+                error_rate,
+            ],
+        }
+    }
+}
+
+/// 与基线比较时允许的退化
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BenchThresholds {
+    /// 延迟指标允许的相对增长（0.1 表示慢 10%）
+    pub latency: f64,
+    /// 错误率允许的绝对增长（0.01 表示增加 1 个百分点）
+    pub error_rate: f64,
+}
+
+impl Default for BenchThresholds {
+    fn default() -> Self {
+        Self {
+            latency: 0.1,
+            error_rate: 0.01,
+        }
+    }
+}
+
+/// 单个指标与基线的差异
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricDiff {
+    pub metric: &'static str,
+    /// 延迟为毫秒，错误率为比例
+    pub baseline: f64,
+    pub current: f64,
+    /// 延迟为相对变化，错误率为绝对变化；基线延迟为0时为 None
+    pub change: Option<f64>,
+    pub regressed: bool,
+}
+
+/// 压测结果与基线的比较（用于输出差异汇总）
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchComparison {
+    pub name: String,
+    pub thresholds: BenchThresholds,
+    pub metrics: Vec<MetricDiff>,
+}
+
+impl BenchComparison {
+    /// 超过阈值的指标
+    pub fn regressions(&self) -> Vec<&MetricDiff> {
+        self.metrics.iter().filter(|m| m.regressed).collect()
+    }
+}
+
+impl fmt::Display for BenchComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "=== Baseline comparison: {} ===", self.name)?;
+        for diff in &self.metrics {
+            let (baseline, current, change) = match (diff.metric, diff.change) {
+                ("error_rate", change) => (
+                    format!("{:.2}%", diff.baseline * 100.0),
+                    format!("{:.2}%", diff.current * 100.0),
+                    format!("{:+.2}pp", change.unwrap_or_default() * 100.0),
+                ),
+                (_, change) => (
+                    format!("{:.2}ms", diff.baseline),
+                    format!("{:.2}ms", diff.current),
+                    change.map_or("n/a".to_string(), |c| format!("{:+.1}%", c * 100.0)),
+                ),
+            };
+            let verdict = if diff.regressed { "  REGRESSED" } else { "" };
+            writeln!(
+                f,
+                "{:<12} {baseline:>10} -> {current:>10} ({change}){verdict}",
+                diff.metric
+            )?;
+        }
+        let regressions = self.regressions().len();
+        if regressions > 0 {
+            writeln!(
+                f,
+                "{regressions} metric(s) regressed (thresholds: latency +{:.1}%, error rate +{:.2}pp)",
+                self.thresholds.latency * 100.0,
+                self.thresholds.error_rate * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 延迟统计（毫秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// 解析比例（`10%`、`0.5 %` 或 `0.1`）
+pub fn parse_ratio(value: &str) -> Result<f64> {
+    let value = value.trim();
+    let ratio = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().map(|p| p / 100.0),
+        None => value.parse(),
+    };
+    ratio
+        .ok()
+        .filter(|ratio| ratio.is_finite())
+        .ok_or_else(|| HttpieError::Parse(format!("Invalid ratio '{value}'")))
+}
+
+/// 解析时长（`500ms`、`10s`、`2m`，无单位时按秒）
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let invalid = || HttpieError::Parse(format!("Invalid duration '{value}'"));
+
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return Err(invalid()),
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| invalid())
+}
@@ -0,0 +1,454 @@
+//! 二进制响应处理模块
+//!
+//! 识别二进制响应体，按charset解码文本响应体，生成大小/哈希/十六进制预览摘要，
+//! 并支持将响应体无损保存到文件、按 `Range` 请求部分内容以及续传未完成的下载。
+
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use encoding_rs::{Encoding, UTF_8};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 请求部分内容的指令（`# @range bytes=0-1023`）
+pub const RANGE_DIRECTIVE: &str = "range";
+
+/// 字节嗅探时检查的最大长度
+const SNIFF_LEN: usize = 8192;
+/// 十六进制预览的字节数
+const PREVIEW_LEN: usize = 32;
+/// 下载进度的刷新间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 二进制响应体摘要
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinarySummary {
+    pub size: usize,
+    pub sha256: String,
+    pub preview: String,
+}
+
+impl BinarySummary {
+    /// 根据响应字节生成摘要
+    pub fn new(bytes: &[u8]) -> Self {
+        let sha256 = Sha256::digest(bytes)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let preview = bytes
+            .iter()
+            .take(PREVIEW_LEN)
+            .map(|b| format!("{b:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self {
+            size: bytes.len(),
+            sha256,
+            preview,
+        }
+    }
+}
+
+impl fmt::Display for BinarySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "<binary body: {} bytes>", self.size)?;
+        writeln!(f, "sha256: {}", self.sha256)?;
+        write!(f, "preview: {}", self.preview)?;
+        if self.size > PREVIEW_LEN {
+            write!(f, " ...")?;
+        }
+        Ok(())
+    }
+}
+
+/// 响应重定向目标（`>> file`、`>>! file` 或续传的 `>>+ file`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseRedirect {
+    pub path: String,
+    #[serde(default)]
+    pub overwrite: bool,
+    /// 续传：按已有部分文件的长度发送Range请求头，并把响应追加到文件末尾
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resume: bool,
+}
+
+impl ResponseRedirect {
+    /// 解析重定向行，非重定向行返回 None
+    pub fn parse(line: &str) -> Option<Self> {
+        let rest = line.trim().strip_prefix(">>")?;
+        let (overwrite, resume, path) = if let Some(path) = rest.strip_prefix('!') {
+            (true, false, path)
+        } else if let Some(path) = rest.strip_prefix('+') {
+            (false, true, path)
+        } else {
+            (false, false, rest)
+        };
+        let path = path.trim();
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            path: path.to_string(),
+            overwrite,
+            resume,
+        })
+    }
+}
+
+/// 解析并校验 `@range` 指令的值（`bytes=0-1023`、`bytes=1024-`、`bytes=-500`，多个范围以逗号分隔）
+pub fn parse_range(value: &str) -> Result<String> {
+    let invalid = || {
+        HttpieError::InvalidRequest(format!(
+            "Invalid @{RANGE_DIRECTIVE} '{value}': expected bytes=start-end"
+        ))
+    };
+    let ranges = value.trim().strip_prefix("bytes=").ok_or_else(invalid)?;
+    for range in ranges.split(',') {
+        let (start, end) = range.trim().split_once('-').ok_or_else(invalid)?;
+        let bound = |text: &str| text.is_empty() || text.parse::<u64>().is_ok();
+        if (start.is_empty() && end.is_empty()) || !bound(start) || !bound(end) {
+            return Err(invalid());
+        }
+        if let (Ok(start), Ok(end)) = (start.parse::<u64>(), end.parse::<u64>())
+            && start > end
+        {
+            return Err(invalid());
+        }
+    }
+    Ok(value.trim().to_string())
+}
+
+/// 发送时附加的 `Range` 请求头
+///
+/// 已声明 `Range` 请求头时不附加；其次是 `@range` 指令，最后是续传重定向已有部分文件的长度。
+pub fn range_header(request: &HttpRequest) -> Option<String> {
+    if request
+        .headers
+        .keys()
+        .any(|key| key.eq_ignore_ascii_case("Range"))
+    {
+        return None;
+    }
+    if let Some(range) = request.directive(RANGE_DIRECTIVE) {
+        return Some(range.to_string());
+    }
+    let redirect = request.response_redirect.as_ref().filter(|r| r.resume)?;
+    let existing = fs::metadata(request.resolve_path(&redirect.path))
+        .ok()?
+        .len();
+    (existing > 0).then(|| format!("bytes={existing}-"))
+}
+
+/// `Content-Range` 响应头（`bytes 0-1023/4096`、`bytes 0-1023/*` 或 `bytes */4096`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRange {
+    /// 响应体覆盖的字节范围（含两端）
+    pub range: Option<(u64, u64)>,
+    /// 完整资源的长度，未知时为 None
+    pub total: Option<u64>,
+}
+
+impl ContentRange {
+    /// 解析响应头的值，格式不正确时返回 None
+    pub fn parse(value: &str) -> Option<Self> {
+        let (range, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+        let range = match range.trim() {
+            "*" => None,
+            range => {
+                let (start, end) = range.split_once('-')?;
+                Some((start.parse().ok()?, end.parse().ok()?))
+            }
+        };
+        let total = match total.trim() {
+            "*" => None,
+            total => Some(total.parse().ok()?),
+        };
+        Some(Self { range, total })
+    }
+}
+
+/// 续传保存的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeOutcome {
+    /// 写入了完整的响应体（没有部分文件，或服务器忽略了Range）
+    Downloaded,
+    /// 从 `offset` 处追加到已有的部分文件
+    Resumed { offset: u64 },
+    /// 部分文件已经完整（416且长度与资源一致）
+    Complete,
+}
+
+/// 续传保存：根据响应状态和 `Content-Range` 把响应体写入或追加到已有的部分文件
+///
+/// 范围与部分文件不衔接、或响应不是成功状态时报错且不修改文件。
+pub fn resume_body(
+    path: &Path,
+    status: u16,
+    content_range: Option<&str>,
+    bytes: &[u8],
+) -> Result<ResumeOutcome> {
+    let existing = fs::metadata(path).map_or(0, |metadata| metadata.len());
+    let content_range = content_range.and_then(ContentRange::parse);
+    let cannot_resume = |reason: String| {
+        HttpieError::InvalidRequest(format!("Cannot resume {}: {reason}", path.display()))
+    };
+
+    match status {
+        206 if existing > 0 => {
+            let (start, _) = content_range
+                .and_then(|range| range.range)
+                .ok_or_else(|| cannot_resume("206 response without Content-Range".to_string()))?;
+            if start != existing {
+                return Err(cannot_resume(format!(
+                    "server returned bytes from {start}, expected {existing}"
+                )));
+            }
+            let mut file = fs::OpenOptions::new().append(true).open(path)?;
+            file.write_all(bytes)?;
+            Ok(ResumeOutcome::Resumed { offset: existing })
+        }
+        416 if existing > 0 => match content_range.and_then(|range| range.total) {
+            Some(total) if total == existing => Ok(ResumeOutcome::Complete),
+            total => Err(cannot_resume(format!(
+                "range not satisfiable ({existing} bytes on disk, {} on server)",
+                total.map_or_else(|| "unknown".to_string(), |total| total.to_string())
+            ))),
+        },
+        200..=299 => {
+            save_body(path, bytes, true)?;
+            Ok(ResumeOutcome::Downloaded)
+        }
+        _ => Err(cannot_resume(format!("HTTP {status}"))),
+    }
+}
+
+/// 下载进度，在标准错误的同一行按固定间隔刷新
+#[derive(Debug)]
+pub struct DownloadProgress {
+    offset: u64,
+    total: Option<u64>,
+    received: u64,
+    last_draw: Option<Instant>,
+}
+
+impl DownloadProgress {
+    /// `offset` 为续传的起始字节，`total` 为完整资源的长度
+    pub fn new(offset: u64, total: Option<u64>) -> Self {
+        Self {
+            offset,
+            total,
+            received: 0,
+            last_draw: None,
+        }
+    }
+
+    /// 记录新收到的字节
+    pub fn advance(&mut self, bytes: usize) {
+        self.received += bytes as u64;
+        if self
+            .last_draw
+            .is_none_or(|drawn| drawn.elapsed() >= PROGRESS_INTERVAL)
+        {
+            eprint!("\r{}", self.render());
+            self.last_draw = Some(Instant::now());
+        }
+    }
+
+    /// 输出最终进度并换行
+    pub fn finish(&self) {
+        eprintln!("\r{}", self.render());
+    }
+
+    /// 进度文本（如 `Downloaded 1.5 MiB / 3.0 MiB (50%)`）
+    pub fn render(&self) -> String {
+        let done = self.offset + self.received;
+        match self.total {
+            Some(total) if total > 0 => format!(
+                "Downloaded {} / {} ({}%)",
+                format_size(done),
+                format_size(total),
+                done.min(total) * 100 / total
+            ),
+            _ => format!("Downloaded {}", format_size(done)),
+        }
+    }
+}
+
+/// 以二进制单位格式化字节数
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Content-Type中声明的charset（如 `text/html; charset=ISO-8859-1` 中的 `ISO-8859-1`）
+pub fn charset(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// 按Content-Type中的charset解码文本，未声明或无法识别时按UTF-8；字节顺序标记优先，
+/// 非法字节替换为U+FFFD
+pub fn decode_text<'a>(content_type: &str, bytes: &'a [u8]) -> Cow<'a, str> {
+    let encoding = charset(content_type)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+    encoding.decode(bytes).0
+}
+
+/// 字节是否为JSON对象或数组（用于识别以通用类型返回的JSON）
+pub fn is_json(bytes: &[u8]) -> bool {
+    matches!(bytes.trim_ascii_start().first(), Some(b'{' | b'['))
+        && serde_json::from_slice::<serde::de::IgnoredAny>(bytes).is_ok()
+}
+
+/// 根据Content-Type和字节内容判断响应体是否为二进制
+pub fn is_binary(content_type: &str, bytes: &[u8]) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+
+    if mime.starts_with("text/")
+        || mime.ends_with("json")
+        || mime.ends_with("xml")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || mime == "application/javascript"
+        || mime == "application/x-www-form-urlencoded"
+    {
+        return false;
+    }
+
+    if mime.starts_with("image/")
+        || mime.starts_with("audio/")
+        || mime.starts_with("video/")
+        || mime.starts_with("font/")
+        || matches!(
+            mime.as_str(),
+            "application/octet-stream"
+                | "application/pdf"
+                | "application/zip"
+                | "application/gzip"
+                | "application/x-protobuf"
+                | "application/protobuf"
+                | "application/grpc"
+                | "application/wasm"
+        )
+    {
+        return true;
+    }
+
+    // 未知类型：通过字节嗅探判断
+    let sample = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if sample.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => false,
+        // 截断可能切断多字节字符，仅在错误不在末尾时判定为二进制
+        Err(e) => e.error_len().is_some(),
+    }
+}
+
+/// 根据Content-Type推断文件扩展名
+pub fn extension_for(content_type: &str) -> &'static str {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    match mime {
+        "application/json" => "json",
+        "application/xml" | "text/xml" => "xml",
+        "text/html" => "html",
+        "text/plain" => "txt",
+        "text/csv" => "csv",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "application/pdf" => "pdf",
+        "application/zip" => "zip",
+        "application/gzip" => "gz",
+        _ if mime.ends_with("+json") => "json",
+        _ => "bin",
+    }
+}
+
+/// 将请求名称转换为安全的文件名
+pub fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let sanitized = sanitized.trim_matches('_');
+    if sanitized.is_empty() {
+        "response".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// 保存响应体，`overwrite`为false且文件已存在时追加数字后缀
+pub fn save_body(path: &Path, bytes: &[u8], overwrite: bool) -> Result<PathBuf> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let target = if overwrite {
+        path.to_path_buf()
+    } else {
+        available_path(path)
+    };
+    fs::write(&target, bytes)?;
+
+    Ok(target)
+}
+
+/// 查找不冲突的文件路径（file.json -> file-1.json -> file-2.json）
+fn available_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|i| path.with_file_name(format!("{stem}-{i}{extension}")))
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded suffix search")
+}
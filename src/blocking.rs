@@ -0,0 +1,111 @@
+//! 同步客户端模块
+//!
+//! 提供与 [`crate::HttpClient`] 对应的同步API，调用方无需自行运行tokio运行时。
+//! 由 `blocking` 特性控制。
+//!
+//! 没有基于 `reqwest::blocking` 实现：发送经由异步的 [`crate::HttpTransport`]，
+//! 响应处理器、`LISTEN`、GRPC和下载也都是异步的，改用 `reqwest::blocking`
+//! 需要另写一套请求构建和响应处理，行为容易与异步客户端不一致。因此客户端持有一个
+//! 单线程tokio运行时，在其上运行异步客户端，请求构建、传输层、重定向记录和
+//! 响应处理都与异步客户端共用（`reqwest::blocking` 内部同样运行tokio运行时）。
+
+use crate::client::{self, HttpProtocol, RequestOutcome};
+use crate::error::Result;
+use crate::models::HttpRequest;
+#[cfg(feature = "scripting")]
+use crate::script::ScriptIsolation;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio::runtime::Runtime;
+
+/// 同步HTTP客户端
+///
+/// 不能在异步运行时内调用，此时应直接使用 [`crate::HttpClient`]。
+pub struct HttpClient {
+    runtime: Runtime,
+    inner: client::HttpClient,
+}
+
+impl HttpClient {
+    /// 创建新的同步HTTP客户端，无法创建运行时时返回错误
+    pub fn new() -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            runtime,
+            inner: client::HttpClient::default(),
+        })
+    }
+
+    /// 启用脚本功能
+    #[cfg(feature = "scripting")]
+    pub fn with_script_engine(mut self) -> Result<Self> {
+        self.inner = self.inner.with_script_engine()?;
+        Ok(self)
+    }
+
+    /// 设置响应处理器之间的隔离方式（默认每个响应处理器使用新的运行时）
+    #[cfg(feature = "scripting")]
+    pub fn with_script_isolation(mut self, isolation: ScriptIsolation) -> Self {
+        self.inner = self.inner.with_script_isolation(isolation);
+        self
+    }
+
+    /// 控制是否打印响应（默认打印）
+    pub fn with_print_response(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_print_response(enabled);
+        self
+    }
+
+    /// 控制是否打印测试结果（默认打印）；结果总会在 [`RequestOutcome`] 中返回
+    pub fn with_print_test_results(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_print_test_results(enabled);
+        self
+    }
+
+    /// 设置二进制响应体的保存目录
+    pub fn with_download_dir(mut self, download_dir: Option<PathBuf>) -> Self {
+        self.inner = self.inner.with_download_dir(download_dir);
+        self
+    }
+
+    pub fn with_dns_overrides(
+        mut self,
+        dns_overrides: &HashMap<String, SocketAddr>,
+    ) -> Result<Self> {
+        self.inner = self.inner.with_dns_overrides(dns_overrides)?;
+        Ok(self)
+    }
+
+    /// 设置响应体在内存中缓冲的最大字节数（超出部分被截断）
+    pub fn with_max_body_bytes(mut self, max_body_bytes: Option<usize>) -> Self {
+        self.inner = self.inner.with_max_body_bytes(max_body_bytes);
+        self
+    }
+
+    /// 设置打印响应体的最大字节数
+    pub fn with_max_body_print(mut self, max_body_print: Option<usize>) -> Self {
+        self.inner = self.inner.with_max_body_print(max_body_print);
+        self
+    }
+
+    /// 设置HTTP协议策略
+    pub fn with_http_protocol(mut self, protocol: HttpProtocol) -> Result<Self> {
+        self.inner = self.inner.with_http_protocol(protocol)?;
+        Ok(self)
+    }
+
+    /// 执行HTTP请求，丢弃执行结果（需要测试结果时使用 [`execute_with_outcome`](Self::execute_with_outcome)）
+    pub fn execute(&mut self, request: &HttpRequest) -> Result<()> {
+        self.execute_with_outcome(request).map(|_| ())
+    }
+
+    /// 执行HTTP请求并返回执行结果
+    pub fn execute_with_outcome(&mut self, request: &HttpRequest) -> Result<RequestOutcome> {
+        self.runtime
+            .block_on(self.inner.execute_with_outcome(request))
+    }
+}
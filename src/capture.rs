@@ -0,0 +1,103 @@
+//! 变量捕获模块
+//!
+//! 解析 `# @capture name = source` 指令，从响应中提取值写入运行期变量上下文，
+//! 后续请求可通过 `{{name}}` 引用。source 支持 JSONPath（`$.data.token`）、
+//! `header 名称` 和 `status`。
+
+use crate::error::{HttpieError, Result};
+use crate::jsonpath;
+use crate::models::HttpRequest;
+use crate::script::ResponseObject;
+use serde_json::Value;
+
+/// 变量捕获指令
+pub const CAPTURE_DIRECTIVE: &str = "capture";
+
+/// 捕获值的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureSource {
+    /// 响应体中的JSONPath
+    JsonPath(String),
+    /// 响应头（不区分大小写）
+    Header(String),
+    /// 状态码
+    Status,
+}
+
+/// 单条捕获规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    pub name: String,
+    pub source: CaptureSource,
+}
+
+impl Capture {
+    /// 解析指令值（`name = source`）
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            HttpieError::InvalidRequest(format!("Invalid @capture '{value}': {reason}"))
+        };
+
+        let (name, source) = value
+            .split_once('=')
+            .ok_or_else(|| invalid("expected 'name = source'"))?;
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(invalid("variable name must be alphanumeric"));
+        }
+
+        let source = source.trim();
+        let source = if source.starts_with('$') {
+            jsonpath::parse(source).map_err(|e| invalid(&e.to_string()))?;
+            CaptureSource::JsonPath(source.to_string())
+        } else if source == "status" {
+            CaptureSource::Status
+        } else if let Some(header) = source.strip_prefix("header ") {
+            CaptureSource::Header(header.trim().to_string())
+        } else {
+            return Err(invalid(
+                "source must be a JSONPath, 'header NAME' or 'status'",
+            ));
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            source,
+        })
+    }
+
+    /// 读取请求上的所有捕获规则
+    pub fn from_request(request: &HttpRequest) -> Result<Vec<Self>> {
+        request
+            .directives_named(CAPTURE_DIRECTIVE)
+            .map(Self::parse)
+            .collect()
+    }
+
+    /// 从响应中提取值，未匹配时返回 None
+    pub fn extract(&self, response: &ResponseObject) -> Option<String> {
+        match &self.source {
+            CaptureSource::Status => Some(response.status.to_string()),
+            CaptureSource::Header(header) => response
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(header))
+                .map(|(_, value)| value.clone()),
+            CaptureSource::JsonPath(path) => {
+                // 未声明为JSON的响应体尝试按JSON解析
+                let parsed;
+                let body = match &response.body {
+                    Value::String(text) => {
+                        parsed = serde_json::from_str(text).ok()?;
+                        &parsed
+                    }
+                    body => body,
+                };
+                jsonpath::select(body, path).ok()?.map(|value| match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+            }
+        }
+    }
+}
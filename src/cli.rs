@@ -0,0 +1,864 @@
+//! 命令行参数模块
+//!
+//! 定义clap命令行参数，并把解析结果转换为库中的配置类型。
+
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use httpie::{
+    CaseSelector, Config, DEFAULT_CONFIG_FILE, DEFAULT_ENV_FILE, DEFAULT_HTTP_FILE, DynamicValues,
+    Environment, EnvironmentLoader, HttpProtocol, HttpieError, Notifier, RunOrder, SecretPolicy,
+    TraceFormat, TransportConfig,
+    adhoc::ADHOC_METHODS,
+    client,
+    hooks::{DEFAULT_HOOKS_FILE, ScriptHooks},
+    netrc::Netrc,
+};
+
+/// 每个响应默认最多打印的响应体字节数
+pub const DEFAULT_MAX_BODY_PRINT: usize = 1024 * 1024;
+
+/// 命令行参数定义
+pub fn command() -> Command {
+    Command::new("httpie")
+        .version("0.1.0")
+        .about("A simple HTTP client that parses .http files")
+        .arg(
+            Arg::new("file")
+                .long("file")
+                .value_name("FILE")
+                .help("HTTP request definition file (repeat to run several files)")
+                .default_value(DEFAULT_HTTP_FILE)
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .arg(
+            Arg::new("case")
+                .long("case")
+                .value_name("CASE")
+                .help(
+                    "Test case to execute: NAME, NAME#N, FILE::NAME, wildcards * and ? (repeatable)",
+                )
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .arg(
+            Arg::new("index")
+                .long("index")
+                .value_name("N")
+                .help("Execute the Nth request of the file (1-based)")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with("case")
+                .global(true),
+        )
+        .arg(
+            Arg::new("env")
+                .long("env")
+                .value_name("NAME")
+                .help("Environment to use from the environment file (default: development)")
+                .global(true),
+        )
+        .arg(
+            Arg::new("env-file")
+                .long("env-file")
+                .value_name("PATH|URL")
+                .help("Environment file or https:// URL (default: nearest httpie.env.json files up to the repo root)")
+                .default_value(DEFAULT_ENV_FILE)
+                .global(true),
+        )
+        .arg(
+            Arg::new("allow-exec")
+                .long("allow-exec")
+                .help("Allow 'exec' secrets in local environment files to run shell commands")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("env-auth")
+                .long("env-auth")
+                .value_name("VALUE")
+                .help("Authorization header for a remote environment file")
+                .global(true),
+        )
+        .arg(
+            Arg::new("env-cache-ttl")
+                .long("env-cache-ttl")
+                .value_name("SECONDS")
+                .help("How long a downloaded environment file is reused (default: 300)")
+                .value_parser(clap::value_parser!(u64))
+                .global(true),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("FILE")
+                .help("Project config file with client, header, output and report defaults")
+                .default_value(DEFAULT_CONFIG_FILE)
+                .global(true),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .help("Request timeout")
+                .value_parser(clap::value_parser!(u64))
+                .global(true),
+        )
+        .arg(
+            Arg::new("max-redirects")
+                .long("max-redirects")
+                .value_name("N")
+                .help("Maximum number of redirects to follow (0 = do not follow)")
+                .value_parser(clap::value_parser!(usize))
+                .global(true),
+        )
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .value_name("URL")
+                .help("Send requests through this proxy")
+                .global(true),
+        )
+        .arg(
+            Arg::new("insecure")
+                .long("insecure")
+                .help("Skip TLS certificate verification")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("cacert")
+                .long("cacert")
+                .value_name("FILE")
+                .help("Additional trusted CA certificate (PEM)")
+                .global(true),
+        )
+        .arg(
+            Arg::new("raw")
+                .long("raw")
+                .help("Do not decompress response bodies; show them as sent on the wire")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("session")
+                .long("session")
+                .value_name("NAME")
+                .help("Load cookies and captured variables from a saved session"),
+        )
+        .arg(
+            Arg::new("save-session")
+                .long("save-session")
+                .value_name("NAME")
+                .help("Save cookies and captured variables after the run"),
+        )
+        .arg(
+            Arg::new("history")
+                .long("history")
+                .help("Record executed requests in the history (.httpie/history.jsonl)")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("history-bodies")
+                .long("history-bodies")
+                .help("Also record response bodies in the history")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .value_name("DURATION")
+                .help("Re-run the requests every DURATION (e.g. 30s, 5m) until interrupted"),
+        )
+        .arg(
+            Arg::new("webhook")
+                .long("webhook")
+                .value_name("URL")
+                .help("Notify this webhook (Slack compatible) when a case starts failing or recovers")
+                .requires("interval"),
+        )
+        .arg(
+            Arg::new("alert-after")
+                .long("alert-after")
+                .value_name("N")
+                .help("Consecutive failures before a case is reported as failing")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("1")
+                .requires("interval"),
+        )
+        .arg(
+            Arg::new("metrics-file")
+                .long("metrics-file")
+                .value_name("FILE")
+                .help("Write Prometheus metrics for the run to FILE"),
+        )
+        .arg(
+            Arg::new("metrics-push")
+                .long("metrics-push")
+                .value_name("URL")
+                .help("Push Prometheus metrics for the run to a Pushgateway URL"),
+        )
+        .arg(
+            Arg::new("notify-on-failure")
+                .long("notify-on-failure")
+                .help("POST a run summary to the [notify] webhook when any request or test fails")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("export-curl")
+                .long("export-curl")
+                .help("Print equivalent curl commands instead of executing requests")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("download-dir")
+                .long("download-dir")
+                .value_name("DIR")
+                .help("Directory to save binary response bodies into"),
+        )
+        .arg(
+            Arg::new("save-responses")
+                .long("save-responses")
+                .value_name("DIR")
+                .help("Save every response body to DIR/<request-name>.<ext> with a .meta.json sidecar (status, headers, duration)"),
+        )
+        .arg(
+            Arg::new("hooks")
+                .long("hooks")
+                .value_name("FILE")
+                .help("Script whose beforeAll/beforeEach/afterEach/afterAll functions run around the requests (default: httpie.hooks.js if present)"),
+        )
+        .arg(
+            Arg::new("script-isolation")
+                .long("script-isolation")
+                .value_name("MODE")
+                .value_parser(["isolated", "shared"])
+                .help("Run each response handler in a fresh runtime (isolated, default) or share one runtime between all scripts"),
+        )
+        .arg(
+            Arg::new("concurrency")
+                .long("concurrency")
+                .value_name("N")
+                .help("Number of requests kept in flight at once")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("resolve")
+                .long("resolve")
+                .value_name("HOST:PORT:ADDRESS")
+                .help("Force HOST to resolve to ADDRESS (repeatable)")
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .arg(
+            Arg::new("ignore-netrc")
+                .long("ignore-netrc")
+                .help("Do not read Basic credentials from ~/.netrc (or the file in NETRC)")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("request-id")
+                .long("request-id")
+                .help("Add an X-Request-Id header (a UUID per request, kept when replayed with `history rerun`) to every request")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("var")
+                .long("var")
+                .value_name("NAME=VALUE")
+                .help("Set a variable, overriding file, environment and .env values (repeatable)")
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .arg(
+            Arg::new("http1.1")
+                .long("http1.1")
+                .help("Only use HTTP/1.1")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("http2-prior-knowledge")
+                .global(true),
+        )
+        .arg(
+            Arg::new("http2-prior-knowledge")
+                .long("http2-prior-knowledge")
+                .help("Use HTTP/2 without negotiation (also for plain-text h2c)")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("http3")
+                .long("http3")
+                .help("Try HTTP/3 (QUIC) first for HTTPS requests, falling back to HTTP/1.1 or HTTP/2")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["http1.1", "http2-prior-knowledge"])
+                .global(true),
+        )
+        .arg(
+            Arg::new("max-body-bytes")
+                .long("max-body-bytes")
+                .value_name("BYTES")
+                .help("Maximum response body size buffered in memory; larger bodies are truncated")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("max-body-print")
+                .long("max-body-print")
+                .value_name("BYTES")
+                .help("Maximum number of body bytes printed per response (default: 1048576, 0 = unlimited)")
+                .value_parser(clap::value_parser!(usize))
+                .global(true),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .value_name("FORMAT")
+                .help("Inject tracing headers into every request: w3c (traceparent, default) or b3; repeatable")
+                .value_parser(["w3c", "b3"])
+                .num_args(0..=1)
+                .default_missing_value("w3c")
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-infer-content-type")
+                .long("no-infer-content-type")
+                .help("Do not add Content-Type: application/json to JSON bodies automatically")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Treat parser warnings (unknown directives, skipped sections, ...) as errors")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("dialect")
+                .long("dialect")
+                .value_name("DIALECT")
+                .help("Parse files written for another tool, recognizing only its syntax")
+                .value_parser(["native", "intellij", "httpyac", "rest-client"])
+                .default_value("native")
+                .global(true),
+        )
+        .arg(
+            Arg::new("implicit-sections")
+                .long("implicit-sections")
+                .help("Also start a new request at a request line after a blank line, without ###")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("fail-fast")
+                .long("fail-fast")
+                .help("Stop at the first failure, including failed tests")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("continue-on-error"),
+        )
+        .arg(
+            Arg::new("continue-on-error")
+                .long("continue-on-error")
+                .help("Run every request even after transport errors or failed tests")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("slowest")
+                .long("slowest")
+                .value_name("N")
+                .help("Number of slowest requests listed in the run summary (0 = none)")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("5"),
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .help("Show a progress bar instead of printing responses (one line per request when stdout is not a terminal)")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("verbose"),
+        )
+        .arg(
+            Arg::new("order")
+                .long("order")
+                .value_name("ORDER")
+                .help("Order in which cases run")
+                .value_parser(["file", "name", "random"])
+                .default_value("file"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_name("N")
+                .help("Seed for --order random and for $uuid, $randomInt, trace and request IDs (a random order seed is chosen and printed when omitted)")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("frozen-time")
+                .long("frozen-time")
+                .value_name("UNIX_SECONDS")
+                .help("Fixed value for $timestamp, for reproducible snapshots and recordings")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("log-format")
+                .long("log-format")
+                .value_name("FORMAT")
+                .help("Log output format on stderr (filter with RUST_LOG, default: warn)")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .global(true),
+        )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .short('v')
+                .help("Print connection details with each response: remote address and TLS certificate validity")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("print")
+                .long("print")
+                .short('p')
+                .value_name("WHAT")
+                .help("Parts to print: H request headers, B request body, h response headers, b response body (default: hb)")
+                .global(true),
+        )
+        .arg(
+            Arg::new("print-headers")
+                .long("print-headers")
+                .value_name("NAMES")
+                .help("Only print these comma-separated headers, e.g. content-type,x-request-id")
+                .global(true),
+        )
+        .arg(
+            Arg::new("hide-headers")
+                .long("hide-headers")
+                .value_name("NAMES")
+                .help("Do not print these comma-separated headers, e.g. date,server")
+                .global(true),
+        )
+        .arg(
+            Arg::new("sort-keys")
+                .long("sort-keys")
+                .help("Sort object keys when printing JSON bodies")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("indent")
+                .long("indent")
+                .value_name("N")
+                .help("Number of spaces per indentation level for JSON bodies")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("2")
+                .global(true),
+        )
+        .arg(
+            Arg::new("compact")
+                .long("compact")
+                .help("Print JSON bodies on a single line")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("filter")
+                .long("filter")
+                .value_name("JSONPATH")
+                .help("Print only the part of JSON response bodies selected by a JSONPath, e.g. '$.data.items[*].id' (a request's @filter directive takes precedence)")
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-pager")
+                .long("no-pager")
+                .help("Never page large responses through $PAGER (default: less -R when stdout is a terminal)")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .help("Quiet mode: do not print HTTP responses")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert a request from another format into .http syntax")
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("FORMAT")
+                        .help("Source format")
+                        .value_parser(["curl"])
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("input")
+                        .value_name("INPUT")
+                        .help("Command to convert (read from stdin when omitted)"),
+                ),
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Format .http files in place")
+                .arg(
+                    Arg::new("files")
+                        .value_name("FILES")
+                        .help("Files to format (defaults to --file)")
+                        .num_args(1..),
+                )
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .help("Only report files that are not formatted")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("envs")
+                .about("List environments and the variables --file needs but they lack")
+                .arg(
+                    Arg::new("name")
+                        .value_name("NAME")
+                        .help("Only inspect this environment"),
+                ),
+        )
+        .subcommands(ADHOC_METHODS.iter().map(|method| adhoc_subcommand(method)))
+        .subcommand(
+            Command::new("history")
+                .about("List, show and re-run previously executed requests")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("list").about("List recent requests").arg(
+                        Arg::new("limit")
+                            .short('n')
+                            .long("limit")
+                            .value_name("N")
+                            .help("Number of entries to show")
+                            .value_parser(clap::value_parser!(usize))
+                            .default_value("20"),
+                    ),
+                )
+                .subcommand(
+                    Command::new("show")
+                        .about("Show a recorded request and its response")
+                        .arg(history_id_arg()),
+                )
+                .subcommand(
+                    Command::new("rerun")
+                        .about("Send a recorded request again")
+                        .arg(history_id_arg()),
+                ),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Load-test a single request with concurrent workers")
+                .arg(
+                    Arg::new("concurrency")
+                        .short('c')
+                        .long("concurrency")
+                        .value_name("N")
+                        .help("Number of concurrent workers")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::new("requests")
+                        .short('n')
+                        .long("requests")
+                        .value_name("N")
+                        .help("Total number of requests to send")
+                        .value_parser(clap::value_parser!(usize))
+                        .conflicts_with("duration"),
+                )
+                .arg(
+                    Arg::new("duration")
+                        .short('d')
+                        .long("duration")
+                        .value_name("DURATION")
+                        .help("Run for a fixed duration, e.g. 30s, 500ms, 2m"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .value_name("FILE")
+                        .help("Write the summary as JSON to FILE"),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .value_name("FILE")
+                        .help("Compare against a summary saved with --json and fail on regressions"),
+                )
+                .arg(
+                    Arg::new("max-latency-regression")
+                        .long("max-latency-regression")
+                        .value_name("RATIO")
+                        .help("Allowed latency increase over the baseline, e.g. 10% or 0.1")
+                        .default_value("10%")
+                        .requires("baseline"),
+                )
+                .arg(
+                    Arg::new("max-error-rate-increase")
+                        .long("max-error-rate-increase")
+                        .value_name("RATIO")
+                        .help("Allowed error rate increase over the baseline in percentage points, e.g. 1%")
+                        .default_value("1%")
+                        .requires("baseline"),
+                )
+                .arg(
+                    Arg::new("diff")
+                        .long("diff")
+                        .value_name("FILE")
+                        .help("Write the baseline comparison as JSON to FILE")
+                        .requires("baseline"),
+                ),
+        )
+}
+
+/// 命令行请求子命令，如 `httpie post URL name=test`
+fn adhoc_subcommand(method: &'static str) -> Command {
+    Command::new(method)
+        .about(format!(
+            "Send a {} request without a .http file",
+            method.to_ascii_uppercase()
+        ))
+        .arg(
+            Arg::new("url")
+                .value_name("URL")
+                .help("Request URL (scheme defaults to http://, :3000/path means localhost)")
+                .required(true),
+        )
+        .arg(
+            Arg::new("items")
+                .value_name("ITEMS")
+                .help("Header:value, field=value, field:=json or param==value")
+                .num_args(0..),
+        )
+}
+
+/// 历史记录编号参数
+fn history_id_arg() -> Arg {
+    Arg::new("id")
+        .value_name("ID")
+        .help("History entry number")
+        .value_parser(clap::value_parser!(u64))
+        .required(true)
+}
+
+/// 全局钩子脚本：`--hooks` 优先，其次是配置 `scripts.hooks`，最后是存在时的 `httpie.hooks.js`
+pub fn script_hooks(
+    matches: &ArgMatches,
+    config: &Config,
+) -> Result<Option<ScriptHooks>, HttpieError> {
+    let path = match (matches.get_one::<String>("hooks"), &config.scripts.hooks) {
+        (Some(path), _) => PathBuf::from(path),
+        (None, Some(path)) => path.clone(),
+        (None, None) if Path::new(DEFAULT_HOOKS_FILE).exists() => PathBuf::from(DEFAULT_HOOKS_FILE),
+        (None, None) => return Ok(None),
+    };
+    ScriptHooks::load(path).map(Some)
+}
+
+/// `--notify-on-failure` 或配置 `notify.on_failure` 启用时的失败通知
+pub fn notifier(
+    matches: &ArgMatches,
+    config: &Config,
+    file_paths: &[&String],
+) -> Result<Option<Notifier>, HttpieError> {
+    let notify = &config.notify;
+    if !matches.get_flag("notify-on-failure") && !notify.on_failure.unwrap_or(false) {
+        return Ok(None);
+    }
+    let Some(webhook) = &notify.webhook else {
+        return Err(HttpieError::InvalidRequest(
+            "Failure notifications need a webhook: set [notify] webhook in the config file"
+                .to_string(),
+        ));
+    };
+    let mut notifier = Notifier::new(webhook).with_source(
+        file_paths
+            .iter()
+            .map(|file| file.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    if let Some(template) = &notify.template {
+        notifier = notifier.with_template(template);
+    }
+    Ok(Some(notifier))
+}
+
+/// 确定环境文件路径（由远及近）及其中允许使用的密钥，见 [`EnvironmentLoader::resolve_files`]
+pub fn environment_files(
+    matches: &ArgMatches,
+    file_path: &str,
+) -> Result<(Vec<PathBuf>, SecretPolicy), HttpieError> {
+    // 未显式指定 --env-file 时查找.http文件所在目录及上级目录中的环境文件
+    let env_file = match matches.value_source("env-file") {
+        Some(ValueSource::DefaultValue) => None,
+        _ => matches.get_one::<String>("env-file").map(String::as_str),
+    };
+    EnvironmentLoader::resolve_files(
+        Path::new(file_path),
+        env_file,
+        matches.get_flag("allow-exec"),
+        |mut remote| {
+            if let Some(authorization) = matches.get_one::<String>("env-auth") {
+                remote = remote.with_authorization(Some(authorization.clone()));
+            }
+            if let Some(&ttl) = matches.get_one::<u64>("env-cache-ttl") {
+                remote = remote.with_cache_ttl(Duration::from_secs(ttl));
+            }
+            remote
+        },
+    )
+}
+
+/// 合并域名解析覆盖，命令行 --resolve 优先于环境文件中的 dns 配置
+pub fn dns_overrides(
+    matches: &ArgMatches,
+    environment: &Environment,
+) -> Result<HashMap<String, SocketAddr>, HttpieError> {
+    let resolve = matches.get_many::<String>("resolve").unwrap_or_default();
+    client::dns_overrides(environment, resolve.map(String::as_str))
+}
+
+/// 读取netrc凭据，`--ignore-netrc` 时不读取
+pub fn load_netrc(matches: &ArgMatches) -> Result<Option<Netrc>, HttpieError> {
+    if matches.get_flag("ignore-netrc") {
+        return Ok(None);
+    }
+    Netrc::load()
+}
+
+/// 命令行 --trace 指定的追踪请求头格式
+pub fn trace_formats(matches: &ArgMatches) -> Result<Vec<TraceFormat>, HttpieError> {
+    matches
+        .get_many::<String>("trace")
+        .unwrap_or_default()
+        .map(|format| format.parse())
+        .collect()
+}
+
+/// 解析命令行 --var NAME=VALUE
+pub fn variable_overrides(matches: &ArgMatches) -> Result<HashMap<String, String>, HttpieError> {
+    matches
+        .get_many::<String>("var")
+        .unwrap_or_default()
+        .map(|entry| match entry.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.to_string()))
+            }
+            _ => Err(HttpieError::InvalidRequest(format!(
+                "Invalid --var '{entry}', expected NAME=VALUE"
+            ))),
+        })
+        .collect()
+}
+
+/// 传输层配置：配置文件中的客户端默认值，命令行参数优先
+pub fn transport_config(
+    matches: &ArgMatches,
+    config: &Config,
+    dns_overrides: HashMap<String, SocketAddr>,
+    max_body_bytes: Option<usize>,
+) -> TransportConfig {
+    let mut transport_config = TransportConfig {
+        dns_overrides,
+        protocol: http_protocol(matches),
+        max_body_bytes,
+        ..TransportConfig::default()
+    };
+    config.apply(&mut transport_config);
+
+    if let Some(&timeout) = matches.get_one::<u64>("timeout") {
+        transport_config.timeout = Some(Duration::from_secs(timeout));
+    }
+    if let Some(&max_redirects) = matches.get_one::<usize>("max-redirects") {
+        transport_config.max_redirects = Some(max_redirects);
+    }
+    if let Some(proxy) = matches.get_one::<String>("proxy") {
+        transport_config.proxy = Some(proxy.clone());
+    }
+    if matches.get_flag("insecure") {
+        transport_config.insecure = true;
+    }
+    if let Some(cacert) = matches.get_one::<String>("cacert") {
+        transport_config.ca_cert = Some(PathBuf::from(cacert));
+    }
+    transport_config.raw = matches.get_flag("raw");
+    transport_config
+}
+
+/// 根据命令行参数确定HTTP协议策略
+fn http_protocol(matches: &ArgMatches) -> HttpProtocol {
+    if matches.get_flag("http1.1") {
+        HttpProtocol::Http1Only
+    } else if matches.get_flag("http2-prior-knowledge") {
+        HttpProtocol::Http2PriorKnowledge
+    } else if matches.get_flag("http3") {
+        HttpProtocol::Http3
+    } else {
+        HttpProtocol::Auto
+    }
+}
+
+/// 动态变量的取值来源：指定种子时随机值可复现，`--frozen-time` 固定 `$timestamp`
+pub fn dynamic_values(matches: &ArgMatches, config: &Config) -> DynamicValues {
+    let mut values = DynamicValues::new();
+    if let Some(seed) = matches
+        .get_one::<u64>("seed")
+        .copied()
+        .or(config.report.seed)
+    {
+        values = values.with_seed(seed);
+    }
+    if let Some(&timestamp) = matches.get_one::<u64>("frozen-time") {
+        values = values.with_frozen_time(timestamp);
+    }
+    values
+}
+
+/// 选择用例的方式（`--case` 或 `--index`）
+pub fn case_selectors(matches: &ArgMatches) -> Vec<CaseSelector> {
+    match matches.get_one::<usize>("index") {
+        Some(index) => vec![CaseSelector::Index(*index)],
+        None => matches
+            .get_many::<String>("case")
+            .unwrap_or_default()
+            .map(|case| CaseSelector::parse(case))
+            .collect(),
+    }
+}
+
+/// 根据命令行参数确定用例执行顺序
+pub fn run_order(matches: &ArgMatches, config: &Config) -> RunOrder {
+    let order = match matches.value_source("order") {
+        Some(ValueSource::CommandLine) => matches.get_one::<String>("order"),
+        _ => config.report.order.as_ref(),
+    };
+    match order.map(String::as_str) {
+        Some("name") => RunOrder::Name,
+        Some("random") => {
+            let seed = matches
+                .get_one::<u64>("seed")
+                .copied()
+                .or(config.report.seed)
+                .unwrap_or_else(rand::random);
+            eprintln!(
+                "Running cases in random order with seed {seed} (reproduce with --seed {seed})"
+            );
+            RunOrder::Random { seed }
+        }
+        _ => RunOrder::File,
+    }
+}
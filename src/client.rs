@@ -2,36 +2,306 @@
 //!
 //! 负责执行HTTP请求和格式化响应输出。
 
-use crate::error::Result;
-use crate::models::HttpRequest;
-use crate::script::{ResponseObject, ScriptEngine, TestResult};
-use reqwest::Client;
+use crate::aggregate::AggregateAssertion;
+use crate::assertion::Assertion;
+use crate::binary::{
+    self, BinarySummary, ContentRange, DownloadProgress, ResumeOutcome, format_size,
+};
+use crate::capture::Capture;
+use crate::compress;
+use crate::config::HostProfiles;
+use crate::error::{HttpieError, Result};
+use crate::expect::ResponseExpectation;
+use crate::grpc;
+use crate::hooks::{ScriptHook, ScriptHooks};
+use crate::listen;
+use crate::models::{Environment, Headers, HttpRequest, HttpVersion};
+#[cfg(feature = "scripting")]
+use crate::modules;
+use crate::output::{HeaderFilter, JsonFormat, OutputOptions, PrintParts};
+use crate::pager::Pager;
+use crate::redirect::{self, RedirectHop};
+#[cfg(feature = "scripting")]
+use crate::script::{LogLevel, ScriptEngine, ScriptIsolation, ScriptOrigin};
+use crate::script::{ResponseObject, ScriptControl, ScriptLog, TestOutcome, TestResult};
+use crate::session::CookieJar;
+use crate::tls::TlsDetails;
+use crate::trace;
+use crate::transport::{self, ClientCache, HttpTransport, ReqwestTransport};
+use crate::upload;
+use crate::variable::VariableContext;
+use bytes::{Bytes, BytesMut};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode, Version};
 use serde_json;
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::fmt::{self, Write as _};
+use std::io::{self, IsTerminal, Write as _};
+use std::net::{IpAddr, SocketAddr};
+#[cfg(feature = "scripting")]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_util::io::ReaderStream;
+use tokio_util::sync::CancellationToken;
+#[cfg(feature = "scripting")]
+use tracing::error;
+use tracing::{info, warn};
 
 /// HTTP客户端
 pub struct HttpClient {
     client: Client,
+    /// 带 `@resolve`、`@http3` 指令或主机TLS设置的请求使用的客户端，重建客户端时清空
+    clients: ClientCache,
+    /// 替换默认reqwest传输层的自定义实现
+    custom_transport: Option<Arc<dyn HttpTransport>>,
     formatter: ResponseFormatter,
+    #[cfg(feature = "scripting")]
     script_engine: Option<ScriptEngine>,
+    #[cfg(feature = "scripting")]
+    script_isolation: ScriptIsolation,
     print_response: bool,
+    print_test_results: bool,
+    output: OutputOptions,
+    download_dir: Option<PathBuf>,
+    cancellation: CancellationToken,
+    transport_config: TransportConfig,
+    pager: Option<Pager>,
+    filter: Option<String>,
+    variables: VariableContext,
+}
+
+/// 按请求覆盖域名解析的指令（`# @resolve host:port:address`）
+pub const RESOLVE_DIRECTIVE: &str = "resolve";
+
+/// 优先以HTTP/3（QUIC）发送请求的指令（`# @http3`），效果与 `--http3` 相同
+pub const HTTP3_DIRECTIVE: &str = "http3";
+
+/// HTTP/3的QUIC握手和空闲超时，UDP被阻断时在此之后回退到HTTP/1.1或HTTP/2
+#[cfg(feature = "http3")]
+const HTTP3_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 打印响应时只显示响应体中JSONPath选中部分的指令（`# @filter $.data.items[*].id`）
+pub const FILTER_DIRECTIVE: &str = "filter";
+
+/// 把某个测试标记为预期失败的指令（`# @xfail-test 测试名`），可以重复
+pub const XFAIL_TEST_DIRECTIVE: &str = "xfail-test";
+
+/// 客户端级别的HTTP协议策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HttpProtocol {
+    /// 自动协商（默认）
+    #[default]
+    Auto,
+    /// 仅使用HTTP/1.1
+    Http1Only,
+    /// 不经协商直接使用HTTP/2（支持明文h2c）
+    Http2PriorKnowledge,
+    /// HTTPS请求优先使用HTTP/3（QUIC），失败时回退到HTTP/1.1或HTTP/2；
+    /// 需要 `http3` 特性，未启用时按自动协商发送
+    Http3,
 }
 
 impl Default for HttpClient {
     fn default() -> Self {
-        let client = Client::builder()
-            .no_proxy()
+        let client = TransportConfig::default()
             .build()
             .unwrap_or_else(|_| Client::new());
 
         Self {
             client,
+            clients: ClientCache::default(),
+            custom_transport: None,
             formatter: ResponseFormatter::new(),
+            #[cfg(feature = "scripting")]
             script_engine: None,
+            #[cfg(feature = "scripting")]
+            script_isolation: ScriptIsolation::default(),
             print_response: true,
+            print_test_results: true,
+            output: OutputOptions::default(),
+            download_dir: None,
+            cancellation: CancellationToken::new(),
+            transport_config: TransportConfig::default(),
+            pager: None,
+            filter: None,
+            variables: VariableContext::new(),
+        }
+    }
+}
+
+/// 传输层配置（域名解析覆盖、协议策略、超时、重定向、代理和TLS），用于构建reqwest客户端
+#[derive(Debug, Clone, Default)]
+pub struct TransportConfig {
+    pub dns_overrides: HashMap<String, SocketAddr>,
+    pub protocol: HttpProtocol,
+    /// 响应体在内存中缓冲的最大字节数，超出部分被丢弃
+    pub max_body_bytes: Option<usize>,
+    /// 整个请求的超时时间
+    pub timeout: Option<Duration>,
+    /// 最多跟随的重定向次数，`Some(0)` 表示不跟随，None 使用reqwest默认值（10次）
+    pub max_redirects: Option<usize>,
+    /// 代理地址，未设置时不使用代理（包括系统代理环境变量）
+    pub proxy: Option<String>,
+    /// 跳过TLS证书校验
+    pub insecure: bool,
+    /// 额外信任的CA证书（PEM）
+    pub ca_cert: Option<PathBuf>,
+    /// 会话Cookie存储，未设置时不保存也不发送Cookie
+    pub cookies: Option<Arc<CookieJar>>,
+    /// 不解压响应体，保留线上的原始字节（`--raw`）
+    pub raw: bool,
+    /// 按主机覆盖的TLS设置（项目配置 `[hosts."主机"]`）
+    pub host_profiles: HostProfiles,
+}
+
+impl TransportConfig {
+    /// 构建异步reqwest客户端
+    pub fn build(&self) -> Result<Client> {
+        let mut builder = Client::builder().no_proxy();
+        for (domain, addr) in &self.dns_overrides {
+            builder = builder.resolve(domain, *addr);
+        }
+        builder = match self.protocol {
+            HttpProtocol::Auto => builder,
+            HttpProtocol::Http1Only => builder.http1_only(),
+            HttpProtocol::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+            // reqwest只为rustls后端创建HTTP/3连接器，TCP连接同样使用rustls
+            #[cfg(feature = "http3")]
+            HttpProtocol::Http3 => builder
+                .use_rustls_tls()
+                .http3_max_idle_timeout(HTTP3_IDLE_TIMEOUT),
+            #[cfg(not(feature = "http3"))]
+            HttpProtocol::Http3 => builder,
+        };
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(certificate) = self.ca_certificate()? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some(cookies) = &self.cookies {
+            builder = builder.cookie_provider(cookies.clone());
         }
+        builder = builder
+            .redirect(self.redirect_policy())
+            .danger_accept_invalid_certs(self.insecure)
+            .tls_info(true);
+
+        Ok(builder.build()?)
+    }
+
+    fn redirect_policy(&self) -> reqwest::redirect::Policy {
+        redirect::policy(self.max_redirects)
+    }
+
+    fn proxy(&self) -> Result<Option<reqwest::Proxy>> {
+        self.proxy
+            .as_deref()
+            .map(|proxy| {
+                reqwest::Proxy::all(proxy).map_err(|e| {
+                    HttpieError::InvalidRequest(format!("Invalid proxy '{proxy}': {e}"))
+                })
+            })
+            .transpose()
+    }
+
+    fn ca_certificate(&self) -> Result<Option<reqwest::Certificate>> {
+        self.ca_cert
+            .as_deref()
+            .map(|path| {
+                let pem = std::fs::read(path)
+                    .map_err(|_| HttpieError::FileNotFound(path.display().to_string()))?;
+                reqwest::Certificate::from_pem(&pem).map_err(|e| {
+                    HttpieError::InvalidRequest(format!(
+                        "Invalid CA certificate '{}': {e}",
+                        path.display()
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// 合并请求上的 `@resolve`、`@http3` 指令和请求主机的TLS设置，都没有时返回 None
+    pub fn for_request(&self, request: &HttpRequest) -> Result<Option<Self>> {
+        let mut entries = request.directives_named(RESOLVE_DIRECTIVE).peekable();
+        let profile = self
+            .host_profiles
+            .for_url(&request.url)
+            .filter(|profile| profile.has_tls());
+        // GRPC请求始终使用HTTP/2
+        let grpc = request.grpc.is_some() && self.protocol != HttpProtocol::Http2PriorKnowledge;
+        let http3 = !grpc
+            && request.directive(HTTP3_DIRECTIVE).is_some()
+            && self.protocol != HttpProtocol::Http3;
+        if entries.peek().is_none() && profile.is_none() && !grpc && !http3 {
+            return Ok(None);
+        }
+
+        let mut config = self.clone();
+        if grpc {
+            config.protocol = HttpProtocol::Http2PriorKnowledge;
+        }
+        if http3 {
+            config.protocol = HttpProtocol::Http3;
+        }
+        if let Some(profile) = profile {
+            config.insecure |= profile.insecure.unwrap_or(false);
+            if let Some(ca_cert) = &profile.ca_cert {
+                config.ca_cert = Some(ca_cert.clone());
+            }
+        }
+        for entry in entries {
+            let (host, addr) = parse_resolve(entry)?;
+            config.dns_overrides.insert(host, addr);
+        }
+        Ok(Some(config))
+    }
+}
+
+/// 合并域名解析覆盖，`resolve` 中的条目（如命令行 `--resolve`）优先于环境文件中的 dns 配置
+pub fn dns_overrides<'a>(
+    environment: &Environment,
+    resolve: impl IntoIterator<Item = &'a str>,
+) -> Result<HashMap<String, SocketAddr>> {
+    let mut dns_overrides = environment.dns_overrides().clone();
+    for entry in resolve {
+        let (host, addr) = parse_resolve(entry)?;
+        dns_overrides.insert(host, addr);
+    }
+    Ok(dns_overrides)
+}
+
+/// 解析curl风格的解析覆盖（`host:port:address`，IPv6地址可加方括号）
+///
+/// reqwest按主机名覆盖解析，实际连接端口仍取自URL。
+pub fn parse_resolve(entry: &str) -> Result<(String, SocketAddr)> {
+    let invalid = || {
+        HttpieError::InvalidRequest(format!(
+            "Invalid resolve entry '{entry}', expected host:port:address"
+        ))
+    };
+
+    let mut parts = entry.trim().splitn(3, ':');
+    let (Some(host), Some(port), Some(address)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    if host.is_empty() {
+        return Err(invalid());
     }
+
+    let port: u16 = port.parse().map_err(|_| invalid())?;
+    let address: IpAddr = address
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .map_err(|_| invalid())?;
+
+    Ok((host.to_string(), SocketAddr::new(address, port)))
 }
 
 impl HttpClient {
@@ -41,17 +311,40 @@ impl HttpClient {
     }
 
     /// 启用脚本功能
+    #[cfg(feature = "scripting")]
     pub fn with_script_engine(mut self) -> Result<Self> {
-        self.script_engine = Some(ScriptEngine::new()?);
+        self.script_engine = Some(ScriptEngine::new()?.with_isolation(self.script_isolation));
         Ok(self)
     }
 
+    /// 设置响应处理器之间的隔离方式（默认每个响应处理器使用新的运行时）
+    #[cfg(feature = "scripting")]
+    pub fn with_script_isolation(mut self, isolation: ScriptIsolation) -> Self {
+        self.script_isolation = isolation;
+        self.script_engine = self
+            .script_engine
+            .map(|engine| engine.with_isolation(isolation));
+        self
+    }
+
     /// 控制是否打印响应（默认打印）
     pub fn with_print_response(mut self, enabled: bool) -> Self {
         self.print_response = enabled;
         self
     }
 
+    /// 控制是否打印测试结果（默认打印）；结果总会在 [`RequestOutcome`] 中返回
+    pub fn with_print_test_results(mut self, enabled: bool) -> Self {
+        self.print_test_results = enabled;
+        self
+    }
+
+    /// 设置二进制响应体的保存目录
+    pub fn with_download_dir(mut self, download_dir: Option<PathBuf>) -> Self {
+        self.download_dir = download_dir;
+        self
+    }
+
     pub fn with_dns_overrides(
         mut self,
         dns_overrides: &HashMap<String, SocketAddr>,
@@ -60,69 +353,719 @@ impl HttpClient {
             return Ok(self);
         }
 
-        let mut builder = Client::builder().no_proxy();
-        for (domain, addr) in dns_overrides {
-            builder = builder.resolve(domain, *addr);
+        self.transport_config
+            .dns_overrides
+            .extend(dns_overrides.clone());
+        self.client = self.transport_config.build()?;
+        self.clients = ClientCache::default();
+        Ok(self)
+    }
+
+    /// 替换传输层配置并重建客户端
+    pub fn with_transport_config(mut self, transport_config: TransportConfig) -> Result<Self> {
+        self.client = transport_config.build()?;
+        self.clients = ClientCache::default();
+        self.transport_config = transport_config;
+        Ok(self)
+    }
+
+    /// 设置HTTP协议策略（`--http1.1`、`--http2-prior-knowledge`）
+    pub fn with_http_protocol(mut self, protocol: HttpProtocol) -> Result<Self> {
+        if protocol == self.transport_config.protocol {
+            return Ok(self);
         }
 
-        self.client = builder.build()?;
+        self.transport_config.protocol = protocol;
+        self.client = self.transport_config.build()?;
+        self.clients = ClientCache::default();
         Ok(self)
     }
 
-    /// 执行HTTP请求
+    /// 设置响应体在内存中缓冲的最大字节数（超出部分被截断）
+    pub fn with_max_body_bytes(mut self, max_body_bytes: Option<usize>) -> Self {
+        self.transport_config.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// 打印响应时同时打印连接信息（对端地址、TLS证书有效期）
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.output.verbose = verbose;
+        self
+    }
+
+    /// 设置打印响应体的最大字节数
+    pub fn with_max_body_print(mut self, max_body_print: Option<usize>) -> Self {
+        self.output.max_body_print = max_body_print;
+        self
+    }
+
+    /// 选择打印请求和响应的哪些部分（`--print`）
+    pub fn with_print_parts(mut self, parts: PrintParts) -> Self {
+        self.output.parts = parts;
+        self
+    }
+
+    /// 设置JSON响应体的排版方式（`--sort-keys`、`--indent`、`--compact`）
+    pub fn with_json_format(mut self, json: JsonFormat) -> Self {
+        self.output.json = json;
+        self
+    }
+
+    /// 按名称过滤打印的头（`--print-headers`、`--hide-headers`）
+    pub fn with_header_filter(mut self, filter: HeaderFilter) -> Self {
+        self.output.headers = filter;
+        self
+    }
+
+    /// 输出超过阈值的响应通过分页器显示（默认不分页）
+    pub fn with_pager(mut self, pager: Option<Pager>) -> Self {
+        self.pager = pager;
+        self
+    }
+
+    /// 打印响应时只显示响应体中JSONPath选中的部分（请求的 `@filter` 指令优先）
+    pub fn with_filter(mut self, filter: Option<String>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// 获取传输层配置
+    pub fn transport_config(&self) -> &TransportConfig {
+        &self.transport_config
+    }
+
+    /// 设置取消令牌，取消后进行中的请求立即中止
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// 获取取消令牌
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// 设置运行期变量上下文（捕获值和脚本全局变量写入其中）
+    pub fn with_variables(mut self, variables: VariableContext) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// 获取运行期变量上下文
+    pub fn variables(&self) -> &VariableContext {
+        &self.variables
+    }
+
+    /// 使用自定义传输层发送请求（如其他HTTP库或测试替身），替代默认的reqwest实现
+    ///
+    /// 之后修改的传输层配置（超时、代理等）只对默认实现生效。
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.custom_transport = Some(transport);
+        self
+    }
+
+    /// 发送请求使用的传输层（可廉价克隆，用于并发发送）
+    pub fn transport(&self) -> Arc<dyn HttpTransport> {
+        match &self.custom_transport {
+            Some(transport) => transport.clone(),
+            None => Arc::new(
+                ReqwestTransport::from_parts(self.client.clone(), self.transport_config.clone())
+                    .with_client_cache(self.clients.clone()),
+            ),
+        }
+    }
+
+    /// 执行HTTP请求，丢弃执行结果（需要测试结果时使用 [`execute_with_outcome`](Self::execute_with_outcome)）
     pub async fn execute(&mut self, request: &HttpRequest) -> Result<()> {
-        let mut req_builder = self.client.request(request.method.clone(), &request.url);
+        self.execute_with_outcome(request).await.map(|_| ())
+    }
+
+    /// 执行HTTP请求并返回执行结果
+    pub async fn execute_with_outcome(&mut self, request: &HttpRequest) -> Result<RequestOutcome> {
+        let request = &self.variables.resolve(request);
+        let raw = match &request.listen {
+            Some(listen) => {
+                listen::receive_with_cancellation(None, request, listen, &self.cancellation).await?
+            }
+            None => {
+                let transport = self.transport();
+                transport::send_with_cancellation(transport.as_ref(), request, &self.cancellation)
+                    .await?
+            }
+        };
+        self.process(request, raw).await
+    }
+
+    /// 处理已接收的响应：执行脚本、求值内联断言、捕获变量、比对期望、保存并打印响应
+    pub async fn process(
+        &mut self,
+        request: &HttpRequest,
+        raw: RawResponse,
+    ) -> Result<RequestOutcome> {
+        // 按Content-Encoding解压（`--raw` 时保留原始字节），保存和脚本都使用解压后的内容
+        let mut raw = raw;
+        let (bytes, encoded) =
+            compress::decode_response(&raw.headers, raw.bytes, !self.transport_config.raw);
+        raw.bytes = bytes;
+
+        // 创建响应对象
+        let mut response_obj =
+            ResponseObject::from_bytes(raw.status, &raw.headers, raw.bytes.clone());
+        // 原始TCP请求没有HTTP版本
+        response_obj.http_version = request.raw.is_none().then(|| format!("{:?}", raw.version));
+        response_obj.truncated = raw.truncated;
+        response_obj.remote_addr = raw.remote_addr;
+        response_obj.tls = raw.tls.clone();
+        response_obj.redirects = raw.redirects.clone();
+        // 未解压的响应体按二进制展示
+        let still_encoded = encoded
+            .as_ref()
+            .is_some_and(|encoded| self.transport_config.raw || encoded.decoded_size.is_none());
+        if still_encoded && response_obj.binary.is_none() {
+            response_obj.body = serde_json::Value::Null;
+            response_obj.binary = Some(BinarySummary::new(&raw.bytes));
+        }
+        response_obj.encoded = encoded;
+        // 如果有响应处理器脚本，执行脚本
+        let ScriptOutput {
+            mut test_results,
+            control,
+            logs,
+        } = match &request.response_handler {
+            Some(script) => self.run_script(request, script, &response_obj).await?,
+            None => ScriptOutput::default(),
+        };
 
-        // 添加请求头
-        for (key, value) in &request.headers {
-            req_builder = req_builder.header(key, value);
+        // 内联断言直接在Rust中求值，聚合断言（`p95`、`error_rate`）由运行器在全部迭代完成后求值
+        for assertion in request
+            .assertions
+            .iter()
+            .filter(|a| !AggregateAssertion::is_aggregate(a))
+        {
+            test_results.push(Assertion::parse(assertion)?.check(&response_obj, raw.duration));
         }
 
-        // 添加请求体
-        if let Some(body) = &request.body {
-            req_builder = req_builder.body(body.clone());
+        // 按 `@capture` 指令把响应中的值写入变量上下文
+        for capture in Capture::from_request(request)? {
+            match capture.extract(&response_obj) {
+                Some(value) => self.variables.set(capture.name, value),
+                None => warn!(
+                    capture = %capture.name,
+                    "capture of '{}' matched nothing",
+                    request.name
+                ),
+            }
         }
 
-        // 发送请求
-        let response = req_builder.send().await?;
+        // 与期望的夹具文件比对
+        if let Some(expectation) = ResponseExpectation::from_request(request)? {
+            test_results.push(expectation.check(&response_obj)?);
+        }
 
-        // 如果有响应处理器脚本，执行脚本
-        if let Some(script) = &request.response_handler {
-            if let Some(ref mut engine) = self.script_engine {
-                // 创建响应对象
-                let response_obj = ResponseObject::from_response(response).await?;
-
-                // 执行脚本
-                let test_results = engine
-                    .execute_response_script(script.clone(), response_obj.clone())
-                    .await?;
-
-                // 打印测试结果
-                self.formatter
-                    .format_test_results(&request.name, &test_results);
-
-                // 格式化并打印响应（使用克隆的响应对象），受开关控制
-                if self.print_response {
-                    self.formatter
-                        .format_response_from_object(&request.name, &response_obj)
-                        .await?;
+        // `@xfail-test` 标记的测试预期失败，意外通过时计为失败
+        for name in request
+            .directives_named(XFAIL_TEST_DIRECTIVE)
+            .map(str::trim)
+        {
+            let mut matched = false;
+            for result in test_results.iter_mut().filter(|r| r.name == name) {
+                matched = true;
+                result.xfail = true;
+                if result.passed {
+                    result.message = Some("expected to fail (@xfail-test) but passed".to_string());
                 }
-            } else {
-                return Err(crate::error::HttpieError::ScriptError(
-                    "Script engine not initialized. Call with_script_engine() first.".to_string(),
-                ));
             }
+            if !matched {
+                warn!("@xfail-test '{name}' matched no test of '{}'", request.name);
+            }
+        }
+
+        for result in &mut test_results {
+            result.request_name = Some(request.name.clone());
+        }
+
+        // 测试结果和响应渲染为一整块后一次性输出，避免与其他请求或日志交错
+        let mut block = if self.print_test_results {
+            self.formatter
+                .render_test_results_with_logs(&request.name, &test_results, &logs)
         } else {
-            // 没有脚本，直接格式化并打印响应（受开关控制）
-            if self.print_response {
-                self.formatter
-                    .format_response(&request.name, response)
-                    .await?;
+            String::new()
+        };
+
+        // 保存响应体：显式重定向优先，其次是二进制响应的下载目录
+        if raw.truncated && (request.response_redirect.is_some() || self.download_dir.is_some()) {
+            warn!(
+                bytes = raw.bytes.len(),
+                "response body of '{}' was truncated", request.name
+            );
+        }
+        if let Some(redirect) = &request.response_redirect
+            && redirect.resume
+        {
+            let target = request.resolve_path(&redirect.path);
+            let content_range = raw
+                .headers
+                .get("content-range")
+                .and_then(|value| value.to_str().ok());
+            match binary::resume_body(&target, raw.status, content_range, &raw.bytes)? {
+                ResumeOutcome::Downloaded => {
+                    info!("Saved response body to {}", target.display());
+                }
+                ResumeOutcome::Resumed { offset } => info!(
+                    "Resumed {} at byte {offset} (+{} bytes)",
+                    target.display(),
+                    raw.bytes.len()
+                ),
+                ResumeOutcome::Complete => {
+                    info!("{} is already complete", target.display());
+                }
             }
+        } else if let Some(redirect) = &request.response_redirect {
+            let target = request.resolve_path(&redirect.path);
+            let saved = binary::save_body(&target, &raw.bytes, redirect.overwrite)?;
+            info!("Saved response body to {}", saved.display());
+        } else if let Some(dir) = &self.download_dir
+            && response_obj.binary.is_some()
+        {
+            let file_name = format!(
+                "{}.{}",
+                binary::sanitize_file_name(&request.name),
+                binary::extension_for(&response_obj.content_type)
+            );
+            let saved = binary::save_body(&dir.join(file_name), &raw.bytes, false)?;
+            info!("Saved response body to {}", saved.display());
         }
 
+        // 格式化并打印响应（受开关控制）
+        if self.print_response {
+            let filter = request
+                .directive(FILTER_DIRECTIVE)
+                .or(self.filter.as_deref());
+            let filtered = match filter {
+                Some(path) => {
+                    let filtered = response_obj.filtered(path)?;
+                    if filtered.is_none() {
+                        warn!(
+                            "Response of '{}' is not JSON, ignoring filter '{path}'",
+                            request.name
+                        );
+                    }
+                    filtered
+                }
+                None => None,
+            };
+            let output = self.formatter.render_request_response(
+                request,
+                filtered.as_ref().unwrap_or(&response_obj),
+                &self.output,
+            );
+            match &self.pager {
+                Some(pager) if pager.should_page(&output) => {
+                    self.formatter.flush(&std::mem::take(&mut block));
+                    pager.page(&output)?;
+                }
+                _ => block.push_str(&output),
+            }
+        }
+        self.formatter.flush(&block);
+
+        Ok(RequestOutcome {
+            response: response_obj,
+            duration: raw.duration,
+            test_results,
+            control,
+            logs,
+        })
+    }
+
+    /// 按与请求测试结果相同的格式输出追加的测试结果（如最后一次迭代的聚合断言）
+    pub fn print_test_results(&self, request_name: &str, test_results: &[TestResult]) {
+        if self.print_test_results {
+            self.formatter.flush(
+                &self
+                    .formatter
+                    .render_test_results(request_name, test_results),
+            );
+        }
+    }
+
+    /// 执行响应处理器脚本
+    #[cfg(feature = "scripting")]
+    async fn run_script(
+        &mut self,
+        request: &HttpRequest,
+        script: &str,
+        response_obj: &ResponseObject,
+    ) -> Result<ScriptOutput> {
+        // 导入的路径相对于请求所在的.http文件
+        let dir = request
+            .source_file
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new("."));
+        let linked = modules::link(script, dir)?;
+        // 脚本出错时的行号映射回响应处理器在.http文件中的位置
+        let origin = ScriptOrigin {
+            file: request
+                .source_file
+                .as_ref()
+                .map(|file| file.display().to_string()),
+            line: request.handler_line.unwrap_or(1),
+            prelude_lines: linked
+                .lines()
+                .count()
+                .saturating_sub(script.lines().count()),
+        };
+        let engine = self.script_engine_mut()?;
+        let test_results = engine
+            .execute_response_script_at(linked, response_obj.clone(), &origin)
+            .await?;
+        let output = ScriptOutput {
+            test_results,
+            control: engine.take_control(),
+            logs: engine.take_logs(),
+        };
+        self.import_global_variables();
+        Ok(output)
+    }
+
+    /// 注册全局钩子脚本
+    #[cfg(feature = "scripting")]
+    pub fn load_hooks(&mut self, hooks: &ScriptHooks) -> Result<()> {
+        self.script_engine_mut()?.load_hooks(hooks)
+    }
+
+    /// 调用全局钩子，`context.variables` 设为当前的运行期变量
+    #[cfg(feature = "scripting")]
+    pub fn call_hook(&mut self, hook: ScriptHook, mut context: serde_json::Value) -> Result<()> {
+        context["variables"] = serde_json::json!(self.variables.snapshot());
+        let engine = self.script_engine_mut()?;
+        engine.call_hook(hook, &context)?;
+        // 钩子没有对应的测试结果，日志直接输出
+        for log in engine.take_logs() {
+            match log.level {
+                LogLevel::Log => info!(%hook, "{}", log.message),
+                LogLevel::Warn => warn!(%hook, "{}", log.message),
+                LogLevel::Error => error!(%hook, "{}", log.message),
+            }
+        }
+        self.import_global_variables();
         Ok(())
     }
+
+    #[cfg(feature = "scripting")]
+    fn script_engine_mut(&mut self) -> Result<&mut ScriptEngine> {
+        self.script_engine.as_mut().ok_or_else(|| {
+            HttpieError::ScriptError(
+                "Script engine not initialized. Call with_script_engine() first.".to_string(),
+            )
+        })
+    }
+
+    /// 脚本设置的全局变量同样可供后续请求引用
+    #[cfg(feature = "scripting")]
+    fn import_global_variables(&self) {
+        let Some(engine) = &self.script_engine else {
+            return;
+        };
+        for (name, value) in engine.get_all_global_variables() {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            self.variables.set(name.clone(), value);
+        }
+    }
+
+    /// 未启用脚本特性时，包含响应处理器的请求直接报错
+    #[cfg(not(feature = "scripting"))]
+    async fn run_script(
+        &mut self,
+        request: &HttpRequest,
+        _script: &str,
+        _response_obj: &ResponseObject,
+    ) -> Result<ScriptOutput> {
+        Err(scripting_disabled(request))
+    }
+
+    /// 未启用脚本特性时不能使用全局钩子
+    #[cfg(not(feature = "scripting"))]
+    pub fn load_hooks(&mut self, hooks: &ScriptHooks) -> Result<()> {
+        Err(HttpieError::ScriptError(format!(
+            "Hooks {} require the 'scripting' feature, but httpie was built without it",
+            hooks.path().display()
+        )))
+    }
+
+    /// 未启用脚本特性时不能使用全局钩子
+    #[cfg(not(feature = "scripting"))]
+    pub fn call_hook(&mut self, hook: ScriptHook, _context: serde_json::Value) -> Result<()> {
+        Err(HttpieError::ScriptError(format!(
+            "Hook {hook} requires the 'scripting' feature, but httpie was built without it"
+        )))
+    }
+}
+
+/// 未启用 `scripting` 特性时处理响应处理器的错误
+#[cfg(not(feature = "scripting"))]
+pub(crate) fn scripting_disabled(request: &HttpRequest) -> HttpieError {
+    HttpieError::ScriptError(format!(
+        "Request '{}' has a response handler, but httpie was built without the 'scripting' feature",
+        request.name
+    ))
+}
+
+/// 已接收但尚未处理的原始响应
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    pub status: u16,
+    pub version: Version,
+    pub headers: HeaderMap,
+    pub bytes: Bytes,
+    pub duration: Duration,
+    /// 响应体是否因超过缓冲上限而被截断
+    pub truncated: bool,
+    /// 对端的IP地址和端口
+    pub remote_addr: Option<SocketAddr>,
+    /// HTTPS连接的TLS信息
+    pub tls: Option<TlsDetails>,
+    /// 跟随的重定向，按发生顺序排列
+    pub redirects: Vec<RedirectHop>,
+}
+
+/// 单个请求的执行结果
+#[derive(Debug, Clone)]
+pub struct RequestOutcome {
+    pub response: ResponseObject,
+    pub duration: Duration,
+    pub test_results: Vec<TestResult>,
+    /// 响应处理器通过 `client.skip()` 或 `client.exit()` 请求的控制流
+    pub control: Option<ScriptControl>,
+    /// 响应处理器通过 `client.log()`、`client.warn()`、`client.error()` 输出的日志
+    pub logs: Vec<ScriptLog>,
+}
+
+/// 响应处理器的执行结果
+#[derive(Default)]
+struct ScriptOutput {
+    test_results: Vec<TestResult>,
+    control: Option<ScriptControl>,
+    logs: Vec<ScriptLog>,
+}
+
+impl RequestOutcome {
+    /// 所有测试（脚本测试、内联断言和期望比对）是否通过（预期失败的测试失败也算通过）
+    pub fn passed(&self) -> bool {
+        !self.test_results.iter().any(TestResult::is_failure)
+    }
+
+    /// 失败的测试（包括意外通过的预期失败测试）
+    pub fn failed_tests(&self) -> impl Iterator<Item = &TestResult> {
+        self.test_results
+            .iter()
+            .filter(|result| result.is_failure())
+    }
+}
+
+/// 发送请求并读取响应体（最多 `config.max_body_bytes` 字节）
+///
+/// 协议策略为 [`HttpProtocol::Http3`] 且请求行未指定版本时，HTTPS请求先以HTTP/3发送，
+/// 失败（如UDP被阻断或服务端不支持）时幂等请求以HTTP/1.1或HTTP/2重新发送；
+/// POST、PATCH等请求可能已经送达服务端，直接返回错误，避免重复执行。
+pub async fn fetch(
+    client: &Client,
+    request: &HttpRequest,
+    config: &TransportConfig,
+) -> Result<RawResponse> {
+    #[cfg(feature = "grpc")]
+    if let Some(call) = &request.grpc {
+        return grpc::fetch(client, request, call).await;
+    }
+    #[cfg(not(feature = "grpc"))]
+    if request.grpc.is_some() {
+        return Err(grpc::grpc_disabled(request));
+    }
+
+    // 请求行上指定的HTTP版本
+    let version = request.http_version.map(HttpVersion::to_reqwest);
+    if config.protocol == HttpProtocol::Http3
+        && version.is_none()
+        && request.target_url()?.scheme() == "https"
+    {
+        #[cfg(feature = "http3")]
+        match send(
+            client,
+            request,
+            Some(Version::HTTP_3),
+            config.max_body_bytes,
+        )
+        .await
+        {
+            Ok(raw) => return Ok(raw),
+            Err(e) if !is_idempotent(&request.method) => return Err(e),
+            Err(e) => warn!(
+                "HTTP/3 request '{}' failed, retrying over HTTP/1.1 or HTTP/2: {e}",
+                request.name
+            ),
+        }
+        #[cfg(not(feature = "http3"))]
+        warn!(
+            "HTTP/3 was requested for '{}', but httpie was built without the 'http3' feature; sending over HTTP/1.1 or HTTP/2",
+            request.name
+        );
+    }
+    send(client, request, version, config.max_body_bytes).await
+}
+
+/// 重复发送与发送一次效果相同的请求方法（RFC 9110）
+#[cfg(feature = "http3")]
+fn is_idempotent(method: &reqwest::Method) -> bool {
+    use reqwest::Method;
+
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE | Method::PUT | Method::DELETE
+    )
+}
+
+/// 以指定的HTTP版本（None 时自动协商）发送一次请求
+async fn send(
+    client: &Client,
+    request: &HttpRequest,
+    version: Option<Version>,
+    max_body_bytes: Option<usize>,
+) -> Result<RawResponse> {
+    let mut req_builder = client.request(request.method.clone(), request.target_url()?);
+    if let Some(version) = version {
+        req_builder = req_builder.version(version);
+    }
+
+    // 添加请求头
+    for (key, value) in &request.headers {
+        req_builder = req_builder.header(key, value);
+    }
+    if !request
+        .headers
+        .keys()
+        .any(|key| key.eq_ignore_ascii_case("Accept-Encoding"))
+    {
+        req_builder = req_builder.header("Accept-Encoding", compress::ACCEPT_ENCODING);
+    }
+    if let Some(range) = binary::range_header(request) {
+        req_builder = req_builder.header("Range", range);
+    }
+
+    // 添加请求体：大文件以已知长度流式上传，其余按 `@compress` 指令压缩后整体发送
+    if let Some((path, len)) = upload::streamed_file(request)? {
+        let file = tokio::fs::File::open(&path).await?;
+        req_builder = req_builder
+            .header("Content-Length", len)
+            .body(reqwest::Body::wrap_stream(ReaderStream::new(file)));
+    } else if let Some((body, encoding)) = compress::encode_body(request)? {
+        if let Some(encoding) = encoding {
+            req_builder = req_builder.header("Content-Encoding", encoding);
+        }
+        req_builder = req_builder.body(body);
+    }
+
+    // 发送请求
+    let started = Instant::now();
+    let (response, redirects) = redirect::capture(req_builder.send()).await;
+    let response = response?;
+    let status = response.status().as_u16();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let remote_addr = response.remote_addr();
+    let tls = TlsDetails::from_tls_info(response.extensions().get());
+    // 保存到文件的响应在终端中显示下载进度
+    let progress = (request.response_redirect.is_some() && io::stderr().is_terminal())
+        .then(|| download_progress(&headers, response.content_length()));
+    let (bytes, truncated) = read_body(response, max_body_bytes, progress).await?;
+
+    Ok(RawResponse {
+        status,
+        version,
+        headers,
+        bytes,
+        duration: started.elapsed(),
+        truncated,
+        remote_addr,
+        tls,
+        redirects,
+    })
+}
+
+/// 根据 `Content-Range`（续传时）或响应体长度创建下载进度
+fn download_progress(headers: &HeaderMap, content_length: Option<u64>) -> DownloadProgress {
+    let content_range = headers
+        .get("content-range")
+        .and_then(|value| value.to_str().ok())
+        .and_then(ContentRange::parse);
+    match content_range {
+        Some(range) => DownloadProgress::new(
+            range.range.map_or(0, |(start, _)| start),
+            range.total.or(content_length),
+        ),
+        None => DownloadProgress::new(0, content_length),
+    }
+}
+
+/// 分块读取响应体，超过上限时停止读取并标记截断，同时更新下载进度
+async fn read_body(
+    mut response: reqwest::Response,
+    max_body_bytes: Option<usize>,
+    mut progress: Option<DownloadProgress>,
+) -> Result<(Bytes, bool)> {
+    if max_body_bytes.is_none() && progress.is_none() {
+        return Ok((response.bytes().await?, false));
+    }
+    let limit = max_body_bytes.unwrap_or(usize::MAX);
+
+    let mut buffer = BytesMut::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await? {
+        if let Some(progress) = &mut progress {
+            progress.advance(chunk.len());
+        }
+        let remaining = limit - buffer.len();
+        if chunk.len() > remaining {
+            buffer.extend_from_slice(&chunk[..remaining]);
+            truncated = true;
+            break;
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+
+    if let Some(progress) = &progress {
+        progress.finish();
+    }
+    Ok((buffer.freeze(), truncated))
+}
+
+/// 获取发送该请求使用的客户端：带 `@resolve` 指令的请求使用按合并配置新建的客户端
+pub fn transport_for(
+    client: &Client,
+    config: &TransportConfig,
+    request: &HttpRequest,
+) -> Result<Client> {
+    match config.for_request(request)? {
+        Some(config) => config.build(),
+        None => Ok(client.clone()),
+    }
+}
+
+/// 发送请求，取消令牌触发时中止并返回 [`HttpieError::Cancelled`]
+pub async fn fetch_with_cancellation(
+    client: &Client,
+    request: &HttpRequest,
+    config: &TransportConfig,
+    token: &CancellationToken,
+) -> Result<RawResponse> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(HttpieError::Cancelled),
+        result = fetch(client, request, config) => result,
+    }
 }
 
 /// 响应格式化器
@@ -141,70 +1084,110 @@ impl ResponseFormatter {
         request_name: &str,
         response: reqwest::Response,
     ) -> Result<()> {
-        // 打印测试用例名称
-        println!("=== {request_name} ===");
+        let response_obj = ResponseObject::from_response(response).await?;
+        self.format_response_from_object(request_name, &response_obj)
+            .await
+    }
 
-        // 打印状态行
-        println!(
-            "Status: {} {}",
-            response.status().as_u16(),
-            response.status().canonical_reason().unwrap_or("Unknown")
-        );
+    /// 格式化响应体：任何Content-Type的JSON文本都按JSON排版，其他文本原样输出
+    fn format_body(&self, body: &str, json: &JsonFormat) -> String {
+        json.render_text(body).unwrap_or_else(|| body.to_string())
+    }
 
-        // 打印响应头
-        if !response.headers().is_empty() {
-            println!("Headers:");
-            for (name, value) in response.headers() {
-                println!("  {}: \"{}\"", name, value.to_str().unwrap_or("<invalid>"));
-            }
+    /// 渲染响应体，超过 `max_body_print` 字节时只保留开头部分
+    fn write_body(
+        &self,
+        out: &mut String,
+        body: &str,
+        max_body_print: Option<usize>,
+    ) -> fmt::Result {
+        if body.trim().is_empty() {
+            return Ok(());
         }
 
-        // 获取响应体
-        let body = response.text().await?;
+        match max_body_print {
+            Some(limit) if body.len() > limit => {
+                let mut end = limit;
+                while !body.is_char_boundary(end) {
+                    end -= 1;
+                }
+                writeln!(out, "{}", &body[..end])?;
+                writeln!(
+                    out,
+                    "... ({} more bytes not shown, adjust with --max-body-print)",
+                    body.len() - end
+                )
+            }
+            _ => writeln!(out, "{body}"),
+        }
+    }
 
-        // 打印Body标题和内容
-        println!("Body:");
-        self.format_body(&body);
-        println!(); // 结尾空行
+    /// 格式化测试结果
+    pub fn format_test_results(&self, request_name: &str, test_results: &[TestResult]) {
+        self.flush(&self.render_test_results(request_name, test_results));
+    }
 
-        Ok(())
+    /// 渲染测试结果，没有测试结果时为空
+    pub fn render_test_results(&self, request_name: &str, test_results: &[TestResult]) -> String {
+        self.render_test_results_with_logs(request_name, test_results, &[])
     }
 
-    /// 格式化响应体
-    fn format_body(&self, body: &str) {
-        if body.trim().is_empty() {
-            return;
+    /// 渲染测试结果和脚本日志：测试外的日志在前，测试内的日志跟在所属测试之后
+    pub fn render_test_results_with_logs(
+        &self,
+        request_name: &str,
+        test_results: &[TestResult],
+        logs: &[ScriptLog],
+    ) -> String {
+        let mut out = String::new();
+        if !test_results.is_empty() || !logs.is_empty() {
+            // 写入String不会失败
+            let _ = self.write_test_results(&mut out, request_name, test_results, logs);
         }
+        out
+    }
 
-        // 尝试格式化JSON
-        if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(body)
-            && let Ok(pretty_json) = serde_json::to_string_pretty(&json_value)
-        {
-            println!("{pretty_json}");
-            return;
+    fn write_test_results(
+        &self,
+        out: &mut String,
+        request_name: &str,
+        test_results: &[TestResult],
+        logs: &[ScriptLog],
+    ) -> fmt::Result {
+        writeln!(out, "\n=== Test Results for {} ===", request_name)?;
+        for log in logs.iter().filter(|log| log.test.is_none()) {
+            writeln!(out, "{log}")?;
         }
-
-        // 如果不是JSON，直接打印
-        println!("{body}");
+        for result in test_results {
+            let status = match result.outcome() {
+                TestOutcome::Passed => "✓ PASS",
+                TestOutcome::Xfailed => "✓ XFAIL",
+                TestOutcome::Xpassed => "✗ XPASS",
+                TestOutcome::Failed => "✗ FAIL",
+            };
+            writeln!(out, "{} {} ({}ms)", status, result.name, result.duration_ms)?;
+            if let Some(message) = &result.message {
+                writeln!(out, "  Message: {}", message)?;
+            }
+            for log in logs
+                .iter()
+                .filter(|log| log.test.as_deref() == Some(result.name.as_str()))
+            {
+                writeln!(out, "  {log}")?;
+            }
+        }
+        writeln!(out)
     }
 
-    /// 格式化测试结果
-    pub fn format_test_results(&self, request_name: &str, test_results: &[TestResult]) {
-        if !test_results.is_empty() {
-            println!("\n=== Test Results for {} ===", request_name);
-            for result in test_results {
-                let status = if result.passed {
-                    "✓ PASS"
-                } else {
-                    "✗ FAIL"
-                };
-                println!("{} {}", status, result.name);
-                if let Some(message) = &result.message {
-                    println!("  Message: {}", message);
-                }
-            }
-            println!();
+    /// 一次性写出整块输出，并发执行或日志输出时同一请求的内容保持连续
+    pub fn flush(&self, block: &str) {
+        if block.is_empty() {
+            return;
         }
+        let mut stdout = io::stdout().lock();
+        // 与print!不同，标准输出已关闭（如管道被下游关闭）时不panic
+        let _ = stdout.write_all(block.as_bytes());
+        let _ = stdout.flush();
     }
 
     /// 从ResponseObject格式化响应
@@ -213,34 +1196,205 @@ impl ResponseFormatter {
         request_name: &str,
         response_obj: &ResponseObject,
     ) -> Result<()> {
+        self.flush(&self.render_response_limited(request_name, response_obj, None));
+        Ok(())
+    }
+
+    /// 从ResponseObject渲染响应，响应体最多渲染 `max_body_print` 字节
+    pub fn render_response_limited(
+        &self,
+        request_name: &str,
+        response_obj: &ResponseObject,
+        max_body_print: Option<usize>,
+    ) -> String {
+        let options = OutputOptions {
+            max_body_print,
+            ..OutputOptions::default()
+        };
+        self.render_response(request_name, None, None, response_obj, &options)
+    }
+
+    /// 按输出选项格式化请求和响应，用于打印或分页显示
+    pub fn render_request_response(
+        &self,
+        request: &HttpRequest,
+        response_obj: &ResponseObject,
+        options: &OutputOptions,
+    ) -> String {
+        let trace_id = trace::trace_id(&request.headers);
+        self.render_response(
+            &request.name,
+            trace_id,
+            Some(request),
+            response_obj,
+            options,
+        )
+    }
+
+    fn render_response(
+        &self,
+        request_name: &str,
+        trace_id: Option<&str>,
+        request: Option<&HttpRequest>,
+        response_obj: &ResponseObject,
+        options: &OutputOptions,
+    ) -> String {
+        let mut out = String::new();
+        // 写入String不会失败
+        let _ = self.write_response(
+            &mut out,
+            request_name,
+            trace_id,
+            request,
+            response_obj,
+            options,
+        );
+        out
+    }
+
+    fn write_response(
+        &self,
+        out: &mut String,
+        request_name: &str,
+        trace_id: Option<&str>,
+        request: Option<&HttpRequest>,
+        response_obj: &ResponseObject,
+        options: &OutputOptions,
+    ) -> fmt::Result {
         // 打印测试用例名称
-        println!("=== {request_name} ===");
+        writeln!(out, "=== {request_name} ===")?;
+        if let Some(request) = request {
+            self.write_request(out, request, options)?;
+        }
 
         // 打印状态行
-        println!("Status: {}", response_obj.status);
+        writeln!(
+            out,
+            "Status: {} {}",
+            response_obj.status,
+            StatusCode::from_u16(response_obj.status)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or("Unknown")
+        )?;
 
-        // 打印响应头
-        if !response_obj.headers.is_empty() {
-            println!("Headers:");
-            for (name, value) in &response_obj.headers {
-                println!("  {}: \"{}\"", name, value);
+        // 打印实际协商的HTTP版本
+        if let Some(version) = &response_obj.http_version {
+            writeln!(out, "Version: {version}")?;
+        }
+        for hop in &response_obj.redirects {
+            writeln!(
+                out,
+                "Redirect: {} {} -> {}",
+                hop.status, hop.url, hop.location
+            )?;
+        }
+        if options.verbose {
+            if let Some(addr) = response_obj.remote_addr {
+                writeln!(out, "Remote: {addr}")?;
+            }
+            if let Some(tls) = &response_obj.tls {
+                match &tls.cert {
+                    Some(cert) => writeln!(
+                        out,
+                        "TLS certificate: valid {} to {}",
+                        cert.not_before, cert.not_after
+                    ),
+                    None => writeln!(out, "TLS certificate: unavailable"),
+                }?;
             }
         }
+        // 压缩的响应同时显示线上和解压后的大小
+        if let Some(encoded) = &response_obj.encoded {
+            let decoded = encoded
+                .decoded_size
+                .map_or_else(|| "unknown".to_string(), |size| format_size(size as u64));
+            writeln!(
+                out,
+                "Size: {} {} on the wire, {decoded} decoded",
+                format_size(encoded.encoded_size as u64),
+                encoded.encoding
+            )?;
+        }
+        if let Some(trace_id) = trace_id {
+            writeln!(out, "Trace: {trace_id}")?;
+        }
+
+        // 打印响应头
+        if options.parts.response_headers {
+            self.write_headers(out, "Headers:", &response_obj.headers, &options.headers)?;
+        }
+        if !options.parts.response_body {
+            return writeln!(out); // 结尾空行
+        }
 
         // 打印Body标题和内容
-        println!("Body:");
-        match &response_obj.body {
-            serde_json::Value::String(s) => self.format_body(s),
-            other => {
-                if let Ok(pretty_json) = serde_json::to_string_pretty(other) {
-                    println!("{}", pretty_json);
-                } else {
-                    println!("{}", other);
-                }
+        writeln!(out, "Body:")?;
+        if let Some(summary) = &response_obj.binary {
+            writeln!(out, "{summary}")?;
+            writeln!(out)?; // 结尾空行
+            return Ok(());
+        }
+        let rendered = match &response_obj.body {
+            serde_json::Value::String(s) => self.format_body(s, &options.json),
+            other => options.json.render(other),
+        };
+        self.write_body(out, &rendered, options.max_body_print)?;
+        if response_obj.truncated {
+            writeln!(out, "[body truncated: exceeded max-body-bytes limit]")?;
+        }
+        writeln!(out) // 结尾空行
+    }
+
+    /// 打印请求行以及 `--print` 选择的请求头和请求体
+    fn write_request(
+        &self,
+        out: &mut String,
+        request: &HttpRequest,
+        options: &OutputOptions,
+    ) -> fmt::Result {
+        let parts = options.parts;
+        if !parts.request_headers && !parts.request_body {
+            return Ok(());
+        }
+        let url = request
+            .target_url()
+            .map_or_else(|_| request.url.clone(), String::from);
+        writeln!(out, "Request: {} {url}", request.method)?;
+        if parts.request_headers {
+            self.write_headers(out, "Request Headers:", &request.headers, &options.headers)?;
+        }
+        if parts.request_body {
+            if let Some(body) = &request.body {
+                writeln!(out, "Request Body:")?;
+                self.write_body(out, body, options.max_body_print)?;
+            } else if let Some(path) = &request.body_file {
+                writeln!(out, "Request Body:")?;
+                writeln!(out, "< {path}")?;
             }
         }
-        println!(); // 结尾空行
+        writeln!(out)
+    }
 
+    /// 打印过滤后的头，没有要显示的头时不打印标题
+    fn write_headers(
+        &self,
+        out: &mut String,
+        title: &str,
+        headers: &Headers,
+        filter: &HeaderFilter,
+    ) -> fmt::Result {
+        let mut shown = headers
+            .iter()
+            .filter(|(name, _)| filter.allows(name))
+            .peekable();
+        if shown.peek().is_none() {
+            return Ok(());
+        }
+        writeln!(out, "{title}")?;
+        for (name, value) in shown {
+            writeln!(out, "  {}: \"{}\"", name, value)?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,170 @@
+//! 压缩模块
+//!
+//! `# @compress gzip` 在发送前压缩请求体并设置 `Content-Encoding`，
+//! 用于测试接受压缩负载的接口，或减少大请求体的传输量。
+//! 响应体按 `Content-Encoding`（gzip、deflate、br）自动解压，同时记录压缩前后的大小；
+//! `--raw` 模式保留线上的原始字节。
+
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use crate::upload;
+use bytes::Bytes;
+use flate2::Compression as Level;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use reqwest::header::HeaderMap;
+use std::io::{Read, Write};
+use std::str::FromStr;
+use tracing::warn;
+
+/// 压缩请求体的指令（`# @compress gzip`）
+pub const COMPRESS_DIRECTIVE: &str = "compress";
+
+/// 请求未声明时发送的 `Accept-Encoding`
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+/// 请求体压缩算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip（RFC 1952）
+    Gzip,
+    /// HTTP中的deflate，即zlib格式（RFC 1950）
+    Deflate,
+}
+
+impl FromStr for Compression {
+    type Err = HttpieError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" => Ok(Self::Gzip),
+            "deflate" => Ok(Self::Deflate),
+            other => Err(HttpieError::Parse(format!(
+                "Invalid @{COMPRESS_DIRECTIVE} '{other}', expected gzip or deflate"
+            ))),
+        }
+    }
+}
+
+impl Compression {
+    /// 读取请求上的 `@compress` 指令
+    pub fn from_request(request: &HttpRequest) -> Result<Option<Self>> {
+        request
+            .directive(COMPRESS_DIRECTIVE)
+            .map(str::parse)
+            .transpose()
+    }
+
+    /// `Content-Encoding` 请求头的值
+    pub fn encoding(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+
+    /// 压缩数据
+    pub fn compress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            Self::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Level::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+}
+
+/// 发送的请求体（文本或 `< 文件`）：有 `@compress` 指令时返回压缩后的字节和需要补充的
+/// `Content-Encoding`
+///
+/// 请求中已声明 `Content-Encoding` 时不再补充。
+pub fn encode_body(request: &HttpRequest) -> Result<Option<(Vec<u8>, Option<&'static str>)>> {
+    let body = match &request.body {
+        Some(body) => body.clone().into_bytes(),
+        None => match upload::read_body_file(request)? {
+            Some(body) => body,
+            None => return Ok(None),
+        },
+    };
+    let Some(compression) = Compression::from_request(request)? else {
+        return Ok(Some((body, None)));
+    };
+
+    let declared = request
+        .headers
+        .keys()
+        .any(|key| key.eq_ignore_ascii_case("Content-Encoding"));
+    let encoding = (!declared).then(|| compression.encoding());
+    Ok(Some((compression.compress(&body)?, encoding)))
+}
+
+/// 压缩响应体的大小信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedBody {
+    /// `Content-Encoding` 的值
+    pub encoding: String,
+    /// 线上传输的字节数
+    pub encoded_size: usize,
+    /// 解压后的字节数，编码不支持或数据不完整时为 None
+    pub decoded_size: Option<usize>,
+}
+
+/// 按 `Content-Encoding` 解压响应体
+///
+/// `decode` 为false时（`--raw`）返回原始字节，但仍计算解压后的大小；
+/// 解压失败（如响应体被截断）时保留原始字节。
+pub fn decode_response(
+    headers: &HeaderMap,
+    bytes: Bytes,
+    decode: bool,
+) -> (Bytes, Option<EncodedBody>) {
+    let Some(encoding) = headers
+        .get("content-encoding")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty() && !value.eq_ignore_ascii_case("identity"))
+    else {
+        return (bytes, None);
+    };
+
+    let decoded = decode_all(encoding, &bytes);
+    if let Err(e) = &decoded {
+        warn!("Could not decode {encoding} response body: {e}");
+    }
+    let info = EncodedBody {
+        encoding: encoding.to_string(),
+        encoded_size: bytes.len(),
+        decoded_size: decoded.as_ref().ok().map(Vec::len),
+    };
+    match decoded {
+        Ok(decoded) if decode => (Bytes::from(decoded), Some(info)),
+        _ => (bytes, Some(info)),
+    }
+}
+
+/// 按逆序依次解码多层编码（`Content-Encoding: gzip, br`）
+fn decode_all(encoding: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let mut data = data.to_vec();
+    for coding in encoding.rsplit(',').map(str::trim) {
+        let mut decoded = Vec::new();
+        match coding.to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => GzDecoder::new(&data[..]).read_to_end(&mut decoded)?,
+            "deflate" => ZlibDecoder::new(&data[..]).read_to_end(&mut decoded)?,
+            "br" => brotli::Decompressor::new(&data[..], 4096).read_to_end(&mut decoded)?,
+            "identity" => continue,
+            other => {
+                return Err(HttpieError::InvalidRequest(format!(
+                    "unsupported content encoding '{other}'"
+                )));
+            }
+        };
+        data = decoded;
+    }
+    Ok(data)
+}
@@ -0,0 +1,278 @@
+//! 条件执行模块
+//!
+//! `# @if 条件` 为假时跳过请求，`# @require 条件` 为假时请求失败。条件由比较组成，
+//! 可以用 `&&`、`||` 组合（`&&` 优先），比较前可加 `!` 取反：
+//! `{{env}} == "staging" && {{feature_x}}`。
+//!
+//! 运算符有 `==`、`!=`、`<`、`<=`、`>`、`>=`（两侧都是数字时按数值比较）和 `contains`；
+//! 单独的操作数为空、`false` 或 `0` 时为假。解析文件时替换文件和环境变量，
+//! 发送前再替换运行期变量（如 `@capture` 捕获的值），仍未定义的变量视为空字符串。
+
+use crate::error::{HttpieError, Result};
+use std::fmt;
+
+/// 条件为假时跳过请求的指令（`# @if {{env}} == "staging"`）
+pub const IF_DIRECTIVE: &str = "if";
+
+/// 条件为假时请求失败的指令（`# @require {{token}}`）
+pub const REQUIRE_DIRECTIVE: &str = "require";
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+impl Operator {
+    fn parse(token: &str) -> Option<Self> {
+        Some(match token {
+            "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "contains" => Self::Contains,
+            _ => return None,
+        })
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Contains => "contains",
+        }
+    }
+}
+
+/// 单个比较，没有运算符时按操作数的真值判断
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comparison {
+    negated: bool,
+    left: String,
+    right: Option<(Operator, String)>,
+}
+
+impl Comparison {
+    fn evaluate(&self) -> bool {
+        let left = unresolved_as_empty(&self.left);
+        let result = match &self.right {
+            None => !matches!(left.as_str(), "" | "false" | "0"),
+            Some((operator, right)) => compare(&left, *operator, &unresolved_as_empty(right)),
+        };
+        result != self.negated
+    }
+}
+
+/// 解析后的条件：`||` 分隔的各组中，每组的比较用 `&&` 连接
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    any: Vec<Vec<Comparison>>,
+}
+
+impl Condition {
+    /// 解析条件文本
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            HttpieError::InvalidRequest(format!("Invalid condition '{}': {reason}", text.trim()))
+        };
+        let tokens = tokenize(text).map_err(|reason| invalid(&reason))?;
+        if tokens.is_empty() {
+            return Err(invalid("expected an expression"));
+        }
+
+        let mut any = vec![Vec::new()];
+        let mut tokens = tokens.into_iter().peekable();
+        loop {
+            let mut negated = false;
+            while tokens.next_if(|t| *t == Token::Not).is_some() {
+                negated = !negated;
+            }
+            let Some(Token::Operand(left)) = tokens.next() else {
+                return Err(invalid("expected a value"));
+            };
+            let right = match tokens.next_if(|t| matches!(t, Token::Operator(_))) {
+                Some(Token::Operator(operator)) => match tokens.next() {
+                    Some(Token::Operand(right)) => Some((operator, right)),
+                    _ => return Err(invalid("expected a value after the operator")),
+                },
+                _ => None,
+            };
+            let comparison = Comparison {
+                negated,
+                left,
+                right,
+            };
+            any.last_mut().unwrap().push(comparison);
+            match tokens.next() {
+                None => break,
+                Some(Token::And) => {}
+                Some(Token::Or) => any.push(Vec::new()),
+                Some(_) => return Err(invalid("expected '&&' or '||' between comparisons")),
+            }
+        }
+        Ok(Self { any })
+    }
+
+    /// 对每个操作数应用替换（用于替换变量）
+    pub fn map_operands(mut self, replace: impl Fn(&str) -> String) -> Self {
+        for comparison in self.any.iter_mut().flatten() {
+            comparison.left = replace(&comparison.left);
+            if let Some((_, right)) = &mut comparison.right {
+                *right = replace(right);
+            }
+        }
+        self
+    }
+
+    /// 求值
+    pub fn evaluate(&self) -> bool {
+        self.any
+            .iter()
+            .any(|all| all.iter().all(Comparison::evaluate))
+    }
+}
+
+/// 规范形式：操作数加引号，替换变量后的值含空格或运算符时仍能重新解析
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, all) in self.any.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" || ")?;
+            }
+            for (j, comparison) in all.iter().enumerate() {
+                if j > 0 {
+                    f.write_str(" && ")?;
+                }
+                if comparison.negated {
+                    f.write_str("!")?;
+                }
+                write!(f, "{}", quote(&comparison.left))?;
+                if let Some((operator, right)) = &comparison.right {
+                    write!(f, " {} {}", operator.as_str(), quote(right))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Operand(String),
+    Operator(Operator),
+    Not,
+    And,
+    Or,
+}
+
+/// 分词：引号内的文本（支持 `\"` 和 `\\` 转义）是一个操作数，其余按空白和符号分隔
+fn tokenize(text: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => match chars.next() {
+                        Some(escaped) => value.push(escaped),
+                        None => return Err("unterminated string".to_string()),
+                    },
+                    Some(close) if close == c => break,
+                    Some(other) => value.push(other),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+            tokens.push(Token::Operand(value));
+            continue;
+        }
+
+        let symbol: String = chars
+            .clone()
+            .take_while(|c| "=!<>&|".contains(*c))
+            .collect();
+        if !symbol.is_empty() {
+            let token = match symbol.as_str() {
+                "&&" => Token::And,
+                "||" => Token::Or,
+                "!" => Token::Not,
+                other => match Operator::parse(other) {
+                    Some(operator) => Token::Operator(operator),
+                    None => return Err(format!("unknown operator '{other}'")),
+                },
+            };
+            for _ in 0..symbol.chars().count() {
+                chars.next();
+            }
+            tokens.push(token);
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || "=!<>&|\"'".contains(c) {
+                break;
+            }
+            word.push(c);
+            chars.next();
+        }
+        tokens.push(match Operator::parse(&word) {
+            Some(operator) => Token::Operator(operator),
+            None => Token::Operand(word),
+        });
+    }
+    Ok(tokens)
+}
+
+fn compare(left: &str, operator: Operator, right: &str) -> bool {
+    let numbers = left.parse::<f64>().ok().zip(right.parse::<f64>().ok());
+    let ordering = match numbers {
+        Some((left, right)) => left.partial_cmp(&right),
+        None => Some(left.cmp(right)),
+    };
+    match operator {
+        Operator::Eq => ordering.is_some_and(|o| o.is_eq()),
+        Operator::Ne => !ordering.is_some_and(|o| o.is_eq()),
+        Operator::Lt => ordering.is_some_and(|o| o.is_lt()),
+        Operator::Le => ordering.is_some_and(|o| o.is_le()),
+        Operator::Gt => ordering.is_some_and(|o| o.is_gt()),
+        Operator::Ge => ordering.is_some_and(|o| o.is_ge()),
+        Operator::Contains => left.contains(right),
+    }
+}
+
+/// 去掉仍未替换的 `{{name}}`
+fn unresolved_as_empty(value: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,243 @@
+//! 项目配置模块
+//!
+//! 读取项目目录下的 `httpie.config.toml`，提供客户端默认值（超时、重定向、代理、
+//! TLS）、默认请求头、按主机应用的认证/请求头/TLS设置、输出和报告设置以及全局钩子脚本。
+//! 命令行参数优先于配置文件。
+
+use crate::client::TransportConfig;
+use crate::error::{HttpieError, Result};
+use crate::models::Headers;
+use crate::runner::FailurePolicy;
+use crate::script::ScriptIsolation;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 可配置的执行顺序名称
+const RUN_ORDERS: &[&str] = &["file", "name", "random"];
+
+/// 项目配置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub client: ClientConfig,
+    /// 请求中未声明时自动添加的请求头
+    pub headers: Headers,
+    /// `[hosts."主机"]` 按主机应用的设置
+    pub hosts: HostProfiles,
+    pub output: OutputConfig,
+    pub report: ReportConfig,
+    pub notify: NotifyConfig,
+    pub scripts: ScriptsConfig,
+}
+
+/// `[client]` 客户端默认值
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientConfig {
+    /// 请求超时（秒）
+    pub timeout: Option<u64>,
+    /// 是否跟随重定向（默认跟随）
+    pub follow_redirects: Option<bool>,
+    /// 最多跟随的重定向次数
+    pub max_redirects: Option<usize>,
+    /// 代理地址
+    pub proxy: Option<String>,
+    /// 跳过TLS证书校验
+    pub insecure: Option<bool>,
+    /// 额外信任的CA证书（PEM），相对路径基于配置文件所在目录
+    pub ca_cert: Option<PathBuf>,
+}
+
+/// 按主机应用的设置，URL主机匹配时自动生效
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HostProfile {
+    /// 请求中未声明 `Authorization` 时添加，值中可以使用变量
+    pub authorization: Option<String>,
+    /// 请求中未声明时添加的请求头，优先于 `[headers]`
+    pub headers: Headers,
+    /// 跳过该主机的TLS证书校验
+    pub insecure: Option<bool>,
+    /// 该主机额外信任的CA证书（PEM），相对路径基于配置文件所在目录
+    pub ca_cert: Option<PathBuf>,
+}
+
+impl HostProfile {
+    /// 需要添加的请求头（含 `Authorization`）
+    pub fn request_headers(&self) -> Headers {
+        let mut headers = self.headers.clone();
+        if let Some(authorization) = &self.authorization {
+            headers.insert("Authorization".to_string(), authorization.clone());
+        }
+        headers
+    }
+
+    /// 是否包含TLS设置
+    pub fn has_tls(&self) -> bool {
+        self.insecure.is_some() || self.ca_cert.is_some()
+    }
+}
+
+/// 按主机名索引的设置，键为主机名或 `*.example.com`（匹配所有子域名）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct HostProfiles(pub IndexMap<String, HostProfile>);
+
+impl HostProfiles {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 查找主机（不区分大小写）的设置：精确匹配优先，其次是后缀最长的通配符
+    pub fn find(&self, host: &str) -> Option<&HostProfile> {
+        let host = host.to_lowercase();
+        let exact = self
+            .0
+            .iter()
+            .find(|(pattern, _)| pattern.eq_ignore_ascii_case(&host));
+        if let Some((_, profile)) = exact {
+            return Some(profile);
+        }
+        self.0
+            .iter()
+            .filter_map(|(pattern, profile)| {
+                let suffix = pattern.strip_prefix('*')?.to_lowercase();
+                (suffix.starts_with('.') && host.ends_with(&suffix)).then_some((suffix, profile))
+            })
+            .max_by_key(|(suffix, _)| suffix.len())
+            .map(|(_, profile)| profile)
+    }
+
+    /// URL主机对应的设置，URL无法解析时返回None
+    pub fn for_url(&self, url: &str) -> Option<&HostProfile> {
+        if self.is_empty() {
+            return None;
+        }
+        let url = url::Url::parse(url).ok()?;
+        self.find(url.host_str()?)
+    }
+}
+
+/// `[output]` 输出设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub quiet: Option<bool>,
+    pub max_body_print: Option<usize>,
+    pub max_body_bytes: Option<usize>,
+}
+
+/// `[report]` 执行和报告设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportConfig {
+    /// `stop-on-error`、`fail-fast` 或 `continue-on-error`
+    pub failure_policy: Option<String>,
+    /// `file`、`name` 或 `random`
+    pub order: Option<String>,
+    pub seed: Option<u64>,
+}
+
+/// `[notify]` 失败通知设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Slack、Teams或通用webhook的地址
+    pub webhook: Option<String>,
+    /// 消息模板（占位符见 [`Notifier::with_template`](crate::notify::Notifier::with_template)）
+    pub template: Option<String>,
+    /// 不加 `--notify-on-failure` 也在失败时通知
+    pub on_failure: Option<bool>,
+}
+
+/// `[scripts]` 脚本设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptsConfig {
+    /// 全局钩子脚本（相对于配置文件所在目录），未设置时使用 `httpie.hooks.js`
+    pub hooks: Option<PathBuf>,
+    /// 响应处理器之间的隔离方式：`isolated`（默认）或 `shared`
+    pub isolation: Option<String>,
+}
+
+impl ScriptsConfig {
+    /// 配置的脚本隔离方式
+    pub fn isolation(&self) -> Result<Option<ScriptIsolation>> {
+        self.isolation.as_deref().map(str::parse).transpose()
+    }
+}
+
+impl Config {
+    /// 加载配置文件，文件不存在时使用默认配置
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let mut config: Self = toml::from_str(&content)
+            .map_err(|e| HttpieError::Parse(format!("Invalid config {}: {e}", path.display())))?;
+
+        let ca_certs = config
+            .hosts
+            .0
+            .values_mut()
+            .filter_map(|profile| profile.ca_cert.as_mut());
+        let relative_paths = config
+            .client
+            .ca_cert
+            .iter_mut()
+            .chain(ca_certs)
+            .chain(config.scripts.hooks.iter_mut());
+        for relative in relative_paths {
+            if relative.is_relative()
+                && let Some(dir) = path.parent()
+            {
+                *relative = dir.join(&*relative);
+            }
+        }
+        config.report.failure_policy()?;
+        config.scripts.isolation()?;
+        if let Some(order) = config.report.order.as_deref()
+            && !RUN_ORDERS.contains(&order)
+        {
+            return Err(HttpieError::Parse(format!(
+                "Invalid order '{order}', expected file, name or random"
+            )));
+        }
+        Ok(config)
+    }
+
+    /// 把客户端默认值写入传输层配置
+    pub fn apply(&self, transport: &mut TransportConfig) {
+        let client = &self.client;
+        transport.timeout = client.timeout.map(Duration::from_secs);
+        transport.max_redirects = match client.follow_redirects {
+            Some(false) => Some(0),
+            _ => client.max_redirects,
+        };
+        transport.proxy = client.proxy.clone();
+        transport.insecure = client.insecure.unwrap_or(false);
+        transport.ca_cert = client.ca_cert.clone();
+        transport.host_profiles = self.hosts.clone();
+    }
+}
+
+impl ReportConfig {
+    /// 配置的失败处理策略
+    pub fn failure_policy(&self) -> Result<Option<FailurePolicy>> {
+        self.failure_policy
+            .as_deref()
+            .map(|policy| match policy {
+                "stop-on-error" => Ok(FailurePolicy::StopOnError),
+                "fail-fast" => Ok(FailurePolicy::FailFast),
+                "continue-on-error" => Ok(FailurePolicy::ContinueOnError),
+                other => Err(HttpieError::Parse(format!(
+                    "Invalid failure_policy '{other}', expected stop-on-error, fail-fast or continue-on-error"
+                ))),
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,70 @@
+//! 响应Cookie模块
+//!
+//! 把 `Set-Cookie` 响应头解析为结构化的Cookie，脚本通过 `response.cookies` 检查
+//! `Secure`、`HttpOnly`、`SameSite` 等属性。与会话中保存的Cookie不同，这里保留响应中
+//! 声明的原始属性，未声明的属性为空。
+
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use serde::{Deserialize, Serialize};
+
+/// 一个 `Set-Cookie` 响应头
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    /// `Expires` 属性的原始文本
+    pub expires: Option<String>,
+    pub max_age: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+    /// `SameSite` 属性（`Strict`、`Lax` 或 `None`）
+    pub same_site: Option<String>,
+}
+
+impl SetCookie {
+    /// 解析一个 `Set-Cookie` 头的值，缺少名称时返回None
+    ///
+    /// 属性名不区分大小写，无法识别的属性被忽略。
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut parts = header.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let mut cookie = Self {
+            name: name.trim().to_string(),
+            value: value.trim().trim_matches('"').to_string(),
+            ..Self::default()
+        };
+        if cookie.name.is_empty() {
+            return None;
+        }
+
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            let value = (!value.is_empty()).then(|| value.to_string());
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" => cookie.domain = value,
+                "path" => cookie.path = value,
+                "expires" => cookie.expires = value,
+                "max-age" => cookie.max_age = value.and_then(|age| age.parse().ok()),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "samesite" => cookie.same_site = value,
+                _ => {}
+            }
+        }
+        Some(cookie)
+    }
+
+    /// 响应头中的所有Cookie，按出现顺序排列
+    pub fn from_headers(headers: &HeaderMap) -> Vec<Self> {
+        headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(Self::parse)
+            .collect()
+    }
+}
@@ -0,0 +1,444 @@
+//! curl命令转换模块
+//!
+//! 将解析后的HTTP请求导出为等价的curl命令，并支持将curl命令导入为HTTP请求。
+
+use crate::error::{HttpieError, Result};
+use crate::models::{Headers, HttpRequest, HttpVersion};
+use crate::runner::CaseSelector;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use reqwest::Method;
+use std::collections::{BTreeSet, VecDeque};
+use std::str::FromStr;
+
+/// 需要参数但在导入时被忽略的curl选项
+const IGNORED_OPTIONS_WITH_VALUE: &[&str] = &[
+    "-o",
+    "--output",
+    "-m",
+    "--max-time",
+    "--connect-timeout",
+    "-x",
+    "--proxy",
+    "-U",
+    "--proxy-user",
+    "--retry",
+    "--retry-delay",
+    "--retry-max-time",
+    "-w",
+    "--write-out",
+    "--cacert",
+    "--capath",
+    "-E",
+    "--cert",
+    "--cert-type",
+    "--key",
+    "--key-type",
+    "-r",
+    "--range",
+    "-c",
+    "--cookie-jar",
+    "-D",
+    "--dump-header",
+    "--max-redirs",
+    "--resolve",
+    "--connect-to",
+    "--interface",
+    "--limit-rate",
+    "-y",
+    "--speed-time",
+    "-Y",
+    "--speed-limit",
+];
+
+/// 导入时忽略的不带参数的curl选项（输出、重试和连接行为等）
+const IGNORED_FLAGS: &[&str] = &[
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-L",
+    "--location",
+    "-k",
+    "--insecure",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-f",
+    "--fail",
+    "--fail-with-body",
+    "-N",
+    "--no-buffer",
+    "-g",
+    "--globoff",
+    "-#",
+    "--progress-bar",
+    "--no-progress-meter",
+    "--compressed",
+    "--path-as-is",
+    "-4",
+    "--ipv4",
+    "-6",
+    "--ipv6",
+];
+
+/// 参数可以直接跟在选项后面的短选项（如 `-XPUT`、`-HAccept:x`）
+const SHORT_OPTIONS_WITH_VALUE: &str = "XHduAebFoxmwrcEDUyY";
+
+/// 可以合并书写的短开关（如 `-sSL`）
+const SHORT_FLAGS: &str = "sSLkvifNgGI46#";
+
+/// 将请求转换为curl命令
+pub fn to_curl(request: &HttpRequest) -> String {
+    // 第一行：curl [方法] URL
+    let mut command = "curl".to_string();
+    if request.method == Method::HEAD {
+        command.push_str(" --head");
+    } else if request.method != Method::GET || request.body.is_some() {
+        command.push_str(&format!(" -X {}", request.method.as_str()));
+    }
+    match request.http_version {
+        Some(HttpVersion::Http11) => command.push_str(" --http1.1"),
+        Some(HttpVersion::Http2) => command.push_str(" --http2"),
+        None => {}
+    }
+    // 查询和路径参数编码进URL，无法构造时按原样输出
+    let url = request
+        .target_url()
+        .map_or_else(|_| request.url.clone(), String::from);
+    command.push(' ');
+    command.push_str(&shell_quote(&url));
+
+    // 其余选项各占一行
+    let mut parts = vec![command];
+
+    for (name, value) in &request.headers {
+        parts.push(format!("-H {}", shell_quote(&format!("{name}: {value}"))));
+    }
+
+    if let Some(body) = &request.body {
+        parts.push(format!("--data-raw {}", shell_quote(body)));
+    }
+
+    parts.join(" \\\n  ")
+}
+
+/// 把选中的请求导出为curl命令，每个命令前有 `# 名称` 注释，命令之间空一行
+///
+/// 没有选择方式时导出全部请求；不带 `#N` 的名称选中所有名称包含该文本的请求。
+/// 每种选择方式都必须至少选中一个请求。
+pub fn export(requests: &[HttpRequest], selectors: &[CaseSelector]) -> Result<String> {
+    let requests: Vec<&HttpRequest> = requests.iter().collect();
+    let mut positions = BTreeSet::new();
+    for selector in selectors {
+        let matched: Vec<usize> = match selector {
+            CaseSelector::Name(case) if selector.is_name_filter() => (0..requests.len())
+                .filter(|&i| requests[i].name.contains(case.as_str()))
+                .collect(),
+            selector => selector.positions(&requests),
+        };
+        if matched.is_empty() {
+            return Err(HttpieError::InvalidRequest(format!(
+                "Case {selector} not found"
+            )));
+        }
+        positions.extend(matched);
+    }
+    let selected: Vec<&HttpRequest> = if selectors.is_empty() {
+        requests
+    } else {
+        positions.into_iter().map(|i| requests[i]).collect()
+    };
+
+    Ok(selected
+        .into_iter()
+        .map(|request| format!("# {}\n{}\n\n", request.name, to_curl(request)))
+        .collect())
+}
+
+/// 使用单引号转义shell参数
+pub fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c))
+    {
+        return value.to_string();
+    }
+
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// 判断一行文本是否为curl命令
+pub fn is_curl_command(line: &str) -> bool {
+    line == "curl" || line.starts_with("curl ") || line.starts_with("curl\t")
+}
+
+/// 将curl命令解析为HTTP请求
+pub fn from_curl(command: &str) -> Result<HttpRequest> {
+    let mut args: VecDeque<String> = tokenize(command)?.into();
+
+    match args.pop_front() {
+        Some(first) if first == "curl" => {}
+        _ => {
+            return Err(HttpieError::Parse(
+                "curl command must start with 'curl'".to_string(),
+            ));
+        }
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers = Headers::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut as_get = false;
+    let mut head = false;
+    let mut http_version = None;
+
+    while let Some(arg) = args.pop_front() {
+        if let Some(expanded) = expand_short_option(&arg) {
+            for part in expanded.into_iter().rev() {
+                args.push_front(part);
+            }
+            continue;
+        }
+        let mut value = |flag: &str| {
+            args.pop_front()
+                .ok_or_else(|| HttpieError::Parse(format!("curl option '{flag}' requires a value")))
+        };
+
+        match arg.as_str() {
+            "-X" | "--request" => method = Some(value(&arg)?),
+            "-H" | "--header" => {
+                let header = value(&arg)?;
+                if let Some((name, header_value)) = header.split_once(':') {
+                    headers.insert(name.trim().to_string(), header_value.trim().to_string());
+                } else if let Some(name) = header.strip_suffix(';') {
+                    headers.insert(name.trim().to_string(), String::new());
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii"
+            | "--data-urlencode" => data.push(value(&arg)?),
+            "--json" => {
+                data.push(value(&arg)?);
+                headers
+                    .entry("Content-Type".to_string())
+                    .or_insert_with(|| "application/json".to_string());
+                headers
+                    .entry("Accept".to_string())
+                    .or_insert_with(|| "application/json".to_string());
+            }
+            "-u" | "--user" => {
+                let credentials = value(&arg)?;
+                headers.insert(
+                    "Authorization".to_string(),
+                    format!("Basic {}", BASE64.encode(credentials)),
+                );
+            }
+            "-A" | "--user-agent" => {
+                headers.insert("User-Agent".to_string(), value(&arg)?);
+            }
+            "-e" | "--referer" => {
+                headers.insert("Referer".to_string(), value(&arg)?);
+            }
+            "-b" | "--cookie" => {
+                headers.insert("Cookie".to_string(), value(&arg)?);
+            }
+            "--url" => url = Some(value(&arg)?),
+            "-G" | "--get" => as_get = true,
+            "-I" | "--head" => head = true,
+            "--http1.1" => http_version = Some(HttpVersion::Http11),
+            "--http2" | "--http2-prior-knowledge" => http_version = Some(HttpVersion::Http2),
+            "-F" | "--form" | "--form-string" => {
+                return Err(HttpieError::InvalidRequest(format!(
+                    "curl option '{arg}' (multipart form) is not supported, \
+                     write the multipart body in the .http file instead"
+                )));
+            }
+            flag if IGNORED_OPTIONS_WITH_VALUE.contains(&flag) => {
+                value(flag)?;
+            }
+            flag if IGNORED_FLAGS.contains(&flag) => {}
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(HttpieError::InvalidRequest(format!(
+                    "Unsupported curl option '{flag}'"
+                )));
+            }
+            _ => {
+                if let Some(previous) = &url {
+                    return Err(HttpieError::InvalidRequest(format!(
+                        "curl command has more than one URL: '{previous}' and '{arg}'"
+                    )));
+                }
+                url = Some(arg);
+            }
+        }
+    }
+
+    let mut url = url.ok_or_else(|| HttpieError::Parse("curl command has no URL".to_string()))?;
+    let mut body = (!data.is_empty()).then(|| data.join("&"));
+
+    // -G 将数据作为查询参数追加到URL
+    if as_get && let Some(query) = body.take() {
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(&query);
+    }
+
+    let method = match method {
+        Some(method) => method.to_uppercase(),
+        None if head => "HEAD".to_string(),
+        None if body.is_some() => "POST".to_string(),
+        None => "GET".to_string(),
+    };
+    let method = Method::from_str(&method).map_err(|_| HttpieError::InvalidMethod(method))?;
+
+    let name = format!("{} {}", method.as_str(), url);
+    Ok(HttpRequest::new(name, method, url)
+        .with_headers(headers)
+        .with_body(body)
+        .with_http_version(http_version))
+}
+
+/// 拆分紧跟参数的短选项（`-XPUT` → `-X PUT`）和合并的短开关（`-sSL` → `-s -S -L`），
+/// 其他参数返回None
+fn expand_short_option(arg: &str) -> Option<Vec<String>> {
+    let rest = arg
+        .strip_prefix('-')
+        .filter(|rest| !rest.starts_with('-'))?;
+    let mut chars = rest.chars();
+    let first = chars.next()?;
+    let attached = chars.as_str();
+    if attached.is_empty() {
+        return None;
+    }
+    if SHORT_OPTIONS_WITH_VALUE.contains(first) {
+        return Some(vec![format!("-{first}"), attached.to_string()]);
+    }
+    rest.chars()
+        .all(|flag| SHORT_FLAGS.contains(flag))
+        .then(|| rest.chars().map(|flag| format!("-{flag}")).collect())
+}
+
+/// 将请求渲染为.http文件格式
+pub fn to_http(request: &HttpRequest) -> String {
+    let mut output = format!(
+        "### {}\n{} {}",
+        request.name,
+        request.method.as_str(),
+        request.url
+    );
+    if let Some(version) = request.http_version {
+        output.push_str(&format!(" {version}"));
+    }
+    output.push('\n');
+
+    for (index, (key, value)) in request.query.iter().enumerate() {
+        let prefix = if index == 0 { '?' } else { '&' };
+        output.push_str(&format!("    {prefix}{key}={value}\n"));
+    }
+    for (name, value) in &request.headers {
+        output.push_str(&format!("{name}: {value}\n"));
+    }
+
+    if let Some(body) = &request.body {
+        output.push('\n');
+        output.push_str(body);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// 按shell规则拆分命令参数（支持引号、转义和反斜杠续行）
+fn tokenize(command: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err(unterminated_quote()),
+                    }
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => current.push(ch),
+                            Some('\n') => {}
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => return Err(unterminated_quote()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err(unterminated_quote()),
+                    }
+                }
+            }
+            // ANSI-C引号（浏览器"复制为cURL"常用）
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(ch) => current.push(ch),
+                            None => return Err(unterminated_quote()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err(unterminated_quote()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // 续行
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(ch) => {
+                    in_token = true;
+                    current.push(ch);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+fn unterminated_quote() -> HttpieError {
+    HttpieError::Parse("Unterminated quote in curl command".to_string())
+}
@@ -0,0 +1,116 @@
+//! 解析方言模块
+//!
+//! 从其他工具迁移来的.http文件按该工具的方言解析：只识别该工具支持的扩展语法，
+//! 本工具独有的扩展（`@capture`、`### @defaults` 等）和该工具有、本工具不支持的指令
+//! 都会产生警告并被忽略，迁移时能清楚地看到哪些写法不受支持。
+
+use crate::error::{HttpieError, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// IntelliJ HTTP Client 中本工具不支持的指令
+const INTELLIJ_DIRECTIVES: &[&str] = &[
+    "no-redirect",
+    "no-log",
+    "no-cookie-jar",
+    "no-auto-encoding",
+    "timeout",
+    "connection-timeout",
+    "use-os-credentials",
+];
+
+/// httpyac 中本工具不支持的指令
+const HTTPYAC_DIRECTIVES: &[&str] = &[
+    "ref",
+    "forceRef",
+    "import",
+    "disabled",
+    "sleep",
+    "loop",
+    "note",
+    "title",
+    "description",
+    "verbose",
+    "debug",
+    "no-log",
+    "no-redirect",
+    "no-cookie-jar",
+    "keepStreaming",
+];
+
+/// VS Code REST Client 中本工具不支持的指令
+const REST_CLIENT_DIRECTIVES: &[&str] = &["note", "prompt", "no-redirect", "no-cookie-jar"];
+
+/// .http文件的方言，决定识别哪些扩展语法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParserDialect {
+    /// 本工具的全部语法
+    #[default]
+    Native,
+    /// IntelliJ HTTP Client
+    IntelliJ,
+    /// httpyac
+    Httpyac,
+    /// VS Code REST Client
+    RestClient,
+}
+
+impl ParserDialect {
+    /// 是否识别 `> {% %}` 响应处理器
+    pub fn handlers(self) -> bool {
+        self != Self::RestClient
+    }
+
+    /// 是否识别 `?? status == 200` 内联断言
+    pub fn assertions(self) -> bool {
+        matches!(self, Self::Native | Self::Httpyac)
+    }
+
+    /// 是否允许只以空行分隔、没有 `###` 的请求
+    pub fn implicit_sections(self) -> bool {
+        self == Self::Httpyac
+    }
+
+    /// 是否识别本工具独有的指令（`@capture`、`@repeat` 等）和 `### @defaults` 段落
+    pub fn extensions(self) -> bool {
+        self == Self::Native
+    }
+
+    /// 该方言定义、本工具不支持而忽略的指令
+    pub fn ignores(self, directive: &str) -> bool {
+        let ignored = match self {
+            Self::Native => &[][..],
+            Self::IntelliJ => INTELLIJ_DIRECTIVES,
+            Self::Httpyac => HTTPYAC_DIRECTIVES,
+            Self::RestClient => REST_CLIENT_DIRECTIVES,
+        };
+        ignored.contains(&directive)
+    }
+}
+
+impl FromStr for ParserDialect {
+    type Err = HttpieError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "native" => Ok(Self::Native),
+            "intellij" => Ok(Self::IntelliJ),
+            "httpyac" => Ok(Self::Httpyac),
+            "rest-client" => Ok(Self::RestClient),
+            other => Err(HttpieError::Parse(format!(
+                "Invalid dialect '{other}', expected native, intellij, httpyac or rest-client"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ParserDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Native => "native",
+            Self::IntelliJ => "IntelliJ HTTP Client",
+            Self::Httpyac => "httpyac",
+            Self::RestClient => "VS Code REST Client",
+        })
+    }
+}
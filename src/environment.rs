@@ -1,25 +1,384 @@
 //! 环境配置模块
 //!
-//! 处理环境配置文件的加载和管理。
+//! 处理环境配置文件的加载和管理。环境文件也可以是 `https://` 地址，
+//! 下载后缓存在当前用户的缓存目录中，缓存未过期时不再请求。远程环境文件
+//! 不能使用 `exec` 和 `keyring` 密钥，避免下载的内容在本机执行命令。
 
-use crate::error::Result;
+use crate::DEFAULT_ENV_FILE;
+use crate::error::{HttpieError, Result};
 use crate::models::Environment;
-use std::path::Path;
+use crate::secret::SecretPolicy;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// 远程环境文件的默认缓存时间
+pub const DEFAULT_ENV_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// 远程环境文件请求使用的Authorization头，未通过参数指定时从该环境变量读取
+pub const ENV_AUTHORIZATION_VAR: &str = "HTTPIE_ENV_AUTHORIZATION";
+
+/// 远程环境文件
+#[derive(Debug, Clone)]
+pub struct RemoteEnvironment {
+    url: String,
+    authorization: Option<String>,
+    cache_ttl: Duration,
+    cache_dir: PathBuf,
+}
+
+impl RemoteEnvironment {
+    /// 创建远程环境文件，缓存在当前用户的缓存目录中
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            authorization: std::env::var(ENV_AUTHORIZATION_VAR).ok(),
+            cache_ttl: DEFAULT_ENV_CACHE_TTL,
+            cache_dir: default_cache_dir(),
+        }
+    }
+
+    /// 设置请求的Authorization头
+    pub fn with_authorization(mut self, authorization: Option<String>) -> Self {
+        self.authorization = authorization;
+        self
+    }
+
+    /// 设置缓存时间，为0时每次都重新下载
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// 设置缓存目录，不存在时以仅当前用户可访问的权限创建
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    /// 缓存文件路径，按URL的哈希命名
+    pub fn cache_path(&self) -> PathBuf {
+        let hash: String = Sha256::digest(self.url.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        self.cache_dir.join(format!("{hash}.json"))
+    }
+
+    /// 返回本地环境文件路径：缓存未过期时直接使用，否则重新下载；
+    /// 下载失败但有旧缓存时使用旧缓存并输出警告
+    ///
+    /// 只接受 `https://` 地址；缓存目录或缓存文件不属于当前用户时报错，不读取其中的内容。
+    pub fn fetch(&self) -> Result<PathBuf> {
+        if !is_remote(&self.url)? {
+            return Err(HttpieError::InvalidRequest(format!(
+                "Remote environment file must be an https:// URL: {}",
+                self.url
+            )));
+        }
+
+        let cache_path = self.cache_path();
+        let cached = match fs::symlink_metadata(&cache_path) {
+            Ok(metadata) => {
+                ensure_private(&self.cache_dir)?;
+                ensure_private(&cache_path)?;
+                Some(metadata)
+            }
+            Err(_) => None,
+        };
+        let fresh = cached
+            .as_ref()
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < self.cache_ttl);
+        if fresh {
+            return Ok(cache_path);
+        }
+
+        match self.download() {
+            Ok(content) => {
+                // 写入前校验，避免缓存错误页面
+                serde_json::from_str::<serde_json::Value>(&content)?;
+                self.write_cache(&cache_path, &content)?;
+                Ok(cache_path)
+            }
+            Err(e) if cached.is_some() => {
+                warn!(
+                    url = %self.url,
+                    "Failed to refresh environment: {e}, using cached copy"
+                );
+                Ok(cache_path)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 写入缓存文件，缓存目录和文件仅当前用户可访问
+    fn write_cache(&self, cache_path: &Path, content: &str) -> Result<()> {
+        create_private_dir(&self.cache_dir)?;
+        ensure_private(&self.cache_dir)?;
+        open_private(
+            cache_path,
+            fs::OpenOptions::new().write(true).truncate(true),
+        )?
+        .write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    /// 在独立线程中下载，调用方可以处于异步运行时内
+    fn download(&self) -> Result<String> {
+        let url = self.url.clone();
+        let authorization = self.authorization.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            runtime.block_on(async {
+                let mut request = reqwest::Client::new().get(&url);
+                if let Some(authorization) = authorization {
+                    request = request.header(reqwest::header::AUTHORIZATION, authorization);
+                }
+                let response = request.send().await?.error_for_status()?;
+                Ok(response.text().await?)
+            })
+        })
+        .join()
+        .unwrap_or_else(|_| {
+            Err(HttpieError::InvalidRequest(
+                "environment download thread panicked".to_string(),
+            ))
+        })
+    }
+}
+
+/// 是否为远程环境文件地址，只接受 `https://`；`http://` 地址报错，
+/// 明文下载的内容可能被篡改
+pub fn is_remote(env_file: &str) -> Result<bool> {
+    if env_file.starts_with("http://") {
+        return Err(HttpieError::InvalidRequest(format!(
+            "Remote environment file must be an https:// URL: {env_file}"
+        )));
+    }
+    Ok(env_file.starts_with("https://"))
+}
+
+/// 默认的远程环境文件缓存目录：`XDG_CACHE_HOME`，其次是主目录下的 `.cache`
+/// （Windows 为 `LOCALAPPDATA`），都没有时使用系统临时目录
+fn default_cache_dir() -> PathBuf {
+    let base = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+    match base {
+        Some(base) => base.join("httpie").join("env"),
+        None => std::env::temp_dir().join("httpie-env-cache"),
+    }
+}
+
+/// 创建目录（含上级目录），新建的目录仅当前用户可访问（Unix上为 `0700`）
+pub(crate) fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)?;
+    Ok(())
+}
+
+/// 按 `options` 打开文件，文件不存在时创建，打开的文件仅当前用户可读写（Unix上为 `0600`）
+pub(crate) fn open_private(path: &Path, options: &mut fs::OpenOptions) -> Result<fs::File> {
+    options.create(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        // mode只对新建的文件生效，之前以默认权限创建的文件在这里收紧
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        Ok(file)
+    }
+    #[cfg(not(unix))]
+    Ok(options.open(path)?)
+}
+
+/// 检查缓存目录或文件属于当前用户、且其他用户不可写，缓存文件不能是符号链接
+#[cfg(unix)]
+fn ensure_private(path: &Path) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::symlink_metadata(path)?;
+    let metadata = if metadata.is_symlink() && path.is_dir() {
+        fs::metadata(path)?
+    } else {
+        metadata
+    };
+    // SAFETY: geteuid 没有前置条件，总是成功
+    let uid = unsafe { libc::geteuid() };
+    if metadata.is_symlink() || metadata.uid() != uid || metadata.mode() & 0o022 != 0 {
+        return Err(HttpieError::InvalidRequest(format!(
+            "Refusing to use environment cache '{}': it must be owned by the current user and not writable by others",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_private(_path: &Path) -> Result<()> {
+    Ok(())
+}
 
 /// 环境配置加载器
 pub struct EnvironmentLoader;
 
 impl EnvironmentLoader {
-    /// 从指定路径加载环境配置
+    /// 从指定路径或 `https://` 地址加载环境配置，远程环境文件不能使用密钥
     pub fn load_from_path(env_file: &str) -> Result<Environment> {
+        if is_remote(env_file)? {
+            let cache_path = RemoteEnvironment::new(env_file).fetch()?;
+            return Environment::from_files_with_policy(&[cache_path], None, SecretPolicy::Deny);
+        }
         if Path::new(env_file).exists() {
             Environment::from_file(env_file)
         } else {
-            eprintln!("Warning: Environment file '{env_file}' not found, using empty environment");
+            warn!("Environment file '{env_file}' not found, using empty environment");
             Ok(Environment::new())
         }
     }
 
+    /// 加载 `.env` 文件（`KEY=VALUE`，支持 `export` 前缀、引号和 `#` 注释行），
+    /// 文件不存在时为空
+    pub fn load_dotenv(path: &Path) -> Result<HashMap<String, String>> {
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = fs::read_to_string(path)?;
+
+        let mut variables = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(HttpieError::Parse(format!(
+                    "Invalid line {} in {}: expected KEY=VALUE",
+                    index + 1,
+                    path.display()
+                )));
+            };
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+                .unwrap_or(value);
+            variables.insert(key.trim().to_string(), value.to_string());
+        }
+        Ok(variables)
+    }
+
+    /// 确定环境文件路径（由远及近）及其中允许使用的密钥
+    ///
+    /// 未指定 `env_file` 时从.http文件所在目录向上查找，都没有时使用当前目录下的默认文件；
+    /// 远程地址经 `remote` 配置后下载到缓存，并且不允许使用密钥；
+    /// 本地文件只有 `allow_exec` 时才能使用 `exec`。
+    pub fn resolve_files(
+        http_file: &Path,
+        env_file: Option<&str>,
+        allow_exec: bool,
+        remote: impl FnOnce(RemoteEnvironment) -> RemoteEnvironment,
+    ) -> Result<(Vec<PathBuf>, SecretPolicy)> {
+        let local_policy = if allow_exec {
+            SecretPolicy::AllowExec
+        } else {
+            SecretPolicy::KeyringOnly
+        };
+        let env_file = match env_file {
+            Some(env_file) => env_file,
+            None => {
+                let found = Self::discover(http_file, DEFAULT_ENV_FILE);
+                if !found.is_empty() {
+                    return Ok((found, local_policy));
+                }
+                DEFAULT_ENV_FILE
+            }
+        };
+        if !is_remote(env_file)? {
+            return Ok((vec![PathBuf::from(env_file)], local_policy));
+        }
+        let cache_path = remote(RemoteEnvironment::new(env_file)).fetch()?;
+        Ok((vec![cache_path], SecretPolicy::Deny))
+    }
+
+    /// 加载环境文件中的环境，显式指定的环境不存在时报错
+    ///
+    /// 未指定环境时，环境文件不存在或无法解析只输出警告并使用空环境；
+    /// 使用了不允许的密钥来源等其他错误照常返回，避免带着未替换的密钥变量发送请求。
+    pub fn load_files(
+        env_files: &[PathBuf],
+        name: Option<&str>,
+        secret_policy: SecretPolicy,
+    ) -> Result<Environment> {
+        if let [env_file] = env_files
+            && !env_file.exists()
+        {
+            if name.is_some() {
+                return Err(HttpieError::FileNotFound(env_file.display().to_string()));
+            }
+            warn!(
+                "Environment file '{}' not found, using empty environment",
+                env_file.display()
+            );
+            return Ok(Environment::new());
+        }
+
+        let environment = Environment::from_files_with_policy(env_files, name, secret_policy);
+        match (name, environment) {
+            (None, Err(e @ (HttpieError::FileNotFound(_) | HttpieError::Json(_)))) => {
+                warn!("Failed to load environment file: {e}");
+                Ok(Environment::new())
+            }
+            (_, environment) => environment,
+        }
+    }
+
+    /// 从.http文件所在目录向上查找环境文件，到包含 `.git` 的仓库根目录为止
+    /// （不在仓库中时查找到文件系统根目录），返回由远及近的路径
+    pub fn discover(http_file: &Path, env_filename: &str) -> Vec<PathBuf> {
+        let start = http_file
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let Ok(start) = start.canonicalize() else {
+            return Vec::new();
+        };
+
+        let mut found = Vec::new();
+        for dir in start.ancestors() {
+            let env_file = dir.join(env_filename);
+            if env_file.is_file() {
+                found.push(env_file);
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        found.reverse();
+        found
+    }
+
     /// 从基础路径和环境文件名加载配置
     pub fn load_from_base_path(base_path: &Path, env_filename: &str) -> Result<Environment> {
         let env_file = base_path.join(env_filename);
@@ -2,6 +2,7 @@
 //!
 //! 定义了HTTP客户端库中使用的所有错误类型。
 
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -20,10 +21,86 @@ pub enum HttpieError {
     FileNotFound(String),
     #[error("Invalid request format: {0}")]
     InvalidRequest(String),
+    #[error("{location}: {message}{}", snippet(.location, .source_line))]
+    Syntax {
+        location: SourceLocation,
+        message: String,
+        /// 出错行的原文，用于输出带插入符的片段
+        source_line: String,
+    },
     #[error("Script execution error: {0}")]
     ScriptError(String),
     #[error("Script parsing error: {0}")]
     ScriptParsingError(String),
+    #[error("Request '{0}' failed: {1}")]
+    RequestFailed(String, String),
+    #[error("{0} request(s) failed")]
+    RunFailed(usize),
+    #[error("Run aborted by '{0}': {1}")]
+    RunAborted(String, String),
+    #[error("{0} file(s) need formatting")]
+    Unformatted(usize),
+    #[error("Request cancelled")]
+    Cancelled,
+}
+
+/// 源文件中的位置（行号和列号从1开始）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{file}:{}:{}", self.line, self.column),
+            None => write!(f, "{}:{}", self.line, self.column),
+        }
+    }
+}
+
+impl HttpieError {
+    /// 为解析类错误（`Parse`、`InvalidRequest`、`InvalidMethod`）附加源位置，
+    /// 其他错误和已带位置的错误保持不变
+    pub fn at(self, location: SourceLocation, source_line: &str) -> Self {
+        let message = match self {
+            Self::Parse(message) | Self::InvalidRequest(message) => message,
+            Self::InvalidMethod(method) => format!("Invalid HTTP method: {method}"),
+            other => return other,
+        };
+        Self::Syntax {
+            location,
+            message,
+            source_line: source_line.to_string(),
+        }
+    }
+
+    /// 为带位置的错误补充文件名
+    pub fn in_file(mut self, path: &str) -> Self {
+        if let Self::Syntax { location, .. } = &mut self
+            && location.file.is_none()
+        {
+            location.file = Some(path.to_string());
+        }
+        self
+    }
+}
+
+/// 生成带插入符的源码片段
+pub(crate) fn snippet(location: &SourceLocation, source_line: &str) -> String {
+    if source_line.is_empty() {
+        return String::new();
+    }
+    let gutter = location.line.to_string().len();
+    format!(
+        "\n{:>gutter$} | {source_line}\n{:>gutter$} | {:>column$}",
+        location.line,
+        "",
+        "^",
+        column = location.column.max(1)
+    )
 }
 
 /// Result类型别名，简化错误处理
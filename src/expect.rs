@@ -0,0 +1,259 @@
+//! 响应快照比对模块
+//!
+//! 处理 `# @expect-response` 指令，将实际响应体与期望的夹具文件进行比对，
+//! 支持忽略字段和正则掩码等归一化规则；二进制响应体按原始字节比对。
+
+use crate::binary::BinarySummary;
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use crate::script::{ResponseObject, TestResult};
+use regex::Regex;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// 期望响应夹具文件指令
+pub const EXPECT_RESPONSE_DIRECTIVE: &str = "expect-response";
+/// 比对时忽略的字段（逗号分隔）
+pub const EXPECT_IGNORE_DIRECTIVE: &str = "expect-ignore";
+/// 比对前应用到字符串上的正则掩码（可重复）
+pub const EXPECT_MASK_DIRECTIVE: &str = "expect-mask";
+
+/// 掩码替换后的占位符
+const MASK_PLACEHOLDER: &str = "<masked>";
+/// 差异报告中最多列出的条目数
+const MAX_REPORTED_DIFFS: usize = 20;
+
+/// 响应期望
+#[derive(Debug, Clone)]
+pub struct ResponseExpectation {
+    pub fixture: PathBuf,
+    pub ignore_keys: Vec<String>,
+    pub masks: Vec<Regex>,
+}
+
+/// 单条差异
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BodyDifference {
+    pub path: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl std::fmt::Display for BodyDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => {
+                write!(f, "{}: expected {}, got {}", self.path, expected, actual)
+            }
+            (Some(expected), None) => write!(f, "{}: missing (expected {})", self.path, expected),
+            (None, Some(actual)) => write!(f, "{}: unexpected {}", self.path, actual),
+            (None, None) => write!(f, "{}: differs", self.path),
+        }
+    }
+}
+
+impl ResponseExpectation {
+    /// 从请求指令构建期望，未声明 `@expect-response` 时返回 None
+    pub fn from_request(request: &HttpRequest) -> Result<Option<Self>> {
+        let Some(fixture) = request.directive(EXPECT_RESPONSE_DIRECTIVE) else {
+            return Ok(None);
+        };
+        if fixture.is_empty() {
+            return Err(HttpieError::InvalidRequest(format!(
+                "@{EXPECT_RESPONSE_DIRECTIVE} requires a fixture path in '{}'",
+                request.name
+            )));
+        }
+
+        let ignore_keys = request
+            .directives_named(EXPECT_IGNORE_DIRECTIVE)
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let masks = request
+            .directives_named(EXPECT_MASK_DIRECTIVE)
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    HttpieError::Parse(format!("Invalid @{EXPECT_MASK_DIRECTIVE} '{pattern}': {e}"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Self {
+            fixture: request.resolve_path(fixture),
+            ignore_keys,
+            masks,
+        }))
+    }
+
+    /// 将响应与夹具比对，差异以失败的测试结果返回
+    pub fn check(&self, response: &ResponseObject) -> Result<TestResult> {
+        let test_name = format!("Response matches {}", self.fixture.display());
+
+        // 二进制响应体按原始字节比对
+        if response.binary.is_some() {
+            let expected = fs::read(&self.fixture)
+                .map_err(|_| HttpieError::FileNotFound(self.fixture.display().to_string()))?;
+            if expected == response.raw_body {
+                return Ok(TestResult::success(test_name));
+            }
+            let (expected, actual) = (
+                BinarySummary::new(&expected),
+                BinarySummary::new(&response.raw_body),
+            );
+            let message = format!(
+                "binary body differs: expected {} bytes (sha256 {}), got {} bytes (sha256 {})",
+                expected.size, expected.sha256, actual.size, actual.sha256
+            );
+            return Ok(TestResult::failure(test_name, message, "ResponseMismatch"));
+        }
+
+        let expected_text = fs::read_to_string(&self.fixture)
+            .map_err(|_| HttpieError::FileNotFound(self.fixture.display().to_string()))?;
+
+        let differences = match serde_json::from_str::<Value>(&expected_text) {
+            Ok(expected) => {
+                let actual = match &response.body {
+                    Value::String(s) => serde_json::from_str(s).unwrap_or(Value::String(s.clone())),
+                    other => other.clone(),
+                };
+                let mut differences = Vec::new();
+                diff_json(
+                    &self.normalize(expected),
+                    &self.normalize(actual),
+                    "$",
+                    &mut differences,
+                );
+                differences
+            }
+            Err(_) => {
+                let actual_text = match &response.body {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                diff_text(
+                    &self.mask(expected_text.trim_end()),
+                    &self.mask(actual_text.trim_end()),
+                )
+            }
+        };
+
+        if differences.is_empty() {
+            return Ok(TestResult::success(test_name));
+        }
+
+        let mut message = format!("{} difference(s):", differences.len());
+        for difference in differences.iter().take(MAX_REPORTED_DIFFS) {
+            message.push_str("\n    ");
+            message.push_str(&difference.to_string());
+        }
+        if differences.len() > MAX_REPORTED_DIFFS {
+            message.push_str(&format!(
+                "\n    ... and {} more",
+                differences.len() - MAX_REPORTED_DIFFS
+            ));
+        }
+
+        Ok(TestResult::failure(test_name, message, "ResponseMismatch"))
+    }
+
+    /// 应用忽略字段和掩码规则
+    fn normalize(&self, value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(key, _)| !self.ignore_keys.contains(key))
+                    .map(|(key, value)| (key, self.normalize(value)))
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.normalize(v)).collect())
+            }
+            Value::String(s) => Value::String(self.mask(&s)),
+            other => other,
+        }
+    }
+
+    /// 对文本应用所有正则掩码
+    fn mask(&self, text: &str) -> String {
+        self.masks.iter().fold(text.to_string(), |acc, re| {
+            re.replace_all(&acc, MASK_PLACEHOLDER).into_owned()
+        })
+    }
+}
+
+/// 递归比较两个JSON值，记录差异路径
+pub fn diff_json(expected: &Value, actual: &Value, path: &str, out: &mut Vec<BodyDifference>) {
+    match (expected, actual) {
+        (Value::Object(exp), Value::Object(act)) => {
+            for (key, exp_value) in exp {
+                let child = format!("{path}.{key}");
+                match act.get(key) {
+                    Some(act_value) => diff_json(exp_value, act_value, &child, out),
+                    None => out.push(BodyDifference {
+                        path: child,
+                        expected: Some(exp_value.to_string()),
+                        actual: None,
+                    }),
+                }
+            }
+            for (key, act_value) in act {
+                if !exp.contains_key(key) {
+                    out.push(BodyDifference {
+                        path: format!("{path}.{key}"),
+                        expected: None,
+                        actual: Some(act_value.to_string()),
+                    });
+                }
+            }
+        }
+        (Value::Array(exp), Value::Array(act)) => {
+            for (index, (exp_value, act_value)) in exp.iter().zip(act).enumerate() {
+                diff_json(exp_value, act_value, &format!("{path}[{index}]"), out);
+            }
+            for (index, exp_value) in exp.iter().enumerate().skip(act.len()) {
+                out.push(BodyDifference {
+                    path: format!("{path}[{index}]"),
+                    expected: Some(exp_value.to_string()),
+                    actual: None,
+                });
+            }
+            for (index, act_value) in act.iter().enumerate().skip(exp.len()) {
+                out.push(BodyDifference {
+                    path: format!("{path}[{index}]"),
+                    expected: None,
+                    actual: Some(act_value.to_string()),
+                });
+            }
+        }
+        _ if expected != actual => out.push(BodyDifference {
+            path: path.to_string(),
+            expected: Some(expected.to_string()),
+            actual: Some(actual.to_string()),
+        }),
+        _ => {}
+    }
+}
+
+/// 逐行比较文本
+fn diff_text(expected: &str, actual: &str) -> Vec<BodyDifference> {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let max = expected_lines.len().max(actual_lines.len());
+
+    (0..max)
+        .filter_map(|i| {
+            let exp = expected_lines.get(i).copied();
+            let act = actual_lines.get(i).copied();
+            (exp != act).then(|| BodyDifference {
+                path: format!("line {}", i + 1),
+                expected: exp.map(|s| format!("{s:?}")),
+                actual: act.map(|s| format!("{s:?}")),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,343 @@
+//! 格式化模块
+//!
+//! 将.http文件解析为保留注释的语法树 [`HttpDocument`]，再按统一风格输出：
+//! 变量声明写作 `@name = value`，请求行与请求头之间的空白归一，请求头名称
+//! 按单词首字母大写，响应处理脚本统一缩进4个空格，请求之间保留一个空行。
+//! 请求体内容（除行尾空白外）原样保留。
+
+use crate::assertion::ASSERTION_PREFIX;
+use crate::binary::ResponseRedirect;
+use crate::curl;
+use crate::handler;
+use crate::parser::{DEFAULTS_SECTION, is_request_line};
+use std::fmt;
+
+/// 脚本块和请求行续行的缩进
+const INDENT: &str = "    ";
+
+/// 请求块内的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Blank,
+    /// 注释、指令或其他原样保留的文本
+    Text(String),
+    Variable {
+        name: String,
+        value: String,
+    },
+    Header {
+        name: String,
+        value: String,
+    },
+    Assertion(String),
+    Redirect(ResponseRedirect),
+}
+
+impl Node {
+    /// 按行内容归类，`text` 为已去除多余空白的行
+    fn classify(text: &str) -> Self {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Self::Blank;
+        }
+        if let Some(rest) = trimmed.strip_prefix('@')
+            && let Some((name, value)) = rest.split_once('=')
+        {
+            return Self::Variable {
+                name: name.trim().to_string(),
+                value: value.trim().to_string(),
+            };
+        }
+        if let Some(assertion) = trimmed.strip_prefix(ASSERTION_PREFIX) {
+            return Self::Assertion(assertion.trim().to_string());
+        }
+        if let Some(redirect) = ResponseRedirect::parse(trimmed) {
+            return Self::Redirect(redirect);
+        }
+        Self::Text(text.to_string())
+    }
+
+    /// 请求头行；注释等非请求头内容按普通行归类
+    fn header(line: &str) -> Self {
+        let trimmed = line.trim();
+        let is_other = ["#", "//", "@", ASSERTION_PREFIX, ">"]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix));
+        match trimmed.split_once(':') {
+            Some((name, value)) if !is_other && !name.trim().is_empty() => Self::Header {
+                name: header_case(name.trim()),
+                value: value.trim().to_string(),
+            },
+            _ => Self::classify(trimmed),
+        }
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blank => Ok(()),
+            Self::Text(text) => write!(f, "{text}"),
+            Self::Variable { name, value } => write!(f, "@{name} = {value}"),
+            Self::Header { name, value } => write!(f, "{name}: {value}"),
+            Self::Assertion(assertion) => write!(f, "{ASSERTION_PREFIX} {assertion}"),
+            Self::Redirect(redirect) => {
+                let marker = match (redirect.overwrite, redirect.resume) {
+                    (true, _) => ">>!",
+                    (_, true) => ">>+",
+                    _ => ">>",
+                };
+                write!(f, "{marker} {}", redirect.path)
+            }
+        }
+    }
+}
+
+/// 以 `###` 开头的请求块
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestBlock {
+    pub title: String,
+    /// 请求行之前的注释和指令
+    pub prelude: Vec<Node>,
+    /// 请求行及其续行（查询参数或curl命令的后续行）
+    pub request_line: Vec<String>,
+    pub headers: Vec<Node>,
+    pub body: Vec<Node>,
+    /// 响应处理脚本（不含 `> {%` 和 `%}`）
+    pub script: Option<Vec<String>>,
+    /// 脚本之后的内容
+    pub trailer: Vec<Node>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Prelude,
+    Headers,
+    Body,
+    /// 响应处理器之后
+    Trailer,
+}
+
+impl RequestBlock {
+    fn parse(title_line: &str, lines: &[&str]) -> Self {
+        let mut block = Self {
+            title: title_line.trim()[3..].trim().to_string(),
+            ..Self::default()
+        };
+        // 默认请求头段落没有请求行，内容按请求头格式化
+        let mut phase = if block.title == DEFAULTS_SECTION {
+            Phase::Headers
+        } else {
+            Phase::Prelude
+        };
+        let mut script = Vec::new();
+        let handler = handler::find(lines);
+        let mut lines = lines.iter().enumerate().peekable();
+
+        while let Some((index, line)) = lines.next() {
+            let trimmed = line.trim();
+            if matches!(phase, Phase::Headers | Phase::Body)
+                && let Some(handler) = handler.as_ref().filter(|h| h.start == index)
+            {
+                // 标记统一写作 `> {%` 和 `%}`，没有结束标记时脚本延续到段落末尾
+                script.extend(handler.script.iter().map(|line| line.trim_end()));
+                let end = handler.end.unwrap_or(usize::MAX);
+                while lines.next_if(|(index, _)| *index < end).is_some() {}
+                phase = Phase::Trailer;
+                continue;
+            }
+            match phase {
+                Phase::Prelude if is_request_line(trimmed) => {
+                    if curl::is_curl_command(trimmed) {
+                        block.request_line.push(trimmed.to_string());
+                        let mut continued = trimmed.ends_with('\\');
+                        while continued && let Some((_, next)) = lines.next() {
+                            block.request_line.push(format!("{INDENT}{}", next.trim()));
+                            continued = next.trim_end().ends_with('\\');
+                        }
+                        phase = Phase::Body;
+                    } else {
+                        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+                        block.request_line.push(parts.join(" "));
+                        phase = Phase::Headers;
+                    }
+                }
+                Phase::Prelude => match Node::classify(trimmed) {
+                    Node::Blank => {}
+                    node => block.prelude.push(node),
+                },
+                Phase::Headers if trimmed.is_empty() => phase = Phase::Body,
+                Phase::Headers if is_query_line(trimmed) && block.headers.is_empty() => {
+                    block.request_line.push(format!("{INDENT}{trimmed}"));
+                }
+                Phase::Headers => block.headers.push(Node::header(line)),
+                Phase::Body => block.body.push(Node::classify(line.trim_end())),
+                Phase::Trailer => match Node::classify(trimmed) {
+                    Node::Blank => {}
+                    node => block.trailer.push(node),
+                },
+            }
+        }
+
+        trim_blanks(&mut block.body);
+        if phase == Phase::Trailer {
+            block.script = Some(reindent(&script));
+        }
+        block
+    }
+}
+
+impl fmt::Display for RequestBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.title.is_empty() {
+            writeln!(f, "###")?;
+        } else {
+            writeln!(f, "### {}", self.title)?;
+        }
+        for node in &self.prelude {
+            writeln!(f, "{node}")?;
+        }
+        for line in &self.request_line {
+            writeln!(f, "{line}")?;
+        }
+        for node in &self.headers {
+            writeln!(f, "{node}")?;
+        }
+        if !self.body.is_empty() {
+            writeln!(f)?;
+            for node in &self.body {
+                writeln!(f, "{node}")?;
+            }
+        }
+        if let Some(script) = &self.script {
+            writeln!(f)?;
+            writeln!(f, "> {{%")?;
+            for line in script {
+                writeln!(f, "{line}")?;
+            }
+            writeln!(f, "%}}")?;
+        }
+        for node in &self.trailer {
+            writeln!(f, "{node}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 保留注释的.http文件语法树
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpDocument {
+    /// 第一个请求之前的内容（文件变量、注释）
+    pub preamble: Vec<Node>,
+    pub requests: Vec<RequestBlock>,
+}
+
+impl HttpDocument {
+    /// 解析文件内容，不做变量替换和校验
+    pub fn parse(content: &str) -> Self {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut starts = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.trim().starts_with("###"))
+            .map(|(index, _)| index)
+            .peekable();
+
+        let first = starts.peek().copied().unwrap_or(lines.len());
+        let mut preamble: Vec<Node> = lines[..first]
+            .iter()
+            .map(|line| Node::classify(line.trim()))
+            .collect();
+        preamble.dedup_by(|a, b| *a == Node::Blank && *b == Node::Blank);
+        trim_blanks(&mut preamble);
+
+        let mut requests = Vec::new();
+        while let Some(start) = starts.next() {
+            let end = starts.peek().copied().unwrap_or(lines.len());
+            requests.push(RequestBlock::parse(lines[start], &lines[start + 1..end]));
+        }
+
+        Self { preamble, requests }
+    }
+}
+
+impl fmt::Display for HttpDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.preamble {
+            writeln!(f, "{node}")?;
+        }
+        for (index, request) in self.requests.iter().enumerate() {
+            if index > 0 || !self.preamble.is_empty() {
+                writeln!(f)?;
+            }
+            write!(f, "{request}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 格式化.http文件内容
+pub fn format(content: &str) -> String {
+    HttpDocument::parse(content).to_string()
+}
+
+/// 请求行之后的查询参数续行
+fn is_query_line(trimmed: &str) -> bool {
+    !trimmed.starts_with(ASSERTION_PREFIX) && trimmed.starts_with(['?', '&'])
+}
+
+/// 请求头名称按 `-` 分隔的单词首字母大写，其余字母保持原样
+fn header_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// 去除公共缩进后统一缩进脚本行
+fn reindent(lines: &[&str]) -> Vec<String> {
+    let expanded: Vec<String> = lines
+        .iter()
+        .map(|line| line.replace('\t', INDENT))
+        .collect();
+    let indent = expanded
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut script: Vec<String> = expanded
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{INDENT}{}", &line[indent..])
+            }
+        })
+        .collect();
+    while script.first().is_some_and(String::is_empty) {
+        script.remove(0);
+    }
+    while script.last().is_some_and(String::is_empty) {
+        script.pop();
+    }
+    script
+}
+
+/// 去除首尾空行
+fn trim_blanks(nodes: &mut Vec<Node>) {
+    while nodes.first() == Some(&Node::Blank) {
+        nodes.remove(0);
+    }
+    while nodes.last() == Some(&Node::Blank) {
+        nodes.pop();
+    }
+}
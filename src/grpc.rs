@@ -0,0 +1,269 @@
+//! GRPC请求模块
+//!
+//! `GRPC localhost:50051/pkg.Service/Method` 请求按 `# @proto ./api.desc` 指定的描述符集
+//! （`protoc --include_imports --descriptor_set_out=api.desc`）把JSON请求体转码为protobuf，
+//! 以HTTP/2发送，再把响应消息解码为JSON，脚本、断言和捕获与普通请求一样使用。
+//! 响应头包含尾部的 `grpc-status` 和 `grpc-message`。
+//!
+//! 不带scheme或使用 `grpc://`、`http://` 时以明文（h2c）连接，`grpcs://`、`https://` 使用TLS。
+
+use crate::error::{HttpieError, Result};
+use serde::{Deserialize, Serialize};
+
+/// GRPC请求的请求行方法
+pub const GRPC_METHOD: &str = "GRPC";
+
+/// 指定描述符集文件的指令（`# @proto ./api.desc`），相对路径基于.http文件所在目录
+pub const PROTO_DIRECTIVE: &str = "proto";
+
+/// 要调用的GRPC方法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GrpcCall {
+    /// 完整的服务名（`pkg.Service`）
+    pub service: String,
+    pub method: String,
+}
+
+impl GrpcCall {
+    /// 解析 `GRPC` 之后的目标，返回发送使用的URL和调用的方法
+    pub fn parse(target: &str) -> Result<(String, Self)> {
+        let invalid = |reason: &str| {
+            HttpieError::InvalidRequest(format!("Invalid GRPC target '{target}': {reason}"))
+        };
+
+        let (scheme, rest) = match target.split_once("://") {
+            Some(("grpc" | "http", rest)) => ("http", rest),
+            Some(("grpcs" | "https", rest)) => ("https", rest),
+            Some((scheme, _)) => return Err(invalid(&format!("unsupported scheme '{scheme}'"))),
+            None => ("http", target),
+        };
+        let Some((authority, path)) = rest.split_once('/') else {
+            return Err(invalid("expected host/pkg.Service/Method"));
+        };
+        let (service, method) = match path.split_once('/') {
+            Some((service, method))
+                if !authority.is_empty()
+                    && !service.is_empty()
+                    && !method.is_empty()
+                    && !method.contains('/') =>
+            {
+                (service, method)
+            }
+            _ => return Err(invalid("expected host/pkg.Service/Method")),
+        };
+
+        let call = Self {
+            service: service.to_string(),
+            method: method.to_string(),
+        };
+        Ok((format!("{scheme}://{authority}/{service}/{method}"), call))
+    }
+}
+
+impl std::fmt::Display for GrpcCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.service, self.method)
+    }
+}
+
+/// 未启用 `grpc` 特性时发送GRPC请求的错误
+#[cfg(not(feature = "grpc"))]
+pub(crate) fn grpc_disabled(request: &crate::models::HttpRequest) -> HttpieError {
+    HttpieError::InvalidRequest(format!(
+        "Request '{}' is a GRPC request, but httpie was built without the 'grpc' feature",
+        request.name
+    ))
+}
+
+#[cfg(feature = "grpc")]
+pub use transcode::{decode_messages, encode_message, load_method};
+
+#[cfg(feature = "grpc")]
+pub(crate) use transcode::fetch;
+
+#[cfg(feature = "grpc")]
+mod transcode {
+    use super::{GrpcCall, PROTO_DIRECTIVE};
+    use crate::client::RawResponse;
+    use crate::error::{HttpieError, Result};
+    use crate::models::HttpRequest;
+    use crate::upload;
+    use bytes::{BufMut, Bytes, BytesMut};
+    use http_body_util::BodyExt;
+    use prost::Message;
+    use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+    use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+    use reqwest::{Client, Version};
+    use serde_json::{Value, json};
+    use std::fs;
+    use std::time::Instant;
+
+    /// 消息前缀：1字节压缩标志和4字节大端长度
+    const PREFIX_LEN: usize = 5;
+
+    /// 从描述符集文件中查找方法
+    pub fn load_method(path: &std::path::Path, call: &GrpcCall) -> Result<MethodDescriptor> {
+        let bytes =
+            fs::read(path).map_err(|_| HttpieError::FileNotFound(path.display().to_string()))?;
+        let pool = DescriptorPool::decode(bytes.as_slice()).map_err(|e| {
+            HttpieError::Parse(format!("Invalid descriptor set {}: {e}", path.display()))
+        })?;
+        let service = pool.get_service_by_name(&call.service).ok_or_else(|| {
+            HttpieError::InvalidRequest(format!(
+                "Service '{}' not found in {}",
+                call.service,
+                path.display()
+            ))
+        })?;
+        service
+            .methods()
+            .find(|method| method.name() == call.method)
+            .ok_or_else(|| {
+                HttpieError::InvalidRequest(format!(
+                    "Method '{}' not found in service '{}'",
+                    call.method, call.service
+                ))
+            })
+    }
+
+    /// 把JSON（为空时视为 `{}`）转码为带前缀的protobuf消息
+    pub fn encode_message(descriptor: MessageDescriptor, json: &str) -> Result<Bytes> {
+        let json = if json.trim().is_empty() { "{}" } else { json };
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let message = DynamicMessage::deserialize(descriptor.clone(), &mut deserializer)
+            .and_then(|message| deserializer.end().map(|_| message))
+            .map_err(|e| {
+                HttpieError::InvalidRequest(format!(
+                    "Invalid {} message: {e}",
+                    descriptor.full_name()
+                ))
+            })?;
+
+        let encoded = message.encode_to_vec();
+        let mut framed = BytesMut::with_capacity(PREFIX_LEN + encoded.len());
+        framed.put_u8(0);
+        framed.put_u32(encoded.len() as u32);
+        framed.extend_from_slice(&encoded);
+        Ok(framed.freeze())
+    }
+
+    /// 把带前缀的protobuf消息解码为JSON
+    pub fn decode_messages(descriptor: MessageDescriptor, mut data: &[u8]) -> Result<Vec<Value>> {
+        let invalid = |reason: String| {
+            HttpieError::Parse(format!(
+                "Invalid {} response: {reason}",
+                descriptor.full_name()
+            ))
+        };
+
+        let mut messages = Vec::new();
+        while !data.is_empty() {
+            if data.len() < PREFIX_LEN {
+                return Err(invalid("truncated message prefix".to_string()));
+            }
+            if data[0] != 0 {
+                return Err(invalid("compressed messages are not supported".to_string()));
+            }
+            let len = u32::from_be_bytes([data[1], data[2], data[3], data[4]]) as usize;
+            let Some(payload) = data.get(PREFIX_LEN..PREFIX_LEN + len) else {
+                return Err(invalid("truncated message".to_string()));
+            };
+            let message = DynamicMessage::decode(descriptor.clone(), payload)
+                .map_err(|e| invalid(e.to_string()))?;
+            messages.push(serde_json::to_value(&message)?);
+            data = &data[PREFIX_LEN + len..];
+        }
+        Ok(messages)
+    }
+
+    /// 发送GRPC请求，响应体为解码后的JSON：一条消息时为该消息，多条（服务端流）时为数组，
+    /// 没有消息且状态非0时为 `{"code", "message"}`
+    pub(crate) async fn fetch(
+        client: &Client,
+        request: &HttpRequest,
+        call: &GrpcCall,
+    ) -> Result<RawResponse> {
+        let proto = request.directive(PROTO_DIRECTIVE).ok_or_else(|| {
+            HttpieError::InvalidRequest(format!(
+                "GRPC request '{}' requires a '# @{PROTO_DIRECTIVE}' directive",
+                request.name
+            ))
+        })?;
+        let method = load_method(&request.resolve_path(proto.trim()), call)?;
+        let json = match upload::read_body_file(request)? {
+            Some(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            None => request.body.clone().unwrap_or_default(),
+        };
+        let body = encode_message(method.input(), &json)?;
+
+        let mut builder = client
+            .post(request.target_url()?)
+            .version(Version::HTTP_2)
+            .header(CONTENT_TYPE, "application/grpc")
+            .header("te", "trailers");
+        // 其余请求头作为GRPC元数据发送
+        for (key, value) in &request.headers {
+            if !key.eq_ignore_ascii_case("content-type") && !key.eq_ignore_ascii_case("te") {
+                builder = builder.header(key, value);
+            }
+        }
+
+        let started = Instant::now();
+        let response = builder.body(body).send().await?;
+        let status = response.status().as_u16();
+        let version = response.version();
+        let remote_addr = response.remote_addr();
+        let mut headers = response.headers().clone();
+        let mut data = BytesMut::new();
+        let mut body = reqwest::Body::from(response);
+        while let Some(frame) = body.frame().await {
+            match frame?.into_data() {
+                Ok(chunk) => data.extend_from_slice(&chunk),
+                Err(frame) => {
+                    if let Ok(trailers) = frame.into_trailers() {
+                        headers.extend(trailers);
+                    }
+                }
+            }
+        }
+        let duration = started.elapsed();
+
+        let mut messages = decode_messages(method.output(), &data)?;
+        let grpc_status = header(&headers, "grpc-status");
+        let json = match messages.len() {
+            0 => match grpc_status.as_deref() {
+                Some(code) if code != "0" => json!({
+                    "code": code.parse::<i64>().map_or(Value::from(code), Value::from),
+                    "message": header(&headers, "grpc-message").unwrap_or_default(),
+                }),
+                _ => Value::Null,
+            },
+            1 => messages.remove(0),
+            _ => Value::Array(messages),
+        };
+        let bytes = match json {
+            Value::Null => Bytes::new(),
+            json => Bytes::from(serde_json::to_vec_pretty(&json)?),
+        };
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        Ok(RawResponse {
+            status,
+            version,
+            headers,
+            bytes,
+            duration,
+            truncated: false,
+            remote_addr,
+            tls: None,
+            redirects: Vec::new(),
+        })
+    }
+
+    fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    }
+}
@@ -0,0 +1,133 @@
+//! 响应处理器块识别模块
+//!
+//! 响应处理器以 `> {%` 开始、`%}` 结束。标记内部和前后的空白不影响识别，
+//! 也可以拆成多行书写（`>` 换行 `{%`、`> {` 换行 `%`，结束时 `%` 换行 `}`），
+//! 还可以写在同一行：`> {% client.log(1) %}`。
+
+/// 段落中的响应处理器块
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerBlock<'a> {
+    /// 开始标记的第一行
+    pub start: usize,
+    /// 结束标记之后的第一行；没有结束标记时为None
+    pub end: Option<usize>,
+    /// 脚本各行（不含标记）
+    pub script: Vec<&'a str>,
+}
+
+impl HandlerBlock<'_> {
+    /// 脚本内容（去掉首尾空白）
+    pub fn script(&self) -> String {
+        self.script.join("\n").trim().to_string()
+    }
+}
+
+/// 识别状态
+#[derive(Clone, Copy)]
+enum State {
+    Outside,
+    /// 开始标记已读到 `>`，起始行
+    Gt(usize),
+    /// 开始标记已读到 `> {`，起始行
+    GtBrace(usize),
+    /// 脚本内，起始行
+    Inside(usize),
+    /// 脚本内读到单独的 `%`，可能是拆行的结束标记
+    Percent(usize),
+}
+
+/// 查找第一个响应处理器块
+pub fn find<'a>(lines: &[&'a str]) -> Option<HandlerBlock<'a>> {
+    let mut state = State::Outside;
+    let mut script = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim();
+        state = match state {
+            State::Outside => match inline_open(trimmed) {
+                Some(rest) => match rest.strip_suffix("%}") {
+                    Some(inline) => {
+                        script.push(inline.trim());
+                        return Some(HandlerBlock {
+                            start: index,
+                            end: Some(index + 1),
+                            script,
+                        });
+                    }
+                    None => {
+                        if !rest.trim().is_empty() {
+                            script.push(rest.trim());
+                        }
+                        State::Inside(index)
+                    }
+                },
+                None if is_marker(trimmed, ">") => State::Gt(index),
+                None if is_marker(trimmed, ">{") => State::GtBrace(index),
+                None => State::Outside,
+            },
+            State::Gt(start) if is_marker(trimmed, "{%") => State::Inside(start),
+            State::Gt(start) if is_marker(trimmed, "{") => State::GtBrace(start),
+            State::GtBrace(start) if is_marker(trimmed, "%") => State::Inside(start),
+            // 不是开始标记，按普通行重新识别
+            State::Gt(_) | State::GtBrace(_) => {
+                state = State::Outside;
+                continue;
+            }
+            State::Inside(start) | State::Percent(start) if is_marker(trimmed, "%}") => {
+                return Some(closed(start, index + 1, script));
+            }
+            State::Inside(start) if is_marker(trimmed, "%") => State::Percent(start),
+            State::Inside(start) => match trimmed.strip_suffix("%}") {
+                Some(last) => {
+                    script.push(last.trim_end());
+                    return Some(closed(start, index + 1, script));
+                }
+                None => {
+                    script.push(line);
+                    State::Inside(start)
+                }
+            },
+            State::Percent(start) if is_marker(trimmed, "}") => {
+                return Some(closed(start, index + 1, script));
+            }
+            // 单独的 `%` 属于脚本
+            State::Percent(start) => {
+                script.push(lines[index - 1]);
+                state = State::Inside(start);
+                continue;
+            }
+        };
+        index += 1;
+    }
+
+    match state {
+        State::Inside(start) | State::Percent(start) => Some(HandlerBlock {
+            start,
+            end: None,
+            script,
+        }),
+        _ => None,
+    }
+}
+
+fn closed(start: usize, end: usize, script: Vec<&str>) -> HandlerBlock<'_> {
+    HandlerBlock {
+        start,
+        end: Some(end),
+        script,
+    }
+}
+
+/// `> {%` 开头的行返回其后的内容
+fn inline_open(trimmed: &str) -> Option<&str> {
+    trimmed.strip_prefix('>')?.trim_start().strip_prefix("{%")
+}
+
+/// 忽略空白后是否等于标记
+fn is_marker(trimmed: &str, marker: &str) -> bool {
+    trimmed
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .eq(marker.chars())
+}
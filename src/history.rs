@@ -0,0 +1,373 @@
+//! 请求历史模块
+//!
+//! 把每次执行的请求和响应摘要（可选包含响应体）追加到本地JSONL文件，
+//! 供 `httpie history` 列出、查看和重新执行。
+//!
+//! 认证、Cookie相关的头和值来自密钥的头在写入前替换为 [`MASKED_VALUE`]，
+//! 历史文件和所在目录仅当前用户可访问。
+
+use crate::environment::{create_private_dir, open_private};
+use crate::error::{HttpieError, Result};
+use crate::models::{Environment, Headers, HttpRequest};
+use crate::observer::ExecutionObserver;
+use crate::runner::RequestReport;
+use crate::script::ResponseObject;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// 历史文件路径（相对当前目录）
+pub const DEFAULT_HISTORY_FILE: &str = ".httpie/history.jsonl";
+
+/// 屏蔽后写入历史的请求头或响应头的值
+pub const MASKED_VALUE: &str = "********";
+
+/// 值总是屏蔽的请求头和响应头（不区分大小写）
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+/// 一条历史记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// 从1开始递增的编号
+    pub id: u64,
+    /// 执行时间（Unix时间戳，秒）
+    pub timestamp: u64,
+    pub name: String,
+    pub method: String,
+    /// 实际发送的URL（已填充路径参数和查询参数）
+    pub url: String,
+    /// 请求指纹（见 [`HttpRequest::fingerprint`]），旧记录中没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    #[serde(default)]
+    pub request_headers: Headers,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    /// 请求体文件（`< ./file`）的绝对路径，重新执行时再读取
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub response_headers: Headers,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    /// 还原为可重新执行的请求，已屏蔽的请求头不会发送
+    pub fn to_request(&self) -> Result<HttpRequest> {
+        let method = Method::from_bytes(self.method.as_bytes())
+            .map_err(|_| HttpieError::InvalidMethod(self.method.clone()))?;
+        let (masked, headers): (Headers, Headers) = self
+            .request_headers
+            .clone()
+            .into_iter()
+            .partition(|(_, value)| value == MASKED_VALUE);
+        if !masked.is_empty() {
+            let names: Vec<&str> = masked.keys().map(String::as_str).collect();
+            warn!(
+                "History entry #{} was recorded with masked headers, not sending: {}",
+                self.id,
+                names.join(", ")
+            );
+        }
+        let mut request = HttpRequest::new(self.name.clone(), method, self.url.clone())
+            .with_headers(headers)
+            .with_body_file(self.body_file.clone());
+        request.body = self.request_body.clone();
+        Ok(request)
+    }
+
+    /// `history list` 中的一行：编号、距 `now` 的时间、状态码、请求行、耗时和名称
+    pub fn summary_line(&self, now: u64) -> String {
+        let status = self
+            .status
+            .map_or_else(|| "ERR".to_string(), |status| status.to_string());
+        format!(
+            "#{:<5} {:>8}  {:<4} {} {} ({} ms)  {}",
+            self.id,
+            format_age(now.saturating_sub(self.timestamp)),
+            status,
+            self.method,
+            self.url,
+            self.duration_ms,
+            self.name
+        )
+    }
+}
+
+/// `history show` 的完整记录：请求、状态或错误、响应头和响应体
+impl fmt::Display for HistoryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "#{} {}", self.id, self.name)?;
+        writeln!(f, "{} {}", self.method, self.url)?;
+        if let Some(fingerprint) = &self.fingerprint {
+            writeln!(f, "Fingerprint: {fingerprint}")?;
+        }
+        for (name, value) in &self.request_headers {
+            writeln!(f, "{name}: {value}")?;
+        }
+        if let Some(body) = &self.request_body {
+            writeln!(f, "\n{body}")?;
+        }
+        writeln!(f)?;
+        match (&self.error, self.status) {
+            (Some(error), _) => writeln!(f, "Error: {error}")?,
+            (None, Some(status)) => writeln!(f, "Status: {status} ({} ms)", self.duration_ms)?,
+            (None, None) => {}
+        }
+        for (name, value) in &self.response_headers {
+            writeln!(f, "{name}: {value}")?;
+        }
+        if let Some(body) = &self.response_body {
+            writeln!(f, "\n{body}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 当前的Unix时间戳（秒）
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// 以最大的时间单位显示经过的时长，如 `5m ago`
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+/// 历史记录存储
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    /// 使用指定的JSONL文件
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 读取全部记录（按执行顺序），文件不存在时返回空列表，无法解析的行输出警告后跳过
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)?;
+        let entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(index, line)| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!(
+                        "Skipping invalid history entry at {}:{}: {e}",
+                        self.path.display(),
+                        index + 1
+                    );
+                    None
+                }
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    /// 按编号查找记录
+    pub fn get(&self, id: u64) -> Result<HistoryEntry> {
+        self.entries()?
+            .into_iter()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| HttpieError::InvalidRequest(format!("History entry #{id} not found")))
+    }
+
+    /// 追加记录，编号为最后一条记录的编号加一，返回分配的编号
+    pub fn append(&self, mut entry: HistoryEntry) -> Result<u64> {
+        entry.id = self.last_id()? + 1;
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            create_private_dir(dir)?;
+        }
+        let mut file = open_private(&self.path, OpenOptions::new().append(true))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(entry.id)
+    }
+
+    /// 最后一条可解析记录的编号，没有记录时为0
+    ///
+    /// 只从文件末尾向前读取到找到完整的一行为止，不解析整个文件。
+    fn last_id(&self) -> Result<u64> {
+        #[derive(Deserialize)]
+        struct Id {
+            id: u64,
+        }
+
+        let Ok(mut file) = File::open(&self.path) else {
+            return Ok(0);
+        };
+        let len = file.metadata()?.len();
+        let mut window = 4096;
+        loop {
+            let start = len.saturating_sub(window);
+            file.seek(SeekFrom::Start(start))?;
+            let mut tail = Vec::new();
+            file.read_to_end(&mut tail)?;
+            let tail = String::from_utf8_lossy(&tail);
+            let mut lines = tail.lines();
+            // 窗口不在文件开头时第一行可能不完整
+            if start > 0 {
+                lines.next();
+            }
+            if let Some(id) = lines
+                .rev()
+                .find_map(|line| serde_json::from_str::<Id>(line).ok())
+            {
+                return Ok(id.id);
+            }
+            if start == 0 {
+                return Ok(0);
+            }
+            window *= 4;
+        }
+    }
+}
+
+/// 屏蔽敏感的头：认证和Cookie相关的头，以及值中包含 `secrets` 中任一密钥的头
+fn mask_headers(headers: &Headers, secrets: &[String]) -> Headers {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let sensitive = SENSITIVE_HEADERS
+                .iter()
+                .any(|sensitive| name.eq_ignore_ascii_case(sensitive))
+                || secrets
+                    .iter()
+                    .any(|secret| !secret.is_empty() && value.contains(secret.as_str()));
+            let value = if sensitive {
+                MASKED_VALUE.to_string()
+            } else {
+                value.clone()
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// 把执行的请求写入历史的观察者
+pub struct HistoryRecorder {
+    history: History,
+    include_bodies: bool,
+    environment: Option<Environment>,
+    response: Option<ResponseObject>,
+}
+
+impl HistoryRecorder {
+    pub fn new(history: History) -> Self {
+        Self {
+            history,
+            include_bodies: false,
+            environment: None,
+            response: None,
+        }
+    }
+
+    /// 同时屏蔽值中包含该环境已读取密钥的请求头和响应头
+    pub fn with_environment(mut self, environment: &Environment) -> Self {
+        self.environment = Some(environment.clone());
+        self
+    }
+
+    /// 同时记录响应体（请求体始终记录，用于重新执行）
+    pub fn with_bodies(mut self, include_bodies: bool) -> Self {
+        self.include_bodies = include_bodies;
+        self
+    }
+}
+
+impl ExecutionObserver for HistoryRecorder {
+    fn on_response(
+        &mut self,
+        _request: &HttpRequest,
+        response: &ResponseObject,
+        _duration: Duration,
+    ) {
+        self.response = Some(response.clone());
+    }
+
+    fn on_request_complete(&mut self, request: &HttpRequest, report: &RequestReport) {
+        // 跳过的请求没有发送，不记录
+        if report.is_skipped() {
+            return;
+        }
+        let response = self.response.take();
+        let response_body =
+            response
+                .as_ref()
+                .filter(|_| self.include_bodies)
+                .and_then(|response| match &response.body {
+                    Value::Null => None,
+                    Value::String(text) => Some(text.clone()),
+                    body => Some(body.to_string()),
+                });
+        let secrets = self
+            .environment
+            .as_ref()
+            .map(Environment::resolved_secret_values)
+            .unwrap_or_default();
+        let body_file = request.body_file.as_deref().map(|path| {
+            let path = request.resolve_path(path);
+            std::path::absolute(&path)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        });
+        let entry = HistoryEntry {
+            id: 0,
+            timestamp: unix_timestamp(),
+            name: request.name.clone(),
+            method: request.method.to_string(),
+            url: request
+                .target_url()
+                .map_or_else(|_| request.url.clone(), String::from),
+            fingerprint: Some(request.fingerprint()),
+            request_headers: mask_headers(&request.headers, &secrets),
+            request_body: request.body.clone(),
+            body_file,
+            status: report.status,
+            duration_ms: report.duration.as_millis() as u64,
+            response_headers: response
+                .map(|response| mask_headers(&response.headers, &secrets))
+                .unwrap_or_default(),
+            response_body,
+            error: report.error.clone(),
+        };
+        if let Err(e) = self.history.append(entry) {
+            warn!("Failed to record history: {}", e);
+        }
+    }
+}
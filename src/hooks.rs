@@ -0,0 +1,151 @@
+//! 全局脚本钩子模块
+//!
+//! `httpie.hooks.js`（或配置文件 `[scripts] hooks` 指定的脚本）中导出的
+//! `beforeAll`、`beforeEach`、`afterEach`、`afterAll` 函数在整次运行和每个请求前后执行，
+//! 用于准备数据、计时或自定义报告。导出可以写作 `export function beforeAll(context)`，
+//! 也可以写作 `module.exports = { beforeAll }` 或 `exports.beforeAll = ...`。
+//!
+//! 钩子与响应处理器共享脚本引擎：`client.global.set` 设置的变量可供后续请求引用，
+//! 参数 `context.variables` 是当前的运行期变量。
+
+use crate::error::{HttpieError, Result};
+use crate::modules;
+use crate::script::ScriptOrigin;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 默认的钩子脚本文件
+pub const DEFAULT_HOOKS_FILE: &str = "httpie.hooks.js";
+
+/// [`ScriptHooks::program`] 在源码之前插入的行数
+const PROGRAM_PRELUDE_LINES: usize = 3;
+
+/// 钩子函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptHook {
+    /// 运行开始前，参数 `{ variables }`；抛出异常时不发送任何请求
+    BeforeAll,
+    /// 每个请求发送前，参数 `{ request, index, total, variables }`
+    BeforeEach,
+    /// 每个请求完成后，参数 `{ request, result, variables }`
+    AfterEach,
+    /// 运行结束后，参数 `{ report, variables }`
+    AfterAll,
+}
+
+impl ScriptHook {
+    pub const ALL: [Self; 4] = [
+        Self::BeforeAll,
+        Self::BeforeEach,
+        Self::AfterEach,
+        Self::AfterAll,
+    ];
+
+    /// 导出的函数名
+    pub fn function_name(self) -> &'static str {
+        match self {
+            Self::BeforeAll => "beforeAll",
+            Self::BeforeEach => "beforeEach",
+            Self::AfterEach => "afterEach",
+            Self::AfterAll => "afterAll",
+        }
+    }
+}
+
+impl fmt::Display for ScriptHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.function_name())
+    }
+}
+
+/// 已读取的钩子脚本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptHooks {
+    path: PathBuf,
+    source: String,
+    /// 链接导入时插入在源码之前的行数
+    prelude_lines: usize,
+}
+
+impl ScriptHooks {
+    pub fn new(path: impl Into<PathBuf>, source: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            source: source.into(),
+            prelude_lines: 0,
+        }
+    }
+
+    /// 读取钩子脚本，并链接其中相对于脚本所在目录的导入
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| {
+            HttpieError::ScriptError(format!("Failed to read hooks {}: {e}", path.display()))
+        })?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let linked = modules::link(&source, dir)?;
+        let prelude_lines = linked
+            .lines()
+            .count()
+            .saturating_sub(source.lines().count());
+        Ok(Self {
+            prelude_lines,
+            ..Self::new(path, linked)
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// [`program`](Self::program) 在钩子文件中的位置，用于映射钩子错误的行号
+    pub fn origin(&self) -> ScriptOrigin {
+        ScriptOrigin {
+            file: Some(self.path.display().to_string()),
+            line: 1,
+            prelude_lines: PROGRAM_PRELUDE_LINES + self.prelude_lines,
+        }
+    }
+
+    /// 在脚本引擎中注册钩子的程序
+    ///
+    /// 脚本在独立的函数作用域中执行，导出的函数（以及同名的顶层函数）
+    /// 保存到 `globalThis.__httpie_hooks`。
+    pub fn program(&self) -> String {
+        let mut program = String::from(
+            "globalThis.__httpie_hooks = (function () {\n\
+             var module = { exports: {} };\n\
+             var exports = module.exports;\n",
+        );
+        for line in self.source.lines() {
+            program.push_str(strip_export(line));
+            program.push('\n');
+        }
+        program.push_str(";\nvar hooks = {};\n");
+        for hook in ScriptHook::ALL {
+            let name = hook.function_name();
+            program.push_str(&format!(
+                "hooks.{name} = typeof module.exports.{name} === \"function\" ? module.exports.{name} \
+                 : typeof {name} === \"function\" ? {name} : undefined;\n"
+            ));
+        }
+        program.push_str("return hooks;\n})();\n");
+        program
+    }
+}
+
+/// 去掉 `export function`、`export const` 等声明前的 `export`
+fn strip_export(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    match trimmed.strip_prefix("export ") {
+        Some(rest)
+            if ["function", "async ", "const ", "let ", "var "]
+                .iter()
+                .any(|keyword| rest.trim_start().starts_with(keyword)) =>
+        {
+            rest.trim_start()
+        }
+        _ => line,
+    }
+}
@@ -0,0 +1,328 @@
+// 脚本运行时的固定脚手架，在构建期写入启动快照。
+// 每个请求只需注入 response 对象并重置测试结果。
+
+globalThis.__httpie_globals = globalThis.__httpie_globals || {};
+globalThis.__httpie_tests = [];
+globalThis.__httpie_control = null;
+globalThis.__httpie_logs = [];
+// 正在执行的 client.test 的名称，日志按测试归属
+globalThis.__httpie_current_test = null;
+// client.skip()/client.exit() 抛出的哨兵，用于立即结束脚本；client.test 不把它记为失败
+globalThis.__httpie_stop = { toString: function () { return "httpie: script stopped"; } };
+
+function stopScript(action, reason) {
+    globalThis.__httpie_control = {
+        action: action,
+        reason: reason === undefined || reason === null ? "" : String(reason),
+    };
+    throw globalThis.__httpie_stop;
+}
+
+function formatLogValue(value) {
+    if (typeof value === "string" || value instanceof Error) {
+        return String(value);
+    }
+    try {
+        const json = JSON.stringify(value);
+        return json === undefined ? String(value) : json;
+    } catch (error) {
+        return String(value);
+    }
+}
+
+// 记录日志，随测试结果一起输出到报告
+function writeLog(level, args) {
+    globalThis.__httpie_logs.push({
+        level: level,
+        message: args.map(formatLogValue).join(" "),
+        test: globalThis.__httpie_current_test,
+    });
+}
+
+// 客户端对象
+globalThis.client = {
+    global: {
+        set: function (key, value) {
+            globalThis.__httpie_globals[key] = value;
+        },
+        get: function (key) {
+            return globalThis.__httpie_globals[key];
+        },
+    },
+    test: function (name, testFn) {
+        const started = Date.now();
+        const outer = globalThis.__httpie_current_test;
+        globalThis.__httpie_current_test = name;
+        try {
+            testFn();
+            globalThis.__httpie_tests.push({
+                name: name,
+                passed: true,
+                message: null,
+                duration_ms: Date.now() - started,
+                error_kind: null,
+            });
+        } catch (error) {
+            if (error === globalThis.__httpie_stop) {
+                throw error;
+            }
+            globalThis.__httpie_tests.push({
+                name: name,
+                passed: false,
+                message: error && error.message !== undefined ? error.message : String(error),
+                duration_ms: Date.now() - started,
+                error_kind: (error && error.name) || "Error",
+            });
+        } finally {
+            globalThis.__httpie_current_test = outer;
+        }
+    },
+    assert: function (condition, message) {
+        globalThis.assert(condition, message);
+    },
+    // 把当前请求标记为跳过并结束脚本
+    skip: function (reason) {
+        stopScript("skip", reason);
+    },
+    // 中止运行：不再发送后续请求（后置钩子仍会执行）并结束脚本
+    exit: function (reason) {
+        stopScript("exit", reason);
+    },
+    log: function (...args) {
+        writeLog("log", args);
+    },
+    warn: function (...args) {
+        writeLog("warn", args);
+    },
+    error: function (...args) {
+        writeLog("error", args);
+    },
+};
+
+// 控制台对象，与 client.log 等相同
+globalThis.console = {
+    log: globalThis.client.log,
+    info: globalThis.client.log,
+    warn: globalThis.client.warn,
+    error: globalThis.client.error,
+};
+
+// 全局assert函数
+globalThis.assert = function (condition, message) {
+    if (!condition) {
+        const error = new Error(message || "Assertion failed");
+        error.name = "AssertionError";
+        throw error;
+    }
+};
+
+// HTML辅助函数：css(html, "a.link@href") 返回第一个匹配的文本或属性，cssAll 返回全部匹配。
+// 选择器支持标签、*、#id、.class、[attr]、[attr=v]（及 ^= $= *=）、后代和子元素（>）组合，
+// 末尾的 @属性名 取属性值，否则取去掉多余空白的文本内容。
+(function () {
+    const VOID_ELEMENTS = ["area", "base", "br", "col", "embed", "hr", "img", "input",
+        "link", "meta", "param", "source", "track", "wbr"];
+    const RAW_TEXT_ELEMENTS = ["script", "style", "textarea", "title"];
+    // 打开这些元素时隐式闭合的同级元素（如未闭合的 <li>）
+    const IMPLIED_END = {
+        li: ["li"], dt: ["dt", "dd"], dd: ["dt", "dd"], p: ["p"], option: ["option"],
+        tr: ["tr", "td", "th"], td: ["td", "th"], th: ["td", "th"],
+    };
+    const ENTITIES = { amp: "&", lt: "<", gt: ">", quot: "\"", apos: "'", nbsp: " " };
+
+    function decodeEntities(text) {
+        return text.replace(/&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);/g, function (entity, code) {
+            if (code[0] === "#") {
+                const point = code[1] === "x" || code[1] === "X"
+                    ? parseInt(code.slice(2), 16)
+                    : parseInt(code.slice(1), 10);
+                return String.fromCodePoint(point);
+            }
+            return ENTITIES[code.toLowerCase()] !== undefined ? ENTITIES[code.toLowerCase()] : entity;
+        });
+    }
+
+    function parseAttributes(source) {
+        const attributes = {};
+        const pattern = /([^\s"'>\/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+)))?/g;
+        let match;
+        while ((match = pattern.exec(source)) !== null) {
+            const value = match[2] !== undefined ? match[2] : match[3] !== undefined ? match[3] : match[4];
+            attributes[match[1].toLowerCase()] = value === undefined ? "" : decodeEntities(value);
+        }
+        return attributes;
+    }
+
+    // 宽松解析：未闭合的元素在父元素闭合时一并闭合，多余的闭合标签被忽略
+    function parseHtml(html) {
+        const root = { tag: "#root", attributes: {}, children: [], parent: null };
+        const pattern = /<!--[\s\S]*?-->|<![^>]*>|<(\/?)([a-zA-Z][\w:-]*)((?:\s+[^\s"'>\/=]+(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s>]+))?)*)\s*(\/?)>|[^<]+|</g;
+        let current = root;
+        let match;
+        while ((match = pattern.exec(html)) !== null) {
+            const token = match[0];
+            if (match[2] === undefined) {
+                if (token[0] !== "<" || token === "<") {
+                    current.children.push(decodeEntities(token));
+                }
+                continue;
+            }
+            const tag = match[2].toLowerCase();
+            if (match[1]) {
+                let node = current;
+                while (node !== root && node.tag !== tag) {
+                    node = node.parent;
+                }
+                if (node !== root) {
+                    current = node.parent;
+                }
+                continue;
+            }
+            const implied = IMPLIED_END[tag] || [];
+            while (current !== root && implied.indexOf(current.tag) >= 0) {
+                current = current.parent;
+            }
+            const element = { tag: tag, attributes: parseAttributes(match[3]), children: [], parent: current };
+            current.children.push(element);
+            if (RAW_TEXT_ELEMENTS.indexOf(tag) >= 0) {
+                const end = html.toLowerCase().indexOf("</" + tag, pattern.lastIndex);
+                const stop = end < 0 ? html.length : end;
+                element.children.push(decodeEntities(html.slice(pattern.lastIndex, stop)));
+                pattern.lastIndex = stop;
+            } else if (!match[4] && VOID_ELEMENTS.indexOf(tag) < 0) {
+                current = element;
+            }
+        }
+        return root;
+    }
+
+    function parseCompound(source) {
+        const compound = { tag: null, id: null, classes: [], attributes: [] };
+        const pattern = /^(\*|[a-zA-Z][\w-]*)|#([\w-]+)|\.([\w-]+)|\[\s*([^\s~|^$*=\]]+)\s*(?:([\^$*]?=)\s*(?:"([^"]*)"|'([^']*)'|([^\]\s]*)))?\s*\]/g;
+        let match;
+        let consumed = 0;
+        while ((match = pattern.exec(source)) !== null && match.index === consumed) {
+            consumed += match[0].length;
+            if (match[1] !== undefined) {
+                compound.tag = match[1] === "*" ? null : match[1].toLowerCase();
+            } else if (match[2] !== undefined) {
+                compound.id = match[2];
+            } else if (match[3] !== undefined) {
+                compound.classes.push(match[3]);
+            } else {
+                const value = match[6] !== undefined ? match[6] : match[7] !== undefined ? match[7] : match[8];
+                compound.attributes.push({ name: match[4].toLowerCase(), operator: match[5], value: value });
+            }
+        }
+        if (consumed !== source.length) {
+            throw new SyntaxError("Invalid CSS selector: " + source);
+        }
+        return compound;
+    }
+
+    function parseSelector(selector) {
+        let extract = null;
+        const attribute = /@([\w:-]+)\s*$/.exec(selector);
+        if (attribute !== null) {
+            extract = attribute[1].toLowerCase();
+            selector = selector.slice(0, attribute.index);
+        }
+        const parts = selector.replace(/\s*>\s*/g, " > ").trim().split(/\s+/);
+        const steps = [];
+        let combinator = " ";
+        for (const part of parts) {
+            if (part === ">") {
+                combinator = ">";
+                continue;
+            }
+            if (part === "") {
+                continue;
+            }
+            steps.push({ combinator: combinator, compound: parseCompound(part) });
+            combinator = " ";
+        }
+        if (steps.length === 0) {
+            throw new SyntaxError("Invalid CSS selector: " + selector);
+        }
+        return { steps: steps, extract: extract };
+    }
+
+    function matchesCompound(element, compound) {
+        if (compound.tag !== null && element.tag !== compound.tag) {
+            return false;
+        }
+        if (compound.id !== null && element.attributes.id !== compound.id) {
+            return false;
+        }
+        const classes = (element.attributes["class"] || "").split(/\s+/);
+        if (!compound.classes.every(function (name) { return classes.indexOf(name) >= 0; })) {
+            return false;
+        }
+        return compound.attributes.every(function (attribute) {
+            const actual = element.attributes[attribute.name];
+            if (actual === undefined) {
+                return false;
+            }
+            switch (attribute.operator) {
+                case "=": return actual === attribute.value;
+                case "^=": return actual.startsWith(attribute.value);
+                case "$=": return actual.endsWith(attribute.value);
+                case "*=": return actual.indexOf(attribute.value) >= 0;
+                default: return true;
+            }
+        });
+    }
+
+    // 从最后一步向上匹配祖先
+    function matchesSteps(element, steps, index) {
+        if (!matchesCompound(element, steps[index].compound)) {
+            return false;
+        }
+        if (index === 0) {
+            return true;
+        }
+        let parent = element.parent;
+        if (steps[index].combinator === ">") {
+            return parent !== null && parent.tag !== "#root" && matchesSteps(parent, steps, index - 1);
+        }
+        while (parent !== null && parent.tag !== "#root") {
+            if (matchesSteps(parent, steps, index - 1)) {
+                return true;
+            }
+            parent = parent.parent;
+        }
+        return false;
+    }
+
+    function textContent(node) {
+        return typeof node === "string" ? node : node.children.map(textContent).join("");
+    }
+
+    function select(html, selector) {
+        const parsed = parseSelector(selector);
+        const results = [];
+        (function walk(node) {
+            for (const child of node.children) {
+                if (typeof child === "string") {
+                    continue;
+                }
+                if (matchesSteps(child, parsed.steps, parsed.steps.length - 1)) {
+                    if (parsed.extract === null) {
+                        results.push(textContent(child).replace(/\s+/g, " ").trim());
+                    } else if (child.attributes[parsed.extract] !== undefined) {
+                        results.push(child.attributes[parsed.extract]);
+                    }
+                }
+                walk(child);
+            }
+        })(parseHtml(String(html === undefined || html === null ? "" : html)));
+        return results;
+    }
+
+    globalThis.cssAll = select;
+    globalThis.css = function (html, selector) {
+        const results = select(html, selector);
+        return results.length > 0 ? results[0] : null;
+    };
+    globalThis.querySelector = globalThis.css;
+})();
@@ -0,0 +1,112 @@
+//! JSONPath模块
+//!
+//! 支持常用的JSONPath子集：`$`、`.field`、`['field']`、`[index]`（负数从末尾计数）
+//! 以及通配符 `[*]`、`.*`（数组的所有元素或对象的所有值）。
+
+use crate::error::{HttpieError, Result};
+use serde_json::Value;
+
+/// 路径中的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(i64),
+    /// `[*]` 或 `.*`
+    Wildcard,
+}
+
+/// 解析JSONPath表达式
+pub fn parse(path: &str) -> Result<Vec<Segment>> {
+    let invalid = || HttpieError::Parse(format!("Invalid JSONPath '{path}'"));
+    let rest = path.trim().strip_prefix('$').ok_or_else(invalid)?;
+
+    let mut segments = Vec::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut key = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next == '.' || next == '[' {
+                        break;
+                    }
+                    key.push(next);
+                    chars.next();
+                }
+                match key.as_str() {
+                    "" => return Err(invalid()),
+                    "*" => segments.push(Segment::Wildcard),
+                    _ => segments.push(Segment::Key(key)),
+                }
+            }
+            '[' => {
+                let mut inner = String::new();
+                for next in chars.by_ref() {
+                    if next == ']' {
+                        break;
+                    }
+                    inner.push(next);
+                }
+                let inner = inner.trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                match quoted {
+                    Some(key) => segments.push(Segment::Key(key.to_string())),
+                    None if inner == "*" => segments.push(Segment::Wildcard),
+                    None => segments.push(Segment::Index(inner.parse().map_err(|_| invalid())?)),
+                }
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// 按JSONPath选取值，路径不存在时返回 None（含通配符时返回第一个匹配）
+pub fn select<'a>(value: &'a Value, path: &str) -> Result<Option<&'a Value>> {
+    Ok(select_all(value, path)?.into_iter().next())
+}
+
+/// 按JSONPath选取所有匹配的值，按文档顺序排列
+pub fn select_all<'a>(value: &'a Value, path: &str) -> Result<Vec<&'a Value>> {
+    let mut current = vec![value];
+    for segment in parse(path)? {
+        current = current
+            .into_iter()
+            .flat_map(|value| children(value, &segment))
+            .collect();
+    }
+    Ok(current)
+}
+
+/// 提取JSONPath选中的片段：不含通配符时为选中的值（不存在时为None），
+/// 含通配符时为所有匹配组成的数组
+pub fn extract(value: &Value, path: &str) -> Result<Option<Value>> {
+    let wildcard = parse(path)?.contains(&Segment::Wildcard);
+    let matches = select_all(value, path)?;
+    if wildcard {
+        return Ok(Some(Value::Array(matches.into_iter().cloned().collect())));
+    }
+    Ok(matches.first().map(|&value| value.clone()))
+}
+
+/// 一段路径在单个值上选中的子值
+fn children<'a>(value: &'a Value, segment: &Segment) -> Vec<&'a Value> {
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+        (Segment::Index(index), Value::Array(items)) => {
+            let index = if *index < 0 {
+                items.len().checked_sub(index.unsigned_abs() as usize)
+            } else {
+                Some(*index as usize)
+            };
+            index.and_then(|i| items.get(i)).into_iter().collect()
+        }
+        (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+        (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
@@ -3,27 +3,120 @@
 //! 这是一个功能完整的HTTP客户端库，支持解析.http文件格式，
 //! 变量替换，环境配置等功能。
 
+// reqwest的HTTP/3支持是不稳定特性，只在编译时设置了 `reqwest_unstable` cfg时可用；
+// 本仓库不通过 .cargo/config.toml 全局设置，避免影响依赖本库的项目和用户自己的 RUSTFLAGS
+#[cfg(all(feature = "http3", not(reqwest_unstable)))]
+compile_error!(
+    "the `http3` feature uses reqwest's unstable HTTP/3 support and must be built with \
+     RUSTFLAGS=\"--cfg reqwest_unstable\", e.g. \
+     `RUSTFLAGS=\"--cfg reqwest_unstable\" cargo build --features http3`"
+);
+
+pub mod adhoc;
+pub mod aggregate;
+pub mod archive;
+pub mod assertion;
+pub mod bench;
+pub mod binary;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod capture;
 pub mod client;
+pub mod compress;
+pub mod condition;
+pub mod config;
+pub mod cookie;
+pub mod curl;
+pub mod dialect;
 pub mod environment;
 pub mod error;
+pub mod expect;
+pub mod format;
+pub mod grpc;
+pub mod handler;
+pub mod history;
+pub mod hooks;
+pub mod jsonpath;
+pub mod listen;
+pub mod metrics;
 pub mod models;
+pub mod modules;
+pub mod monitor;
+pub mod netrc;
+pub mod notify;
+pub mod observer;
+pub mod output;
+pub mod pager;
 pub mod parser;
+pub mod progress;
+pub mod raw;
+pub mod redirect;
+pub mod runner;
 pub mod script;
+pub mod secret;
+pub mod session;
+pub mod tls;
+pub mod trace;
+pub mod transport;
+pub mod upload;
 pub mod variable;
 
 // 重新导出主要的公共API
-pub use client::{HttpClient, ResponseFormatter};
-pub use environment::EnvironmentLoader;
+pub use aggregate::AggregateAssertion;
+pub use archive::ResponseArchive;
+pub use assertion::Assertion;
+pub use bench::{
+    Bench, BenchComparison, BenchLimit, BenchOutcome, BenchReport, BenchSummary, BenchThresholds,
+};
+pub use capture::Capture;
+pub use client::{HttpClient, HttpProtocol, RequestOutcome, ResponseFormatter, TransportConfig};
+pub use condition::Condition;
+pub use config::{Config, HostProfile, HostProfiles};
+pub use dialect::ParserDialect;
+pub use environment::{EnvironmentLoader, RemoteEnvironment};
 pub use error::{HttpieError, Result};
-pub use models::{Environment, HttpRequest};
-pub use parser::HttpParser;
-pub use script::{ResponseObject, ScriptEngine, TestResult};
-pub use variable::VariableReplacer;
+pub use expect::ResponseExpectation;
+pub use format::HttpDocument;
+pub use grpc::GrpcCall;
+pub use history::{History, HistoryEntry, HistoryRecorder};
+pub use hooks::{ScriptHook, ScriptHooks};
+pub use listen::Listen;
+pub use models::{
+    Directive, Environment, Headers, Hook, HttpRequest, HttpVersion, Iteration, RequestSuite,
+};
+pub use notify::Notifier;
+pub use observer::ExecutionObserver;
+pub use output::{HeaderFilter, JsonFormat, OutputOptions, PrintParts};
+pub use pager::Pager;
+pub use parser::{
+    HttpParser, ParseOutput, ParseWarning, Requests, duplicate_requests, required_variables,
+};
+pub use progress::{ProgressMode, ProgressReporter};
+pub use raw::RawConnection;
+pub use runner::{
+    Abort, CaseSelector, FailureDetails, FailurePolicy, RequestReport, RunOrder, RunReport,
+    RunSummary, Runner,
+};
+#[cfg(feature = "scripting")]
+pub use script::ScriptEngine;
+pub use script::{
+    LogLevel, ResponseObject, ScriptControl, ScriptFailure, ScriptIsolation, ScriptLog,
+    ScriptOrigin, TestOutcome, TestResult,
+};
+pub use secret::{SecretPolicy, SecretSource};
+pub use session::{CookieJar, Session, SessionRecorder};
+pub use tokio_util::sync::CancellationToken;
+pub use trace::{TraceContext, TraceFormat};
+pub use transport::{HttpTransport, MockTransport, ReqwestTransport};
+pub use variable::{DynamicValues, VariableContext, VariableReplacer, is_secret_name};
 
 // 常量定义
 pub const DEFAULT_HTTP_FILE: &str = "./test.http";
 pub const DEFAULT_ENV_FILE: &str = "httpie.env.json";
+pub const DEFAULT_DOTENV_FILE: &str = ".env";
+pub const DEFAULT_CONFIG_FILE: &str = "httpie.config.toml";
 pub const DEFAULT_ENVIRONMENT: &str = "development";
+pub const SHARED_ENVIRONMENT: &str = "$shared";
 pub const SUPPORTED_METHODS: &[&str] = &[
-    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "GRAPHQL",
+    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "GRAPHQL", "LISTEN", "GRPC", "RAW",
 ];
@@ -0,0 +1,366 @@
+//! 回调监听模块
+//!
+//! `LISTEN :8089 /callback timeout=30s` 步骤启动临时HTTP监听，等待外部系统（如上一个请求
+//! 触发的webhook）发来的请求，把接收到的请求头和请求体作为该步骤的响应交给脚本和断言，
+//! 再用步骤中声明的请求头和请求体回复调用方。
+
+use crate::bench::parse_duration;
+use crate::client::RawResponse;
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use bytes::Bytes;
+use reqwest::Version;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// 监听步骤的请求行方法
+pub const LISTEN_METHOD: &str = "LISTEN";
+
+/// 未指定 `timeout=` 时的等待时间
+pub const DEFAULT_LISTEN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 请求行和请求头的最大字节数
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// 监听步骤的参数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Listen {
+    pub port: u16,
+    /// 只接受该路径（不含查询参数）的请求，None 接受任意路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 等待超时（毫秒）
+    pub timeout_ms: u64,
+}
+
+impl Listen {
+    /// 解析 `LISTEN` 之后的参数：`:端口`（可直接跟路径）、可选的路径和 `timeout=时长`
+    pub fn parse(args: &[&str]) -> Result<Self> {
+        let invalid = |reason: &str| {
+            HttpieError::InvalidRequest(format!(
+                "Invalid LISTEN step '{}': {reason}",
+                args.join(" ")
+            ))
+        };
+
+        let Some((address, rest)) = args.split_first() else {
+            return Err(invalid("expected ':port'"));
+        };
+        let address = address.strip_prefix("localhost").unwrap_or(address);
+        let address = address.strip_prefix(':').unwrap_or(address);
+        let (port, mut path) = match address.find('/') {
+            Some(index) => (&address[..index], Some(address[index..].to_string())),
+            None => (address, None),
+        };
+        let port = match port.parse::<u16>() {
+            Ok(port) if port > 0 => port,
+            _ => return Err(invalid("expected ':port'")),
+        };
+
+        let mut timeout = DEFAULT_LISTEN_TIMEOUT;
+        for arg in rest {
+            if let Some(value) = arg.strip_prefix("timeout=") {
+                timeout = parse_duration(value)?;
+            } else if arg.starts_with('/') && path.is_none() {
+                path = Some(arg.to_string());
+            } else {
+                return Err(invalid(&format!("unexpected '{arg}'")));
+            }
+        }
+
+        Ok(Self {
+            port,
+            path,
+            timeout_ms: timeout.as_millis() as u64,
+        })
+    }
+
+    /// 等待超时时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// 用于展示和报告的监听地址
+    pub fn url(&self) -> String {
+        format!(
+            "http://localhost:{}{}",
+            self.port,
+            self.path.as_deref().unwrap_or("/")
+        )
+    }
+
+    fn matches(&self, target: &str) -> bool {
+        let path = target.split('?').next().unwrap_or_default();
+        self.path.as_deref().is_none_or(|expected| expected == path)
+    }
+}
+
+/// 绑定所有网卡上的端口
+pub async fn bind(port: u16) -> Result<TcpListener> {
+    TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+        .await
+        .map_err(|e| HttpieError::InvalidRequest(format!("Failed to listen on port {port}: {e}")))
+}
+
+/// 预先绑定一批请求中监听步骤的端口，使在监听步骤开始前到达的回调排队等待
+///
+/// 绑定失败的端口不在结果中，由监听步骤执行时重新绑定并报告错误。
+pub async fn bind_all<'a>(
+    requests: impl IntoIterator<Item = &'a HttpRequest>,
+) -> HashMap<u16, Arc<TcpListener>> {
+    let mut listeners = HashMap::new();
+    for listen in requests.into_iter().filter_map(|r| r.listen.as_ref()) {
+        if listeners.contains_key(&listen.port) {
+            continue;
+        }
+        if let Ok(listener) = bind(listen.port).await {
+            listeners.insert(listen.port, Arc::new(listener));
+        }
+    }
+    listeners
+}
+
+/// 等待回调，取消令牌触发时中止并返回 [`HttpieError::Cancelled`]
+///
+/// 没有预先绑定的监听器时现在绑定。
+pub async fn receive_with_cancellation(
+    listener: Option<Arc<TcpListener>>,
+    request: &HttpRequest,
+    listen: &Listen,
+    token: &CancellationToken,
+) -> Result<RawResponse> {
+    let listener = match listener {
+        Some(listener) => listener,
+        None => Arc::new(bind(listen.port).await?),
+    };
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(HttpieError::Cancelled),
+        result = receive(&listener, request, listen) => result,
+    }
+}
+
+/// 等待一个匹配路径的请求，用步骤的请求头和请求体回复，返回接收到的请求
+///
+/// 路径不匹配的请求得到404，读取失败的连接被忽略，两者都不结束等待。
+pub async fn receive(
+    listener: &TcpListener,
+    request: &HttpRequest,
+    listen: &Listen,
+) -> Result<RawResponse> {
+    let started = Instant::now();
+    info!("Waiting for a request on {}", listen.url());
+    let wait = async {
+        loop {
+            let (mut stream, peer) = listener.accept().await?;
+            let received = match read_request(&mut stream).await {
+                Ok(received) => received,
+                Err(e) => {
+                    warn!("Ignoring unreadable request from {peer}: {e}");
+                    continue;
+                }
+            };
+            if !listen.matches(&received.target) {
+                write_reply(&mut stream, 404, &HeaderMap::new(), b"").await;
+                continue;
+            }
+
+            let mut headers = HeaderMap::new();
+            for (name, value) in &request.headers {
+                if let (Ok(name), Ok(value)) = (
+                    HeaderName::from_bytes(name.as_bytes()),
+                    HeaderValue::from_str(value),
+                ) {
+                    headers.insert(name, value);
+                }
+            }
+            let body = request.body.as_deref().unwrap_or_default();
+            write_reply(&mut stream, 200, &headers, body.as_bytes()).await;
+            info!(
+                "Received {} {} from {peer}",
+                received.method, received.target
+            );
+            return Ok::<_, HttpieError>(received);
+        }
+    };
+
+    let received = tokio::time::timeout(listen.timeout(), wait)
+        .await
+        .map_err(|_| {
+            HttpieError::RequestFailed(
+                request.name.clone(),
+                format!(
+                    "no request received on {} within {:?}",
+                    listen.url(),
+                    listen.timeout()
+                ),
+            )
+        })??;
+
+    Ok(RawResponse {
+        status: 200,
+        version: Version::HTTP_11,
+        headers: received.headers,
+        bytes: received.body,
+        duration: started.elapsed(),
+        truncated: false,
+        remote_addr: None,
+        tls: None,
+        redirects: Vec::new(),
+    })
+}
+
+/// 接收到的请求
+struct Received {
+    method: String,
+    target: String,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// 读取一个HTTP/1.x请求（支持 `Content-Length` 和分块传输）
+async fn read_request(stream: &mut TcpStream) -> Result<Received> {
+    let invalid = |reason: &str| HttpieError::Parse(format!("Invalid callback request: {reason}"));
+
+    let mut buffer = Vec::new();
+    let head_end = loop {
+        if let Some(index) = find(&buffer, b"\r\n\r\n") {
+            break index;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err(invalid("headers too large"));
+        }
+        if !read_more(stream, &mut buffer).await? {
+            return Err(invalid("connection closed before headers ended"));
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err(invalid("malformed request line"));
+    };
+
+    let mut headers = HeaderMap::new();
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.trim().as_bytes()),
+            HeaderValue::from_str(value.trim()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+
+    let mut body = buffer.split_off(head_end + 4);
+    let chunked = headers
+        .get("transfer-encoding")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("chunked"));
+    if chunked {
+        body = loop {
+            if let Some(decoded) = decode_chunked(&body) {
+                break decoded;
+            }
+            if !read_more(stream, &mut body).await? {
+                return Err(invalid("connection closed before the last chunk"));
+            }
+        };
+    } else {
+        let length = match headers.get("content-length") {
+            Some(value) => value
+                .to_str()
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .ok_or_else(|| invalid("invalid Content-Length"))?,
+            None => 0,
+        };
+        while body.len() < length {
+            if !read_more(stream, &mut body).await? {
+                return Err(invalid("connection closed before the body ended"));
+            }
+        }
+        body.truncate(length);
+    }
+
+    Ok(Received {
+        method: method.to_string(),
+        target: target.to_string(),
+        headers,
+        body: Bytes::from(body),
+    })
+}
+
+/// 追加读取数据，连接关闭时返回false
+async fn read_more(stream: &mut TcpStream, buffer: &mut Vec<u8>) -> Result<bool> {
+    let mut chunk = [0u8; 8192];
+    let read = stream.read(&mut chunk).await?;
+    buffer.extend_from_slice(&chunk[..read]);
+    Ok(read > 0)
+}
+
+/// 解码分块传输的请求体，数据不完整或格式错误时返回None
+fn decode_chunked(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = find(data, b"\r\n")?;
+        let size_text = std::str::from_utf8(&data[..line_end]).ok()?;
+        let size_text = size_text.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_text, 16).ok()?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Some(body);
+        }
+        if data.len() < size + 2 {
+            return None;
+        }
+        body.extend_from_slice(&data[..size]);
+        data = &data[size + 2..];
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// 回复调用方并关闭连接，写入失败只记录警告
+async fn write_reply(stream: &mut TcpStream, status: u16, headers: &HeaderMap, body: &[u8]) {
+    let reason = match status {
+        200 => "OK",
+        _ => "Not Found",
+    };
+    let mut reply = format!("HTTP/1.1 {status} {reason}\r\n");
+    for (name, value) in headers {
+        if name == "content-length" || name == "connection" {
+            continue;
+        }
+        reply.push_str(&format!(
+            "{name}: {}\r\n",
+            value.to_str().unwrap_or_default()
+        ));
+    }
+    reply.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    ));
+
+    let mut bytes = reply.into_bytes();
+    bytes.extend_from_slice(body);
+    if let Err(e) = stream.write_all(&bytes).await {
+        warn!("Failed to reply to callback: {e}");
+    }
+    let _ = stream.shutdown().await;
+}
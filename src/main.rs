@@ -1,130 +1,611 @@
-use clap::{Arg, ArgAction, Command};
-use std::path::Path;
-use tracing::{error, info};
+use clap::ArgMatches;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::info;
+use tracing_subscriber::EnvFilter;
 
 use httpie::{
-    DEFAULT_ENV_FILE, DEFAULT_HTTP_FILE, Environment, HttpClient, HttpParser, HttpRequest,
-    HttpieError,
+    Bench, BenchLimit, BenchSummary, BenchThresholds, CancellationToken, CaseSelector, Config,
+    CookieJar, DEFAULT_DOTENV_FILE, Environment, EnvironmentLoader, FailurePolicy, HeaderFilter,
+    History, HistoryRecorder, HttpClient, HttpParser, HttpRequest, HttpieError, JsonFormat, Pager,
+    ParseWarning, PrintParts, ProgressMode, ProgressReporter, ResponseArchive, Runner,
+    SecretPolicy, Session, SessionRecorder, TransportConfig, VariableContext,
+    adhoc::{self, ADHOC_METHODS},
+    bench,
+    client::ResponseFormatter,
+    curl, format,
+    history::{self, DEFAULT_HISTORY_FILE},
+    is_secret_name, jsonpath, metrics,
+    monitor::{Monitor, MonitorLoop},
+    required_variables,
+    session::DEFAULT_SESSION_DIR,
+    trace,
 };
 
+mod cli;
+
 #[tokio::main]
-async fn main() -> Result<(), HttpieError> {
-    tracing_subscriber::fmt::init();
-
-    let matches = Command::new("httpie")
-        .version("0.1.0")
-        .about("A simple HTTP client that parses .http files")
-        .arg(
-            Arg::new("file")
-                .long("file")
-                .value_name("FILE")
-                .help("HTTP request definition file")
-                .default_value(DEFAULT_HTTP_FILE),
-        )
-        .arg(
-            Arg::new("case")
-                .long("case")
-                .value_name("CASE")
-                .help("Specific test case to execute"),
-        )
-        .arg(
-            Arg::new("quiet")
-                .long("quiet")
-                .help("Quiet mode: do not print HTTP responses")
-                .action(ArgAction::SetTrue),
-        )
-        .get_matches();
-
-    let file_path = matches.get_one::<String>("file").unwrap();
-    let case_name = matches.get_one::<String>("case");
-    let quiet = matches.get_flag("quiet");
-
-    // 尝试加载环境变量文件
-    let env_file = Path::new(DEFAULT_ENV_FILE);
-    let environment = if env_file.exists() {
-        Environment::from_file(&env_file.to_string_lossy()).unwrap_or_else(|e| {
-            eprintln!("Warning: Failed to load environment file: {e}");
-            Environment::new()
-        })
+async fn main() {
+    // 以Display形式输出错误，解析错误带有 `文件:行:列` 和源码片段
+    if let Err(e) = run().await {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), HttpieError> {
+    let matches = cli::command().get_matches();
+    init_logging(matches.get_one::<String>("log-format").unwrap());
+
+    if let Some(("convert", sub_matches)) = matches.subcommand() {
+        return convert_command(sub_matches.get_one::<String>("input"));
+    }
+
+    let file_paths: Vec<&String> = matches.get_many::<String>("file").unwrap().collect();
+    // 环境文件发现等只关心一个文件的功能使用第一个文件
+    let file_path = file_paths[0].as_str();
+
+    if let Some(("fmt", sub_matches)) = matches.subcommand() {
+        let files: Vec<&String> = match sub_matches.get_many::<String>("files") {
+            Some(files) => files.collect(),
+            None => file_paths.clone(),
+        };
+        return fmt_command(&files, sub_matches.get_flag("check"));
+    }
+
+    let config = Config::load(Path::new(matches.get_one::<String>("config").unwrap()))?;
+    let quiet = matches.get_flag("quiet") || config.output.quiet.unwrap_or(false);
+    let verbose = matches.get_flag("verbose");
+    let max_body_bytes = matches
+        .get_one::<usize>("max-body-bytes")
+        .copied()
+        .or(config.output.max_body_bytes);
+    let max_body_print = matches
+        .get_one::<usize>("max-body-print")
+        .copied()
+        .or(config.output.max_body_print)
+        .or(Some(cli::DEFAULT_MAX_BODY_PRINT))
+        .filter(|&limit| limit > 0);
+    let print_parts = matches
+        .get_one::<String>("print")
+        .map(|parts| parts.parse::<PrintParts>())
+        .transpose()?
+        .unwrap_or_default();
+    let mut header_filter = HeaderFilter::new();
+    if let Some(names) = matches.get_one::<String>("print-headers") {
+        header_filter = header_filter.with_only(names);
+    }
+    if let Some(names) = matches.get_one::<String>("hide-headers") {
+        header_filter = header_filter.with_hidden(names);
+    }
+    let json_format = JsonFormat {
+        sort_keys: matches.get_flag("sort-keys"),
+        indent: *matches.get_one::<usize>("indent").unwrap(),
+        compact: matches.get_flag("compact"),
+    };
+    let filter = matches.get_one::<String>("filter").cloned();
+    if let Some(filter) = &filter {
+        jsonpath::parse(filter)?;
+    }
+    let pager = if matches.get_flag("no-pager") {
+        None
     } else {
-        eprintln!(
-            "Warning: Environment file '{}' not found, using empty environment",
-            env_file.display()
+        Pager::detect()
+    };
+    let recorder = matches.get_flag("history").then(|| {
+        HistoryRecorder::new(History::new(DEFAULT_HISTORY_FILE))
+            .with_bodies(matches.get_flag("history-bodies"))
+    });
+
+    // 历史和命令行请求不读取.http文件和环境文件
+    if let Some((command, sub_matches)) = matches.subcommand()
+        && (command == "history" || ADHOC_METHODS.contains(&command))
+    {
+        let transport_config = cli::transport_config(
+            &matches,
+            &config,
+            cli::dns_overrides(&matches, &Environment::new())?,
+            max_body_bytes,
         );
-        Environment::new()
+        let client = HttpClient::default()
+            .with_transport_config(transport_config)?
+            .with_print_response(!quiet)
+            .with_verbose(verbose)
+            .with_max_body_print(max_body_print)
+            .with_pager(pager.clone())
+            .with_filter(filter.clone())
+            .with_print_parts(print_parts)
+            .with_json_format(json_format)
+            .with_header_filter(header_filter.clone());
+        if command == "history" {
+            return history_command(sub_matches, client, recorder).await;
+        }
+
+        let items: Vec<String> = sub_matches
+            .get_many::<String>("items")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+        let mut request = adhoc::from_args(
+            command,
+            sub_matches.get_one::<String>("url").unwrap(),
+            &items,
+        )?;
+        let values = cli::dynamic_values(&matches, &config);
+        let trace = values.trace();
+        for format in cli::trace_formats(&matches)? {
+            format.inject(&mut request.headers, &trace);
+        }
+        if matches.get_flag("request-id") {
+            trace::inject_request_id(&mut request.headers, &values.uuid());
+        }
+        if let Some(netrc) = cli::load_netrc(&matches)? {
+            netrc.authorize(&mut request);
+        }
+        return run_single(client, request, recorder).await;
+    }
+
+    let (env_files, secret_policy) = cli::environment_files(&matches, file_path)?;
+
+    if let Some(("envs", sub_matches)) = matches.subcommand() {
+        return envs_command(
+            &env_files,
+            secret_policy,
+            file_path,
+            sub_matches.get_one::<String>("name"),
+            &cli::variable_overrides(&matches)?,
+        );
+    }
+    let case_selectors = cli::case_selectors(&matches);
+    let export_curl = matches.get_flag("export-curl");
+    let download_dir = matches.get_one::<String>("download-dir").map(PathBuf::from);
+    let concurrency = *matches.get_one::<usize>("concurrency").unwrap();
+    let failure_policy = if matches.get_flag("fail-fast") {
+        FailurePolicy::FailFast
+    } else if matches.get_flag("continue-on-error") {
+        FailurePolicy::ContinueOnError
+    } else {
+        config.report.failure_policy()?.unwrap_or_default()
     };
+    let order = cli::run_order(&matches, &config);
+    let notifier = cli::notifier(&matches, &config, &file_paths)?;
+    let progress = matches.get_flag("progress");
 
-    let dns_overrides = environment.dns_overrides().clone();
-    let mut parser = HttpParser::new(environment);
+    let environment = EnvironmentLoader::load_files(
+        &env_files,
+        matches.get_one::<String>("env").map(String::as_str),
+        secret_policy,
+    )?
+    .with_dotenv(EnvironmentLoader::load_dotenv(Path::new(
+        DEFAULT_DOTENV_FILE,
+    ))?);
+    let mut transport_config = cli::transport_config(
+        &matches,
+        &config,
+        cli::dns_overrides(&matches, &environment)?,
+        max_body_bytes,
+    );
+    let recorder = recorder.map(|recorder| recorder.with_environment(&environment));
+    let mut parser = HttpParser::new(environment)
+        .with_infer_content_type(!matches.get_flag("no-infer-content-type"))
+        .with_implicit_sections(matches.get_flag("implicit-sections"))
+        .with_dialect(matches.get_one::<String>("dialect").unwrap().parse()?)
+        .with_variable_overrides(cli::variable_overrides(&matches)?)
+        .with_default_headers(config.headers.clone())
+        .with_host_profiles(config.hosts.clone())
+        .with_trace_formats(cli::trace_formats(&matches)?)
+        .with_request_id(matches.get_flag("request-id"))
+        .with_netrc(cli::load_netrc(&matches)?)
+        .with_dynamic_values(cli::dynamic_values(&matches, &config));
 
-    let requests = parser.parse_file(file_path)?;
+    let interval = matches
+        .get_one::<String>("interval")
+        .map(|interval| bench::parse_duration(interval))
+        .transpose()?;
+    let mut requests = Vec::new();
+    for file in &file_paths {
+        let output = parser.parse_file_with_warnings(file)?;
+        report_warnings(output.warnings, matches.get_flag("strict"))?;
+        requests.extend(output.requests);
+    }
 
     if requests.is_empty() {
         info!("No valid HTTP requests found in file: {}", file_path);
         return Ok(());
     }
 
-    info!("Found {} request(s) in file", requests.len());
+    info!(
+        "Found {} request(s) in {} file(s)",
+        requests.len(),
+        file_paths.len()
+    );
+
+    if let Some(("bench", sub_matches)) = matches.subcommand() {
+        return bench_command(
+            sub_matches,
+            &requests,
+            case_selectors.first(),
+            transport_config,
+        )
+        .await;
+    }
+
+    // 仅导出curl命令，不执行请求
+    if export_curl {
+        print!("{}", curl::export(&requests, &case_selectors)?);
+        return Ok(());
+    }
+
+    // 会话：加载保存的变量和Cookie，需要保存时记录本次运行的Cookie
+    let session_dir = Path::new(DEFAULT_SESSION_DIR);
+    let save_session = matches.get_one::<String>("save-session");
+    if let Some(name) = save_session {
+        Session::path(session_dir, name)?;
+    }
+    let variables = VariableContext::new();
+    transport_config.cookies = match matches.get_one::<String>("session") {
+        Some(name) => Some(Session::load(session_dir, name)?.restore(&variables)),
+        None => save_session.map(|_| Arc::new(CookieJar::default())),
+    };
+    let cookies = transport_config.cookies.clone();
 
     // 创建HTTP客户端并启用脚本功能
-    let mut client = HttpClient::default()
-        .with_dns_overrides(&dns_overrides)?
-        .with_script_engine()?
-        .with_print_response(!quiet);
+    let client = HttpClient::default()
+        .with_transport_config(transport_config)?
+        .with_variables(variables.clone())
+        .with_print_response(!quiet && !progress)
+        .with_verbose(verbose)
+        .with_max_body_print(max_body_print)
+        .with_pager(pager)
+        .with_filter(filter)
+        .with_print_parts(print_parts)
+        .with_json_format(json_format)
+        .with_header_filter(header_filter)
+        .with_download_dir(download_dir);
+    #[cfg(feature = "scripting")]
+    let client = {
+        let isolation = match matches.get_one::<String>("script-isolation") {
+            Some(isolation) => isolation.parse()?,
+            None => config.scripts.isolation()?.unwrap_or_default(),
+        };
+        client
+            .with_script_isolation(isolation)
+            .with_script_engine()?
+    };
+
+    // Ctrl+C 取消进行中的请求
+    let cancellation = CancellationToken::new();
+    tokio::spawn(cancel_on_ctrl_c(cancellation.clone()));
 
     // 执行请求
-    match case_name {
-        Some(case) => execute_specific_case(&mut client, &requests, case, file_path).await?,
-        None => execute_all_requests(&mut client, &requests).await?,
+    let mut runner = Runner::new(client)
+        .with_case_selectors(case_selectors)
+        .with_qualified_names(file_paths.len() > 1)
+        .with_concurrency(concurrency)
+        .with_failure_policy(failure_policy)
+        .with_order(order)
+        .with_hooks(cli::script_hooks(&matches, &config)?)
+        .with_cancellation(cancellation);
+    if let Some(recorder) = recorder {
+        runner = runner.with_observer(recorder);
+    }
+    if progress {
+        runner = runner.with_observer(ProgressReporter::new(ProgressMode::detect()));
+    }
+    if let Some(dir) = matches.get_one::<String>("save-responses") {
+        runner = runner.with_observer(ResponseArchive::new(dir));
     }
+    if let (Some(name), Some(cookies)) = (save_session, cookies) {
+        runner = runner.with_observer(SessionRecorder::new(
+            session_dir,
+            name,
+            variables.clone(),
+            cookies,
+        ));
+    }
+    let metrics_file = matches.get_one::<String>("metrics-file").map(PathBuf::from);
+    let metrics_push = matches.get_one::<String>("metrics-push").cloned();
 
-    Ok(())
+    if let Some(interval) = interval {
+        let monitor =
+            Monitor::new().with_failure_threshold(*matches.get_one::<u32>("alert-after").unwrap());
+        let files = file_paths.iter().map(|file| file.to_string()).collect();
+        let monitor = MonitorLoop::new(runner, parser, files, interval)
+            .with_monitor(monitor)
+            .with_webhook(matches.get_one::<String>("webhook").cloned())
+            .with_metrics_file(metrics_file)
+            .with_metrics_push(metrics_push)
+            .with_on_cycle(|cycle| eprint!("{cycle}"))
+            .run(requests)
+            .await?;
+        eprint!("\n{}", monitor.summary());
+        return Ok(());
+    }
+    let report = runner.with_notifier(notifier).run(&requests).await?;
+
+    if let Some(path) = metrics_file {
+        fs::write(path, metrics::prometheus_text(&report))?;
+    }
+    if let Some(url) = metrics_push {
+        metrics::push(&url, &report).await?;
+    }
+
+    if report.cancelled {
+        eprintln!(
+            "Cancelled after {} completed request(s): {} passed, {} failed",
+            report.total(),
+            report.passed(),
+            report.failed()
+        );
+    } else {
+        if report.total() > 0 {
+            eprint!(
+                "\n{}",
+                report.summary(*matches.get_one::<usize>("slowest").unwrap())
+            );
+        }
+        eprint!("{}", report.failure_details());
+    }
+    report.check()
 }
 
-/// 执行指定的测试用例
-async fn execute_specific_case(
-    client: &mut HttpClient,
+/// 初始化日志，输出到标准错误，避免与响应输出交错
+///
+/// `RUST_LOG` 控制过滤（默认只输出警告），`json` 格式每行一个事件，包含请求span的字段。
+fn init_logging(format: &str) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr);
+    if format == "json" {
+        builder.json().init();
+    } else {
+        builder.init();
+    }
+}
+
+/// 输出解析警告，`--strict` 时以第一个警告作为错误返回
+fn report_warnings(warnings: Vec<ParseWarning>, strict: bool) -> Result<(), HttpieError> {
+    for warning in &warnings {
+        warning.log();
+    }
+    match warnings.into_iter().next() {
+        Some(warning) if strict => Err(warning.into_error()),
+        _ => Ok(()),
+    }
+}
+
+/// 对选中的请求进行压测
+async fn bench_command(
+    matches: &ArgMatches,
     requests: &[HttpRequest],
-    case_name: &str,
-    _file_path: &str,
+    selector: Option<&CaseSelector>,
+    transport_config: TransportConfig,
 ) -> Result<(), HttpieError> {
-    // 查找指定的测试用例
+    let request = bench::select_request(requests, selector)?;
 
-    match requests.iter().find(|r| r.name.contains(case_name)) {
-        Some(request) => {
-            eprintln!("Found matching case: '{}'", request.name);
-            eprintln!("Executing request to: {}", request.url);
-            client.execute(request).await
-        }
+    let limit = match matches.get_one::<String>("duration") {
+        Some(duration) => BenchLimit::Duration(bench::parse_duration(duration)?),
         None => {
-            // 用例未找到
-            Err(HttpieError::InvalidRequest(format!(
-                "Case '{case_name}' not found"
-            )))
+            BenchLimit::Iterations(matches.get_one::<usize>("requests").copied().unwrap_or(100))
         }
+    };
+
+    // 在发送请求之前读取基线和校验阈值
+    let baseline = matches
+        .get_one::<String>("baseline")
+        .map(BenchSummary::load)
+        .transpose()?;
+    let ratio = |name: &str| bench::parse_ratio(matches.get_one::<String>(name).unwrap());
+    let thresholds = BenchThresholds {
+        latency: ratio("max-latency-regression")?,
+        error_rate: ratio("max-error-rate-increase")?,
+    };
+
+    let cancellation = CancellationToken::new();
+    tokio::spawn(cancel_on_ctrl_c(cancellation.clone()));
+
+    let outcome = Bench::new(request.clone())
+        .with_transport_config(transport_config)
+        .with_concurrency(*matches.get_one::<usize>("concurrency").unwrap())
+        .with_limit(limit)
+        .with_cancellation(cancellation)
+        .with_baseline(baseline)
+        .with_thresholds(thresholds)
+        .evaluate()
+        .await?;
+
+    print!("{}", outcome.report);
+    if let Some(path) = matches.get_one::<String>("json") {
+        fs::write(
+            path,
+            serde_json::to_string_pretty(&outcome.report.summary())?,
+        )?;
+        eprintln!("Wrote benchmark summary to {path}");
     }
+    if let Some(comparison) = &outcome.comparison {
+        print!("{comparison}");
+        if let Some(diff) = matches.get_one::<String>("diff") {
+            fs::write(diff, serde_json::to_string_pretty(comparison)?)?;
+            eprintln!("Wrote baseline comparison to {diff}");
+        }
+    }
+    if !outcome.assertions.is_empty() {
+        print!(
+            "{}",
+            ResponseFormatter::new().render_test_results(&request.name, &outcome.assertions)
+        );
+    }
+    outcome.check()
 }
 
-/// 执行所有请求
-async fn execute_all_requests(
-    client: &mut HttpClient,
-    requests: &[HttpRequest],
+/// 列出、查看或重新执行历史记录
+async fn history_command(
+    matches: &ArgMatches,
+    client: HttpClient,
+    recorder: Option<HistoryRecorder>,
 ) -> Result<(), HttpieError> {
-    info!("Executing all {} request(s)", requests.len());
-
-    for (index, request) in requests.iter().enumerate() {
-        info!(
-            "Executing request {}/{}: {}",
-            index + 1,
-            requests.len(),
-            request.name
-        );
+    let history = History::new(DEFAULT_HISTORY_FILE);
+    match matches.subcommand() {
+        Some(("list", sub_matches)) => {
+            let entries = history.entries()?;
+            let limit = *sub_matches.get_one::<usize>("limit").unwrap();
+            let now = history::unix_timestamp();
+            for entry in &entries[entries.len().saturating_sub(limit)..] {
+                println!("{}", entry.summary_line(now));
+            }
+            Ok(())
+        }
+        Some(("show", sub_matches)) => {
+            let entry = history.get(*sub_matches.get_one::<u64>("id").unwrap())?;
+            print!("{entry}");
+            Ok(())
+        }
+        Some(("rerun", sub_matches)) => {
+            let request = history
+                .get(*sub_matches.get_one::<u64>("id").unwrap())?
+                .to_request()?;
+            run_single(client, request, recorder).await
+        }
+        _ => unreachable!("history requires a subcommand"),
+    }
+}
+
+/// 执行单个请求，传输错误时返回错误
+async fn run_single(
+    client: HttpClient,
+    request: HttpRequest,
+    recorder: Option<HistoryRecorder>,
+) -> Result<(), HttpieError> {
+    let mut runner = Runner::new(client);
+    if let Some(recorder) = recorder {
+        runner = runner.with_observer(recorder);
+    }
+    let report = runner.run(&[request]).await?;
+    eprint!("{}", report.failure_details());
+    match report.first_error() {
+        Some(failed) => Err(HttpieError::RequestFailed(
+            failed.name.clone(),
+            failed.error.clone().unwrap_or_default(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 第一次Ctrl+C触发取消，第二次立即退出
+async fn cancel_on_ctrl_c(token: CancellationToken) {
+    if tokio::signal::ctrl_c().await.is_err() {
+        return;
+    }
+    eprintln!(
+        "Interrupted, cancelling in-flight requests (press Ctrl+C again to exit immediately)"
+    );
+    token.cancel();
+
+    if tokio::signal::ctrl_c().await.is_ok() {
+        std::process::exit(130);
+    }
+}
+
+/// 将curl命令转换为.http格式
+fn convert_command(input: Option<&String>) -> Result<(), HttpieError> {
+    let command = match input {
+        Some(command) => command.clone(),
+        None => io::read_to_string(io::stdin())?,
+    };
 
-        if let Err(e) = client.execute(request).await {
-            error!("Failed to execute request '{}': {}", request.name, e);
-            return Err(e);
+    let request = curl::from_curl(command.trim())?;
+    print!("{}", curl::to_http(&request));
+
+    Ok(())
+}
+
+/// 格式化.http文件，`check` 模式下只列出需要格式化的文件
+fn fmt_command(files: &[&String], check: bool) -> Result<(), HttpieError> {
+    let mut unformatted = 0;
+    for file in files {
+        let content =
+            fs::read_to_string(file).map_err(|_| HttpieError::FileNotFound(file.to_string()))?;
+        let formatted = format::format(&content);
+        if formatted == content {
+            continue;
+        }
+        if check {
+            println!("Would reformat: {file}");
+            unformatted += 1;
+        } else {
+            fs::write(file, formatted)?;
+            println!("Formatted: {file}");
         }
     }
 
+    if unformatted > 0 {
+        return Err(HttpieError::Unformatted(unformatted));
+    }
+    Ok(())
+}
+
+/// 列出环境文件中的环境及变量，并检查 --file 引用的变量是否都有定义
+fn envs_command(
+    env_files: &[PathBuf],
+    secret_policy: SecretPolicy,
+    file_path: &str,
+    name: Option<&String>,
+    overrides: &HashMap<String, String>,
+) -> Result<(), HttpieError> {
+    let names = match name {
+        Some(name) => vec![name.clone()],
+        None => {
+            let mut names = BTreeSet::new();
+            for env_file in env_files {
+                names.extend(Environment::names(&env_file.to_string_lossy())?);
+            }
+            names.into_iter().collect()
+        }
+    };
+    let dotenv = EnvironmentLoader::load_dotenv(Path::new(DEFAULT_DOTENV_FILE))?;
+    let required = match fs::read_to_string(file_path) {
+        Ok(content) => required_variables(&content),
+        Err(_) => Default::default(),
+    };
+
+    for name in names {
+        let environment =
+            Environment::from_files_with_policy(env_files, Some(&name), secret_policy)?
+                .with_dotenv(dotenv.clone());
+        println!("{name}");
+
+        let mut variables: Vec<_> = environment.variables().iter().collect();
+        variables.sort();
+        for (key, value) in variables {
+            let value = if is_secret_name(key) {
+                "********"
+            } else {
+                value
+            };
+            println!("  {key} = {value}");
+        }
+        let mut secrets: Vec<_> = environment.secrets().iter().collect();
+        secrets.sort_by_key(|(key, _)| *key);
+        for (key, secret) in secrets {
+            println!("  {key} = <{}>", secret.kind());
+        }
+
+        let missing: Vec<&str> = required
+            .iter()
+            .map(String::as_str)
+            .filter(|key| {
+                environment.lookup(key).is_none()
+                    && !environment.secrets().contains_key(*key)
+                    && !overrides.contains_key(*key)
+            })
+            .collect();
+        if !missing.is_empty() {
+            println!("  missing for {file_path}: {}", missing.join(", "));
+        }
+    }
     Ok(())
 }
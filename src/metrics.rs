@@ -0,0 +1,198 @@
+//! 指标导出模块
+//!
+//! 把运行报告转换为Prometheus文本格式：请求数（按状态码类别）、耗时直方图、
+//! 测试结果和运行摘要，可以写入文件（供node_exporter的textfile收集器读取）
+//! 或推送到Pushgateway，便于定时监控任务接入仪表盘。
+
+use crate::error::{HttpieError, Result};
+use crate::runner::{RequestReport, RunReport};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 请求耗时直方图的桶上界（秒）
+pub const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// 单个请求名称的耗时直方图
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; DURATION_BUCKETS.len()];
+        }
+        for (bucket, &bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// 把运行报告转换为Prometheus文本格式
+pub fn prometheus_text(report: &RunReport) -> String {
+    let mut requests: BTreeMap<(&str, &str, String), u64> = BTreeMap::new();
+    let mut durations: BTreeMap<&str, Histogram> = BTreeMap::new();
+    let mut tests: BTreeMap<(&str, &str), u64> = BTreeMap::new();
+
+    for request in &report.requests {
+        *requests
+            .entry((&request.name, &request.method, status_class(request)))
+            .or_default() += 1;
+        if request.status.is_some() {
+            durations
+                .entry(&request.name)
+                .or_default()
+                .observe(request.duration.as_secs_f64());
+        }
+        for test in &request.test_results {
+            *tests
+                .entry((&request.name, request.test_outcome(test).as_str()))
+                .or_default() += 1;
+        }
+    }
+
+    let mut out = String::new();
+    header(
+        &mut out,
+        "httpie_requests_total",
+        "counter",
+        "Executed requests by status class",
+    );
+    for ((name, method, class), count) in &requests {
+        let labels = labels(&[
+            ("request", name),
+            ("method", method),
+            ("status_class", class),
+        ]);
+        let _ = writeln!(out, "httpie_requests_total{labels} {count}");
+    }
+
+    header(
+        &mut out,
+        "httpie_request_duration_seconds",
+        "histogram",
+        "Request duration until the response body was read",
+    );
+    for (name, histogram) in &durations {
+        for (bucket, bound) in histogram.buckets.iter().zip(DURATION_BUCKETS) {
+            let labels = labels(&[("request", name), ("le", &bound.to_string())]);
+            let _ = writeln!(
+                out,
+                "httpie_request_duration_seconds_bucket{labels} {bucket}"
+            );
+        }
+        let labels_inf = labels(&[("request", name), ("le", "+Inf")]);
+        let labels = labels(&[("request", name)]);
+        let _ = writeln!(
+            out,
+            "httpie_request_duration_seconds_bucket{labels_inf} {}",
+            histogram.count
+        );
+        let _ = writeln!(
+            out,
+            "httpie_request_duration_seconds_sum{labels} {}",
+            histogram.sum
+        );
+        let _ = writeln!(
+            out,
+            "httpie_request_duration_seconds_count{labels} {}",
+            histogram.count
+        );
+    }
+
+    header(
+        &mut out,
+        "httpie_tests_total",
+        "counter",
+        "Test and assertion results",
+    );
+    for ((name, result), count) in &tests {
+        let labels = labels(&[("request", name), ("result", result)]);
+        let _ = writeln!(out, "httpie_tests_total{labels} {count}");
+    }
+
+    header(
+        &mut out,
+        "httpie_run_duration_seconds",
+        "gauge",
+        "Duration of the whole run",
+    );
+    let _ = writeln!(
+        out,
+        "httpie_run_duration_seconds {}",
+        report.duration.as_secs_f64()
+    );
+    header(
+        &mut out,
+        "httpie_run_success",
+        "gauge",
+        "1 when every request succeeded and all tests passed",
+    );
+    let _ = writeln!(out, "httpie_run_success {}", u8::from(report.success()));
+    header(
+        &mut out,
+        "httpie_run_timestamp_seconds",
+        "gauge",
+        "Unix time the run finished",
+    );
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let _ = writeln!(out, "httpie_run_timestamp_seconds {timestamp}");
+    out
+}
+
+/// 推送到Pushgateway（如 `http://pushgateway:9091/metrics/job/httpie`），替换该分组的指标
+pub async fn push(url: &str, report: &RunReport) -> Result<()> {
+    let response = reqwest::Client::new()
+        .put(url)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(prometheus_text(report))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(HttpieError::InvalidRequest(format!(
+            "Failed to push metrics to {url}: HTTP {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
+
+/// 状态码类别（如 `2xx`），传输错误为 `error`
+fn status_class(request: &RequestReport) -> String {
+    match request.status {
+        Some(status) => format!("{}xx", status / 100),
+        None if request.is_skipped() => "skipped".to_string(),
+        None => "error".to_string(),
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn labels(pairs: &[(&str, &str)]) -> String {
+    let pairs: Vec<String> = pairs
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', r"\\")
+                .replace('"', "\\\"")
+                .replace('\n', r"\n");
+            format!("{name}=\"{value}\"")
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
@@ -2,23 +2,239 @@
 //!
 //! 定义了HTTP客户端库中使用的核心数据结构。
 
-use crate::DEFAULT_ENVIRONMENT;
+use crate::binary::ResponseRedirect;
 use crate::error::{HttpieError, Result};
+use crate::grpc::GrpcCall;
+use crate::listen::Listen;
+use crate::raw::RawConnection;
+use crate::secret::{SecretPolicy, SecretSource};
+use crate::variable::template_names;
+use crate::{DEFAULT_ENVIRONMENT, SHARED_ENVIRONMENT};
+use indexmap::IndexMap;
 use reqwest::Method;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use url::{ParseError, Url};
+
+/// 请求集合规范格式的当前版本
+pub const REQUEST_SUITE_VERSION: u32 = 1;
+
+/// HTTP头，按书写（或接收）顺序保存
+///
+/// 名称的大小写只在导出（curl命令、`.http` 和JSON）时保留，发送时由HTTP库统一转换为小写。
+pub type Headers = IndexMap<String, String>;
+
+/// 请求指令（`# @name value`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Directive {
+    pub name: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+/// 请求行上指定的HTTP版本（`GET url HTTP/2`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HttpVersion {
+    #[serde(rename = "HTTP/1.1")]
+    Http11,
+    #[serde(rename = "HTTP/2")]
+    Http2,
+}
+
+impl HttpVersion {
+    /// 解析版本标记（`HTTP/1.1`、`HTTP/2`、`HTTP/2.0`）
+    pub fn parse(token: &str) -> Option<Self> {
+        match token.to_ascii_uppercase().as_str() {
+            "HTTP/1.1" => Some(Self::Http11),
+            "HTTP/2" | "HTTP/2.0" => Some(Self::Http2),
+            _ => None,
+        }
+    }
+
+    /// 转换为reqwest的版本类型
+    pub fn to_reqwest(self) -> reqwest::Version {
+        match self {
+            Self::Http11 => reqwest::Version::HTTP_11,
+            Self::Http2 => reqwest::Version::HTTP_2,
+        }
+    }
+}
+
+impl fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http11 => write!(f, "HTTP/1.1"),
+            Self::Http2 => write!(f, "HTTP/2"),
+        }
+    }
+}
+
+/// 重复执行时的迭代信息（`# @repeat N`，序号从1开始）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Iteration {
+    pub index: usize,
+    pub total: usize,
+}
+
+/// 钩子段落（`### @setup` / `### @teardown`），不受用例筛选影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Hook {
+    /// 在选中的用例之前执行
+    Setup,
+    /// 在选中的用例之后执行，即使用例失败
+    Teardown,
+}
+
+impl Hook {
+    /// 根据请求名称识别钩子（`@setup`、`@teardown`，后面可跟描述）
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.split_whitespace().next()? {
+            "@setup" => Some(Self::Setup),
+            "@teardown" => Some(Self::Teardown),
+            _ => None,
+        }
+    }
+}
+
+impl Directive {
+    /// 创建新的指令
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
 
 /// HTTP请求结构体
-#[derive(Debug, Clone)]
+///
+/// 序列化时方法以字符串表示、请求头保持书写顺序，空字段省略，保证输出稳定。
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpRequest {
     pub name: String,
+    #[serde(with = "method_serde")]
     pub method: Method,
     pub url: String,
-    pub headers: HashMap<String, String>,
+    /// 查询参数，按书写顺序在发送时编码追加到URL
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<(String, String)>,
+    /// 路径参数，填充URL中的 `:name` 或 `{name}` 路径段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_params: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: Headers,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub body: Option<String>,
+    /// 请求体文件（`< ./file`），发送时读取，相对路径基于来源文件所在目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub response_handler: Option<String>,
+    /// 响应处理器脚本第一行在来源文件中的行号，用于定位脚本错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handler_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directives: Vec<Directive>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_redirect: Option<ResponseRedirect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<HttpVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iteration: Option<Iteration>,
+    /// 请求在来源文件中的序号（从1开始），重复执行的各次迭代共用同一序号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook: Option<Hook>,
+    /// 内联断言（`?? status == 200`，不含前缀）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<String>,
+    /// 监听步骤（`LISTEN :8089 /callback`）的参数，此时请求头和请求体用于回复调用方
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen: Option<Listen>,
+    /// GRPC请求（`GRPC host/pkg.Service/Method`）调用的方法
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc: Option<GrpcCall>,
+    /// 原始TCP请求（`RAW host:port`）的连接参数，此时请求体按字节原样发送
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<RawConnection>,
+}
+
+/// 请求集合的规范表示，可在JSON/YAML之间往返转换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestSuite {
+    pub version: u32,
+    pub requests: Vec<HttpRequest>,
+}
+
+impl RequestSuite {
+    /// 使用当前格式版本创建请求集合
+    pub fn new(requests: Vec<HttpRequest>) -> Self {
+        Self {
+            version: REQUEST_SUITE_VERSION,
+            requests,
+        }
+    }
+
+    /// 序列化为格式化的JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// 从JSON解析请求集合
+    pub fn from_json(content: &str) -> Result<Self> {
+        Self::check_version(serde_json::from_str(content)?)
+    }
+
+    /// 序列化为YAML
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self)
+            .map_err(|e| HttpieError::Parse(format!("YAML serialization failed: {e}")))
+    }
+
+    /// 从YAML解析请求集合
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        let suite = serde_yaml::from_str(content)
+            .map_err(|e| HttpieError::Parse(format!("Invalid request suite YAML: {e}")))?;
+        Self::check_version(suite)
+    }
+
+    fn check_version(suite: Self) -> Result<Self> {
+        if suite.version > REQUEST_SUITE_VERSION {
+            return Err(HttpieError::Parse(format!(
+                "Unsupported request suite version {} (max {REQUEST_SUITE_VERSION})",
+                suite.version
+            )));
+        }
+        Ok(suite)
+    }
+}
+
+/// HTTP方法与字符串之间的序列化
+mod method_serde {
+    use reqwest::Method;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(method: &Method, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(method.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Method, D::Error> {
+        let method = String::deserialize(deserializer)?;
+        Method::from_str(&method.to_uppercase())
+            .map_err(|_| D::Error::custom(format!("invalid HTTP method '{method}'")))
+    }
 }
 
 impl HttpRequest {
@@ -28,15 +244,42 @@ impl HttpRequest {
             name,
             method,
             url,
-            headers: HashMap::new(),
+            query: Vec::new(),
+            path_params: Vec::new(),
+            headers: Headers::new(),
             body: None,
+            body_file: None,
             response_handler: None,
+            handler_line: None,
+            directives: Vec::new(),
+            source_file: None,
+            response_redirect: None,
+            http_version: None,
+            iteration: None,
+            index: None,
+            hook: None,
+            assertions: Vec::new(),
+            listen: None,
+            grpc: None,
+            raw: None,
         }
     }
 
-    /// 设置请求头
-    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
-        self.headers = headers;
+    /// 设置请求头，按迭代顺序保存
+    pub fn with_headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.headers = headers.into_iter().collect();
+        self
+    }
+
+    /// 设置查询参数
+    pub fn with_query(mut self, query: Vec<(String, String)>) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// 设置路径参数
+    pub fn with_path_params(mut self, path_params: Vec<(String, String)>) -> Self {
+        self.path_params = path_params;
         self
     }
 
@@ -46,11 +289,262 @@ impl HttpRequest {
         self
     }
 
+    /// 设置请求体文件
+    pub fn with_body_file(mut self, body_file: Option<String>) -> Self {
+        self.body_file = body_file;
+        self
+    }
+
     /// 设置响应处理器脚本
     pub fn with_response_handler(mut self, response_handler: Option<String>) -> Self {
         self.response_handler = response_handler;
         self
     }
+
+    /// 设置响应处理器脚本第一行在来源文件中的行号
+    pub fn with_handler_line(mut self, handler_line: Option<usize>) -> Self {
+        self.handler_line = handler_line;
+        self
+    }
+
+    /// 设置请求指令
+    pub fn with_directives(mut self, directives: Vec<Directive>) -> Self {
+        self.directives = directives;
+        self
+    }
+
+    /// 设置请求来源文件
+    pub fn with_source_file(mut self, source_file: Option<PathBuf>) -> Self {
+        self.source_file = source_file;
+        self
+    }
+
+    /// 设置响应体重定向目标（`>> file`）
+    pub fn with_response_redirect(mut self, response_redirect: Option<ResponseRedirect>) -> Self {
+        self.response_redirect = response_redirect;
+        self
+    }
+
+    /// 设置请求使用的HTTP版本
+    pub fn with_http_version(mut self, http_version: Option<HttpVersion>) -> Self {
+        self.http_version = http_version;
+        self
+    }
+
+    /// 设置重复执行的迭代信息
+    pub fn with_iteration(mut self, iteration: Option<Iteration>) -> Self {
+        self.iteration = iteration;
+        self
+    }
+
+    /// 设置在来源文件中的序号
+    pub fn with_index(mut self, index: Option<usize>) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// 设置内联断言
+    pub fn with_assertions(mut self, assertions: Vec<String>) -> Self {
+        self.assertions = assertions;
+        self
+    }
+
+    /// 设置钩子类型
+    pub fn with_hook(mut self, hook: Option<Hook>) -> Self {
+        self.hook = hook;
+        self
+    }
+
+    /// 设置监听步骤参数
+    pub fn with_listen(mut self, listen: Option<Listen>) -> Self {
+        self.listen = listen;
+        self
+    }
+
+    /// 设置GRPC请求调用的方法
+    pub fn with_grpc(mut self, grpc: Option<GrpcCall>) -> Self {
+        self.grpc = grpc;
+        self
+    }
+
+    /// 设置原始TCP请求的连接参数
+    pub fn with_raw(mut self, raw: Option<RawConnection>) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// 获取指定名称的第一个指令值
+    pub fn directive(&self, name: &str) -> Option<&str> {
+        self.directives
+            .iter()
+            .find(|d| d.name == name)
+            .map(|d| d.value.as_str())
+    }
+
+    /// 获取指定名称的所有指令值
+    pub fn directives_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.directives
+            .iter()
+            .filter(move |d| d.name == name)
+            .map(|d| d.value.as_str())
+    }
+
+    /// 请求指纹：方法、目标URL和请求体（或请求体文件路径）的SHA-256，取前16位十六进制
+    ///
+    /// 与请求名称和请求头无关，内容相同的请求指纹相同，可作为历史、录制匹配和缓存的稳定ID。
+    pub fn fingerprint(&self) -> String {
+        let url = self
+            .target_url()
+            .map_or_else(|_| self.url.clone(), String::from);
+        let mut hasher = Sha256::new();
+        hasher.update(self.method.as_str());
+        hasher.update(b"\n");
+        hasher.update(url);
+        hasher.update(b"\n");
+        match (&self.body, &self.body_file) {
+            (_, Some(path)) => hasher.update(format!("< {path}")),
+            (Some(body), None) => hasher.update(body),
+            (None, None) => {}
+        }
+        hasher.finalize()[..8]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// `文件::名称` 形式的完整名称，没有来源文件时为名称
+    pub fn qualified_name(&self) -> String {
+        match &self.source_file {
+            Some(path) => format!("{}::{}", path.display(), self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// 发送时使用的URL：填充路径参数并追加编码后的查询参数
+    pub fn target_url(&self) -> Result<Url> {
+        let invalid = |reason: String| {
+            HttpieError::InvalidRequest(format!("Invalid URL '{}': {reason}", self.url))
+        };
+
+        // 在解析前替换路径模板，避免 `{` `}` 被提前编码
+        let path_start = self.url.find("://").map_or(0, |pos| pos + 3);
+        let path_start = self.url[path_start..]
+            .find('/')
+            .map_or(self.url.len(), |pos| path_start + pos);
+        let path_end = self.url[path_start..]
+            .find(['?', '#'])
+            .map_or(self.url.len(), |pos| path_start + pos);
+        let mut segments = Vec::new();
+        for segment in self.url[path_start..path_end].split('/') {
+            let template = segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')));
+            match template.filter(|name| is_param_name(name)) {
+                Some(name) => {
+                    let value = self
+                        .path_params
+                        .iter()
+                        .find(|(key, _)| key == name)
+                        .map(|(_, value)| value)
+                        .ok_or_else(|| invalid(format!("missing path parameter '{name}'")))?;
+                    segments.push(encode_path_segment(value));
+                }
+                None => segments.push(segment.to_string()),
+            }
+        }
+        let filled = format!(
+            "{}{}{}",
+            &self.url[..path_start],
+            segments.join("/"),
+            &self.url[path_end..]
+        );
+
+        let mut url = Url::parse(&filled).map_err(|e| invalid(describe_url_error(e)))?;
+        // 原始TCP请求的URL为 `tcp://host:port` 或 `tls://host:port`
+        let supported = match self.raw {
+            Some(_) => matches!(url.scheme(), "tcp" | "tls"),
+            None => matches!(url.scheme(), "http" | "https"),
+        };
+        if !supported {
+            // `localhost:8080/api` 会被解析为scheme为localhost的URL
+            return Err(invalid(if self.url.contains("://") {
+                format!("unsupported scheme '{}'", url.scheme())
+            } else {
+                MISSING_SCHEME.to_string()
+            }));
+        }
+        if !self.query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.query);
+        }
+        Ok(url)
+    }
+
+    /// 将相对路径解析为相对于来源文件所在目录的路径
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            return path.to_path_buf();
+        }
+        match self.source_file.as_ref().and_then(|f| f.parent()) {
+            Some(dir) => dir.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+}
+
+/// 缺少scheme时的提示
+const MISSING_SCHEME: &str = "missing scheme, expected e.g. 'https://host/path'";
+
+/// 将URL解析错误转换为可读的原因
+fn describe_url_error(error: ParseError) -> String {
+    match error {
+        ParseError::RelativeUrlWithoutBase => MISSING_SCHEME.to_string(),
+        ParseError::EmptyHost => "missing host".to_string(),
+        ParseError::InvalidPort => "invalid port".to_string(),
+        ParseError::InvalidIpv4Address | ParseError::InvalidIpv6Address => {
+            "malformed IP address".to_string()
+        }
+        ParseError::InvalidDomainCharacter | ParseError::IdnaError => "malformed host".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// 路径参数名只允许字母、数字和下划线
+fn is_param_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// 按RFC 3986对路径段中的非保留字符以外的字节做百分号编码
+fn encode_path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// 环境文件中声明父环境的键
+const EXTENDS_KEY: &str = "extends";
+
+/// 按环境合并两个环境文件的内容，`overlay` 中同一环境的同名项覆盖 `base`
+fn merge_documents(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    let (Some(base), serde_json::Value::Object(overlay)) = (base.as_object_mut(), overlay) else {
+        return;
+    };
+    for (name, value) in overlay {
+        match (base.get_mut(&name), value) {
+            (Some(serde_json::Value::Object(existing)), serde_json::Value::Object(value)) => {
+                existing.extend(value);
+            }
+            (_, value) => {
+                base.insert(name, value);
+            }
+        }
+    }
 }
 
 /// 环境变量管理结构体
@@ -58,6 +552,18 @@ impl HttpRequest {
 pub struct Environment {
     variables: HashMap<String, String>,
     dns_overrides: HashMap<String, SocketAddr>,
+    /// 环境名称（如 `development`）
+    name: Option<String>,
+    /// 环境文件路径（由远及近），用于按请求加载其他环境
+    sources: Vec<PathBuf>,
+    /// `.env` 文件中的变量，优先级低于环境文件
+    dotenv: HashMap<String, String>,
+    /// 值来自命令或系统钥匙串的变量，用到时才读取
+    secrets: HashMap<String, SecretSource>,
+    /// 环境文件中允许使用的密钥来源，按名称加载其他环境时沿用
+    secret_policy: SecretPolicy,
+    /// 已读取的密钥，克隆后共享，避免重复执行命令
+    resolved_secrets: Arc<Mutex<HashMap<String, String>>>,
 }
 
 impl Environment {
@@ -66,27 +572,153 @@ impl Environment {
         Self::default()
     }
 
-    /// 从文件加载环境配置
+    /// 从文件加载默认环境（`development`）的配置，文件中没有该环境时为空
     pub fn from_file(file_path: &str) -> Result<Self> {
+        Self::load(
+            &[PathBuf::from(file_path)],
+            DEFAULT_ENVIRONMENT,
+            false,
+            SecretPolicy::default(),
+        )
+    }
+
+    /// 从文件加载指定名称的环境，文件中没有该环境时报错
+    pub fn from_file_named(file_path: &str, name: &str) -> Result<Self> {
+        Self::load(
+            &[PathBuf::from(file_path)],
+            name,
+            true,
+            SecretPolicy::default(),
+        )
+    }
+
+    /// 合并多个环境文件（由远及近，近处文件中同一环境的同名项覆盖远处）后加载默认环境
+    pub fn from_files(file_paths: &[PathBuf]) -> Result<Self> {
+        Self::load(
+            file_paths,
+            DEFAULT_ENVIRONMENT,
+            false,
+            SecretPolicy::default(),
+        )
+    }
+
+    /// 合并多个环境文件后加载指定名称的环境，所有文件中都没有该环境时报错
+    pub fn from_files_named(file_paths: &[PathBuf], name: &str) -> Result<Self> {
+        Self::load(file_paths, name, true, SecretPolicy::default())
+    }
+
+    /// 按密钥限制合并多个环境文件后加载环境：指定名称时该环境不存在报错，
+    /// 否则加载默认环境；文件中使用了不允许的密钥来源时报错
+    pub fn from_files_with_policy(
+        file_paths: &[PathBuf],
+        name: Option<&str>,
+        policy: SecretPolicy,
+    ) -> Result<Self> {
+        match name {
+            Some(name) => Self::load(file_paths, name, true, policy),
+            None => Self::load(file_paths, DEFAULT_ENVIRONMENT, false, policy),
+        }
+    }
+
+    /// 从相同的环境文件、按相同的密钥限制加载另一个环境，`.env` 变量保持不变
+    pub fn load_named(&self, name: &str) -> Result<Self> {
+        let environment = Self::load(&self.sources, name, true, self.secret_policy)?;
+        Ok(environment.with_dotenv(self.dotenv.clone()))
+    }
+
+    /// 列出环境文件中定义的环境名称（不含 `$shared`），按名称排序
+    pub fn names(file_path: &str) -> Result<Vec<String>> {
         let content = fs::read_to_string(file_path)
             .map_err(|_| HttpieError::FileNotFound(file_path.to_string()))?;
-
         let env_data: serde_json::Value = serde_json::from_str(&content)?;
+        let mut names: Vec<String> = env_data
+            .as_object()
+            .map(|envs| {
+                envs.iter()
+                    .filter(|(name, value)| *name != SHARED_ENVIRONMENT && value.is_object())
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        Ok(names)
+    }
 
-        let mut variables = HashMap::new();
-        let mut dns_overrides = HashMap::new();
-
-        let Some(env_obj) = env_data
-            .get(DEFAULT_ENVIRONMENT)
-            .and_then(|v| v.as_object())
-        else {
-            return Ok(Self {
-                variables,
-                dns_overrides,
-            });
+    fn load(
+        file_paths: &[PathBuf],
+        name: &str,
+        required: bool,
+        secret_policy: SecretPolicy,
+    ) -> Result<Self> {
+        let mut env_data = serde_json::Value::Object(Default::default());
+        for path in file_paths {
+            let content = fs::read_to_string(path)
+                .map_err(|_| HttpieError::FileNotFound(path.display().to_string()))?;
+            merge_documents(&mut env_data, serde_json::from_str(&content)?);
+        }
+        let file_path = file_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let env_obj = env_data.get(name).and_then(|v| v.as_object());
+        if env_obj.is_none() && required {
+            return Err(HttpieError::InvalidRequest(format!(
+                "Environment '{name}' not found in {file_path}"
+            )));
+        }
+
+        // 按共享配置、最上层父环境、……、当前环境的顺序合并，后者覆盖前者
+        let mut chain = Vec::new();
+        let mut current = env_obj.map(|obj| (name, obj));
+        while let Some((current_name, obj)) = current {
+            chain.push(obj);
+            current = match obj.get(EXTENDS_KEY) {
+                Some(parent) => {
+                    let parent = parent.as_str().ok_or_else(|| {
+                        HttpieError::Parse(format!(
+                            "Environment '{current_name}': '{EXTENDS_KEY}' must be a string"
+                        ))
+                    })?;
+                    let parent_obj = env_data
+                        .get(parent)
+                        .and_then(|v| v.as_object())
+                        .ok_or_else(|| {
+                            HttpieError::InvalidRequest(format!(
+                                "Environment '{current_name}' extends unknown environment '{parent}' in {file_path}"
+                            ))
+                        })?;
+                    if chain.iter().any(|obj| std::ptr::eq(*obj, parent_obj)) {
+                        return Err(HttpieError::InvalidRequest(format!(
+                            "Environment '{name}' has an inheritance cycle through '{parent}'"
+                        )));
+                    }
+                    Some((parent, parent_obj))
+                }
+                None => None,
+            };
+        }
+        let shared = env_data.get(SHARED_ENVIRONMENT).and_then(|v| v.as_object());
+
+        let mut environment = Self {
+            name: Some(name.to_string()),
+            sources: file_paths.to_vec(),
+            secret_policy,
+            ..Self::default()
         };
+        for env_obj in shared.into_iter().chain(chain.into_iter().rev()) {
+            environment.merge_object(env_obj)?;
+        }
+        Ok(environment)
+    }
 
+    /// 合并环境文件中一个环境对象的变量和dns配置
+    fn merge_object(&mut self, env_obj: &serde_json::Map<String, serde_json::Value>) -> Result<()> {
         for (key, value) in env_obj {
+            if key == EXTENDS_KEY {
+                continue;
+            }
             if key == "dns" {
                 if let Some(dns_obj) = value.as_object() {
                     for (domain, addr_value) in dns_obj {
@@ -98,12 +730,19 @@ impl Environment {
                                 "Invalid dns override for '{domain}': {addr_str} ({e})"
                             ))
                         })?;
-                        dns_overrides.insert(domain.clone(), addr);
+                        self.dns_overrides.insert(domain.clone(), addr);
                     }
                 }
                 continue;
             }
 
+            if let Some(secret) = SecretSource::from_value(value)? {
+                self.secret_policy.check(key, &secret)?;
+                self.variables.remove(key);
+                self.secrets.insert(key.clone(), secret);
+                continue;
+            }
+
             let str_value = match value {
                 serde_json::Value::String(s) => Some(s.clone()),
                 serde_json::Value::Number(n) => Some(n.to_string()),
@@ -112,14 +751,26 @@ impl Environment {
             };
 
             if let Some(str_value) = str_value {
-                variables.insert(key.clone(), str_value);
+                self.secrets.remove(key);
+                self.variables.insert(key.clone(), str_value);
             }
         }
+        Ok(())
+    }
 
-        Ok(Self {
-            variables,
-            dns_overrides,
-        })
+    /// 获取环境名称
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// 获取最近的环境文件路径
+    pub fn source(&self) -> Option<&Path> {
+        self.sources.last().map(PathBuf::as_path)
+    }
+
+    /// 获取合并的所有环境文件路径（由远及近）
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
     }
 
     /// 获取变量值
@@ -145,4 +796,68 @@ impl Environment {
     pub fn dns_overrides(&self) -> &HashMap<String, SocketAddr> {
         &self.dns_overrides
     }
+
+    /// 设置 `.env` 文件中的变量
+    pub fn with_dotenv(mut self, dotenv: HashMap<String, String>) -> Self {
+        self.dotenv = dotenv;
+        self
+    }
+
+    /// 获取 `.env` 文件中的变量
+    pub fn dotenv(&self) -> &HashMap<String, String> {
+        &self.dotenv
+    }
+
+    /// 按优先级查找 `{{name}}` 的值：环境及文件变量优先，其次是 `.env`
+    pub fn lookup(&self, key: &str) -> Option<&String> {
+        self.variables.get(key).or_else(|| self.dotenv.get(key))
+    }
+
+    /// 获取值来自命令或系统钥匙串的变量
+    pub fn secrets(&self) -> &HashMap<String, SecretSource> {
+        &self.secrets
+    }
+
+    /// 已读取的密钥值，从同一环境克隆出的实例共享
+    pub fn resolved_secret_values(&self) -> Vec<String> {
+        self.resolved_secrets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// 读取 `text` 中以 `{{name}}` 引用且未被普通变量覆盖的密钥，写入变量集合
+    pub fn resolve_secrets(&mut self, text: &str) -> Result<()> {
+        for (name, secret) in &self.secrets {
+            if self.variables.contains_key(name) || !template_names(text).any(|used| used == name) {
+                continue;
+            }
+            let mut resolved = self
+                .resolved_secrets
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let value = match resolved.get(name) {
+                Some(value) => value.clone(),
+                None => {
+                    let value = secret.resolve().map_err(|e| {
+                        HttpieError::InvalidRequest(format!("Failed to resolve '{name}': {e}"))
+                    })?;
+                    resolved.insert(name.clone(), value.clone());
+                    value
+                }
+            };
+            self.variables.insert(name.clone(), value);
+        }
+        Ok(())
+    }
+
+    /// 读取进程环境变量，`.env` 中的同名变量优先
+    pub fn process_env(&self, key: &str) -> Option<String> {
+        self.dotenv
+            .get(key)
+            .cloned()
+            .or_else(|| std::env::var(key).ok())
+    }
 }
@@ -0,0 +1,298 @@
+//! 脚本模块导入
+//!
+//! 响应处理器和全局钩子可以用 `import { validate } from "./helpers.mjs"` 引用其他脚本文件，
+//! 路径相对于导入它的文件（响应处理器相对于所在的.http文件）。执行前把导入的模块链接为
+//! 普通脚本：每个模块包装为函数，在同一个运行时中只求值一次，导出对象保存在
+//! `globalThis.__httpie_modules`。导入像 ES 模块一样先于脚本的其余部分求值。
+//!
+//! 支持的导入：`import "./m.mjs"`、`import x from`、`import * as m from`、
+//! `import { a, b as c } from` 及其组合；支持的导出：`export function`/`class`/
+//! `const`/`let`/`var`（每条声明一个名称）、`export default` 和 `export { a, b as c }`。
+
+use crate::error::{HttpieError, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 保存已求值模块导出对象的全局变量
+const MODULES: &str = "globalThis.__httpie_modules";
+
+/// 把脚本中的导入链接为普通脚本，没有导入时原样返回
+pub fn link(script: &str, base_dir: &Path) -> Result<String> {
+    if !script.lines().any(|line| is_import(line.trim_start())) {
+        return Ok(script.to_string());
+    }
+    let mut linker = Linker::default();
+    let (bindings, body) = linker.imports(script, base_dir)?;
+
+    let mut linked = format!("{MODULES} = {MODULES} || {{}};\n");
+    for definition in &linker.definitions {
+        linked.push_str(definition);
+    }
+    linked.push_str(&bindings);
+    linked.push_str(&body);
+    Ok(linked)
+}
+
+#[derive(Default)]
+struct Linker {
+    /// 按依赖顺序排列的模块定义
+    definitions: Vec<String>,
+    loaded: HashSet<PathBuf>,
+    /// 正在链接的模块，用于检测循环导入
+    stack: Vec<PathBuf>,
+}
+
+impl Linker {
+    /// 链接源码中的导入，返回导入绑定和去掉导入语句的源码
+    ///
+    /// 导入语句替换为同样行数的空行，脚本出错时的行号不变。
+    fn imports(&mut self, source: &str, dir: &Path) -> Result<(String, String)> {
+        let mut bindings = String::new();
+        let mut body = String::new();
+        let mut statement = String::new();
+        for line in source.lines() {
+            if statement.is_empty() && !is_import(line.trim_start()) {
+                body.push_str(line);
+                body.push('\n');
+                continue;
+            }
+            statement.push_str(line);
+            statement.push('\n');
+            if !is_complete(&statement) {
+                continue;
+            }
+            let import = Import::parse(&statement)?;
+            let key = self.load(&resolve(&import.specifier, dir)?)?;
+            bindings.push_str(&import.bindings(&key));
+            body.push_str(&"\n".repeat(statement.lines().count()));
+            statement.clear();
+        }
+        if !statement.is_empty() {
+            return Err(invalid(statement.trim(), "unterminated import"));
+        }
+        Ok((bindings, body))
+    }
+
+    /// 链接模块文件，返回其在 `__httpie_modules` 中的键
+    fn load(&mut self, path: &Path) -> Result<String> {
+        let path = path.canonicalize().map_err(|e| {
+            HttpieError::ScriptError(format!("Failed to import {}: {e}", path.display()))
+        })?;
+        let key = serde_json::to_string(&path.display().to_string())?;
+        if self.stack.contains(&path) {
+            return Err(HttpieError::ScriptError(format!(
+                "Circular import of {}",
+                path.display()
+            )));
+        }
+        if !self.loaded.insert(path.clone()) {
+            return Ok(key);
+        }
+
+        let source = fs::read_to_string(&path).map_err(|e| {
+            HttpieError::ScriptError(format!("Failed to import {}: {e}", path.display()))
+        })?;
+        self.stack.push(path.clone());
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let (bindings, body) = self.imports(&source, dir)?;
+        self.stack.pop();
+
+        let (body, exports) = exports(&body).map_err(|reason| {
+            HttpieError::ScriptError(format!("Failed to import {}: {reason}", path.display()))
+        })?;
+        let mut definition = format!(
+            "if (!{MODULES}[{key}]) {{\n{MODULES}[{key}] = (function () {{\nvar exports = {{}};\n{bindings}{body}"
+        );
+        for (local, exported) in exports {
+            definition.push_str(&format!("exports.{exported} = {local};\n"));
+        }
+        definition.push_str("return exports;\n})();\n}\n");
+        self.definitions.push(definition);
+        Ok(key)
+    }
+}
+
+/// 一条导入语句
+#[derive(Debug, Default)]
+struct Import {
+    specifier: String,
+    default: Option<String>,
+    namespace: Option<String>,
+    /// 导入的名称和本地名称
+    named: Vec<(String, String)>,
+}
+
+impl Import {
+    fn parse(statement: &str) -> Result<Self> {
+        let text = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = text.trim_end_matches(';').trim_end();
+        let rest = text["import".len()..].trim();
+        let (clause, specifier) = match rest.rsplit_once(" from ") {
+            Some((clause, specifier)) => (clause.trim(), specifier.trim()),
+            None => ("", rest),
+        };
+        let specifier =
+            unquote(specifier).ok_or_else(|| invalid(text, "expected a quoted path"))?;
+        let mut import = Self {
+            specifier: specifier.to_string(),
+            ..Self::default()
+        };
+
+        let (head, named) = match clause.find('{') {
+            Some(open) => {
+                let close = clause
+                    .rfind('}')
+                    .ok_or_else(|| invalid(text, "expected '}'"))?;
+                (&clause[..open], Some(&clause[open + 1..close]))
+            }
+            None => (clause, None),
+        };
+        for part in head.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.strip_prefix('*') {
+                Some(namespace) => {
+                    let local = namespace
+                        .trim()
+                        .strip_prefix("as ")
+                        .ok_or_else(|| invalid(text, "expected '* as name'"))?;
+                    import.namespace = Some(identifier(local.trim(), text)?);
+                }
+                None => import.default = Some(identifier(part, text)?),
+            }
+        }
+        for part in named
+            .into_iter()
+            .flat_map(|named| named.split(','))
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            let (imported, local) = match part.split_once(" as ") {
+                Some((imported, local)) => (imported.trim(), local.trim()),
+                None => (part, part),
+            };
+            import
+                .named
+                .push((identifier(imported, text)?, identifier(local, text)?));
+        }
+        Ok(import)
+    }
+
+    /// 绑定导入名称的语句
+    fn bindings(&self, key: &str) -> String {
+        let module = format!("{MODULES}[{key}]");
+        let mut bindings = String::new();
+        if let Some(local) = &self.default {
+            bindings.push_str(&format!("var {local} = {module}.default;\n"));
+        }
+        if let Some(local) = &self.namespace {
+            bindings.push_str(&format!("var {local} = {module};\n"));
+        }
+        for (imported, local) in &self.named {
+            bindings.push_str(&format!("var {local} = {module}.{imported};\n"));
+        }
+        bindings
+    }
+}
+
+/// 去掉模块源码中的 `export`，返回源码和导出的（本地名称, 导出名称）
+fn exports(source: &str) -> std::result::Result<(String, Vec<(String, String)>), String> {
+    let mut body = String::new();
+    let mut exports = Vec::new();
+    for line in source.lines() {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let Some(rest) = line.trim_start().strip_prefix("export ") else {
+            body.push_str(line);
+            body.push('\n');
+            continue;
+        };
+        let rest = rest.trim_start();
+        if let Some(expression) = rest.strip_prefix("default ") {
+            body.push_str(&format!("{indent}exports.default = {expression}\n"));
+            continue;
+        }
+        if let Some(list) = rest.strip_prefix('{') {
+            let (list, tail) = list
+                .split_once('}')
+                .ok_or_else(|| format!("expected '}}' in '{}'", line.trim()))?;
+            if !tail.trim().trim_end_matches(';').is_empty() {
+                return Err(format!("re-exports are not supported: '{}'", line.trim()));
+            }
+            for part in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let (local, exported) = part.split_once(" as ").unwrap_or((part, part));
+                exports.push((local.trim().to_string(), exported.trim().to_string()));
+            }
+            body.push('\n');
+            continue;
+        }
+        let declaration = ["async function", "function", "class", "const", "let", "var"]
+            .iter()
+            .find_map(|keyword| rest.strip_prefix(keyword))
+            .ok_or_else(|| format!("unsupported export: '{}'", line.trim()))?;
+        let name: String = declaration
+            .trim_start_matches('*')
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+            .collect();
+        if name.is_empty() {
+            return Err(format!("expected a name after export: '{}'", line.trim()));
+        }
+        exports.push((name.clone(), name));
+        body.push_str(indent);
+        body.push_str(rest);
+        body.push('\n');
+    }
+    Ok((body, exports))
+}
+
+/// 是否为静态导入语句的开头（不包括 `import(...)` 和 `import.meta`）
+fn is_import(trimmed: &str) -> bool {
+    trimmed
+        .strip_prefix("import")
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| c.is_whitespace() || matches!(c, '{' | '*' | '"' | '\''))
+}
+
+/// 导入语句是否已经完整：括号配对且以路径字符串结尾
+fn is_complete(statement: &str) -> bool {
+    let text = statement.trim().trim_end_matches(';').trim_end();
+    text.matches('{').count() == text.matches('}').count()
+        && (text.ends_with('"') || text.ends_with('\''))
+        && text.len() > "import".len() + 2
+}
+
+/// 解析导入路径：只支持相对路径和绝对路径
+fn resolve(specifier: &str, dir: &Path) -> Result<PathBuf> {
+    let path = Path::new(specifier);
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    if specifier.starts_with("./") || specifier.starts_with("../") {
+        return Ok(dir.join(path));
+    }
+    Err(HttpieError::ScriptError(format!(
+        "Cannot import '{specifier}': only relative or absolute file paths are supported"
+    )))
+}
+
+fn unquote(text: &str) -> Option<&str> {
+    ['"', '\'']
+        .iter()
+        .find_map(|&quote| text.strip_prefix(quote)?.strip_suffix(quote))
+}
+
+fn identifier(name: &str, statement: &str) -> Result<String> {
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if !valid {
+        return Err(invalid(statement, &format!("invalid name '{name}'")));
+    }
+    Ok(name.to_string())
+}
+
+fn invalid(statement: &str, reason: &str) -> HttpieError {
+    HttpieError::ScriptError(format!("Invalid import '{statement}': {reason}"))
+}
@@ -0,0 +1,413 @@
+//! 定时监控模块
+//!
+//! 按固定间隔重复运行请求集合时，记录每个用例的连续失败次数和最近若干次运行的统计，
+//! 在用例开始失败或恢复时产生告警，并可以通过webhook（兼容Slack的 `text` 字段）通知。
+
+use crate::error::{HttpieError, Result};
+use crate::metrics;
+use crate::models::HttpRequest;
+use crate::parser::HttpParser;
+use crate::runner::{RequestReport, RunReport, Runner};
+use indexmap::IndexMap;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::warn;
+
+/// 滚动统计默认保留的最近运行次数
+pub const DEFAULT_STATS_WINDOW: usize = 100;
+
+/// 单个用例的监控统计
+#[derive(Debug, Clone, Default)]
+pub struct CaseStats {
+    /// 累计运行次数
+    pub runs: u64,
+    /// 累计失败次数
+    pub failures: u64,
+    /// 当前连续失败次数
+    pub consecutive_failures: u32,
+    /// 最近运行的结果和耗时
+    recent: VecDeque<(bool, Duration)>,
+}
+
+impl CaseStats {
+    /// 最近窗口内的成功率（0.0~1.0）
+    pub fn success_rate(&self) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        let passed = self.recent.iter().filter(|(passed, _)| *passed).count();
+        passed as f64 / self.recent.len() as f64
+    }
+
+    /// 最近窗口内的平均耗时
+    pub fn average_duration(&self) -> Duration {
+        if self.recent.is_empty() {
+            return Duration::ZERO;
+        }
+        let total: Duration = self.recent.iter().map(|(_, duration)| *duration).sum();
+        total / self.recent.len() as u32
+    }
+}
+
+/// 用例状态变化产生的告警
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Alert {
+    /// 连续失败达到阈值
+    Failing {
+        case: String,
+        failures: u32,
+        reason: String,
+    },
+    /// 告警后重新通过
+    Recovered { case: String, failures: u32 },
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failing {
+                case,
+                failures,
+                reason,
+            } => write!(
+                f,
+                "FAILING: '{case}' failed {failures} time(s) in a row: {reason}"
+            ),
+            Self::Recovered { case, failures } => {
+                write!(f, "RECOVERED: '{case}' passed after {failures} failure(s)")
+            }
+        }
+    }
+}
+
+/// 跨多次运行的监控状态
+#[derive(Debug)]
+pub struct Monitor {
+    cycles: u64,
+    cases: IndexMap<String, CaseStats>,
+    window: usize,
+    failure_threshold: u32,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Monitor {
+    pub fn new() -> Self {
+        Self {
+            cycles: 0,
+            cases: IndexMap::new(),
+            window: DEFAULT_STATS_WINDOW,
+            failure_threshold: 1,
+        }
+    }
+
+    /// 设置滚动统计保留的运行次数（至少为1）
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// 连续失败多少次后告警（至少为1）
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Self {
+        self.failure_threshold = threshold.max(1);
+        self
+    }
+
+    /// 已记录的运行次数
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// 按首次出现顺序排列的用例统计
+    pub fn cases(&self) -> &IndexMap<String, CaseStats> {
+        &self.cases
+    }
+
+    /// 停止监控时输出的各用例统计
+    pub fn summary(&self) -> MonitorSummary<'_> {
+        MonitorSummary { monitor: self }
+    }
+
+    /// 记录一次运行，返回状态发生变化的用例告警
+    pub fn record(&mut self, report: &RunReport) -> Vec<Alert> {
+        self.cycles += 1;
+        let mut alerts = Vec::new();
+
+        for request in report.requests.iter().filter(|r| !r.is_skipped()) {
+            let stats = self.cases.entry(request.name.clone()).or_default();
+            let passed = request.passed();
+            stats.runs += 1;
+            stats.recent.push_back((passed, request.duration));
+            if stats.recent.len() > self.window {
+                stats.recent.pop_front();
+            }
+
+            if passed {
+                if stats.consecutive_failures >= self.failure_threshold {
+                    alerts.push(Alert::Recovered {
+                        case: request.name.clone(),
+                        failures: stats.consecutive_failures,
+                    });
+                }
+                stats.consecutive_failures = 0;
+            } else {
+                stats.failures += 1;
+                stats.consecutive_failures += 1;
+                if stats.consecutive_failures == self.failure_threshold {
+                    alerts.push(Alert::Failing {
+                        case: request.name.clone(),
+                        failures: stats.consecutive_failures,
+                        reason: failure_reason(request),
+                    });
+                }
+            }
+        }
+        alerts
+    }
+}
+
+/// 监控统计汇总：运行轮数和每个用例的运行次数、失败次数、成功率和平均耗时
+pub struct MonitorSummary<'a> {
+    monitor: &'a Monitor,
+}
+
+impl fmt::Display for MonitorSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let monitor = self.monitor;
+        writeln!(f, "Stopped after {} cycle(s):", monitor.cycles)?;
+        for (name, stats) in &monitor.cases {
+            write!(
+                f,
+                "  {name}: {} run(s), {} failure(s), {:.1}% success (last {}), avg {} ms",
+                stats.runs,
+                stats.failures,
+                stats.success_rate() * 100.0,
+                stats.runs.min(monitor.window as u64),
+                stats.average_duration().as_millis(),
+            )?;
+            if stats.consecutive_failures > 0 {
+                write!(
+                    f,
+                    ", failing {} time(s) in a row",
+                    stats.consecutive_failures
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// 一轮监控的结果：本轮产生的告警和运行报告
+pub struct MonitorCycle<'a> {
+    /// 从1开始的轮次
+    pub number: u64,
+    pub alerts: &'a [Alert],
+    pub report: &'a RunReport,
+}
+
+/// 每条告警一行，之后是本轮的通过数和耗时
+impl fmt::Display for MonitorCycle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for alert in self.alerts {
+            writeln!(f, "{alert}")?;
+        }
+        writeln!(
+            f,
+            "Cycle {}: {} of {} request(s) passed in {} ms",
+            self.number,
+            self.report.passed(),
+            self.report.total(),
+            self.report.duration.as_millis()
+        )
+    }
+}
+
+/// 每轮结束后调用的回调
+type CycleCallback = Box<dyn FnMut(&MonitorCycle<'_>) + Send>;
+
+/// 定时监控：按固定间隔重复运行，每轮重新解析文件使动态变量取新值
+pub struct MonitorLoop {
+    runner: Runner,
+    parser: HttpParser,
+    files: Vec<String>,
+    interval: Duration,
+    monitor: Monitor,
+    webhook: Option<String>,
+    metrics_file: Option<PathBuf>,
+    metrics_push: Option<String>,
+    on_cycle: Option<CycleCallback>,
+}
+
+impl MonitorLoop {
+    /// 运行器的取消令牌同时用于停止监控
+    pub fn new(runner: Runner, parser: HttpParser, files: Vec<String>, interval: Duration) -> Self {
+        Self {
+            runner,
+            parser,
+            files,
+            interval,
+            monitor: Monitor::new(),
+            webhook: None,
+            metrics_file: None,
+            metrics_push: None,
+            on_cycle: None,
+        }
+    }
+
+    /// 设置记录统计和产生告警的监控状态
+    pub fn with_monitor(mut self, monitor: Monitor) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
+    /// 把告警POST到webhook
+    pub fn with_webhook(mut self, webhook: Option<String>) -> Self {
+        self.webhook = webhook;
+        self
+    }
+
+    /// 每轮结束后把Prometheus指标写入文件
+    pub fn with_metrics_file(mut self, path: Option<PathBuf>) -> Self {
+        self.metrics_file = path;
+        self
+    }
+
+    /// 每轮结束后把Prometheus指标推送到Pushgateway
+    pub fn with_metrics_push(mut self, url: Option<String>) -> Self {
+        self.metrics_push = url;
+        self
+    }
+
+    /// 每轮结束后（发送告警之后）以本轮的告警和报告调用 `on_cycle`，由调用方决定如何输出
+    pub fn with_on_cycle(
+        mut self,
+        on_cycle: impl FnMut(&MonitorCycle<'_>) + Send + 'static,
+    ) -> Self {
+        self.on_cycle = Some(Box::new(on_cycle));
+        self
+    }
+
+    /// 从已解析的请求开始运行，直到取消，返回累计的监控状态
+    ///
+    /// 之后每轮重新解析文件，解析失败时输出警告并跳过这一轮，下一轮再重新解析。
+    pub async fn run(mut self, requests: Vec<HttpRequest>) -> Result<Monitor> {
+        let cancellation = self.runner.client_mut().cancellation_token().clone();
+        let mut requests = Some(requests);
+
+        loop {
+            if let Some(requests) = &requests {
+                let report = self.runner.run(requests).await?;
+                if report.cancelled {
+                    break;
+                }
+                self.record(&report).await?;
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(self.interval) => {}
+                _ = cancellation.cancelled() => break,
+            }
+            requests = self.parse();
+        }
+
+        Ok(self.monitor)
+    }
+
+    /// 记录一轮运行，发送告警并输出指标
+    async fn record(&mut self, report: &RunReport) -> Result<()> {
+        let alerts = self.monitor.record(report);
+        if let Some(url) = &self.webhook {
+            for alert in &alerts {
+                if let Err(e) = notify(url, alert).await {
+                    warn!("Failed to send notification: {e}");
+                }
+            }
+        }
+        if let Some(on_cycle) = &mut self.on_cycle {
+            on_cycle(&MonitorCycle {
+                number: self.monitor.cycles(),
+                alerts: &alerts,
+                report,
+            });
+        }
+        if let Some(path) = &self.metrics_file {
+            fs::write(path, metrics::prometheus_text(report))?;
+        }
+        if let Some(url) = &self.metrics_push
+            && let Err(e) = metrics::push(url, report).await
+        {
+            warn!("Failed to push metrics: {e}");
+        }
+        Ok(())
+    }
+
+    /// 重新解析所有文件，任一文件解析失败时返回None
+    fn parse(&mut self) -> Option<Vec<HttpRequest>> {
+        let mut requests = Vec::new();
+        for file in &self.files {
+            match self.parser.parse_file(file) {
+                Ok(parsed) => requests.extend(parsed),
+                Err(e) => {
+                    warn!("Skipping monitor cycle, failed to parse {file}: {e}");
+                    return None;
+                }
+            }
+        }
+        Some(requests)
+    }
+}
+
+/// 失败原因：传输错误或第一个失败的测试
+pub(crate) fn failure_reason(request: &RequestReport) -> String {
+    if let Some(error) = &request.error {
+        return error.clone();
+    }
+    if request.is_xpassed() {
+        return "expected to fail (@xfail) but passed".to_string();
+    }
+    request
+        .test_results
+        .iter()
+        .find(|test| test.is_failure())
+        .map(|test| {
+            format!(
+                "test '{}' failed: {}",
+                test.name,
+                test.message.as_deref().unwrap_or("assertion failed")
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// 把告警POST到webhook，请求体为 `{"text", "case", "status"}`
+pub async fn notify(url: &str, alert: &Alert) -> Result<()> {
+    let (case, status) = match alert {
+        Alert::Failing { case, .. } => (case, "failing"),
+        Alert::Recovered { case, .. } => (case, "recovered"),
+    };
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&json!({
+            "text": alert.to_string(),
+            "case": case,
+            "status": status,
+        }))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(HttpieError::InvalidRequest(format!(
+            "Failed to send notification to {url}: HTTP {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! netrc凭据模块
+//!
+//! 读取 `~/.netrc`（或 `NETRC` 环境变量指定的文件），为主机匹配且未声明 `Authorization`
+//! 的请求补充Basic认证，与curl、HTTPie的行为一致。
+
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// 指定netrc文件路径的环境变量
+pub const NETRC_VAR: &str = "NETRC";
+
+/// 一个主机的登录凭据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetrcEntry {
+    pub login: String,
+    pub password: String,
+}
+
+/// 解析后的netrc文件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Netrc {
+    machines: HashMap<String, NetrcEntry>,
+    default: Option<NetrcEntry>,
+}
+
+impl Netrc {
+    /// 解析netrc内容（`machine`、`default`、`login`、`password`，忽略 `account` 和 `macdef` 宏）
+    ///
+    /// 同一主机出现多次时以第一条为准。
+    pub fn parse(content: &str) -> Self {
+        let mut netrc = Self::default();
+        // 当前条目：主机（None 为 default）和已读取的登录名、密码
+        let mut current: Option<(Option<String>, String, String)> = None;
+        let mut lines = content.lines();
+
+        while let Some(line) = lines.next() {
+            let mut tokens = line.split_whitespace();
+            while let Some(token) = tokens.next() {
+                match token {
+                    "machine" | "default" => {
+                        netrc.finish(current.take());
+                        let host = match token {
+                            "machine" => tokens.next().map(str::to_lowercase),
+                            _ => None,
+                        };
+                        current = Some((host, String::new(), String::new()));
+                    }
+                    "login" | "password" | "account" => {
+                        let value = tokens.next().unwrap_or_default().to_string();
+                        if let Some((_, login, password)) = &mut current {
+                            match token {
+                                "login" => *login = value,
+                                "password" => *password = value,
+                                _ => {}
+                            }
+                        }
+                    }
+                    "macdef" => {
+                        // 宏定义持续到下一个空行
+                        netrc.finish(current.take());
+                        for line in lines.by_ref() {
+                            if line.trim().is_empty() {
+                                break;
+                            }
+                        }
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        netrc.finish(current);
+        netrc
+    }
+
+    fn finish(&mut self, entry: Option<(Option<String>, String, String)>) {
+        let Some((host, login, password)) = entry else {
+            return;
+        };
+        if login.is_empty() {
+            return;
+        }
+        let entry = NetrcEntry { login, password };
+        match host {
+            Some(host) => {
+                self.machines.entry(host).or_insert(entry);
+            }
+            None => {
+                self.default.get_or_insert(entry);
+            }
+        }
+    }
+
+    /// 读取指定的netrc文件
+    pub fn load_from(path: &Path) -> Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// 读取 `NETRC` 指定的文件或主目录下的 `.netrc`（Windows为 `_netrc`），文件不存在时返回None
+    pub fn load() -> Result<Option<Self>> {
+        let Some(path) = default_path() else {
+            return Ok(None);
+        };
+        match Self::load_from(&path) {
+            Ok(netrc) => Ok(Some(netrc)),
+            Err(HttpieError::Io(e)) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 主机的凭据（不区分大小写），没有匹配的 `machine` 时使用 `default`
+    pub fn credentials(&self, host: &str) -> Option<&NetrcEntry> {
+        self.machines
+            .get(&host.to_lowercase())
+            .or(self.default.as_ref())
+    }
+
+    /// 请求未声明 `Authorization` 时按URL主机补充Basic认证
+    ///
+    /// URL中含运行期变量或无法解析时不做处理。
+    pub fn authorize(&self, request: &mut HttpRequest) {
+        if request
+            .headers
+            .keys()
+            .any(|key| key.eq_ignore_ascii_case("Authorization"))
+        {
+            return;
+        }
+        let Ok(url) = request.target_url() else {
+            return;
+        };
+        let Some(entry) = url.host_str().and_then(|host| self.credentials(host)) else {
+            return;
+        };
+        let credentials = format!("{}:{}", entry.login, entry.password);
+        request.headers.insert(
+            "Authorization".to_string(),
+            format!("Basic {}", BASE64.encode(credentials)),
+        );
+    }
+}
+
+/// 默认的netrc路径：`NETRC` 环境变量，其次是主目录下的文件
+fn default_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(NETRC_VAR) {
+        return Some(PathBuf::from(path));
+    }
+    let (home, name) = if cfg!(windows) {
+        (std::env::var_os("USERPROFILE"), "_netrc")
+    } else {
+        (std::env::var_os("HOME"), ".netrc")
+    };
+    home.map(|home| PathBuf::from(home).join(name))
+}
@@ -0,0 +1,125 @@
+//! 失败通知模块
+//!
+//! 运行中有请求或测试失败时，把运行摘要POST到Slack、Teams或通用webhook。
+//! 请求体为JSON：`text` 字段是按模板渲染的消息（Slack和Teams直接显示），
+//! 其余字段是结构化的摘要，供通用webhook处理。通知请求通过执行器自己的传输层发送，
+//! 与用例共享代理和TLS设置。
+
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use crate::monitor::failure_reason;
+use crate::runner::RunReport;
+use crate::transport::HttpTransport;
+use reqwest::Method;
+use serde_json::json;
+
+/// 默认的消息模板
+pub const DEFAULT_TEMPLATE: &str =
+    "httpie: {{failed}} of {{total}} request(s) failed in {{source}}\n{{failures}}";
+
+/// 发送失败通知的webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notifier {
+    webhook: String,
+    template: String,
+    source: String,
+}
+
+impl Notifier {
+    pub fn new(webhook: impl Into<String>) -> Self {
+        Self {
+            webhook: webhook.into(),
+            template: DEFAULT_TEMPLATE.to_string(),
+            source: String::new(),
+        }
+    }
+
+    /// 设置消息模板
+    ///
+    /// 可用占位符：`{{source}}`、`{{total}}`、`{{passed}}`、`{{failed}}`、
+    /// `{{skipped}}`、`{{duration_ms}}` 和 `{{failures}}`（每个失败的请求一行）。
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = template.into();
+        self
+    }
+
+    /// 设置运行的来源（通常是.http文件路径）
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// 按模板渲染的消息
+    pub fn message(&self, report: &RunReport) -> String {
+        let failures: Vec<String> = report
+            .failures()
+            .map(|request| format!("• {}: {}", request.name, failure_reason(request)))
+            .collect();
+        self.template
+            .replace("{{source}}", &self.source)
+            .replace("{{total}}", &report.total().to_string())
+            .replace("{{passed}}", &report.passed().to_string())
+            .replace("{{failed}}", &report.failed().to_string())
+            .replace("{{skipped}}", &report.skipped().to_string())
+            .replace("{{duration_ms}}", &report.duration.as_millis().to_string())
+            .replace("{{failures}}", &failures.join("\n"))
+    }
+
+    /// 通知请求
+    pub fn request(&self, report: &RunReport) -> HttpRequest {
+        let failures: Vec<_> = report
+            .failures()
+            .map(|request| {
+                let mut failure = json!({
+                    "name": request.name,
+                    "status": request.status,
+                    "reason": failure_reason(request),
+                });
+                // 脚本日志保留失败时的上下文
+                if !request.logs.is_empty() {
+                    failure["logs"] = json!(request.logs);
+                }
+                failure
+            })
+            .collect();
+        let body = json!({
+            "text": self.message(report),
+            "source": self.source,
+            "total": report.total(),
+            "passed": report.passed(),
+            "failed": report.failed(),
+            "skipped": report.skipped(),
+            "durationMs": report.duration.as_millis() as u64,
+            "failures": failures,
+        });
+        let mut request = HttpRequest::new(
+            "notification".to_string(),
+            Method::POST,
+            self.webhook.clone(),
+        );
+        request
+            .headers
+            .insert("Content-Type".to_string(), "application/json".to_string());
+        request.body = Some(body.to_string());
+        request
+    }
+
+    /// 运行有失败时发送通知，返回是否发送
+    pub async fn notify_on_failure(
+        &self,
+        transport: &dyn HttpTransport,
+        report: &RunReport,
+    ) -> Result<bool> {
+        if report.failed() == 0 {
+            return Ok(false);
+        }
+        let response = transport.send(&self.request(report)).await?;
+        if !(200..300).contains(&response.status) {
+            return Err(HttpieError::InvalidRequest(format!(
+                "Failed to send notification to {}: HTTP {}",
+                self.webhook, response.status
+            )));
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,32 @@
+//! 执行观察者模块
+//!
+//! 定义运行生命周期的回调接口，供图形界面、进度条和自定义日志接入。
+
+use crate::models::HttpRequest;
+use crate::runner::{RequestReport, RunReport};
+use crate::script::{ResponseObject, TestResult};
+use std::time::Duration;
+
+/// 执行观察者，所有回调均有空的默认实现
+pub trait ExecutionObserver {
+    /// 发送请求前调用（`index` 从0开始），并发执行时可能早于之前请求的完成回调
+    fn on_request_start(&mut self, _request: &HttpRequest, _index: usize, _total: usize) {}
+
+    /// 收到响应并完成脚本处理后调用
+    fn on_response(
+        &mut self,
+        _request: &HttpRequest,
+        _response: &ResponseObject,
+        _duration: Duration,
+    ) {
+    }
+
+    /// 每个测试结果产生时调用
+    fn on_test_result(&mut self, _request: &HttpRequest, _result: &TestResult) {}
+
+    /// 请求处理结束（无论成功或失败）时调用
+    fn on_request_complete(&mut self, _request: &HttpRequest, _report: &RequestReport) {}
+
+    /// 整个运行结束时调用
+    fn on_run_complete(&mut self, _report: &RunReport) {}
+}
@@ -0,0 +1,192 @@
+//! 输出选项模块
+//!
+//! 控制打印请求/响应的哪些部分：`--print` 选择部分（与原版httpie相同的 `hbHB` 写法），
+//! `--print-headers` 和 `--hide-headers` 过滤显示的头，避免代理和CDN添加的头淹没关心的内容；
+//! JSON响应体按 [`JsonFormat`] 排版。
+
+use crate::error::{HttpieError, Result};
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::ser::PrettyFormatter;
+use std::str::FromStr;
+
+/// JSON默认缩进的空格数
+pub const DEFAULT_JSON_INDENT: usize = 2;
+
+/// 要打印的部分
+///
+/// `H` 请求头、`B` 请求体、`h` 响应头、`b` 响应体；默认 `hb`。
+/// 状态行等摘要信息总会打印。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintParts {
+    pub request_headers: bool,
+    pub request_body: bool,
+    pub response_headers: bool,
+    pub response_body: bool,
+}
+
+impl Default for PrintParts {
+    fn default() -> Self {
+        Self {
+            request_headers: false,
+            request_body: false,
+            response_headers: true,
+            response_body: true,
+        }
+    }
+}
+
+impl FromStr for PrintParts {
+    type Err = HttpieError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = Self {
+            request_headers: false,
+            request_body: false,
+            response_headers: false,
+            response_body: false,
+        };
+        for c in s.chars() {
+            match c {
+                'H' => parts.request_headers = true,
+                'B' => parts.request_body = true,
+                'h' => parts.response_headers = true,
+                'b' => parts.response_body = true,
+                _ => {
+                    return Err(HttpieError::Parse(format!(
+                        "Invalid --print value '{s}': expected a combination of H, B, h and b"
+                    )));
+                }
+            }
+        }
+        Ok(parts)
+    }
+}
+
+/// 按名称过滤显示的头（不区分大小写）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderFilter {
+    /// 非空时只显示这些头
+    only: Vec<String>,
+    hidden: Vec<String>,
+}
+
+impl HeaderFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只显示逗号分隔列表中的头
+    pub fn with_only(mut self, names: &str) -> Self {
+        self.only = split_names(names);
+        self
+    }
+
+    /// 隐藏逗号分隔列表中的头
+    pub fn with_hidden(mut self, names: &str) -> Self {
+        self.hidden = split_names(names);
+        self
+    }
+
+    /// 是否显示名为 `name` 的头
+    pub fn allows(&self, name: &str) -> bool {
+        let matches = |names: &[String]| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+        (self.only.is_empty() || matches(&self.only)) && !matches(&self.hidden)
+    }
+}
+
+fn split_names(names: &str) -> Vec<String> {
+    names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// JSON响应体的排版方式
+///
+/// 默认按服务器返回的键顺序、缩进两个空格输出。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonFormat {
+    /// 按键名排序对象（递归）
+    pub sort_keys: bool,
+    /// 每层缩进的空格数
+    pub indent: usize,
+    /// 紧凑输出（单行，忽略缩进）
+    pub compact: bool,
+}
+
+impl Default for JsonFormat {
+    fn default() -> Self {
+        Self {
+            sort_keys: false,
+            indent: DEFAULT_JSON_INDENT,
+            compact: false,
+        }
+    }
+}
+
+impl JsonFormat {
+    /// 排版JSON值
+    pub fn render(&self, value: &Value) -> String {
+        let sorted;
+        let value = if self.sort_keys {
+            sorted = sort_keys(value);
+            &sorted
+        } else {
+            value
+        };
+        if self.compact {
+            return value.to_string();
+        }
+
+        let indent = " ".repeat(self.indent);
+        let mut out = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(
+            &mut out,
+            PrettyFormatter::with_indent(indent.as_bytes()),
+        );
+        match value.serialize(&mut serializer) {
+            Ok(()) => String::from_utf8(out).unwrap_or_else(|_| value.to_string()),
+            Err(_) => value.to_string(),
+        }
+    }
+
+    /// 文本是JSON时排版，否则返回None
+    pub fn render_text(&self, text: &str) -> Option<String> {
+        serde_json::from_str::<Value>(text)
+            .ok()
+            .map(|value| self.render(&value))
+    }
+}
+
+/// 递归按键名排序对象
+fn sort_keys(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sort_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+/// 打印响应的选项
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputOptions {
+    pub parts: PrintParts,
+    pub headers: HeaderFilter,
+    pub json: JsonFormat,
+    /// 响应体最多打印的字节数
+    pub max_body_print: Option<usize>,
+    /// 同时打印连接信息（对端地址、TLS证书有效期）
+    pub verbose: bool,
+}
@@ -0,0 +1,99 @@
+//! 分页输出模块
+//!
+//! 标准输出是终端且响应输出超过阈值时，像git一样通过 `$PAGER`（默认 `less -R`）显示，
+//! 避免巨大的响应体冲掉终端的滚动缓冲区。分页器无法启动时直接打印。
+
+use crate::error::Result;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// 启用分页的默认输出大小（字节）
+pub const DEFAULT_PAGER_THRESHOLD: usize = 64 * 1024;
+
+const DEFAULT_PAGER: &str = "less -R";
+
+/// 外部分页程序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pager {
+    command: String,
+    threshold: usize,
+}
+
+impl Pager {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            threshold: DEFAULT_PAGER_THRESHOLD,
+        }
+    }
+
+    /// 按 `$PAGER` 创建分页器，标准输出不是终端时返回None
+    pub fn detect() -> Option<Self> {
+        if !std::io::stdout().is_terminal() {
+            return None;
+        }
+        Self::from_command(std::env::var("PAGER").ok().as_deref())
+    }
+
+    /// 未设置时使用 `less -R`；设置为空或 `cat` 时不分页
+    pub fn from_command(command: Option<&str>) -> Option<Self> {
+        match command.map(str::trim) {
+            None => Some(Self::new(DEFAULT_PAGER)),
+            Some("" | "cat") => None,
+            Some(command) => Some(Self::new(command)),
+        }
+    }
+
+    /// 设置启用分页的输出大小
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// 输出是否超过阈值，需要分页
+    pub fn should_page(&self, output: &str) -> bool {
+        output.len() > self.threshold
+    }
+
+    /// 通过分页器显示输出，等待分页器退出；分页器无法启动时直接打印
+    pub fn page(&self, output: &str) -> Result<()> {
+        let mut child = match shell(&self.command)
+            // 与git相同：内容不足一屏时直接退出，保留颜色
+            .env(
+                "LESS",
+                std::env::var("LESS").unwrap_or_else(|_| "FRX".into()),
+            )
+            .stdin(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(_) => {
+                print!("{output}");
+                return Ok(());
+            }
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            // 用户提前退出分页器时写入失败（管道已关闭），忽略
+            let _ = stdin.write_all(output.as_bytes());
+        }
+        child.wait()?;
+        Ok(())
+    }
+}
+
+/// 通过系统shell执行命令，支持 `$PAGER` 中带参数的写法
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
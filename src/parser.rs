@@ -3,231 +3,1538 @@
 //! 负责解析.http文件格式，提取HTTP请求信息。
 
 use crate::SUPPORTED_METHODS;
-use crate::error::{HttpieError, Result};
-use crate::models::{Environment, HttpRequest};
-use crate::variable::VariableReplacer;
+use crate::aggregate::AggregateAssertion;
+use crate::assertion::{ASSERTION_PREFIX, Assertion};
+use crate::binary::{self, RANGE_DIRECTIVE, ResponseRedirect};
+use crate::capture::{CAPTURE_DIRECTIVE, Capture};
+use crate::client::{FILTER_DIRECTIVE, HTTP3_DIRECTIVE, RESOLVE_DIRECTIVE, XFAIL_TEST_DIRECTIVE};
+use crate::compress::{COMPRESS_DIRECTIVE, Compression};
+use crate::condition::{Condition, IF_DIRECTIVE, REQUIRE_DIRECTIVE};
+use crate::config::HostProfiles;
+use crate::curl;
+use crate::dialect::ParserDialect;
+use crate::error::{HttpieError, Result, SourceLocation};
+use crate::expect::{EXPECT_IGNORE_DIRECTIVE, EXPECT_MASK_DIRECTIVE, EXPECT_RESPONSE_DIRECTIVE};
+use crate::grpc::{GRPC_METHOD, GrpcCall, PROTO_DIRECTIVE};
+use crate::handler::{self, HandlerBlock};
+use crate::jsonpath;
+use crate::listen::{LISTEN_METHOD, Listen};
+use crate::models::{Directive, Environment, Headers, Hook, HttpRequest, HttpVersion, Iteration};
+use crate::netrc::Netrc;
+use crate::raw::{RAW_METHOD, RawConnection};
+use crate::runner::{ONLY_DIRECTIVE, SKIP_DIRECTIVE, XFAIL_DIRECTIVE};
+use crate::trace::{self, TraceContext, TraceFormat};
+use crate::upload;
+use crate::variable::{DynamicValues, VariableReplacer};
+use regex::Regex;
 use reqwest::Method;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
 use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::LazyLock;
+use tracing::warn;
 
-/// HTTP解析器
+/// 重复执行请求的指令（`# @repeat N`）
+pub const REPEAT_DIRECTIVE: &str = "repeat";
+
+/// 请求名称指令（`# @name 名称`），用于没有标题的请求
+pub const NAME_DIRECTIVE: &str = "name";
+
+/// 为单个请求指定环境的指令（`# @env staging`）
+pub const ENV_DIRECTIVE: &str = "env";
+
+/// 发送前去掉请求体首尾空白的指令（`# @trim-body`）
+pub const TRIM_BODY_DIRECTIVE: &str = "trim-body";
+
+/// GraphQL请求方法，发送时转换为POST
+const GRAPHQL_METHOD: &str = "GRAPHQL";
+
+/// Content-Type请求头名称
+const CONTENT_TYPE: &str = "Content-Type";
+
+/// JSON媒体类型
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// 路径参数指令（`# @path id = 42`），填充URL中的 `:id` 或 `{id}`
+pub const PATH_DIRECTIVE: &str = "path";
+
+/// 用户变量引用 `{{name}}`（不含 `$` 开头的动态变量）
+static VARIABLE_REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_.-]*)\s*\}\}").unwrap());
+
+/// 脚本中设置的全局变量 `client.global.set("name", ...)`
+static SCRIPT_GLOBAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"client\.global\.set\(\s*["']([A-Za-z0-9_.-]+)["']"#).unwrap());
+
+/// 本工具扩展的指令（`@name` 之外），其他未知指令产生警告
+const EXTENSION_DIRECTIVES: &[&str] = &[
+    REPEAT_DIRECTIVE,
+    ENV_DIRECTIVE,
+    PATH_DIRECTIVE,
+    TRIM_BODY_DIRECTIVE,
+    CAPTURE_DIRECTIVE,
+    RANGE_DIRECTIVE,
+    COMPRESS_DIRECTIVE,
+    FILTER_DIRECTIVE,
+    RESOLVE_DIRECTIVE,
+    HTTP3_DIRECTIVE,
+    EXPECT_RESPONSE_DIRECTIVE,
+    EXPECT_IGNORE_DIRECTIVE,
+    EXPECT_MASK_DIRECTIVE,
+    SKIP_DIRECTIVE,
+    ONLY_DIRECTIVE,
+    XFAIL_DIRECTIVE,
+    XFAIL_TEST_DIRECTIVE,
+    IF_DIRECTIVE,
+    REQUIRE_DIRECTIVE,
+    PROTO_DIRECTIVE,
+];
+
+/// 默认请求头段落的标题（`### @defaults`）
+pub const DEFAULTS_SECTION: &str = "@defaults";
+
+/// 请求段落，借用文件内容中的各行（不复制），并记录行号以便定位错误
 #[derive(Debug)]
-pub struct HttpParser {
-    environment: Environment,
+struct Section<'a> {
+    /// 段落在文件内容中覆盖的范围（含段落内的变量定义行）
+    text: &'a str,
+    /// 段落的各行（不含变量定义行）
+    lines: Vec<&'a str>,
+    line_numbers: Vec<usize>,
+    /// 请求行之前定义的文件变量，未变化时各段落共享同一份
+    variables: Rc<HashMap<String, String>>,
 }
 
-impl HttpParser {
-    /// 创建新的HTTP解析器
-    pub fn new(environment: Environment) -> Self {
-        Self { environment }
+impl Section<'_> {
+    /// 定位段落中的某一行（`line` 必须切自 `lines`），`column` 从1开始
+    fn locate(&self, line: &str, column: usize) -> SourceLocation {
+        let index = self
+            .lines
+            .iter()
+            .position(|candidate| std::ptr::eq(*candidate, line))
+            .unwrap_or(0);
+        SourceLocation {
+            file: None,
+            line: self.line_numbers.get(index).copied().unwrap_or(0),
+            column,
+        }
     }
 
-    /// 解析HTTP文件
-    pub fn parse_file(&mut self, file_path: &str) -> Result<Vec<HttpRequest>> {
-        let content = fs::read_to_string(file_path)
-            .map_err(|_| HttpieError::FileNotFound(file_path.to_string()))?;
+    /// 包含某个片段（切自 `lines` 中的某一行）的行在文件中的行号
+    fn line_of(&self, fragment: &str) -> Option<usize> {
+        let start = fragment.as_ptr() as usize;
+        let index = self.lines.iter().position(|line| {
+            let line_start = line.as_ptr() as usize;
+            (line_start..=line_start + line.len()).contains(&start)
+        })?;
+        self.line_numbers.get(index).copied()
+    }
 
-        // 解析文件内变量
-        self.parse_file_variables(&content);
+    /// 响应处理器脚本第一个非空行的行号
+    fn handler_line(&self, block: &HandlerBlock) -> Option<usize> {
+        let first = block.script.iter().find(|line| !line.trim().is_empty())?;
+        self.line_of(first)
+    }
 
-        // 解析请求
-        self.parse_requests(&content)
+    /// 将解析错误定位到段落中的某一行
+    fn error_at(&self, line: &str, column: usize, error: HttpieError) -> HttpieError {
+        error.at(self.locate(line, column), line.trim_end())
     }
 
-    /// 解析文件内变量定义
-    fn parse_file_variables(&mut self, content: &str) {
-        let mut file_variables = HashMap::new();
+    /// 定位到段落中某一行的警告
+    fn warning(&self, line: &str, column: usize, message: impl Into<String>) -> ParseWarning {
+        ParseWarning {
+            location: self.locate(line, column),
+            message: message.into(),
+            source_line: line.trim_end().to_string(),
+        }
+    }
 
-        for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with('@')
-                && let Some(eq_pos) = line.find('=')
-            {
-                let key = line[1..eq_pos].trim().to_string();
-                let value = line[eq_pos + 1..].trim().to_string();
-                file_variables.insert(key, value);
+    /// 定位到段落标题行的警告
+    fn title_warning(&self, message: impl Into<String>) -> ParseWarning {
+        let line = self.lines.first().copied().unwrap_or_default();
+        self.warning(line, first_column(line), message)
+    }
+
+    /// 按原样拼接段落中的若干行，保留每行原有的换行符（`\n` 或 `\r\n`），
+    /// 最后一行不带换行符
+    fn join(&self, lines: &[&str]) -> String {
+        let mut joined = String::new();
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 {
+                let previous = lines[index - 1];
+                let end = offset_of(self.text, previous) + previous.len();
+                let newline = if self.text[end..].starts_with("\r\n") {
+                    "\r\n"
+                } else {
+                    "\n"
+                };
+                joined.push_str(newline);
             }
+            joined.push_str(line);
         }
+        joined
+    }
 
-        self.environment.extend(file_variables);
+    /// 是否为 `### @defaults` 默认请求头段落
+    fn is_defaults(&self) -> bool {
+        self.lines
+            .first()
+            .and_then(|line| line.trim().strip_prefix("###"))
+            .is_some_and(|title| title.trim() == DEFAULTS_SECTION)
     }
 
-    /// 解析HTTP请求
-    fn parse_requests(&self, content: &str) -> Result<Vec<HttpRequest>> {
-        let mut requests = Vec::new();
-        let sections = self.split_into_sections(content);
+    /// 读取默认请求头段落中的 `Name: value` 行，变量在各请求中替换
+    fn default_headers(&self) -> Result<Headers> {
+        let mut headers = Headers::new();
+        for line in self.lines.iter().skip(1) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || is_comment(trimmed) {
+                continue;
+            }
+            match trimmed.split_once(':') {
+                Some((name, value)) if !name.trim().is_empty() => {
+                    headers.insert(name.trim().to_string(), value.trim().to_string());
+                }
+                _ => {
+                    let error = HttpieError::InvalidRequest(format!(
+                        "Invalid {DEFAULTS_SECTION} line '{trimmed}': expected 'Name: value'"
+                    ));
+                    return Err(self.error_at(line, first_column(line), error));
+                }
+            }
+        }
+        Ok(headers)
+    }
+}
+
+/// 解析时发现的非致命问题，`--strict` 时视为错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    pub location: SourceLocation,
+    pub message: String,
+    /// 所在行的原文
+    pub source_line: String,
+}
+
+impl ParseWarning {
+    /// 转换为带位置的解析错误
+    pub fn into_error(self) -> HttpieError {
+        HttpieError::Syntax {
+            location: self.location,
+            message: self.message,
+            source_line: self.source_line,
+        }
+    }
+
+    /// 作为 `warn` 事件输出，文件、行和列作为结构化字段
+    pub fn log(&self) {
+        warn!(
+            file = self.location.file.as_deref(),
+            line = self.location.line,
+            column = self.location.column,
+            "{}",
+            self.message
+        );
+    }
+
+    fn in_file(mut self, path: &str) -> Self {
+        self.location.file.get_or_insert_with(|| path.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// 解析结果：请求和警告
+#[derive(Debug, Clone)]
+pub struct ParseOutput {
+    pub requests: Vec<HttpRequest>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// 兼容模式下没有 `###` 的段落使用的标题行（名称为空，取 `# @name` 指令）
+const IMPLICIT_TITLE: &str = "###";
+
+/// 逐个解析请求的迭代器，由 [`HttpParser::iter_requests`] 创建
+pub struct Requests<'p, 'a> {
+    parser: &'p HttpParser,
+    sections: Sections<'a>,
+    /// 配置中的默认请求头，合并了目前为止读到的 `### @defaults` 段落
+    default_headers: Headers,
+    /// 重复执行的段落、下一次迭代序号和总次数
+    repeat: Option<(Section<'a>, usize, usize)>,
+    /// 已解析请求的指纹和名称，用于提示重复的请求（随请求数增长）
+    fingerprints: HashMap<String, String>,
+    /// 已解析请求的名称、第一个同名请求的序号和同名请求数，用于提示重名（随请求数增长）
+    names: HashMap<String, (usize, usize)>,
+    /// 已解析的请求数，即最后一个请求的序号
+    count: usize,
+    warnings: Vec<ParseWarning>,
+    done: bool,
+}
+
+impl Requests<'_, '_> {
+    fn next_request(&mut self) -> Result<Option<HttpRequest>> {
+        let default_headers = &mut self.default_headers;
+
+        // 每次迭代重新解析段落，使动态变量（$uuid、$randomInt）取新值
+        while let Some((section, index, total)) = &mut self.repeat {
+            let (current, total) = (*index, *total);
+            *index += 1;
+            // 警告只在第一次解析时记录
+            let request = self
+                .parser
+                .parse_section(section, default_headers, &mut Vec::new())?;
+            if current >= total {
+                self.repeat = None;
+            }
+            if let Some(request) = request {
+                let request = request.with_index(Some(self.count));
+                return Ok(Some(HttpParser::with_iteration(request, current, total)));
+            }
+        }
+
+        for section in self.sections.by_ref() {
+            if self.parser.dialect.extensions() && section.is_defaults() {
+                // 只作用于其后的请求，不必先读完整个文件
+                for (name, value) in section.default_headers()? {
+                    default_headers.retain(|key, _| !key.eq_ignore_ascii_case(&name));
+                    default_headers.insert(name, value);
+                }
+                if self.count > 0 {
+                    self.warnings.push(section.title_warning(
+                        "'@defaults' after other requests applies only to the requests below it",
+                    ));
+                }
+                continue;
+            }
+            let warnings = &mut self.warnings;
+            let Some(request) = self
+                .parser
+                .parse_section(&section, default_headers, warnings)?
+            else {
+                continue;
+            };
+            self.count += 1;
+            let request = request.with_index(Some(self.count));
+            self.parser.warn_about(&request, &section, warnings);
+            Self::warn_if_duplicate(&mut self.fingerprints, &request, &section, warnings);
+            Self::warn_if_same_name(&mut self.names, &request, &section, warnings);
+            let Some(total) = HttpParser::repeat_count(&request)? else {
+                return Ok(Some(request));
+            };
+            if total > 1 {
+                self.repeat = Some((section, 2, total));
+            }
+            return Ok(Some(HttpParser::with_iteration(request, 1, total)));
+        }
+        Ok(None)
+    }
+
+    /// 与之前解析的请求相同时记录警告
+    fn warn_if_duplicate(
+        fingerprints: &mut HashMap<String, String>,
+        request: &HttpRequest,
+        section: &Section,
+        warnings: &mut Vec<ParseWarning>,
+    ) {
+        if request.listen.is_some() {
+            return;
+        }
+        match fingerprints.entry(request.fingerprint()) {
+            Entry::Occupied(first) => warnings.push(section.title_warning(format!(
+                "requests '{}' and '{}' are identical (same method, URL and body; fingerprint {})",
+                first.get(),
+                request.name,
+                first.key()
+            ))),
+            Entry::Vacant(slot) => {
+                slot.insert(request.name.clone());
+            }
+        }
+    }
+
+    /// 与之前解析的请求同名时记录警告，并提示如何选择该请求
+    fn warn_if_same_name(
+        names: &mut HashMap<String, (usize, usize)>,
+        request: &HttpRequest,
+        section: &Section,
+        warnings: &mut Vec<ParseWarning>,
+    ) {
+        let index = request.index.unwrap_or_default();
+        let (first, count) = names.entry(request.name.clone()).or_insert((index, 0));
+        *count += 1;
+        if *count > 1 {
+            warnings.push(section.title_warning(format!(
+                "request name '{name}' is already used by request #{first}; \
+                 select this one with --case '{name}#{count}' or --index {index}",
+                name = request.name
+            )));
+        }
+    }
+
+    /// 取出目前为止记录的警告
+    pub fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+impl Iterator for Requests<'_, '_> {
+    type Item = Result<HttpRequest>;
 
-        for section in sections {
-            if let Some(request) = self.parse_request(&section)? {
-                requests.push(request);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_request() {
+            Ok(Some(request)) => Some(Ok(request)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
             }
         }
+    }
+}
+
+/// 将内容逐个分割为请求段落
+///
+/// 文件变量按位置生效：每个请求只看到其请求行之前的定义，后定义的同名变量
+/// 覆盖先前的值。
+struct Sections<'a> {
+    content: &'a str,
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    current: Option<Section<'a>>,
+    defined: Rc<HashMap<String, String>>,
+    /// 当前段落请求行之前的变量
+    section_variables: Option<Rc<HashMap<String, String>>>,
+    /// 空行之后的请求行开始新的段落
+    implicit: bool,
+}
+
+impl<'a> Sections<'a> {
+    fn new(content: &'a str, implicit: bool) -> Self {
+        Self {
+            content,
+            lines: content.lines().enumerate(),
+            current: None,
+            defined: Rc::default(),
+            section_variables: None,
+            implicit,
+        }
+    }
+
+    /// 从 `line` 开始的段落
+    fn start(&self, line: &'a str) -> Section<'a> {
+        let start = offset_of(self.content, line);
+        Section {
+            text: &self.content[start..start],
+            lines: Vec::new(),
+            line_numbers: Vec::new(),
+            variables: Rc::default(),
+        }
+    }
+
+    /// 从 `line`（第 `number` 行）开始、没有 `###` 标题的段落
+    fn start_implicit(&self, line: &'a str, number: usize) -> Section<'a> {
+        Section {
+            lines: vec![IMPLICIT_TITLE],
+            line_numbers: vec![number],
+            ..self.start(line)
+        }
+    }
+
+    /// 兼容模式下遇到新的请求行（第 `number` 行）时结束当前段落
+    ///
+    /// 请求行需位于空行之后，紧邻其前的注释行（如 `# @name`）归属新的请求；
+    /// 响应处理器中的代码不会被当作请求行。
+    fn split_implicit(&mut self, line: &'a str, number: usize) -> Option<Section<'a>> {
+        let section = self.current.as_mut()?;
+        let in_handler = handler::find(&section.lines).is_some_and(|block| block.end.is_none());
+        let mut start = section.lines.len();
+        while start > 1 && is_comment(section.lines[start - 1].trim()) {
+            start -= 1;
+        }
+        if in_handler || !section.lines[start - 1].trim().is_empty() {
+            return None;
+        }
 
-        Ok(requests)
+        let lines = section.lines.split_off(start);
+        let line_numbers = section.line_numbers.split_off(start);
+        let finished = self.finish();
+        let mut next = match lines.first() {
+            Some(first) => self.start_implicit(first, line_numbers[0]),
+            None => self.start_implicit(line, number),
+        };
+        next.lines.extend(lines);
+        next.line_numbers.extend(line_numbers);
+        self.current = Some(next);
+        finished
+    }
+
+    /// 结束当前段落
+    fn finish(&mut self) -> Option<Section<'a>> {
+        let section = self.current.take()?;
+        let variables = self
+            .section_variables
+            .take()
+            .unwrap_or_else(|| self.defined.clone());
+        Some(Section {
+            variables,
+            ..section
+        })
     }
+}
 
-    /// 将内容分割为请求段落
-    fn split_into_sections(&self, content: &str) -> Vec<String> {
-        let mut sections = Vec::new();
-        let mut current_section = String::new();
-        let mut in_request = false;
+impl<'a> Iterator for Sections<'a> {
+    type Item = Section<'a>;
 
-        for line in content.lines() {
+    fn next(&mut self) -> Option<Section<'a>> {
+        let content = self.content;
+        while let Some((index, line)) = self.lines.next() {
             let trimmed = line.trim();
 
-            // 跳过变量定义，但不跳过注释（因为###也是注释）
-            if trimmed.starts_with('@') {
+            // 记录并跳过变量定义，但不跳过注释（因为###也是注释）
+            if let Some(definition) = trimmed.strip_prefix('@') {
+                if let Some((key, value)) = definition.split_once('=') {
+                    Rc::make_mut(&mut self.defined)
+                        .insert(key.trim().to_string(), value.trim().to_string());
+                }
                 continue;
             }
 
             // 检查是否是新的请求开始
+            let mut finished = None;
             if trimmed.starts_with("###") {
-                if in_request && !current_section.trim().is_empty() {
-                    sections.push(current_section.clone());
+                finished = self.finish();
+                self.current = Some(self.start(line));
+            } else {
+                if self.implicit && self.section_variables.is_some() && is_request_line(trimmed) {
+                    finished = self.split_implicit(line, index + 1);
+                }
+                if self.implicit && self.current.is_none() && !trimmed.is_empty() {
+                    self.current = Some(self.start_implicit(line, index + 1));
                 }
-                current_section = String::new();
-                in_request = true;
-                current_section.push_str(line);
-                current_section.push('\n');
-            } else if in_request {
-                current_section.push_str(line);
-                current_section.push('\n');
+                if self.current.is_some()
+                    && self.section_variables.is_none()
+                    && is_request_line(trimmed)
+                {
+                    self.section_variables = Some(self.defined.clone());
+                }
+            }
+            if let Some(section) = self.current.as_mut() {
+                let start = offset_of(content, section.text);
+                section.text = &content[start..offset_of(content, line) + line.len()];
+                section.lines.push(line);
+                section.line_numbers.push(index + 1);
+            }
+            if finished.is_some() {
+                return finished;
+            }
+        }
+        self.finish()
+    }
+}
+
+/// `part` 在 `content` 中的字节偏移（`part` 必须切自 `content`）
+fn offset_of(content: &str, part: &str) -> usize {
+    part.as_ptr() as usize - content.as_ptr() as usize
+}
+
+/// 行首缩进之后的第一列（从1开始）
+fn first_column(line: &str) -> usize {
+    line.len() - line.trim_start().len() + 1
+}
+
+/// `part` 在 `line` 中最后一次出现的列（从1开始），找不到时为行首
+fn column_of(line: &str, part: &str) -> usize {
+    line.rfind(part)
+        .map_or_else(|| first_column(line), |pos| pos + 1)
+}
+
+/// 查询参数续行（`?a=1` 或 `&b=2`，同一行可用 `&` 连接多个参数）
+fn query_params(line: &str) -> Option<Vec<(&str, &str)>> {
+    let trimmed = line.trim();
+    if trimmed.starts_with(ASSERTION_PREFIX) {
+        return None;
+    }
+    let params = trimmed.strip_prefix(['?', '&'])?;
+    Some(
+        params
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                (key.trim(), value.trim())
+            })
+            .collect(),
+    )
+}
+
+/// 将GRAPHQL请求体转换为JSON：查询文本后可跟空行和JSON对象作为 `variables`，
+/// 已是 `{"query": ...}` 形式的请求体原样保留
+fn graphql_body(body: &str) -> String {
+    if let Ok(Value::Object(payload)) = serde_json::from_str::<Value>(body)
+        && payload.contains_key("query")
+    {
+        return body.to_string();
+    }
+
+    // 第一个其后内容整体为JSON对象的空行即分隔符
+    let lines: Vec<&str> = body.lines().collect();
+    let (query, variables) = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.trim().is_empty())
+        .find_map(|(index, _)| {
+            match serde_json::from_str::<Value>(&lines[index + 1..].join("\n")) {
+                Ok(variables @ Value::Object(_)) => Some((lines[..index].join("\n"), variables)),
+                _ => None,
+            }
+        })
+        .map_or((body.to_string(), None), |(query, variables)| {
+            (query, Some(variables))
+        });
+
+    let mut payload = serde_json::Map::new();
+    payload.insert("query".to_string(), Value::String(query.trim().to_string()));
+    if let Some(variables) = variables {
+        payload.insert("variables".to_string(), variables);
+    }
+    Value::Object(payload).to_string()
+}
+
+/// 请求体为JSON对象或数组
+fn is_json_document(body: &str) -> bool {
+    serde_json::from_str::<Value>(body).is_ok_and(|value| value.is_object() || value.is_array())
+}
+
+/// `application/json` 或 `+json` 结尾的媒体类型
+fn is_json_media_type(content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or_default().trim();
+    media_type.eq_ignore_ascii_case(JSON_MEDIA_TYPE)
+        || media_type.to_ascii_lowercase().ends_with("+json")
+}
+
+/// 声明的Content-Type与请求体形态不符时返回警告
+fn content_type_mismatch(request: &HttpRequest) -> Option<String> {
+    let body = request.body.as_deref()?;
+    let (_, content_type) = request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(CONTENT_TYPE))?;
+
+    // 含运行期变量的请求体在发送前无法判断
+    if is_json_media_type(content_type) {
+        (!body.contains("{{") && serde_json::from_str::<Value>(body).is_err()).then(|| {
+            format!(
+                "request '{}' declares Content-Type '{content_type}' but the body is not valid JSON",
+                request.name
+            )
+        })
+    } else {
+        (is_json_document(body) && !content_type.trim_start().starts_with("text/")).then(|| {
+            format!(
+                "request '{}' has a JSON body but declares Content-Type '{content_type}'",
+                request.name
+            )
+        })
+    }
+}
+
+/// 指纹相同的请求对：`(先出现的下标, 重复的下标)`
+///
+/// 同一段落重复执行产生的迭代和 `LISTEN` 步骤不参与比较。
+pub fn duplicate_requests(requests: &[HttpRequest]) -> Vec<(usize, usize)> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for (index, request) in requests.iter().enumerate() {
+        if request.listen.is_some() || request.iteration.is_some_and(|i| i.index > 1) {
+            continue;
+        }
+        match seen.entry(request.fingerprint()) {
+            Entry::Occupied(first) => duplicates.push((*first.get(), index)),
+            Entry::Vacant(slot) => {
+                slot.insert(index);
+            }
+        }
+    }
+    duplicates
+}
+
+/// 文件引用但未在文件内定义的变量
+///
+/// 文件内 `@var`、`# @capture` 和脚本中 `client.global.set` 设置的变量视为已定义，
+/// 其余 `{{name}}` 需要由环境、`.env` 或 `--var` 提供。
+pub fn required_variables(content: &str) -> BTreeSet<String> {
+    let mut defined = BTreeSet::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some((name, _)) = trimmed.strip_prefix('@').and_then(|d| d.split_once('=')) {
+            defined.insert(name.trim().to_string());
+        } else if let Some(directive) = HttpParser::parse_directive(trimmed)
+            && directive.name == CAPTURE_DIRECTIVE
+            && let Ok(capture) = Capture::parse(&directive.value)
+        {
+            defined.insert(capture.name);
+        }
+    }
+    defined.extend(
+        SCRIPT_GLOBAL
+            .captures_iter(content)
+            .map(|captures| captures[1].to_string()),
+    );
+
+    VARIABLE_REFERENCE
+        .captures_iter(content)
+        .map(|captures| captures[1].to_string())
+        .filter(|name| !defined.contains(name))
+        .collect()
+}
+
+/// 补充请求中未声明的默认请求头（不区分大小写）
+fn add_default_headers(headers: &mut Headers, defaults: &Headers, replacer: &VariableReplacer) {
+    for (name, value) in defaults {
+        if !headers.keys().any(|key| key.eq_ignore_ascii_case(name)) {
+            headers.insert(name.clone(), replacer.replace(value));
+        }
+    }
+}
+
+fn unterminated_handler() -> HttpieError {
+    HttpieError::InvalidRequest("Unterminated response handler: expected '%}'".to_string())
+}
+
+/// 请求头名称是否为合法的token（含变量引用时不检查）
+fn is_header_name(name: &str) -> bool {
+    name.contains("{{")
+        || (!name.is_empty()
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)))
+}
+
+/// 注释行：以 `#` 或 `//` 开头
+fn is_comment(trimmed: &str) -> bool {
+    trimmed.starts_with('#') || trimmed.starts_with("//")
+}
+
+/// 请求行：粘贴的curl命令，或以支持的HTTP方法开头的非注释行
+pub(crate) fn is_request_line(trimmed: &str) -> bool {
+    !trimmed.is_empty()
+        && !trimmed.starts_with('#')
+        && (curl::is_curl_command(trimmed)
+            || SUPPORTED_METHODS
+                .iter()
+                .any(|method| trimmed.starts_with(method)))
+}
+
+/// HTTP解析器
+#[derive(Debug)]
+pub struct HttpParser {
+    environment: Environment,
+    /// 为未声明Content-Type的JSON请求体补充 `application/json`
+    infer_content_type: bool,
+    /// 命令行 `--var` 指定的变量，优先于文件内变量
+    overrides: HashMap<String, String>,
+    /// 请求中未声明时自动添加的请求头
+    default_headers: Headers,
+    /// 自动注入的追踪请求头格式
+    trace_formats: Vec<TraceFormat>,
+    /// 是否为每个请求添加 `X-Request-Id`
+    inject_request_id: bool,
+    /// 为未声明Authorization的请求提供Basic认证的netrc凭据
+    netrc: Option<Netrc>,
+    /// 按URL主机添加的认证和请求头
+    host_profiles: HostProfiles,
+    /// 动态变量、追踪ID和请求ID的取值来源
+    dynamic_values: DynamicValues,
+    /// 兼容模式：空行之后的请求行开始新的请求，不需要 `###` 分隔
+    implicit_sections: bool,
+    /// 识别哪些扩展语法
+    dialect: ParserDialect,
+}
+
+impl HttpParser {
+    /// 创建新的HTTP解析器
+    pub fn new(environment: Environment) -> Self {
+        Self {
+            environment,
+            infer_content_type: true,
+            overrides: HashMap::new(),
+            default_headers: Headers::new(),
+            trace_formats: Vec::new(),
+            inject_request_id: false,
+            netrc: None,
+            host_profiles: HostProfiles::default(),
+            dynamic_values: DynamicValues::default(),
+            implicit_sections: false,
+            dialect: ParserDialect::default(),
+        }
+    }
+
+    /// 为每个请求注入追踪请求头（W3C `traceparent` 和/或 B3），已声明的不覆盖
+    pub fn with_trace_formats(mut self, trace_formats: Vec<TraceFormat>) -> Self {
+        self.trace_formats = trace_formats;
+        self
+    }
+
+    /// 为每个请求添加 `X-Request-Id`（UUID，与 `{{$idempotencyKey}}` 相同），已声明的不覆盖
+    pub fn with_request_id(mut self, inject_request_id: bool) -> Self {
+        self.inject_request_id = inject_request_id;
+        self
+    }
+
+    /// 设置netrc凭据，主机匹配且未声明 `Authorization` 的请求自动使用Basic认证
+    pub fn with_netrc(mut self, netrc: Option<Netrc>) -> Self {
+        self.netrc = netrc;
+        self
+    }
+
+    /// 设置按主机应用的认证和请求头，优先于默认请求头和netrc
+    pub fn with_host_profiles(mut self, host_profiles: HostProfiles) -> Self {
+        self.host_profiles = host_profiles;
+        self
+    }
+
+    /// 设置动态变量的取值来源，使用种子和冻结时间时解析结果可复现
+    pub fn with_dynamic_values(mut self, dynamic_values: DynamicValues) -> Self {
+        self.dynamic_values = dynamic_values;
+        self
+    }
+
+    /// 设置默认请求头，请求中已声明的同名请求头（不区分大小写）不受影响
+    pub fn with_default_headers(mut self, default_headers: Headers) -> Self {
+        self.default_headers = default_headers;
+        self
+    }
+
+    /// 设置命令行变量，覆盖文件内 `@var`、环境文件和 `.env` 中的同名变量
+    pub fn with_variable_overrides(mut self, overrides: HashMap<String, String>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// 设置是否允许只以空行分隔、没有 `###` 的请求（默认关闭）
+    ///
+    /// 开启后，空行（及其后紧接的注释行）之后的请求行开始新的请求，名称取自
+    /// `# @name` 指令。
+    pub fn with_implicit_sections(mut self, implicit_sections: bool) -> Self {
+        self.implicit_sections = implicit_sections;
+        self
+    }
+
+    /// 按其他工具的方言解析（默认为本工具的全部语法）
+    pub fn with_dialect(mut self, dialect: ParserDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// 是否允许只以空行分隔的请求：开启兼容模式或方言允许时
+    fn implicit_sections(&self) -> bool {
+        self.implicit_sections || self.dialect.implicit_sections()
+    }
+
+    /// 设置是否为JSON请求体自动补充Content-Type（默认开启）
+    pub fn with_infer_content_type(mut self, infer_content_type: bool) -> Self {
+        self.infer_content_type = infer_content_type;
+        self
+    }
+
+    /// 解析HTTP文件，警告输出到日志
+    pub fn parse_file(&mut self, file_path: &str) -> Result<Vec<HttpRequest>> {
+        let output = self.parse_file_with_warnings(file_path)?;
+        for warning in &output.warnings {
+            warn!("{warning}");
+        }
+        Ok(output.requests)
+    }
+
+    /// 解析HTTP文件，同时返回非致命的警告
+    pub fn parse_file_with_warnings(&mut self, file_path: &str) -> Result<ParseOutput> {
+        let content = fs::read_to_string(file_path)
+            .map_err(|_| HttpieError::FileNotFound(file_path.to_string()))?;
+
+        // 解析请求
+        let source_file = PathBuf::from(file_path);
+        let output = self
+            .parse_requests(&content)
+            .map_err(|e| e.in_file(file_path))?;
+        let requests = output
+            .requests
+            .into_iter()
+            .map(|request| request.with_source_file(Some(source_file.clone())))
+            .collect();
+        let warnings = output
+            .warnings
+            .into_iter()
+            .map(|warning| warning.in_file(file_path))
+            .collect();
+
+        Ok(ParseOutput { requests, warnings })
+    }
+
+    /// 解析HTTP请求
+    fn parse_requests(&self, content: &str) -> Result<ParseOutput> {
+        let mut iter = self.iter_requests(content);
+        let requests = iter.by_ref().collect::<Result<Vec<_>>>()?;
+        Ok(ParseOutput {
+            requests,
+            warnings: iter.take_warnings(),
+        })
+    }
+
+    /// 逐个解析内容中的请求
+    ///
+    /// 迭代器每次只解析一个段落，调用方可以在其余内容解析完成之前处理已解析的请求；
+    /// `### @defaults` 只作用于其后的请求。已返回的请求不再保留，但为提示重复和重名的请求，
+    /// 每个请求的指纹和名称会保留到迭代结束。解析出错时返回错误并结束迭代。
+    pub fn iter_requests<'p, 'a>(&'p self, content: &'a str) -> Requests<'p, 'a> {
+        Requests {
+            parser: self,
+            sections: Sections::new(content, self.implicit_sections()),
+            default_headers: self.default_headers.clone(),
+            repeat: None,
+            fingerprints: HashMap::new(),
+            names: HashMap::new(),
+            count: 0,
+            warnings: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// 解析后检查请求并记录警告
+    fn warn_about(
+        &self,
+        request: &HttpRequest,
+        section: &Section,
+        warnings: &mut Vec<ParseWarning>,
+    ) {
+        if let Some(env) = request.directive(ENV_DIRECTIVE)
+            && let Some(selected) = self.environment.name()
+            && env != selected
+        {
+            warnings.push(section.title_warning(format!(
+                "request '{}' runs against environment '{env}' instead of the selected '{selected}'",
+                request.name
+            )));
+        }
+        if let Some(warning) = content_type_mismatch(request) {
+            warnings.push(section.title_warning(warning));
+        }
+    }
+
+    fn add_host_headers(&self, headers: &mut Headers, url: &str, replacer: &VariableReplacer) {
+        if let Some(profile) = self.host_profiles.for_url(url) {
+            add_default_headers(headers, &profile.request_headers(), replacer);
+        }
+    }
+
+    fn inject_trace_headers(&self, headers: &mut Headers, trace: &TraceContext, request_id: &str) {
+        for format in &self.trace_formats {
+            format.inject(headers, trace);
+        }
+        if self.inject_request_id {
+            trace::inject_request_id(headers, request_id);
+        }
+    }
+
+    /// 解析段落，未定位的错误定位到段落首行
+    fn parse_section(
+        &self,
+        section: &Section,
+        default_headers: &Headers,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<Option<HttpRequest>> {
+        self.parse_request(section, default_headers, warnings)
+            .map_err(|e| match section.lines.first() {
+                Some(line) => section.error_at(line, first_column(line), e),
+                None => e,
+            })
+    }
+
+    /// 请求使用的环境：按 `# @env 名称` 指令加载（未指定时为当前环境），
+    /// 再叠加段落之前定义的文件变量
+    fn request_environment(&self, section: &Section, lines: &[&str]) -> Result<Environment> {
+        let requested = lines.iter().skip(1).find_map(|line| {
+            Self::parse_directive(line)
+                .filter(|directive| directive.name == ENV_DIRECTIVE)
+                .map(|directive| (*line, directive.value))
+        });
+        let mut environment = match requested {
+            Some((line, name)) if self.environment.name() != Some(name.as_str()) => {
+                let load = || {
+                    let sources = self.environment.sources();
+                    if sources.is_empty() {
+                        return Err(HttpieError::InvalidRequest(format!(
+                            "@env '{name}' requires an environment file"
+                        )));
+                    }
+                    self.environment.load_named(&name)
+                };
+                load().map_err(|e| section.error_at(line, column_of(line, &name), e))?
             }
+            _ => self.environment.clone(),
+        };
+        environment.extend(section.variables.as_ref().clone());
+        environment.extend(self.overrides.clone());
+        environment.resolve_secrets(section.text)?;
+        Ok(environment)
+    }
+
+    /// 读取 `@repeat` 指令的次数
+    fn repeat_count(request: &HttpRequest) -> Result<Option<usize>> {
+        request
+            .directive(REPEAT_DIRECTIVE)
+            .map(|value| Self::parse_repeat(value, &request.name))
+            .transpose()
+    }
+
+    /// 解析 `@repeat` 指令的值（正整数）
+    fn parse_repeat(value: &str, request_name: &str) -> Result<usize> {
+        match value.trim().parse::<usize>() {
+            Ok(total) if total > 0 => Ok(total),
+            _ => Err(HttpieError::InvalidRequest(format!(
+                "Invalid @repeat value '{value}' for request '{request_name}'"
+            ))),
         }
+    }
 
-        if in_request && !current_section.trim().is_empty() {
-            sections.push(current_section);
+    /// 标记迭代序号，并在名称后追加 `[index/total]`
+    fn with_iteration(request: HttpRequest, index: usize, total: usize) -> HttpRequest {
+        let name = format!("{} [{index}/{total}]", request.name);
+        HttpRequest {
+            name,
+            ..request.with_iteration(Some(Iteration { index, total }))
         }
-        sections
     }
 
     /// 解析单个请求
-    fn parse_request(&self, section: &str) -> Result<Option<HttpRequest>> {
-        let lines: Vec<&str> = section.lines().collect();
+    fn parse_request(
+        &self,
+        section: &Section,
+        default_headers: &Headers,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<Option<HttpRequest>> {
+        let mut lines = section.lines.clone();
         if lines.is_empty() {
             return Ok(None);
         }
 
-        let replacer = VariableReplacer::new(&self.environment);
+        let request_environment = self.request_environment(section, &lines)?;
+        let trace = self.dynamic_values.trace();
+        let request_id = self.dynamic_values.uuid();
+        let replacer = VariableReplacer::new(&request_environment)
+            .with_dynamic_values(self.dynamic_values.clone())
+            .with_trace(trace.clone())
+            .with_request_id(&request_id);
+
+        // 取出响应处理器之前的内联断言行（`?? status == 200`）
+        let mut assertions = Vec::new();
+        let mut script_start = self
+            .handler_block(section, &lines, 1)?
+            .map_or(lines.len(), |block| block.start);
+        let mut index = 1;
+        while self.dialect.assertions() && index < script_start {
+            match lines[index].trim().strip_prefix(ASSERTION_PREFIX) {
+                Some(assertion) => {
+                    let assertion = replacer.replace(assertion.trim());
+                    let validated = match AggregateAssertion::parse(&assertion) {
+                        Ok(Some(_)) => Ok(()),
+                        Ok(None) => Assertion::parse(&assertion).map(|_| ()),
+                        Err(e) => Err(e),
+                    };
+                    validated.map_err(|e| {
+                        let line = lines[index];
+                        section.error_at(line, first_column(line) + ASSERTION_PREFIX.len() + 1, e)
+                    })?;
+                    assertions.push(assertion);
+                    lines.remove(index);
+                    script_start -= 1;
+                }
+                None => index += 1,
+            }
+        }
 
         // 解析请求名称
         let name_line = lines[0].trim();
         if !name_line.starts_with("###") {
             return Ok(None);
         }
-        let name = name_line[3..].trim().to_string();
+        let title = name_line[3..].trim();
 
         // 查找请求行
-        let mut request_line_idx = None;
-        for (i, line) in lines.iter().enumerate().skip(1) {
-            let trimmed = line.trim();
-            if !trimmed.is_empty() && !trimmed.starts_with('#') {
-                // 检查是否包含HTTP方法
-                for &method in SUPPORTED_METHODS {
-                    if trimmed.starts_with(method) {
-                        request_line_idx = Some(i);
-                        break;
-                    }
-                }
-                if request_line_idx.is_some() {
-                    break;
-                }
+        let Some(request_line_idx) = lines
+            .iter()
+            .skip(1)
+            .position(|line| is_request_line(line.trim()))
+            .map(|idx| idx + 1)
+        else {
+            // 只有注释的段落（如文件末尾的 `###`）不提示
+            if let Some(line) = lines[1..]
+                .iter()
+                .find(|line| !line.trim().is_empty() && !is_comment(line.trim()))
+            {
+                let message = format!("section '{title}' has no request line and was skipped");
+                warnings.push(section.warning(line, first_column(line), message));
             }
-        }
+            return Ok(None);
+        };
+
+        // 标题为空时取 `# @name` 指令，都没有时使用请求行
+        let name = if title.is_empty() {
+            lines[1..request_line_idx]
+                .iter()
+                .filter_map(|line| Self::parse_directive(line))
+                .find(|directive| directive.name == NAME_DIRECTIVE && !directive.value.is_empty())
+                .map_or_else(
+                    || lines[request_line_idx].trim().to_string(),
+                    |directive| directive.value,
+                )
+        } else {
+            title.to_string()
+        };
 
-        let request_line_idx = match request_line_idx {
-            Some(idx) => idx,
-            None => return Ok(None),
+        // 请求头区域：请求行之后到第一个空行或响应处理器（curl命令的续行不算）
+        let header_end = if curl::is_curl_command(lines[request_line_idx].trim()) {
+            request_line_idx + 1
+        } else {
+            self.header_end(section, &lines, request_line_idx)?
         };
 
+        // 解析请求行之前和请求头区域中的指令（# @name value），并提前校验捕获和重复次数
+        let mut directives = Vec::new();
+        let header_area = lines[1..request_line_idx]
+            .iter()
+            .chain(&lines[request_line_idx + 1..header_end]);
+        for line in header_area {
+            let Some(directive) = Self::parse_directive(line) else {
+                continue;
+            };
+            let directive_name = directive.name.as_str();
+            if self.dialect.ignores(directive_name)
+                || (!self.dialect.extensions() && EXTENSION_DIRECTIVES.contains(&directive_name))
+            {
+                let message = format!(
+                    "directive '@{directive_name}' is not supported in the {} dialect and was ignored",
+                    self.dialect
+                );
+                warnings.push(section.warning(line, column_of(line, "@"), message));
+                continue;
+            }
+            let at_value = |e| section.error_at(line, column_of(line, &directive.value), e);
+            // 条件按操作数替换变量，替换后的值含空格或运算符时不影响解析
+            let value = match directive_name {
+                IF_DIRECTIVE | REQUIRE_DIRECTIVE => Condition::parse(&directive.value)
+                    .map(|condition| condition.map_operands(|o| replacer.replace(o)).to_string())
+                    .map_err(at_value)?,
+                _ => replacer.replace(&directive.value),
+            };
+            let checked = match directive.name.as_str() {
+                CAPTURE_DIRECTIVE => Capture::parse(&value).map(|_| ()),
+                REPEAT_DIRECTIVE => Self::parse_repeat(&value, &name).map(|_| ()),
+                PATH_DIRECTIVE => Self::parse_path_param(&value).map(|_| ()),
+                RANGE_DIRECTIVE => binary::parse_range(&value).map(|_| ()),
+                COMPRESS_DIRECTIVE => Compression::from_str(&value).map(|_| ()),
+                FILTER_DIRECTIVE => jsonpath::parse(&value).map(|_| ()),
+                _ => Ok(()),
+            };
+            checked.map_err(at_value)?;
+            if directive.name != NAME_DIRECTIVE
+                && !EXTENSION_DIRECTIVES.contains(&directive.name.as_str())
+            {
+                let message = format!("unknown directive '@{}'", directive.name);
+                warnings.push(section.warning(line, column_of(line, "@"), message));
+            }
+            directives.push(Directive::new(directive.name, value));
+        }
+        let path_params = directives
+            .iter()
+            .filter(|directive| directive.name == PATH_DIRECTIVE)
+            .map(|directive| Self::parse_path_param(&directive.value))
+            .collect::<Result<Vec<_>>>()?;
+
+        // 请求头区域的注释（包括指令）不是请求头；请求体和响应处理器中的注释原样保留
+        let mut index = request_line_idx + 1;
+        let mut header_end = header_end;
+        while index < header_end {
+            if is_comment(lines[index].trim()) {
+                lines.remove(index);
+                header_end -= 1;
+            } else {
+                index += 1;
+            }
+        }
+
+        // 提取响应重定向（>> file），不计入请求体：只接受请求体或响应处理器之后的最后一行，
+        // 请求体和脚本中以 `>>` 开头的行保持不变
+        let mut response_redirect = None;
+        let after = self
+            .handler_block(section, &lines, request_line_idx + 1)?
+            .and_then(|block| block.end)
+            .unwrap_or(request_line_idx + 1);
+        if let Some(index) = lines[after..]
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map(|offset| after + offset)
+            .filter(|&index| ResponseRedirect::parse(lines[index]).is_some())
+        {
+            let line = lines.remove(index);
+            response_redirect = ResponseRedirect::parse(line).map(|redirect| ResponseRedirect {
+                path: replacer.replace(&redirect.path),
+                ..redirect
+            });
+        }
+
+        let raw_request_line = lines[request_line_idx];
+        if curl::is_curl_command(raw_request_line.trim()) {
+            let at_command =
+                |e| section.error_at(raw_request_line, first_column(raw_request_line), e);
+            let mut request = self
+                .parse_curl_request(section, name, &lines[request_line_idx..], &replacer)
+                .map_err(at_command)?
+                .with_path_params(path_params);
+            self.add_host_headers(&mut request.headers, &request.url, &replacer);
+            add_default_headers(&mut request.headers, default_headers, &replacer);
+            self.inject_trace_headers(&mut request.headers, &trace, &request_id);
+            if let Some(netrc) = &self.netrc {
+                netrc.authorize(&mut request);
+            }
+            Self::validate_url(&request).map_err(at_command)?;
+
+            let hook = Hook::from_name(&request.name);
+            return Ok(Some(
+                request
+                    .with_hook(hook)
+                    .with_assertions(assertions)
+                    .with_directives(directives)
+                    .with_response_redirect(response_redirect),
+            ));
+        }
+
         // 解析请求行
-        let request_line = replacer.replace(lines[request_line_idx].trim());
+        let request_line = replacer.replace(raw_request_line.trim());
         let parts: Vec<&str> = request_line.split_whitespace().collect();
         if parts.len() < 2 {
-            return Err(HttpieError::InvalidRequest(
-                "Invalid request line format".to_string(),
-            ));
+            let error = HttpieError::InvalidRequest(
+                "Invalid request line format: expected URL after method".to_string(),
+            );
+            let column = raw_request_line.trim_end().len() + 2;
+            return Err(section.error_at(raw_request_line, column, error));
         }
 
-        let method = Method::from_str(parts[0])
-            .map_err(|_| HttpieError::InvalidMethod(parts[0].to_string()))?;
-        let url = parts[1].to_string();
-
-        // 解析请求头
-        let mut headers = HashMap::new();
-        let mut body_start_idx = None;
+        let mut method = Method::from_str(parts[0]).map_err(|_| {
+            let error = HttpieError::InvalidMethod(parts[0].to_string());
+            section.error_at(raw_request_line, first_column(raw_request_line), error)
+        })?;
 
-        for (i, line) in lines.iter().enumerate().skip(request_line_idx + 1) {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                body_start_idx = Some(i + 1);
-                break;
+        // 监听步骤：`LISTEN :端口 [路径] [timeout=时长]`
+        let listen = match parts[0] {
+            LISTEN_METHOD => Some(Listen::parse(&parts[1..]).map_err(|e| {
+                section.error_at(raw_request_line, column_of(raw_request_line, parts[1]), e)
+            })?),
+            _ => None,
+        };
+        // GRPC请求：`GRPC host/pkg.Service/Method`，以POST发送
+        let grpc = match parts[0] {
+            GRPC_METHOD => {
+                let at_target = |e| {
+                    section.error_at(raw_request_line, column_of(raw_request_line, parts[1]), e)
+                };
+                if !directives.iter().any(|d| d.name == PROTO_DIRECTIVE) {
+                    return Err(at_target(HttpieError::InvalidRequest(format!(
+                        "GRPC request requires a '# @{PROTO_DIRECTIVE} <descriptor set>' directive"
+                    ))));
+                }
+                method = Method::POST;
+                Some(GrpcCall::parse(parts[1]).map_err(at_target)?)
             }
+            _ => None,
+        };
+        // 原始TCP请求：`RAW [tls://]host:port [crlf] [shutdown] [timeout=时长]`
+        let raw = match parts[0] {
+            RAW_METHOD => Some(RawConnection::parse(&parts[1..]).map_err(|e| {
+                section.error_at(raw_request_line, column_of(raw_request_line, parts[1]), e)
+            })?),
+            _ => None,
+        };
+        let url = match (&listen, &grpc, &raw) {
+            (Some(listen), _, _) => listen.url(),
+            (_, Some((url, _)), _) | (_, _, Some((url, _))) => url.clone(),
+            _ => parts[1].to_string(),
+        };
+        let grpc = grpc.map(|(_, call)| call);
+        let raw = raw.map(|(_, connection)| connection);
+        let http_version = match parts.get(2).filter(|_| listen.is_none() && raw.is_none()) {
+            Some(token) => Some(HttpVersion::parse(token).ok_or_else(|| {
+                let error =
+                    HttpieError::InvalidRequest(format!("Unsupported HTTP version '{token}'"));
+                section.error_at(raw_request_line, column_of(raw_request_line, token), error)
+            })?),
+            None => None,
+        };
+
+        // 请求行后的多行查询参数（`?key=value` / `&key=value`）
+        let mut query = Vec::new();
+        while let Some(params) = lines
+            .get(request_line_idx + 1)
+            .and_then(|l| query_params(l))
+        {
+            query.extend(
+                params
+                    .into_iter()
+                    .map(|(key, value)| (replacer.replace(key), replacer.replace(value))),
+            );
+            lines.remove(request_line_idx + 1);
+        }
 
-            if let Some(colon_pos) = trimmed.find(':') {
-                let key = trimmed[..colon_pos].trim().to_string();
-                let value = replacer.replace(trimmed[colon_pos + 1..].trim());
-                headers.insert(key, value);
+        // 解析请求头
+        let mut headers = Headers::new();
+        let header_end = self.header_end(section, &lines, request_line_idx)?;
+        for line in &lines[request_line_idx + 1..header_end] {
+            let trimmed = line.trim();
+            let Some(colon_pos) = trimmed.find(':') else {
+                let message = format!("ignored header line '{trimmed}': expected 'Name: value'");
+                warnings.push(section.warning(line, first_column(line), message));
+                continue;
+            };
+            let key = trimmed[..colon_pos].trim().to_string();
+            if !is_header_name(&key) {
+                let message = format!("suspicious header name '{key}'");
+                warnings.push(section.warning(line, first_column(line), message));
             }
+            let value = replacer.replace(trimmed[colon_pos + 1..].trim());
+            headers.insert(key, value);
         }
 
-        // 解析请求体和响应处理器
-        let mut body = None;
-        let mut response_handler = None;
+        // 解析请求体和响应处理器：请求体在请求头之后的空行与响应处理器之间
+        let handler = self.handler_block(section, &lines, request_line_idx + 1)?;
+        let body_end = handler.as_ref().map_or(lines.len(), |block| block.start);
+        let body_lines = lines.get(header_end + 1..body_end).unwrap_or_default();
+        let handler_line = handler
+            .as_ref()
+            .and_then(|block| section.handler_line(block));
+        let response_handler = handler
+            .map(|block| block.script())
+            .filter(|script| !script.is_empty());
 
-        if let Some(start_idx) = body_start_idx {
-            let body_lines: Vec<&str> = lines.iter().skip(start_idx).copied().collect();
-            if !body_lines.is_empty() {
-                // 查找响应处理器分隔符
-                let mut handler_start_idx = None;
-                for (i, line) in body_lines.iter().enumerate() {
-                    if line.trim() == "> {%" {
-                        handler_start_idx = Some(i);
-                        break;
-                    }
-                }
+        // 请求体按原样保留（换行符、缩进和行尾空白），只去掉与下一个请求之间的空行
+        let end = body_lines
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(0, |idx| idx + 1);
+        let body_content = section.join(&body_lines[..end]);
+        let body_content = if directives.iter().any(|d| d.name == TRIM_BODY_DIRECTIVE) {
+            body_content.trim()
+        } else {
+            body_content.as_str()
+        };
+        let mut body = (!body_content.is_empty()).then(|| replacer.replace(body_content));
 
-                if let Some(handler_idx) = handler_start_idx {
-                    // 分离请求体和响应处理器
-                    let body_content = body_lines[..handler_idx].join("\n").trim().to_string();
-                    if !body_content.is_empty() {
-                        body = Some(replacer.replace(&body_content));
-                    }
+        // 请求体为 `< 文件` 时发送时再读取
+        let body_file = body
+            .as_deref()
+            .and_then(upload::body_file_reference)
+            .map(str::to_string);
+        if body_file.is_some() {
+            body = None;
+        }
 
-                    // 解析响应处理器脚本
-                    let handler_lines: Vec<&str> =
-                        body_lines.iter().skip(handler_idx + 1).copied().collect();
-                    let mut script_lines = Vec::new();
+        // GRAPHQL请求以POST发送 `{"query", "variables"}` 形式的JSON
+        if parts[0] == GRAPHQL_METHOD {
+            method = Method::POST;
+            body = body.as_deref().map(graphql_body);
+            if !headers
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case(CONTENT_TYPE))
+            {
+                headers.insert(CONTENT_TYPE.to_string(), JSON_MEDIA_TYPE.to_string());
+            }
+        }
 
-                    for line in handler_lines {
-                        if line.trim() == "%}" {
-                            break;
-                        }
-                        script_lines.push(line);
-                    }
+        // 监听步骤的请求头用于回复调用方，原始TCP请求不发送请求头，都不添加默认请求头和追踪请求头
+        if listen.is_none() && raw.is_none() {
+            self.add_host_headers(&mut headers, &url, &replacer);
+            add_default_headers(&mut headers, default_headers, &replacer);
+            self.inject_trace_headers(&mut headers, &trace, &request_id);
+        }
 
-                    if !script_lines.is_empty() {
-                        let script_content = script_lines.join("\n").trim().to_string();
-                        if !script_content.is_empty() {
-                            response_handler = Some(script_content);
-                        }
-                    }
-                } else {
-                    // 没有响应处理器，全部作为请求体
-                    let body_content = body_lines.join("\n").trim().to_string();
-                    if !body_content.is_empty() {
-                        body = Some(replacer.replace(&body_content));
-                    }
-                }
-            }
+        // JSON请求体未声明Content-Type时自动补充（GRPC请求的请求体发送前转码）
+        if self.infer_content_type
+            && grpc.is_none()
+            && raw.is_none()
+            && !headers
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case(CONTENT_TYPE))
+            && body.as_deref().is_some_and(is_json_document)
+        {
+            headers.insert(CONTENT_TYPE.to_string(), JSON_MEDIA_TYPE.to_string());
         }
 
-        let request = HttpRequest::new(name, method, url)
+        let hook = Hook::from_name(&name);
+        let mut request = HttpRequest::new(name, method, url)
+            .with_query(query)
+            .with_path_params(path_params)
+            .with_hook(hook)
+            .with_assertions(assertions)
             .with_headers(headers)
             .with_body(body)
-            .with_response_handler(response_handler);
+            .with_body_file(body_file)
+            .with_response_handler(response_handler)
+            .with_handler_line(handler_line)
+            .with_directives(directives)
+            .with_response_redirect(response_redirect)
+            .with_http_version(http_version)
+            .with_listen(listen)
+            .with_grpc(grpc)
+            .with_raw(raw);
+        if let Some(netrc) = self
+            .netrc
+            .as_ref()
+            .filter(|_| request.listen.is_none() && request.raw.is_none())
+        {
+            netrc.authorize(&mut request);
+        }
+
+        let raw_url = raw_request_line
+            .split_whitespace()
+            .nth(1)
+            .unwrap_or_default();
+        Self::validate_url(&request).map_err(|e| {
+            section.error_at(raw_request_line, column_of(raw_request_line, raw_url), e)
+        })?;
 
         Ok(Some(request))
     }
+
+    /// 变量全部替换后提前校验URL；含运行期变量（如捕获值）的URL留到发送时校验
+    fn validate_url(request: &HttpRequest) -> Result<()> {
+        if request.url.contains("{{") {
+            return Ok(());
+        }
+        request.target_url().map(|_| ())
+    }
+
+    /// 解析curl命令形式的请求（支持反斜杠续行，后面可跟响应处理器）
+    fn parse_curl_request(
+        &self,
+        section: &Section,
+        name: String,
+        lines: &[&str],
+        replacer: &VariableReplacer,
+    ) -> Result<HttpRequest> {
+        let mut command_lines = Vec::new();
+        let mut rest_idx = lines.len();
+        for (i, line) in lines.iter().enumerate() {
+            command_lines.push(*line);
+            if !line.trim_end().ends_with('\\') {
+                rest_idx = i + 1;
+                break;
+            }
+        }
+
+        let command = replacer.replace(&command_lines.join("\n"));
+        let mut request = curl::from_curl(&command)?;
+        request.name = name;
+
+        // 查找响应处理器
+        let rest = &lines[rest_idx.min(lines.len())..];
+        if let Some(block) = handler::find(rest).filter(|_| self.dialect.handlers()) {
+            if block.end.is_none() {
+                return Err(unterminated_handler());
+            }
+            let script = block.script();
+            if !script.is_empty() {
+                request.response_handler = Some(script);
+                request.handler_line = section.handler_line(&block);
+            }
+        }
+
+        Ok(request)
+    }
+
+    /// 从第 `from` 行开始查找响应处理器，缺少结束标记时报错并定位到开始标记
+    fn handler_block<'a>(
+        &self,
+        section: &Section,
+        lines: &[&'a str],
+        from: usize,
+    ) -> Result<Option<HandlerBlock<'a>>> {
+        let Some(block) = handler::find(lines.get(from..).unwrap_or_default())
+            .filter(|_| self.dialect.handlers())
+        else {
+            return Ok(None);
+        };
+        let start = from + block.start;
+        if block.end.is_none() {
+            let line = lines[start];
+            return Err(section.error_at(line, first_column(line), unterminated_handler()));
+        }
+        Ok(Some(HandlerBlock {
+            start,
+            end: block.end.map(|end| from + end),
+            script: block.script,
+        }))
+    }
+
+    /// 请求头区域的结束位置：请求行之后的第一个空行或响应处理器
+    fn header_end(
+        &self,
+        section: &Section,
+        lines: &[&str],
+        request_line_idx: usize,
+    ) -> Result<usize> {
+        let handler_start = self
+            .handler_block(section, lines, request_line_idx + 1)?
+            .map_or(lines.len(), |block| block.start);
+        Ok(lines[request_line_idx + 1..handler_start]
+            .iter()
+            .position(|line| line.trim().is_empty())
+            .map_or(handler_start, |idx| request_line_idx + 1 + idx))
+    }
+
+    /// 解析路径参数指令的值（`name = value`）
+    fn parse_path_param(value: &str) -> Result<(String, String)> {
+        match value.split_once('=') {
+            Some((name, param)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), param.trim().to_string()))
+            }
+            _ => Err(HttpieError::InvalidRequest(format!(
+                "Invalid @{PATH_DIRECTIVE} '{value}': expected 'name = value'"
+            ))),
+        }
+    }
+
+    /// 解析指令行（`# @name value` 或 `// @name value`）
+    fn parse_directive(line: &str) -> Option<Directive> {
+        let trimmed = line.trim();
+        let rest = trimmed
+            .strip_prefix('#')
+            .or_else(|| trimmed.strip_prefix("//"))?
+            .trim_start()
+            .strip_prefix('@')?;
+
+        let (name, value) = match rest.find(char::is_whitespace) {
+            Some(pos) => (&rest[..pos], rest[pos..].trim()),
+            None => (rest, ""),
+        };
+        if name.is_empty() {
+            return None;
+        }
+
+        Some(Directive::new(name, value))
+    }
 }
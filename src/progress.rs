@@ -0,0 +1,170 @@
+//! 运行进度模块
+//!
+//! 作为执行观察者显示运行进度：标准输出是终端时在标准错误上绘制进度条，
+//! 显示当前请求名称和通过/失败计数；否则（如重定向到文件或在CI中）逐行输出每个请求的结果。
+
+use crate::models::HttpRequest;
+use crate::observer::ExecutionObserver;
+use crate::runner::{RequestReport, RunReport};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+
+const BAR_TEMPLATE: &str = "{bar:30.cyan/blue} {pos}/{len} {msg}";
+
+/// 进度的显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// 原地刷新的进度条
+    Bar,
+    /// 每个请求完成时输出一行
+    Plain,
+}
+
+impl ProgressMode {
+    /// 标准输出是终端时使用进度条，否则逐行输出
+    pub fn detect() -> Self {
+        if std::io::stdout().is_terminal() {
+            Self::Bar
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+/// 显示运行进度的观察者
+#[derive(Debug)]
+pub struct ProgressReporter {
+    mode: ProgressMode,
+    bar: Option<ProgressBar>,
+    /// 已开始但尚未完成的请求序号；并发执行时多个请求同时进行，按开始顺序完成
+    started: VecDeque<usize>,
+    total: usize,
+    passed: usize,
+    failed: usize,
+}
+
+impl ProgressReporter {
+    pub fn new(mode: ProgressMode) -> Self {
+        Self {
+            mode,
+            bar: None,
+            started: VecDeque::new(),
+            total: 0,
+            passed: 0,
+            failed: 0,
+        }
+    }
+
+    /// 首个请求开始时创建进度条（总数此时才确定）
+    fn bar(&mut self, total: usize) -> &ProgressBar {
+        self.bar.get_or_insert_with(|| {
+            let bar =
+                ProgressBar::with_draw_target(Some(total as u64), ProgressDrawTarget::stderr());
+            if let Ok(style) = ProgressStyle::with_template(BAR_TEMPLATE) {
+                bar.set_style(style.progress_chars("=> "));
+            }
+            bar
+        })
+    }
+
+    fn counts(&self) -> String {
+        format!("✓ {} ✗ {}", self.passed, self.failed)
+    }
+}
+
+impl ExecutionObserver for ProgressReporter {
+    fn on_request_start(&mut self, request: &HttpRequest, index: usize, total: usize) {
+        self.started.push_back(index);
+        self.total = total;
+        if self.mode == ProgressMode::Bar {
+            let message = format!("{}  {}", self.counts(), request.name);
+            self.bar(total).set_message(message);
+        }
+    }
+
+    fn on_request_complete(&mut self, _request: &HttpRequest, report: &RequestReport) {
+        if report.is_skipped() {
+            // 跳过的请求不计入通过和失败
+        } else if report.passed() {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+        let index = self.started.pop_front().unwrap_or_default();
+        let line = progress_line(index, self.total, report);
+        match self.mode {
+            ProgressMode::Plain => eprintln!("{line}"),
+            ProgressMode::Bar => {
+                let message = self.counts();
+                let bar = self.bar(self.total);
+                // 失败的请求保留在进度条上方
+                if !report.passed() {
+                    bar.println(&line);
+                }
+                bar.set_message(message);
+                bar.inc(1);
+            }
+        }
+    }
+
+    fn on_run_complete(&mut self, _report: &RunReport) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+        // 监控模式下同一观察者用于多次运行
+        self.passed = 0;
+        self.failed = 0;
+    }
+}
+
+/// 一个请求的结果行，如 `[2/5] ✓ GET Get users (120 ms)`
+///
+/// 失败时附带第一个失败原因：传输错误或第一个未通过的测试；
+/// 跳过的请求标记为 `-` 并附带跳过原因。
+pub fn progress_line(index: usize, total: usize, report: &RequestReport) -> String {
+    if let Some(reason) = &report.skipped {
+        let mut line = format!(
+            "[{}/{total}] - {} {} (skipped",
+            index + 1,
+            report.method,
+            report.name
+        );
+        if !reason.is_empty() {
+            line.push_str(": ");
+            line.push_str(reason);
+        }
+        line.push(')');
+        return line;
+    }
+    let tick = if report.passed() { "✓" } else { "✗" };
+    let mut line = format!(
+        "[{}/{total}] {tick} {} {} ({} ms)",
+        index + 1,
+        report.method,
+        report.name,
+        report.duration.as_millis()
+    );
+    let xpassed = report
+        .is_xpassed()
+        .then(|| "expected to fail (@xfail) but passed".to_string());
+    let reason = report.error.clone().or(xpassed).or_else(|| {
+        report
+            .test_results
+            .iter()
+            .find(|result| result.is_failure())
+            .map(|result| match &result.message {
+                Some(message) => format!("{}: {message}", result.name),
+                None => result.name.clone(),
+            })
+    });
+    if let Some(reason) = reason {
+        line.push_str(if report.is_xfailed() {
+            " - XFAIL: "
+        } else {
+            " - "
+        });
+        line.push_str(&reason);
+    }
+    line
+}
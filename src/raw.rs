@@ -0,0 +1,285 @@
+//! 原始TCP请求模块
+//!
+//! `RAW host:port` 请求把请求体按字节原样写入TCP连接（`RAW tls://host:port` 先完成TLS握手），
+//! 再读取对端返回的字节，直到对端关闭连接或等待超时，用于测试服务对畸形请求的处理
+//! 和非HTTP协议的端点。响应的状态码为0、没有响应头，响应体就是收到的字节。
+//!
+//! 请求体中的 `\r`、`\n`、`\t`、`\0`、`\\` 和 `\xNN` 转义按对应的字节发送，
+//! 请求体为 `< 文件` 时发送文件的原始字节。
+
+use crate::bench::parse_duration;
+use crate::client::{RawResponse, TransportConfig};
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use crate::upload;
+use bytes::{Bytes, BytesMut};
+use reqwest::Version;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// 原始TCP请求的请求行方法
+pub const RAW_METHOD: &str = "RAW";
+
+/// 未指定 `timeout=` 时连接和读取响应的最长时间
+pub const DEFAULT_RAW_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 原始TCP请求的连接参数，目标地址保存在请求URL中（`tcp://host:port` 或 `tls://host:port`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawConnection {
+    /// 把请求体中的换行转换为CRLF
+    pub crlf: bool,
+    /// 发送后关闭写方向，通知只在连接关闭时才回复的对端请求已结束
+    pub shutdown: bool,
+    /// 从开始连接到停止读取的最长时间（毫秒）
+    pub timeout_ms: u64,
+}
+
+impl RawConnection {
+    /// 解析 `RAW` 之后的参数：`[tcp://|tls://]host:port`、可选的 `crlf`、`shutdown`
+    /// 和 `timeout=时长`，返回请求URL和连接参数
+    pub fn parse(args: &[&str]) -> Result<(String, Self)> {
+        let invalid = |reason: &str| {
+            HttpieError::InvalidRequest(format!(
+                "Invalid RAW request '{}': {reason}",
+                args.join(" ")
+            ))
+        };
+
+        let Some((target, rest)) = args.split_first() else {
+            return Err(invalid("expected host:port"));
+        };
+        let (scheme, address) = match target.split_once("://") {
+            Some((scheme @ ("tcp" | "tls"), address)) => (scheme, address),
+            Some((scheme, _)) => return Err(invalid(&format!("unsupported scheme '{scheme}'"))),
+            None => ("tcp", *target),
+        };
+        let valid_port =
+            |port: &str| port.contains("{{") || port.parse::<u16>().is_ok_and(|port| port > 0);
+        match address.rsplit_once(':') {
+            Some((host, port))
+                if !host.is_empty() && !address.contains('/') && valid_port(port) => {}
+            _ => return Err(invalid("expected host:port")),
+        }
+
+        let mut connection = Self {
+            crlf: false,
+            shutdown: false,
+            timeout_ms: DEFAULT_RAW_TIMEOUT.as_millis() as u64,
+        };
+        for arg in rest {
+            match *arg {
+                "crlf" => connection.crlf = true,
+                "shutdown" => connection.shutdown = true,
+                _ => match arg.strip_prefix("timeout=") {
+                    Some(value) => {
+                        connection.timeout_ms = parse_duration(value)?.as_millis() as u64
+                    }
+                    None => return Err(invalid(&format!("unexpected '{arg}'"))),
+                },
+            }
+        }
+        Ok((format!("{scheme}://{address}"), connection))
+    }
+
+    /// 连接和读取响应的最长时间
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+/// 把请求体文本转换为要发送的字节：按需把换行转换为CRLF，再解析转义
+///
+/// 无法识别的转义按原样发送。
+pub fn encode_body(text: &str, crlf: bool) -> Vec<u8> {
+    let text = if crlf {
+        text.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        text.to_string()
+    };
+
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let escaped = match rest.first() {
+            Some(b'r') => Some((b'\r', 1)),
+            Some(b'n') => Some((b'\n', 1)),
+            Some(b't') => Some((b'\t', 1)),
+            Some(b'0') => Some((0, 1)),
+            Some(b'\\') => Some((b'\\', 1)),
+            Some(b'x') => rest
+                .get(1..3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .map(|byte| (byte, 3)),
+            _ => None,
+        };
+        match escaped {
+            Some((byte, len)) => {
+                bytes.push(byte);
+                rest = &rest[len..];
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+    bytes
+}
+
+/// 建立连接、发送请求体并读取响应字节（最多 `max_body_bytes` 字节）
+///
+/// 连接、TLS握手或发送失败时返回错误；读取阶段对端关闭或重置连接、以及等待超时都正常结束。
+/// 传输层配置中的域名解析覆盖、`insecure` 和CA证书同样生效。
+pub(crate) async fn send(
+    request: &HttpRequest,
+    connection: &RawConnection,
+    config: &TransportConfig,
+) -> Result<RawResponse> {
+    let url = request.target_url()?;
+    let host = url
+        .host_str()
+        .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+        .unwrap_or_default();
+    let Some(port) = url.port() else {
+        return Err(HttpieError::InvalidRequest(format!(
+            "Invalid RAW target '{}': expected host:port",
+            request.url
+        )));
+    };
+    let failed = |reason: String| HttpieError::RequestFailed(request.name.clone(), reason);
+    let payload = match upload::read_body_file(request)? {
+        Some(bytes) => bytes,
+        None => encode_body(request.body.as_deref().unwrap_or_default(), connection.crlf),
+    };
+
+    let started = Instant::now();
+    let deadline = tokio::time::Instant::now() + connection.timeout();
+    let connect = async {
+        match config.dns_overrides.get(host) {
+            Some(addr) => TcpStream::connect(SocketAddr::new(addr.ip(), port)).await,
+            None => TcpStream::connect((host, port)).await,
+        }
+    };
+    let stream = tokio::time::timeout_at(deadline, connect)
+        .await
+        .map_err(|_| failed(format!("connection to {host}:{port} timed out")))?
+        .map_err(|e| failed(format!("failed to connect to {host}:{port}: {e}")))?;
+    let remote_addr = stream.peer_addr().ok();
+
+    let exchanged = if url.scheme() == "tls" {
+        let connector = tls_connector(config)?;
+        let stream = tokio::time::timeout_at(deadline, connector.connect(host, stream))
+            .await
+            .map_err(|_| failed(format!("TLS handshake with {host}:{port} timed out")))?
+            .map_err(|e| failed(format!("TLS handshake with {host}:{port} failed: {e}")))?;
+        exchange(
+            stream,
+            &payload,
+            connection,
+            deadline,
+            config.max_body_bytes,
+        )
+        .await
+    } else {
+        exchange(
+            stream,
+            &payload,
+            connection,
+            deadline,
+            config.max_body_bytes,
+        )
+        .await
+    };
+    let (bytes, truncated) =
+        exchanged.map_err(|e| failed(format!("connection to {host}:{port} failed: {e}")))?;
+
+    Ok(RawResponse {
+        status: 0,
+        version: Version::HTTP_11,
+        headers: HeaderMap::new(),
+        bytes,
+        duration: started.elapsed(),
+        truncated,
+        remote_addr,
+        tls: None,
+        redirects: Vec::new(),
+    })
+}
+
+/// 写入请求体，再读取到对端关闭连接、超过缓冲上限或到达截止时间为止
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    payload: &[u8],
+    connection: &RawConnection,
+    deadline: tokio::time::Instant,
+    max_body_bytes: Option<usize>,
+) -> std::io::Result<(Bytes, bool)> {
+    let write = async {
+        stream.write_all(payload).await?;
+        stream.flush().await?;
+        if connection.shutdown {
+            stream.shutdown().await?;
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    tokio::time::timeout_at(deadline, write)
+        .await
+        .map_err(|_| std::io::Error::new(ErrorKind::TimedOut, "timed out"))??;
+
+    let limit = max_body_bytes.unwrap_or(usize::MAX);
+    let mut buffer = BytesMut::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        let read = match tokio::time::timeout_at(deadline, stream.read(&mut chunk)).await {
+            Err(_) | Ok(Ok(0)) => break,
+            Ok(Ok(read)) => read,
+            // 拒绝畸形请求的服务常常直接重置连接
+            Ok(Err(e))
+                if matches!(
+                    e.kind(),
+                    ErrorKind::ConnectionReset
+                        | ErrorKind::ConnectionAborted
+                        | ErrorKind::UnexpectedEof
+                ) =>
+            {
+                break;
+            }
+            Ok(Err(e)) => return Err(e),
+        };
+        let remaining = limit - buffer.len();
+        if read > remaining {
+            buffer.extend_from_slice(&chunk[..remaining]);
+            return Ok((buffer.freeze(), true));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    Ok((buffer.freeze(), false))
+}
+
+/// 按传输层配置中的 `insecure` 和CA证书构建TLS连接器
+fn tls_connector(config: &TransportConfig) -> Result<tokio_native_tls::TlsConnector> {
+    let mut builder = native_tls::TlsConnector::builder();
+    builder
+        .danger_accept_invalid_certs(config.insecure)
+        .danger_accept_invalid_hostnames(config.insecure);
+    if let Some(path) = &config.ca_cert {
+        let pem = std::fs::read(path)
+            .map_err(|_| HttpieError::FileNotFound(path.display().to_string()))?;
+        let certificate = native_tls::Certificate::from_pem(&pem).map_err(|e| {
+            HttpieError::InvalidRequest(format!("Invalid CA certificate '{}': {e}", path.display()))
+        })?;
+        builder.add_root_certificate(certificate);
+    }
+    let connector = builder
+        .build()
+        .map_err(|e| HttpieError::InvalidRequest(format!("Failed to create TLS connector: {e}")))?;
+    Ok(connector.into())
+}
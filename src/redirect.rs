@@ -0,0 +1,63 @@
+//! 重定向链模块
+//!
+//! reqwest自动跟随重定向时只返回最终响应。这里的重定向策略把每一跳记录到当前任务的
+//! 记录器中，[`capture`] 在发送请求期间安装记录器并取回完整的重定向链。
+
+use reqwest::redirect::Policy;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::future::Future;
+
+/// reqwest默认的最大重定向次数
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+tokio::task_local! {
+    static HOPS: RefCell<Vec<RedirectHop>>;
+}
+
+/// 一次重定向
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectHop {
+    /// 返回重定向的请求URL
+    pub url: String,
+    /// 重定向状态码（如301、302）
+    pub status: u16,
+    /// 跳转的目标URL（已按请求URL解析相对路径）
+    pub location: String,
+}
+
+/// 按最大次数跟随重定向并记录每一跳的策略，`Some(0)` 不跟随重定向
+pub fn policy(max_redirects: Option<usize>) -> Policy {
+    let max = match max_redirects {
+        Some(0) => return Policy::none(),
+        Some(max) => max,
+        None => DEFAULT_MAX_REDIRECTS,
+    };
+    let limited = Policy::limited(max);
+    Policy::custom(move |attempt| {
+        // previous包含初始URL，超过上限的一跳由limited策略报错，不记录
+        if attempt.previous().len() <= max {
+            let hop = RedirectHop {
+                url: attempt
+                    .previous()
+                    .last()
+                    .map(|url| url.to_string())
+                    .unwrap_or_default(),
+                status: attempt.status().as_u16(),
+                location: attempt.url().to_string(),
+            };
+            // 未安装记录器时不记录
+            let _ = HOPS.try_with(|hops| hops.borrow_mut().push(hop));
+        }
+        limited.redirect(attempt)
+    })
+}
+
+/// 执行请求并返回期间跟随的重定向
+pub async fn capture<F: Future>(future: F) -> (F::Output, Vec<RedirectHop>) {
+    HOPS.scope(RefCell::new(Vec::new()), async {
+        let output = future.await;
+        (output, HOPS.with(|hops| hops.take()))
+    })
+    .await
+}
@@ -0,0 +1,1309 @@
+//! 运行器模块
+//!
+//! 负责编排一组请求的执行：筛选用例、并发发送、按顺序处理响应，
+//! 并汇总为结构化的运行报告，供命令行和库使用者共用。
+
+use crate::aggregate;
+use crate::bench::{BenchReport, Sample};
+use crate::client::{HttpClient, RawResponse};
+use crate::condition::{Condition, IF_DIRECTIVE, REQUIRE_DIRECTIVE};
+use crate::error::{HttpieError, Result};
+use crate::hooks::{ScriptHook, ScriptHooks};
+use crate::listen;
+use crate::models::{Hook, HttpRequest, Iteration};
+use crate::notify::Notifier;
+use crate::observer::ExecutionObserver;
+use crate::script::{ScriptControl, ScriptLog, TestOutcome, TestResult};
+use crate::transport;
+use crate::variable::{VariableContext, template_names};
+use futures::FutureExt;
+use futures::stream::{FuturesOrdered, StreamExt};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde_json::{Value, json};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, Span, error, field, info, info_span, warn};
+
+/// 跳过请求的指令（`# @skip 原因`），跳过的请求不发送，在报告中单独统计
+pub const SKIP_DIRECTIVE: &str = "skip";
+
+/// 把请求标记为预期失败的指令（`# @xfail 原因`）：请求失败时记为XFAIL，不影响运行结果；
+/// 意外成功时记为XPASS并计为失败，提醒移除标记
+pub const XFAIL_DIRECTIVE: &str = "xfail";
+
+/// 只运行标记的用例的指令（`# @only`），未通过 `--case` 选择用例时生效
+pub const ONLY_DIRECTIVE: &str = "only";
+
+/// 单个请求的运行记录
+#[derive(Debug, Clone)]
+pub struct RequestReport {
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub duration: Duration,
+    pub test_results: Vec<TestResult>,
+    pub error: Option<String>,
+    /// 重复执行时的迭代信息
+    pub iteration: Option<Iteration>,
+    /// 钩子请求的类型
+    pub hook: Option<Hook>,
+    /// 被 `@skip` 跳过时的原因（可以为空），此时请求没有发送
+    pub skipped: Option<String>,
+    /// 响应处理器输出的日志
+    pub logs: Vec<ScriptLog>,
+    /// 被 `@xfail` 标记为预期失败时的原因（可以为空）
+    pub xfail: Option<String>,
+}
+
+impl RequestReport {
+    /// 请求是否不计为失败：成功完成且所有测试通过，或预期失败的请求确实失败
+    /// （跳过的请求不算失败）
+    pub fn passed(&self) -> bool {
+        match self.xfail {
+            Some(_) if !self.is_skipped() => self.failed_as_sent(),
+            _ => !self.failed_as_sent(),
+        }
+    }
+
+    /// 预期失败的请求确实失败（XFAIL）
+    pub fn is_xfailed(&self) -> bool {
+        self.xfail.is_some() && !self.is_skipped() && self.failed_as_sent()
+    }
+
+    /// 预期失败的请求意外成功（XPASS）
+    pub fn is_xpassed(&self) -> bool {
+        self.xfail.is_some() && !self.is_skipped() && !self.failed_as_sent()
+    }
+
+    /// 测试结果的分类：预期失败（`@xfail`）的请求中失败的测试计为预期失败
+    pub fn test_outcome(&self, test: &TestResult) -> TestOutcome {
+        match test.outcome() {
+            TestOutcome::Failed if self.xfail.is_some() => TestOutcome::Xfailed,
+            outcome => outcome,
+        }
+    }
+
+    /// 不考虑 `@xfail` 时请求是否失败（传输错误或测试失败）
+    fn failed_as_sent(&self) -> bool {
+        self.error.is_some() || self.test_results.iter().any(TestResult::is_failure)
+    }
+
+    /// 请求是否被 `@skip` 跳过
+    pub fn is_skipped(&self) -> bool {
+        self.skipped.is_some()
+    }
+
+    fn new(request: &HttpRequest) -> Self {
+        Self {
+            name: request.name.clone(),
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            status: None,
+            duration: Duration::ZERO,
+            test_results: Vec::new(),
+            error: None,
+            iteration: request.iteration,
+            hook: request.hook,
+            skipped: None,
+            logs: Vec::new(),
+            xfail: request
+                .directive(XFAIL_DIRECTIVE)
+                .map(|reason| reason.trim().to_string()),
+        }
+    }
+}
+
+/// 一次运行的结构化报告
+#[derive(Debug, Clone, Default)]
+pub struct RunReport {
+    pub requests: Vec<RequestReport>,
+    pub duration: Duration,
+    /// 运行是否被取消（此时报告只包含已完成的请求）
+    pub cancelled: bool,
+    /// 响应处理器调用 `client.exit()` 中止了运行
+    pub aborted: Option<Abort>,
+}
+
+/// 响应处理器中止运行的记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abort {
+    /// 中止运行的请求
+    pub request: String,
+    /// `client.exit()` 给出的原因（可以为空）
+    pub reason: String,
+}
+
+impl RunReport {
+    /// 选中的请求总数（包括跳过的请求）
+    pub fn total(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// 成功且测试全部通过的请求数（不包括预期失败的请求）
+    pub fn passed(&self) -> usize {
+        self.requests
+            .iter()
+            .filter(|r| !r.is_skipped() && !r.is_xfailed() && r.passed())
+            .count()
+    }
+
+    /// 被 `@xfail` 标记且确实失败的请求数，不计为失败
+    pub fn xfailed(&self) -> usize {
+        self.requests.iter().filter(|r| r.is_xfailed()).count()
+    }
+
+    /// 失败（传输错误或测试失败）的请求数
+    pub fn failed(&self) -> usize {
+        self.failures().count()
+    }
+
+    /// 被 `@skip`、`@if` 或 `client.skip()` 跳过的请求数
+    pub fn skipped(&self) -> usize {
+        self.skipped_requests().count()
+    }
+
+    /// 所有被跳过的请求
+    pub fn skipped_requests(&self) -> impl Iterator<Item = &RequestReport> {
+        self.requests.iter().filter(|r| r.is_skipped())
+    }
+
+    /// 通过的测试数
+    pub fn tests_passed(&self) -> usize {
+        self.test_outcomes()
+            .filter(|outcome| *outcome == TestOutcome::Passed)
+            .count()
+    }
+
+    /// 失败的测试数（包括意外通过的预期失败测试）
+    pub fn tests_failed(&self) -> usize {
+        self.test_outcomes()
+            .filter(|outcome| outcome.is_failure())
+            .count()
+    }
+
+    /// 预期失败且确实失败的测试数：被 `@xfail-test` 标记，或属于 `@xfail` 请求
+    pub fn tests_xfailed(&self) -> usize {
+        self.test_outcomes()
+            .filter(|outcome| *outcome == TestOutcome::Xfailed)
+            .count()
+    }
+
+    /// 意外通过的请求和测试（XPASS），移除 `@xfail` 标记后即可恢复
+    pub fn unexpected_passes(&self) -> Vec<String> {
+        let mut passes = Vec::new();
+        for request in &self.requests {
+            if request.is_xpassed() {
+                passes.push(request.name.clone());
+            }
+            for test in &request.test_results {
+                if test.outcome() == TestOutcome::Xpassed {
+                    passes.push(format!("{}: test '{}'", request.name, test.name));
+                }
+            }
+        }
+        passes
+    }
+
+    /// 第一个发生传输或执行错误的请求
+    pub fn first_error(&self) -> Option<&RequestReport> {
+        self.requests.iter().find(|r| r.error.is_some())
+    }
+
+    /// 所有失败（传输错误或测试失败）的请求
+    pub fn failures(&self) -> impl Iterator<Item = &RequestReport> {
+        self.requests.iter().filter(|r| !r.passed())
+    }
+
+    /// 运行未被取消或中止且所有请求均成功
+    pub fn success(&self) -> bool {
+        !self.cancelled && self.aborted.is_none() && self.requests.iter().all(RequestReport::passed)
+    }
+
+    /// 按状态码类别（`2xx`、`4xx` 等）统计的请求数，没有收到响应的请求计入 `error`，
+    /// 跳过的请求（包括收到响应后由脚本跳过的请求）只计入 `skipped`
+    pub fn status_classes(&self) -> BTreeMap<String, usize> {
+        let mut classes = BTreeMap::new();
+        for request in &self.requests {
+            let class = match request.status {
+                _ if request.is_skipped() => "skipped".to_string(),
+                Some(status) => format!("{}xx", status / 100),
+                None => "error".to_string(),
+            };
+            *classes.entry(class).or_insert(0) += 1;
+        }
+        classes
+    }
+
+    /// 耗时最长的 `n` 个请求，按耗时降序排列
+    pub fn slowest(&self, n: usize) -> Vec<&RequestReport> {
+        let mut requests: Vec<&RequestReport> =
+            self.requests.iter().filter(|r| !r.is_skipped()).collect();
+        requests.sort_by_key(|request| std::cmp::Reverse(request.duration));
+        requests.truncate(n);
+        requests
+    }
+
+    /// 运行结束时打印的汇总，最多列出 `slowest` 个最慢的请求
+    pub fn summary(&self, slowest: usize) -> RunSummary<'_> {
+        RunSummary {
+            report: self,
+            slowest,
+        }
+    }
+
+    /// 失败请求的详情：错误、失败的测试和脚本日志，没有失败时为空
+    pub fn failure_details(&self) -> FailureDetails<'_> {
+        FailureDetails { report: self }
+    }
+
+    /// 按运行结果返回错误：取消、中止、第一个传输或执行错误，或失败的请求数
+    pub fn check(&self) -> Result<()> {
+        if self.cancelled {
+            return Err(HttpieError::Cancelled);
+        }
+        if let Some(abort) = &self.aborted {
+            return Err(HttpieError::RunAborted(
+                abort.request.clone(),
+                abort.reason.clone(),
+            ));
+        }
+        if let Some(failed) = self.first_error() {
+            return Err(HttpieError::RequestFailed(
+                failed.name.clone(),
+                failed.error.clone().unwrap_or_default(),
+            ));
+        }
+        match self.failed() {
+            0 => Ok(()),
+            failed => Err(HttpieError::RunFailed(failed)),
+        }
+    }
+
+    fn test_outcomes(&self) -> impl Iterator<Item = TestOutcome> + '_ {
+        self.requests.iter().flat_map(|request| {
+            request
+                .test_results
+                .iter()
+                .map(|test| request.test_outcome(test))
+        })
+    }
+}
+
+/// 运行汇总：请求和测试计数、状态码分布、总耗时和最慢请求表
+pub struct RunSummary<'a> {
+    report: &'a RunReport,
+    slowest: usize,
+}
+
+impl fmt::Display for RunSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = self.report;
+        writeln!(f, "=== Summary ===")?;
+        write!(
+            f,
+            "{:<12} {} ({} passed, {} failed",
+            "Requests",
+            report.total(),
+            report.passed(),
+            report.failed()
+        )?;
+        if report.xfailed() > 0 {
+            write!(f, ", {} xfailed", report.xfailed())?;
+        }
+        match report.skipped() {
+            0 => writeln!(f, ")")?,
+            skipped => writeln!(f, ", {skipped} skipped)")?,
+        }
+        let classes: Vec<String> = report
+            .status_classes()
+            .iter()
+            .map(|(class, count)| format!("{class}: {count}"))
+            .collect();
+        if !classes.is_empty() {
+            writeln!(f, "{:<12} {}", "Status", classes.join(", "))?;
+        }
+        write!(
+            f,
+            "{:<12} {} passed, {} failed",
+            "Tests",
+            report.tests_passed(),
+            report.tests_failed()
+        )?;
+        match report.tests_xfailed() {
+            0 => writeln!(f)?,
+            xfailed => writeln!(f, ", {xfailed} xfailed")?,
+        }
+        writeln!(
+            f,
+            "{:<12} {:.2}s",
+            "Duration",
+            report.duration.as_secs_f64()
+        )?;
+        if let Some(abort) = &report.aborted {
+            match abort.reason.as_str() {
+                "" => writeln!(f, "{:<12} by '{}'", "Aborted", abort.request)?,
+                reason => writeln!(f, "{:<12} by '{}': {reason}", "Aborted", abort.request)?,
+            }
+        }
+
+        if report.skipped() > 0 {
+            writeln!(f, "Skipped requests:")?;
+            for request in report.skipped_requests() {
+                match request.skipped.as_deref() {
+                    Some("") | None => writeln!(f, "  - {}", request.name)?,
+                    Some(reason) => writeln!(f, "  - {}: {reason}", request.name)?,
+                }
+            }
+        }
+
+        let xfailed: Vec<&RequestReport> =
+            report.requests.iter().filter(|r| r.is_xfailed()).collect();
+        if !xfailed.is_empty() {
+            writeln!(f, "Expected failures:")?;
+            for request in xfailed {
+                match request.xfail.as_deref() {
+                    Some("") | None => writeln!(f, "  - {}", request.name)?,
+                    Some(reason) => writeln!(f, "  - {}: {reason}", request.name)?,
+                }
+            }
+        }
+        let passes = report.unexpected_passes();
+        if !passes.is_empty() {
+            writeln!(f, "Unexpected passes (XPASS, remove @xfail):")?;
+            for pass in passes {
+                writeln!(f, "  - {pass}")?;
+            }
+        }
+
+        let slowest = report.slowest(self.slowest);
+        if !slowest.is_empty() {
+            writeln!(f, "Slowest requests:")?;
+            for (rank, request) in slowest.iter().enumerate() {
+                let status = request
+                    .status
+                    .map_or_else(|| "ERR".to_string(), |status| status.to_string());
+                writeln!(
+                    f,
+                    "  {:>2}. {:>8} ms  {:<4} {} {}  {}",
+                    rank + 1,
+                    request.duration.as_millis(),
+                    status,
+                    request.method,
+                    request.url,
+                    request.name
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 失败详情：每个失败的请求的错误、失败的测试和脚本日志
+pub struct FailureDetails<'a> {
+    report: &'a RunReport,
+}
+
+impl fmt::Display for FailureDetails<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = self.report;
+        if report.failed() == 0 {
+            return Ok(());
+        }
+
+        writeln!(
+            f,
+            "\n{} of {} request(s) failed:",
+            report.failed(),
+            report.total()
+        )?;
+        for failed in report.failures() {
+            if let Some(error) = &failed.error {
+                writeln!(f, "  - {}: {}", failed.name, error)?;
+            }
+            if failed.is_xpassed() {
+                writeln!(
+                    f,
+                    "  - {}: expected to fail (@xfail) but passed",
+                    failed.name
+                )?;
+            }
+            for test in failed.test_results.iter().filter(|t| t.is_failure()) {
+                writeln!(
+                    f,
+                    "  - {}: test '{}' failed: {}",
+                    failed.name,
+                    test.name,
+                    test.message.as_deref().unwrap_or("assertion failed")
+                )?;
+            }
+            // 日志保留断言的上下文，CI中只看失败摘要也能排查
+            for log in &failed.logs {
+                match &log.test {
+                    Some(test) => writeln!(f, "    {log} (in test '{test}')")?,
+                    None => writeln!(f, "    {log}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 失败处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// 遇到传输或执行错误时停止，测试失败不影响后续请求
+    #[default]
+    StopOnError,
+    /// 遇到任何失败（包括测试失败）时立即停止
+    FailFast,
+    /// 始终执行全部请求
+    ContinueOnError,
+}
+
+impl FailurePolicy {
+    /// 该请求的结果是否应当停止后续请求
+    fn should_stop(self, report: &RequestReport) -> bool {
+        match self {
+            // 预期失败的请求出错时不停止
+            Self::StopOnError => report.error.is_some() && report.xfail.is_none(),
+            Self::FailFast => !report.passed(),
+            Self::ContinueOnError => false,
+        }
+    }
+}
+
+/// 用例执行顺序（钩子请求不参与排序）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RunOrder {
+    /// 按文件中的顺序
+    #[default]
+    File,
+    /// 按名称字母顺序
+    Name,
+    /// 按种子随机打乱，相同种子得到相同顺序
+    Random { seed: u64 },
+}
+
+impl RunOrder {
+    /// 对用例排序，重复执行的各次迭代作为整体移动
+    fn apply(self, cases: Vec<&HttpRequest>) -> Vec<&HttpRequest> {
+        let mut groups: Vec<Vec<&HttpRequest>> = Vec::new();
+        for request in cases {
+            match (groups.last_mut(), request.iteration) {
+                (Some(group), Some(iteration)) if iteration.index > 1 => group.push(request),
+                _ => groups.push(vec![request]),
+            }
+        }
+
+        match self {
+            Self::File => {}
+            Self::Name => groups.sort_by(|a, b| a[0].name.cmp(&b[0].name)),
+            Self::Random { seed } => groups.shuffle(&mut StdRng::seed_from_u64(seed)),
+        }
+        groups.into_iter().flatten().collect()
+    }
+}
+
+/// 选择用例的方式
+///
+/// 命令行 `--case` 的写法由 [`CaseSelector::parse`] 解析：`文件::用例` 限定来源文件，
+/// 文件和用例部分都可以使用通配符 `*` 和 `?`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaseSelector {
+    /// 名称包含该文本的第一个请求；`名称#N` 选择名称恰好为该名称的第N个请求
+    Name(String),
+    /// 来源文件中的序号（从1开始）
+    Index(usize),
+    /// 名称与通配符模式匹配的所有请求
+    Glob(String),
+    /// 在来源文件与模式匹配的请求中选择
+    Qualified {
+        file: String,
+        case: Box<CaseSelector>,
+    },
+}
+
+impl CaseSelector {
+    /// 解析 `--case` 的值
+    ///
+    /// `#N` 为序号，含 `*` 或 `?` 的为通配符模式，其余按名称选择；
+    /// `文件::用例` 中用例部分为空时选择该文件的全部用例。
+    pub fn parse(text: &str) -> Self {
+        if let Some((file, case)) = text.split_once("::") {
+            let case = if case.is_empty() { "*" } else { case };
+            return Self::Qualified {
+                file: file.to_string(),
+                case: Box::new(Self::parse(case)),
+            };
+        }
+        match text.strip_prefix('#').and_then(|n| n.parse().ok()) {
+            Some(index) => Self::Index(index),
+            None if text.contains(['*', '?']) => Self::Glob(text.to_string()),
+            None => Self::Name(text.to_string()),
+        }
+    }
+
+    /// 选中的第一个请求在列表中的位置（重复执行的请求为第一次迭代的位置）
+    pub fn position(&self, requests: &[&HttpRequest]) -> Option<usize> {
+        self.positions(requests).first().copied()
+    }
+
+    /// 选中的所有请求在列表中的位置，按列表顺序排列
+    ///
+    /// 选中重复执行的请求时包含其后的所有迭代。
+    pub fn positions(&self, requests: &[&HttpRequest]) -> Vec<usize> {
+        let first_iteration = |r: &HttpRequest| r.iteration.is_none_or(|i| i.index == 1);
+        let start = match self {
+            Self::Glob(pattern) => {
+                return (0..requests.len())
+                    .filter(|&i| glob_match(pattern, base_name(requests[i])))
+                    .collect();
+            }
+            Self::Qualified { file, case } => {
+                let matching: Vec<usize> = (0..requests.len())
+                    .filter(|&i| from_file(requests[i], file))
+                    .collect();
+                let subset: Vec<&HttpRequest> = matching.iter().map(|&i| requests[i]).collect();
+                return case
+                    .positions(&subset)
+                    .into_iter()
+                    .map(|i| matching[i])
+                    .collect();
+            }
+            Self::Index(index) => requests
+                .iter()
+                .position(|r| r.index == Some(*index) && first_iteration(r)),
+            Self::Name(text) => Self::occurrence(text)
+                .and_then(|(name, occurrence)| {
+                    requests
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, r)| first_iteration(r) && base_name(r) == name)
+                        .nth(occurrence - 1)
+                        .map(|(position, _)| position)
+                })
+                .or_else(|| requests.iter().position(|r| r.name.contains(text.as_str()))),
+        };
+        let Some(start) = start else {
+            return Vec::new();
+        };
+        let count = requests[start]
+            .iteration
+            .map_or(1, |iteration| iteration.total - iteration.index + 1);
+        (start..requests.len().min(start + count)).collect()
+    }
+
+    /// 拆分 `名称#N` 形式的文本
+    fn occurrence(text: &str) -> Option<(&str, usize)> {
+        let (name, occurrence) = text.rsplit_once('#')?;
+        let occurrence = occurrence.parse().ok().filter(|&n| n > 0)?;
+        (!name.is_empty()).then_some((name, occurrence))
+    }
+
+    /// 是否按名称选择所有匹配的请求（没有 `#N` 后缀的名称）
+    pub fn is_name_filter(&self) -> bool {
+        matches!(self, Self::Name(text) if Self::occurrence(text).is_none())
+    }
+}
+
+impl fmt::Display for CaseSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) | Self::Glob(name) => write!(f, "'{name}'"),
+            Self::Index(index) => write!(f, "#{index}"),
+            Self::Qualified { file, case } => write!(f, "{case} in '{file}'"),
+        }
+    }
+}
+
+/// 发送前对请求的判定
+enum Gate {
+    Send,
+    /// 跳过请求，附带原因
+    Skip(String),
+    /// 不发送，请求失败
+    Fail(String),
+}
+
+impl Gate {
+    /// 按 `@skip`、`@if` 和 `@require` 指令判定，条件中的运行期变量此时替换
+    fn of(request: &HttpRequest, variables: &VariableContext) -> Self {
+        if let Some(reason) = request.directive(SKIP_DIRECTIVE) {
+            return Self::Skip(reason.trim().to_string());
+        }
+        // 替换运行期变量后的条件，原因中显示实际比较的值
+        let resolve = |text: &str| {
+            Condition::parse(text).map(|condition| condition.map_operands(|o| variables.replace(o)))
+        };
+        for text in request.directives_named(IF_DIRECTIVE) {
+            match resolve(text) {
+                Ok(condition) if condition.evaluate() => {}
+                Ok(condition) => return Self::Skip(format!("@if {condition} is false")),
+                Err(e) => return Self::Fail(e.to_string()),
+            }
+        }
+        for text in request.directives_named(REQUIRE_DIRECTIVE) {
+            match resolve(text) {
+                Ok(condition) if condition.evaluate() => {}
+                Ok(condition) => {
+                    return Self::Fail(format!("Requirement not met: @require {condition}"));
+                }
+                Err(e) => return Self::Fail(e.to_string()),
+            }
+        }
+        Self::Send
+    }
+}
+
+/// 请求是否依赖之前的请求处理后才确定的运行期状态：带 `@if`/`@require` 条件，
+/// 或URL、查询参数、路径参数、请求头、请求体、断言中仍有 `{{name}}` 引用
+fn depends_on_run_state(request: &HttpRequest) -> bool {
+    let mut directives = request
+        .directives_named(IF_DIRECTIVE)
+        .chain(request.directives_named(REQUIRE_DIRECTIVE));
+    if directives.next().is_some() {
+        return true;
+    }
+    let mut texts = std::iter::once(request.url.as_str())
+        .chain(
+            request
+                .query
+                .iter()
+                .chain(&request.path_params)
+                .map(|(_, value)| value.as_str()),
+        )
+        .chain(request.headers.values().map(String::as_str))
+        .chain(request.body.as_deref())
+        .chain(request.assertions.iter().map(String::as_str));
+    texts.any(|text| template_names(text).next().is_some())
+}
+
+/// 去掉重复执行时追加的 `[index/total]` 后的名称
+fn base_name(request: &HttpRequest) -> &str {
+    request
+        .iteration
+        .and_then(|i| {
+            request
+                .name
+                .strip_suffix(&format!(" [{}/{}]", i.index, i.total))
+        })
+        .unwrap_or(&request.name)
+}
+
+/// 请求的来源文件是否与模式匹配：完整路径、文件名或路径末尾的若干段
+fn from_file(request: &HttpRequest, pattern: &str) -> bool {
+    let Some(path) = &request.source_file else {
+        return false;
+    };
+    glob_match(pattern, &path.to_string_lossy())
+        || path
+            .file_name()
+            .is_some_and(|name| glob_match(pattern, &name.to_string_lossy()))
+        || path.ends_with(pattern)
+}
+
+/// 通配符匹配：`*` 匹配任意字符序列，`?` 匹配单个字符
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    // 最近一个 `*` 在模式中的位置和当时文本的位置，失配时回溯
+    let (mut p, mut t, mut star) = (0, 0, None);
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// 请求运行器
+pub struct Runner {
+    client: HttpClient,
+    case_filters: Vec<CaseSelector>,
+    qualified_names: bool,
+    concurrency: usize,
+    failure_policy: FailurePolicy,
+    order: RunOrder,
+    /// 发送请求的future中也要通知观察者，因此共享
+    observers: Rc<RefCell<Vec<Box<dyn ExecutionObserver>>>>,
+    hooks: Option<ScriptHooks>,
+    notifier: Option<Notifier>,
+}
+
+impl Runner {
+    /// 使用配置好的客户端创建运行器
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            case_filters: Vec::new(),
+            qualified_names: false,
+            concurrency: 1,
+            failure_policy: FailurePolicy::default(),
+            order: RunOrder::default(),
+            observers: Rc::default(),
+            hooks: None,
+            notifier: None,
+        }
+    }
+
+    /// 注册执行观察者，按注册顺序回调
+    pub fn with_observer(self, observer: impl ExecutionObserver + 'static) -> Self {
+        self.observers.borrow_mut().push(Box::new(observer));
+        self
+    }
+
+    /// 只执行名称包含指定文本的第一个用例（钩子请求始终执行）
+    ///
+    /// 多个请求同名时，`名称#N` 选择其中第N个。
+    pub fn with_case_filter(mut self, case_name: Option<String>) -> Self {
+        self.case_filters = case_name.map(CaseSelector::Name).into_iter().collect();
+        self
+    }
+
+    /// 只执行选中的一个用例（钩子请求始终执行）
+    pub fn with_case_selector(mut self, selector: Option<CaseSelector>) -> Self {
+        self.case_filters = selector.into_iter().collect();
+        self
+    }
+
+    /// 只执行任一选择方式选中的用例，保持原有顺序（钩子请求始终执行）
+    ///
+    /// 每种选择方式都必须至少选中一个用例。
+    pub fn with_case_selectors(mut self, selectors: Vec<CaseSelector>) -> Self {
+        self.case_filters = selectors;
+        self
+    }
+
+    /// 报告中使用 `文件::名称` 形式的完整名称，运行多个文件时区分同名用例
+    pub fn with_qualified_names(mut self, qualified_names: bool) -> Self {
+        self.qualified_names = qualified_names;
+        self
+    }
+
+    /// 设置同时进行中的请求数量（至少为1）
+    ///
+    /// 响应仍按请求顺序依次处理，脚本与输出不会交错。
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// 设置失败处理策略
+    pub fn with_failure_policy(mut self, failure_policy: FailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    /// 设置用例执行顺序
+    pub fn with_order(mut self, order: RunOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// 设置全局钩子脚本，每次运行时重新注册
+    ///
+    /// `beforeAll` 抛出异常时运行以错误结束，其余钩子的异常只输出警告。
+    pub fn with_hooks(mut self, hooks: Option<ScriptHooks>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// 设置失败通知，运行有失败且未被取消时通过客户端的传输层发送
+    ///
+    /// 发送失败只输出警告，不影响运行结果。
+    pub fn with_notifier(mut self, notifier: Option<Notifier>) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// 设置取消令牌，取消后中止进行中的请求并返回部分报告
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.client = self.client.with_cancellation(token);
+        self
+    }
+
+    /// 获取客户端的可变引用
+    pub fn client_mut(&mut self) -> &mut HttpClient {
+        &mut self.client
+    }
+
+    /// 筛选需要执行的请求
+    ///
+    /// 返回顺序为：全部 `@setup` 钩子、按执行顺序排列的选中用例、全部 `@teardown` 钩子。
+    /// 匹配到重复执行的请求时，同时选中其后的所有迭代。未指定选择方式而有用例标记了
+    /// `@only` 时，只选中这些用例。
+    pub fn select<'a>(&self, requests: &'a [HttpRequest]) -> Result<Vec<&'a HttpRequest>> {
+        let hooks = |hook: Hook| requests.iter().filter(move |r| r.hook == Some(hook));
+        let cases: Vec<&HttpRequest> = requests.iter().filter(|r| r.hook.is_none()).collect();
+
+        let focused = cases.iter().any(|r| r.directive(ONLY_DIRECTIVE).is_some());
+        let cases = if self.case_filters.is_empty() && focused {
+            let cases: Vec<&HttpRequest> = cases
+                .into_iter()
+                .filter(|r| r.directive(ONLY_DIRECTIVE).is_some())
+                .collect();
+            info!("Running only {} case(s) marked @only", cases.len());
+            cases
+        } else if self.case_filters.is_empty() {
+            cases
+        } else {
+            let mut selected = BTreeSet::new();
+            for selector in &self.case_filters {
+                let positions = selector.positions(&cases);
+                let Some(&first) = positions.first() else {
+                    return Err(HttpieError::InvalidRequest(format!(
+                        "Case {selector} not found"
+                    )));
+                };
+                info!("Found matching case: '{}'", cases[first].name);
+                selected.extend(positions);
+            }
+            selected.into_iter().map(|i| cases[i]).collect()
+        };
+        let cases = self.order.apply(cases);
+
+        Ok(hooks(Hook::Setup)
+            .chain(cases)
+            .chain(hooks(Hook::Teardown))
+            .collect())
+    }
+
+    /// 执行请求并返回运行报告
+    ///
+    /// 按失败处理策略决定是否停止后续请求（默认遇到传输错误时停止）；
+    /// `@setup` 因失败而停止时跳过用例，`@teardown` 在未取消时总会全部执行。
+    /// 响应处理器调用 `client.exit()` 时不再发送后续用例，`@teardown` 仍会执行。
+    /// 取消时停止发送新请求，中止进行中的请求，已完成的部分仍会通知观察者。
+    pub async fn run(&mut self, requests: &[HttpRequest]) -> Result<RunReport> {
+        let selected = self.select(requests)?;
+
+        // 未启用脚本特性时，在发送任何请求之前拒绝带响应处理器的请求
+        #[cfg(not(feature = "scripting"))]
+        if let Some(request) = selected.iter().find(|r| r.response_handler.is_some()) {
+            return Err(crate::client::scripting_disabled(request));
+        }
+        // 未启用GRPC特性时同样在发送前拒绝GRPC请求
+        #[cfg(not(feature = "grpc"))]
+        if let Some(request) = selected.iter().find(|r| r.grpc.is_some()) {
+            return Err(crate::grpc::grpc_disabled(request));
+        }
+        let started = Instant::now();
+        if let Some(hooks) = &self.hooks {
+            self.client.load_hooks(hooks)?;
+            self.client.call_hook(ScriptHook::BeforeAll, json!({}))?;
+        }
+        let total = selected.len();
+        let phase = |hook: Option<Hook>| -> Vec<&HttpRequest> {
+            selected
+                .iter()
+                .copied()
+                .filter(|r| r.hook == hook)
+                .collect()
+        };
+        let (setup, cases, teardown) = (
+            phase(Some(Hook::Setup)),
+            phase(None),
+            phase(Some(Hook::Teardown)),
+        );
+
+        let mut report = RunReport::default();
+        let mut offset = 0;
+        let policy = self.failure_policy;
+        let setup_completed = self
+            .run_batch(&setup, offset, total, policy, &mut report)
+            .await;
+        offset += setup.len();
+        if setup_completed {
+            self.run_batch(&cases, offset, total, policy, &mut report)
+                .await;
+        }
+        offset += cases.len();
+        // 后置钩子用于清理，单个失败不影响其余钩子
+        if !report.cancelled {
+            let policy = FailurePolicy::ContinueOnError;
+            self.run_batch(&teardown, offset, total, policy, &mut report)
+                .await;
+        }
+
+        report.duration = started.elapsed();
+        let context = json!({
+            "report": {
+                "total": report.total(),
+                "passed": report.passed(),
+                "failed": report.failed(),
+                "skipped": report.skipped(),
+                "durationMs": report.duration.as_millis() as u64,
+                "cancelled": report.cancelled,
+                "aborted": report.aborted.as_ref().map(|abort| json!({
+                    "request": abort.request,
+                    "reason": abort.reason,
+                })),
+            },
+        });
+        self.call_hook(ScriptHook::AfterAll, context);
+        for observer in self.observers.borrow_mut().iter_mut() {
+            observer.on_run_complete(&report);
+        }
+        if let Some(notifier) = &self.notifier
+            && !report.cancelled
+        {
+            let transport = self.client.transport();
+            match notifier
+                .notify_on_failure(transport.as_ref(), &report)
+                .await
+            {
+                Ok(true) => info!("Sent failure notification"),
+                Ok(false) => {}
+                Err(e) => warn!("Failed to send notification: {e}"),
+            }
+        }
+        Ok(report)
+    }
+
+    /// 调用每个请求前后和运行结束后的钩子，异常只输出警告
+    fn call_hook(&mut self, hook: ScriptHook, context: Value) {
+        if self.hooks.is_some()
+            && let Err(e) = self.client.call_hook(hook, context)
+        {
+            warn!("{e}");
+        }
+    }
+
+    /// 调用 `afterEach` 钩子
+    fn after_each(&mut self, request: &HttpRequest, request_report: &RequestReport) {
+        let context = json!({
+            "request": hook_request(request),
+            "result": {
+                "name": request_report.name,
+                "status": request_report.status,
+                "durationMs": request_report.duration.as_millis() as u64,
+                "passed": request_report.passed(),
+                "skipped": request_report.skipped,
+                "error": request_report.error,
+                "logs": request_report.logs,
+            },
+        });
+        self.call_hook(ScriptHook::AfterEach, context);
+    }
+
+    /// 执行一批请求并追加到报告，未因失败、取消或中止而提前停止时返回true
+    async fn run_batch(
+        &mut self,
+        batch: &[&HttpRequest],
+        offset: usize,
+        total: usize,
+        policy: FailurePolicy,
+        report: &mut RunReport,
+    ) -> bool {
+        let transport = self.client.transport();
+        let cancellation = self.client.cancellation_token().clone();
+        let variables = self.client.variables().clone();
+        // 带聚合断言的请求（包括重复执行的各次迭代）的采样，以及最后一次采样的报告位置和请求
+        let mut samples: Vec<Sample> = Vec::new();
+        let mut sampled: Option<(usize, HttpRequest)> = None;
+        // 重复执行组的最后一次迭代（不重复的请求自成一组）
+        let is_last = |position: usize| {
+            batch
+                .get(position + 1)
+                .is_none_or(|next| next.iteration.is_none_or(|i| i.index == 1))
+        };
+        // 监听步骤的端口在批次开始时绑定，之前的请求触发的回调不会错过
+        let listeners = listen::bind_all(batch.iter().copied()).await;
+        let observers = self.observers.clone();
+
+        // 在请求即将发送时才判定条件、替换运行期变量
+        let launch = |position: usize, request: &HttpRequest| {
+            let index = offset + position;
+            let observers = observers.clone();
+            // 请求的span覆盖条件判断、发送和响应处理，发送端的日志同样带有请求的字段
+            let span = info_span!(
+                "request",
+                request = %request.name,
+                method = %request.method,
+                url = field::Empty,
+                status = field::Empty,
+                duration_ms = field::Empty,
+            );
+            let gate = span.in_scope(|| Gate::of(request, &variables));
+            let request = variables.resolve(request);
+            span.record("url", field::display(&request.url));
+            let transport = transport.clone();
+            let cancellation = cancellation.clone();
+            let listener = request
+                .listen
+                .as_ref()
+                .and_then(|listen| listeners.get(&listen.port).cloned());
+            async move {
+                // 并发执行时future可能早于循环处理到该请求就开始运行，开始事件在发送前通知
+                info!(
+                    "Executing request {}/{}: {}",
+                    index + 1,
+                    total,
+                    request.name
+                );
+                for observer in observers.borrow_mut().iter_mut() {
+                    observer.on_request_start(&request, index, total);
+                }
+                let fetched = match (&gate, &request.listen) {
+                    (Gate::Skip(_), _) => None,
+                    (Gate::Fail(message), _) => {
+                        Some(Err(HttpieError::InvalidRequest(message.clone())))
+                    }
+                    (Gate::Send, Some(listen)) => Some(
+                        listen::receive_with_cancellation(
+                            listener,
+                            &request,
+                            listen,
+                            &cancellation,
+                        )
+                        .await,
+                    ),
+                    (Gate::Send, None) => Some(
+                        transport::send_with_cancellation(
+                            transport.as_ref(),
+                            &request,
+                            &cancellation,
+                        )
+                        .await,
+                    ),
+                };
+                (request, gate, fetched)
+            }
+            .instrument(span.clone())
+            .map(move |(request, gate, fetched)| (request, gate, fetched, span))
+        };
+        let mut responses = FuturesOrdered::new();
+        let mut launched = 0;
+
+        for position in 0..batch.len() {
+            if cancellation.is_cancelled() {
+                report.cancelled = true;
+                return false;
+            }
+            // 最多提前发送 `concurrency` 个请求；依赖运行期状态的请求（以及配置了钩子时的所有请求）
+            // 要等之前的请求都处理完（捕获、脚本变量已写入）才判定条件、替换变量并发送
+            while let Some(&next) = batch.get(launched)
+                && responses.len() < self.concurrency
+                && (launched == position || (self.hooks.is_none() && !depends_on_run_state(next)))
+            {
+                let context = json!({
+                    "request": hook_request(next),
+                    "index": offset + launched,
+                    "total": total,
+                });
+                self.call_hook(ScriptHook::BeforeEach, context);
+                responses.push_back(launch(launched, next));
+                launched += 1;
+            }
+
+            let (request, gate, fetched, span) = responses
+                .next()
+                .await
+                .expect("one response per selected request");
+            let fetched = match (gate, fetched) {
+                (Gate::Skip(reason), _) => {
+                    let mut request_report = RequestReport::new(&request);
+                    if self.qualified_names {
+                        request_report.name = request.qualified_name();
+                    }
+                    span.in_scope(|| info!("Skipped request '{}'", request_report.name));
+                    request_report.skipped = Some(reason);
+                    if aggregate::has_aggregate(&request.assertions) && is_last(position) {
+                        self.finish_aggregate(&mut samples, &mut sampled, report);
+                    }
+                    for observer in self.observers.borrow_mut().iter_mut() {
+                        observer.on_request_complete(&request, &request_report);
+                    }
+                    self.after_each(&request, &request_report);
+                    report.requests.push(request_report);
+                    continue;
+                }
+                (_, fetched) => fetched.expect("sent unless skipped"),
+            };
+            if matches!(fetched, Err(HttpieError::Cancelled)) {
+                report.cancelled = true;
+                return false;
+            }
+            let (mut request_report, exit) = self.process(&request, fetched).instrument(span).await;
+            let aggregated = aggregate::has_aggregate(&request.assertions);
+            if aggregated && request_report.skipped.is_none() {
+                if request.iteration.is_none_or(|i| i.index == 1) {
+                    samples.clear();
+                }
+                samples.push(Sample {
+                    latency: request_report.duration,
+                    status: request_report.status,
+                });
+                // 聚合断言在最后一次迭代完成后求值，结果附在该迭代上
+                if is_last(position) {
+                    sampled = None;
+                    self.check_aggregate(&request, mem::take(&mut samples), &mut request_report);
+                } else {
+                    sampled = Some((report.requests.len(), request.clone()));
+                }
+            } else if aggregated && is_last(position) {
+                // 最后一次迭代被脚本跳过
+                self.finish_aggregate(&mut samples, &mut sampled, report);
+            }
+            for observer in self.observers.borrow_mut().iter_mut() {
+                observer.on_request_complete(&request, &request_report);
+            }
+            self.after_each(&request, &request_report);
+            let stop = policy.should_stop(&request_report);
+            if let Some(reason) = exit {
+                warn!("Run aborted by '{}'", request_report.name);
+                report.aborted = Some(Abort {
+                    request: request_report.name.clone(),
+                    reason,
+                });
+            }
+            report.requests.push(request_report);
+
+            if stop || report.aborted.is_some() {
+                // 重复执行组中途停止时，已采集的样本照常求值
+                self.finish_aggregate(&mut samples, &mut sampled, report);
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// 用重复执行组的样本求值聚合断言，结果附在 `target` 上并通知观察者
+    fn check_aggregate(
+        &mut self,
+        request: &HttpRequest,
+        samples: Vec<Sample>,
+        target: &mut RequestReport,
+    ) {
+        let elapsed = samples.iter().map(|s| s.latency).sum();
+        let bench = BenchReport::new(&request.name, samples, elapsed);
+        let mut results = aggregate::check_all(&request.assertions, &bench);
+        for result in &mut results {
+            result.request_name = Some(request.name.clone());
+        }
+        // process已输出该请求的测试结果，聚合结果用同一格式补充输出
+        self.client.print_test_results(&request.name, &results);
+        for observer in self.observers.borrow_mut().iter_mut() {
+            for result in &results {
+                observer.on_test_result(request, result);
+            }
+        }
+        target.test_results.extend(results);
+    }
+
+    /// 重复执行组没有在正常的最后一次迭代求值（最后的迭代被跳过，或批次提前停止）时，
+    /// 用已采集的样本求值，结果附在最后一次采样的迭代上
+    fn finish_aggregate(
+        &mut self,
+        samples: &mut Vec<Sample>,
+        sampled: &mut Option<(usize, HttpRequest)>,
+        report: &mut RunReport,
+    ) {
+        let Some((index, request)) = sampled.take() else {
+            return;
+        };
+        if samples.is_empty() {
+            return;
+        }
+        self.check_aggregate(&request, mem::take(samples), &mut report.requests[index]);
+    }
+
+    /// 处理单个请求的响应并生成运行记录，响应处理器调用 `client.exit()` 时同时返回其原因
+    ///
+    /// 在请求的span内执行，测试结果和完成状态作为事件输出。
+    async fn process(
+        &mut self,
+        request: &HttpRequest,
+        fetched: Result<RawResponse>,
+    ) -> (RequestReport, Option<String>) {
+        let mut request_report = RequestReport::new(request);
+        if self.qualified_names {
+            request_report.name = request.qualified_name();
+        }
+
+        let outcome = match fetched {
+            Ok(raw) => {
+                let span = Span::current();
+                span.record("status", raw.status);
+                span.record("duration_ms", raw.duration.as_millis() as u64);
+                request_report.status = Some(raw.status);
+                request_report.duration = raw.duration;
+                self.client.process(request, raw).await
+            }
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(outcome) => {
+                for observer in self.observers.borrow_mut().iter_mut() {
+                    observer.on_response(request, &outcome.response, outcome.duration);
+                    for result in &outcome.test_results {
+                        observer.on_test_result(request, result);
+                    }
+                }
+                for result in &outcome.test_results {
+                    info!(
+                        test = %result.name,
+                        passed = result.passed,
+                        reason = result.message.as_deref(),
+                        duration_ms = result.duration_ms,
+                        "test result"
+                    );
+                }
+                info!("request completed");
+                request_report.logs = outcome.logs;
+                match outcome.control {
+                    // 跳过的请求不计入测试结果
+                    Some(ScriptControl::Skip(reason)) => {
+                        info!("Skipped request '{}'", request_report.name);
+                        request_report.skipped = Some(reason);
+                    }
+                    Some(ScriptControl::Exit(reason)) => {
+                        request_report.test_results = outcome.test_results;
+                        return (request_report, Some(reason));
+                    }
+                    None => request_report.test_results = outcome.test_results,
+                }
+            }
+            Err(e) => {
+                error!("Failed to execute request '{}': {}", request.name, e);
+                request_report.error = Some(e.to_string());
+            }
+        }
+
+        (request_report, None)
+    }
+}
+
+/// 钩子参数中的请求
+fn hook_request(request: &HttpRequest) -> Value {
+    json!({
+        "name": request.name,
+        "method": request.method.as_str(),
+        "url": request.url,
+    })
+}
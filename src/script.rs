@@ -1,27 +1,315 @@
 //! 响应处理器脚本模块
 //!
 //! 实现基于deno_core的JavaScript脚本执行引擎，支持响应处理和测试断言。
-
-use crate::error::{HttpieError, Result};
+//! 脚本引擎由 `scripting` 特性控制，响应对象与测试结果类型始终可用。
+//!
+//! 默认每个响应处理器在从启动快照新建的运行时中执行，脚本的全局状态不会泄漏到下一个请求，
+//! 请求之间只通过 `client.global` 传递值；[`ScriptIsolation::Shared`] 让所有脚本共用一个运行时。
+
+use crate::binary::{self, BinarySummary};
+use crate::compress::EncodedBody;
+use crate::cookie::SetCookie;
+use crate::error::{self, HttpieError, Result, SourceLocation};
+#[cfg(feature = "scripting")]
+use crate::hooks::{ScriptHook, ScriptHooks};
+use crate::models::Headers;
+use crate::redirect::RedirectHop;
+use crate::tls::TlsDetails;
+use bytes::Bytes;
+#[cfg(feature = "scripting")]
 use deno_core::{JsRuntime, RuntimeOptions};
 use reqwest::Response;
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::{Value, json};
+use serde_json::Value;
+#[cfg(feature = "scripting")]
+use serde_json::json;
+use std::borrow::Cow;
+#[cfg(feature = "scripting")]
 use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+/// 构建期生成的启动快照，包含client/console等脚手架
+#[cfg(feature = "scripting")]
+static RUNTIME_SNAPSHOT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/HTTPIE_SNAPSHOT.bin"));
 
 /// 脚本执行引擎
+#[cfg(feature = "scripting")]
 pub struct ScriptEngine {
     runtime: JsRuntime,
     global_variables: HashMap<String, Value>,
+    /// 最近一次执行的脚本调用的控制流原语
+    control: Option<ScriptControl>,
+    /// 最近一次执行的脚本输出的日志
+    logs: Vec<ScriptLog>,
+    isolation: ScriptIsolation,
+    /// 已注册的钩子程序及其来源，用于映射钩子错误的位置
+    hooks_origin: Option<(String, ScriptOrigin)>,
+}
+
+/// 执行响应处理器时的脚本名称，出现在错误的调用栈中
+#[cfg(feature = "scripting")]
+const HANDLER_SCRIPT_NAME: &str = "<response_handler>";
+
+/// 注册全局钩子时的脚本名称
+#[cfg(feature = "scripting")]
+const HOOKS_SCRIPT_NAME: &str = "<hooks>";
+
+/// 响应处理器之间的隔离方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScriptIsolation {
+    /// 每个响应处理器使用新的运行时，只有 `client.global` 的值在请求之间保留
+    #[default]
+    Isolated,
+    /// 所有脚本共用一个运行时，脚本定义的全局函数和变量对之后的脚本可见
+    Shared,
+}
+
+impl FromStr for ScriptIsolation {
+    type Err = HttpieError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "isolated" => Ok(Self::Isolated),
+            "shared" => Ok(Self::Shared),
+            other => Err(HttpieError::Parse(format!(
+                "Invalid script isolation '{other}', expected isolated or shared"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ScriptIsolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Isolated => "isolated",
+            Self::Shared => "shared",
+        })
+    }
+}
+
+/// 脚本通过 `client.skip()` 或 `client.exit()` 请求的控制流
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", content = "reason", rename_all = "lowercase")]
+pub enum ScriptControl {
+    /// 把当前请求标记为跳过，附带原因（可以为空）
+    Skip(String),
+    /// 中止运行，不再发送后续请求
+    Exit(String),
+}
+
+/// 脚本日志的级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// `client.log()`、`console.log()`、`console.info()`
+    Log,
+    /// `client.warn()`、`console.warn()`
+    Warn,
+    /// `client.error()`、`console.error()`
+    Error,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Log => "log",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// 脚本输出的一条日志，随测试结果写入输出和报告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptLog {
+    pub level: LogLevel,
+    /// 参数以空格连接，非字符串的值序列化为JSON
+    pub message: String,
+    /// 在 `client.test()` 内输出时为测试名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<String>,
+}
+
+impl fmt::Display for ScriptLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.level, self.message)
+    }
+}
+
+/// 执行的脚本在来源文件中的位置，用于把脚本错误的行号映射回文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptOrigin {
+    /// 来源文件（如请求所在的.http文件）
+    pub file: Option<String>,
+    /// 脚本第一行在来源文件中的行号（从1开始）
+    pub line: usize,
+    /// 执行前插入在脚本之前的行数（如导入模块的定义）
+    pub prelude_lines: usize,
+}
+
+impl Default for ScriptOrigin {
+    fn default() -> Self {
+        Self {
+            file: None,
+            line: 1,
+            prelude_lines: 0,
+        }
+    }
+}
+
+impl ScriptOrigin {
+    /// 把执行的脚本中的位置映射到来源文件，位于插入的前导部分时返回None
+    pub fn locate(&self, line: usize, column: usize) -> Option<SourceLocation> {
+        let offset = line.checked_sub(self.prelude_lines + 1)?;
+        Some(SourceLocation {
+            file: self.file.clone(),
+            line: self.line + offset,
+            column,
+        })
+    }
+}
+
+/// 从脚本引擎的错误中解析出的错误信息、位置和调用栈
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptFailure {
+    /// 错误信息（如 `Uncaught ReferenceError: token is not defined`）
+    pub message: String,
+    /// 出错位置在执行的脚本中的行号和列号
+    pub position: Option<(usize, usize)>,
+    /// 调用栈（`at ...` 各帧）
+    pub stack: Vec<String>,
+}
+
+impl ScriptFailure {
+    /// 解析错误文本，`script_name` 是执行脚本时使用的名称（如 `<response_handler>`）
+    pub fn parse(error: &str, script_name: &str) -> Self {
+        let mut lines = error.lines();
+        let message = lines.next().unwrap_or_default().trim().to_string();
+        let stack: Vec<String> = lines
+            .map(str::trim)
+            .filter(|line| line.starts_with("at "))
+            .map(String::from)
+            .collect();
+        let position = stack
+            .iter()
+            .find_map(|frame| frame_position(frame, script_name));
+        Self {
+            message,
+            position,
+            stack,
+        }
+    }
+
+    /// 错误信息：带来源文件位置和出错行原文，并附上行号已映射的调用栈
+    pub fn render(&self, script: &str, script_name: &str, origin: &ScriptOrigin) -> String {
+        let location = self
+            .position
+            .and_then(|(line, column)| Some((line, origin.locate(line, column)?)));
+        let mut text = match location {
+            Some((line, location)) => {
+                let source_line = script.lines().nth(line - 1).unwrap_or_default();
+                let snippet = error::snippet(&location, source_line.trim_end());
+                format!("{location}: {}{snippet}", self.message)
+            }
+            None => self.message.clone(),
+        };
+        for frame in &self.stack {
+            text.push_str("\n    ");
+            text.push_str(&map_frame(frame, script_name, origin));
+        }
+        text
+    }
+}
+
+/// 调用栈帧中脚本的行号和列号（`at <response_handler>:3:5` 或 `at f (<response_handler>:3:5)`）
+fn frame_position(frame: &str, script_name: &str) -> Option<(usize, usize)> {
+    let (_, rest) = frame.split_once(&format!("{script_name}:"))?;
+    let (line, rest) = rest.split_once(':')?;
+    let column: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
+
+/// 把调用栈帧中的脚本位置替换为来源文件中的位置
+fn map_frame(frame: &str, script_name: &str, origin: &ScriptOrigin) -> String {
+    let Some((line, column)) = frame_position(frame, script_name) else {
+        return frame.to_string();
+    };
+    match origin.locate(line, column) {
+        Some(location) => frame.replacen(
+            &format!("{script_name}:{line}:{column}"),
+            &location.to_string(),
+            1,
+        ),
+        None => frame.to_string(),
+    }
 }
 
 /// 响应对象，用于在JavaScript中访问HTTP响应信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ResponseObject {
     pub status: u16,
-    pub headers: HashMap<String, String>,
+    pub headers: Headers,
     pub body: Value,
     pub content_type: String,
+    /// 二进制响应体的摘要（此时body为null）
+    pub binary: Option<BinarySummary>,
+    /// 实际使用的HTTP版本（如 `HTTP/1.1`、`HTTP/2.0`）
+    pub http_version: Option<String>,
+    /// 响应体是否因超过缓冲上限而被截断
+    pub truncated: bool,
+    /// 压缩响应的编码和压缩前后的大小
+    pub encoded: Option<EncodedBody>,
+    /// 响应体的原始字节（已解压），哈希、保存和二进制比较使用它而不是解码后的body
+    pub raw_body: Bytes,
+    /// 对端的IP地址和端口
+    pub remote_addr: Option<SocketAddr>,
+    /// HTTPS连接的TLS信息（证书有效期）
+    pub tls: Option<TlsDetails>,
+    /// 跟随的重定向，按发生顺序排列
+    pub redirects: Vec<RedirectHop>,
+    /// `Set-Cookie` 响应头解析出的Cookie（headers中同名响应头只保留最后一个）
+    pub cookies: Vec<SetCookie>,
+}
+
+/// 测试结果的分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestOutcome {
+    /// 测试通过
+    Passed,
+    /// 测试失败
+    Failed,
+    /// 预期失败的测试确实失败（XFAIL），不计为失败
+    Xfailed,
+    /// 预期失败的测试意外通过（XPASS），计为失败
+    Xpassed,
+}
+
+impl TestOutcome {
+    /// 是否计为失败
+    pub fn is_failure(self) -> bool {
+        matches!(self, Self::Failed | Self::Xpassed)
+    }
+
+    /// 小写名称，用于报告和指标标签
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+            Self::Xfailed => "xfailed",
+            Self::Xpassed => "xpassed",
+        }
+    }
+}
+
+impl fmt::Display for TestOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// 测试结果
@@ -30,110 +318,251 @@ pub struct TestResult {
     pub name: String,
     pub passed: bool,
     pub message: Option<String>,
+    /// 测试函数执行耗时（毫秒）
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// 失败时的错误类型（如 `AssertionError`、`TypeError`）
+    #[serde(default)]
+    pub error_kind: Option<String>,
+    /// 产生该结果的请求名称
+    #[serde(default)]
+    pub request_name: Option<String>,
+    /// 被 `@xfail-test` 标记为预期失败
+    #[serde(default)]
+    pub xfail: bool,
 }
 
+impl TestResult {
+    /// 创建通过的测试结果
+    pub fn success(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            passed: true,
+            message: None,
+            duration_ms: 0,
+            error_kind: None,
+            request_name: None,
+            xfail: false,
+        }
+    }
+
+    /// 创建失败的测试结果
+    pub fn failure(
+        name: impl Into<String>,
+        message: impl Into<String>,
+        error_kind: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            passed: false,
+            message: Some(message.into()),
+            duration_ms: 0,
+            error_kind: Some(error_kind.into()),
+            request_name: None,
+            xfail: false,
+        }
+    }
+
+    /// 是否计为失败：未标记的测试失败，或预期失败的测试意外通过（XPASS）
+    pub fn is_failure(&self) -> bool {
+        self.outcome().is_failure()
+    }
+
+    /// 按 `@xfail-test` 标记区分的结果
+    pub fn outcome(&self) -> TestOutcome {
+        match (self.passed, self.xfail) {
+            (true, false) => TestOutcome::Passed,
+            (false, false) => TestOutcome::Failed,
+            (false, true) => TestOutcome::Xfailed,
+            (true, true) => TestOutcome::Xpassed,
+        }
+    }
+}
+
+#[cfg(feature = "scripting")]
 impl ScriptEngine {
     /// 创建新的脚本执行引擎
     pub fn new() -> Result<Self> {
-        let runtime = JsRuntime::new(RuntimeOptions::default());
-
         Ok(Self {
-            runtime,
+            runtime: new_runtime(),
             global_variables: HashMap::new(),
+            control: None,
+            logs: Vec::new(),
+            isolation: ScriptIsolation::default(),
+            hooks_origin: None,
         })
     }
 
+    /// 设置响应处理器之间的隔离方式（默认隔离）
+    pub fn with_isolation(mut self, isolation: ScriptIsolation) -> Self {
+        self.isolation = isolation;
+        self
+    }
+
+    /// 响应处理器之间的隔离方式
+    pub fn isolation(&self) -> ScriptIsolation {
+        self.isolation
+    }
+
     /// 执行响应处理脚本
     pub async fn execute_response_script(
         &mut self,
         script: String,
         response_obj: ResponseObject,
+    ) -> Result<Vec<TestResult>> {
+        self.execute_response_script_at(script, response_obj, &ScriptOrigin::default())
+            .await
+    }
+
+    /// 执行响应处理脚本，出错时按 `origin` 把位置映射回来源文件
+    pub async fn execute_response_script_at(
+        &mut self,
+        script: String,
+        response_obj: ResponseObject,
+        origin: &ScriptOrigin,
+    ) -> Result<Vec<TestResult>> {
+        // 隔离时在新运行时中执行，结束后换回共用的运行时（全局钩子注册在其中）
+        if self.isolation == ScriptIsolation::Isolated {
+            let shared = std::mem::replace(&mut self.runtime, new_runtime());
+            let result = self.run_response_script(script, &response_obj, origin);
+            drop(std::mem::replace(&mut self.runtime, shared));
+            return result;
+        }
+        self.run_response_script(script, &response_obj, origin)
+    }
+
+    /// 在当前运行时中执行响应处理脚本
+    fn run_response_script(
+        &mut self,
+        script: String,
+        response_obj: &ResponseObject,
+        origin: &ScriptOrigin,
     ) -> Result<Vec<TestResult>> {
         // 初始化JavaScript环境
-        self.setup_javascript_environment(&response_obj)?;
+        self.setup_javascript_environment(response_obj)?;
 
         // 执行脚本
-        let result = self.runtime.execute_script("<response_handler>", script);
+        let result = self
+            .runtime
+            .execute_script(HANDLER_SCRIPT_NAME, script.clone());
+        self.control = self.extract_control()?;
+        self.logs = self.extract_logs()?;
 
         match result {
-            Ok(_) => {
-                // 提取测试结果
-                self.extract_test_results()
+            // client.skip()/client.exit() 以抛出哨兵的方式结束脚本，不是错误
+            Ok(_) => self.extract_test_results(),
+            Err(_) if self.control.is_some() => self.extract_test_results(),
+            Err(e) => {
+                let failure = ScriptFailure::parse(&e.to_string(), HANDLER_SCRIPT_NAME);
+                Err(HttpieError::ScriptError(format!(
+                    "Script execution failed: {}",
+                    failure.render(&script, HANDLER_SCRIPT_NAME, origin)
+                )))
             }
-            Err(e) => Err(HttpieError::ScriptError(format!(
-                "Script execution failed: {}",
-                e
-            ))),
         }
     }
 
+    /// 注册全局钩子脚本
+    pub fn load_hooks(&mut self, hooks: &ScriptHooks) -> Result<()> {
+        let program = hooks.program();
+        self.runtime
+            .execute_script(HOOKS_SCRIPT_NAME, program.clone())
+            .map_err(|e| {
+                let failure = ScriptFailure::parse(&e.to_string(), HOOKS_SCRIPT_NAME);
+                HttpieError::ScriptError(format!(
+                    "Failed to load hooks {}: {}",
+                    hooks.path().display(),
+                    failure.render(&program, HOOKS_SCRIPT_NAME, &hooks.origin())
+                ))
+            })?;
+        self.hooks_origin = Some((program, hooks.origin()));
+        Ok(())
+    }
+
+    /// 调用全局钩子，脚本没有导出该函数时什么也不做
+    pub fn call_hook(&mut self, hook: ScriptHook, context: &Value) -> Result<()> {
+        let script = format!(
+            "{}\nglobalThis.__httpie_logs = [];\n(function () {{\n\
+             var hook = globalThis.__httpie_hooks && globalThis.__httpie_hooks[{}];\n\
+             if (typeof hook === \"function\") {{ hook({}); }}\n\
+             }})();",
+            self.import_globals_script()?,
+            serde_json::to_string(hook.function_name())?,
+            serde_json::to_string(context)?
+        );
+        self.runtime.execute_script("<hook>", script).map_err(|e| {
+            // 钩子函数定义在钩子脚本中，调用栈按钩子脚本映射
+            let failure = ScriptFailure::parse(&e.to_string(), HOOKS_SCRIPT_NAME);
+            let message = match &self.hooks_origin {
+                Some((program, origin)) => failure.render(program, HOOKS_SCRIPT_NAME, origin),
+                None => e.to_string(),
+            };
+            HttpieError::ScriptError(format!("Hook {hook} failed: {message}"))
+        })?;
+        self.logs = self.extract_logs()?;
+        self.extract_global_variables()
+    }
+
+    /// 取出最近一次执行的脚本（响应处理器或钩子）输出的日志
+    pub fn take_logs(&mut self) -> Vec<ScriptLog> {
+        std::mem::take(&mut self.logs)
+    }
+
+    /// 读取脚本输出的日志
+    fn extract_logs(&mut self) -> Result<Vec<ScriptLog>> {
+        let result = self
+            .runtime
+            .execute_script(
+                "<extract_logs>",
+                "JSON.stringify(globalThis.__httpie_logs || [])",
+            )
+            .map_err(|e| HttpieError::ScriptError(format!("Failed to extract logs: {}", e)))?;
+        let global = result.open(&mut self.runtime.handle_scope());
+        let result_str = global.to_rust_string_lossy(&mut self.runtime.handle_scope());
+        serde_json::from_str(&result_str)
+            .map_err(|e| HttpieError::ScriptError(format!("Failed to parse logs: {}", e)))
+    }
+
+    /// 取出最近一次执行的脚本请求的控制流
+    pub fn take_control(&mut self) -> Option<ScriptControl> {
+        self.control.take()
+    }
+
+    /// 读取脚本调用的控制流原语
+    fn extract_control(&mut self) -> Result<Option<ScriptControl>> {
+        let result = self
+            .runtime
+            .execute_script(
+                "<extract_control>",
+                "JSON.stringify(globalThis.__httpie_control)",
+            )
+            .map_err(|e| HttpieError::ScriptError(format!("Failed to extract control: {}", e)))?;
+        let global = result.open(&mut self.runtime.handle_scope());
+        let result_str = global.to_rust_string_lossy(&mut self.runtime.handle_scope());
+        serde_json::from_str(&result_str)
+            .map_err(|e| HttpieError::ScriptError(format!("Failed to parse control: {}", e)))
+    }
+
     /// 设置JavaScript环境
+    ///
+    /// 脚手架（client/console/assert）已包含在启动快照中，这里只注入本次请求的response对象。
     fn setup_javascript_environment(&mut self, response_obj: &ResponseObject) -> Result<()> {
-        // 注入response对象
         let response_json = json!({
             "status": response_obj.status,
             "headers": response_obj.headers,
             "body": response_obj.body,
-            "contentType": response_obj.content_type
+            "contentType": response_obj.content_type,
+            "httpVersion": response_obj.http_version,
+            "remoteAddress": response_obj.remote_addr.map(|addr| addr.to_string()),
+            "tls": response_obj.tls,
+            "redirects": response_obj.redirects,
+            "cookies": response_obj.cookies
         });
 
         let setup_script = format!(
-            r#"
-            // 全局变量存储
-            globalThis.__httpie_globals = globalThis.__httpie_globals || {{}};
-            globalThis.__httpie_tests = [];
-
-            // 响应对象
-            globalThis.response = {};
-
-            // 客户端对象
-            globalThis.client = {{
-                global: {{
-                    set: function(key, value) {{
-                        globalThis.__httpie_globals[key] = value;
-                    }},
-                    get: function(key) {{
-                        return globalThis.__httpie_globals[key];
-                    }}
-                }},
-                test: function(name, testFn) {{
-                    try {{
-                        testFn();
-                        globalThis.__httpie_tests.push({{
-                            name: name,
-                            passed: true,
-                            message: null
-                        }});
-                    }} catch (error) {{
-                        globalThis.__httpie_tests.push({{
-                            name: name,
-                            passed: false,
-                            message: error.message
-                        }});
-                    }}
-                }},
-                assert: function(condition, message) {{
-                    if (!condition) {{
-                        throw new Error(message || 'Assertion failed');
-                    }}
-                }}
-            }};
-
-            // 控制台对象
-            globalThis.console = {{
-                log: function(...args) {{
-                    // 简单的日志输出，实际项目中可以改进
-                }}
-            }};
-
-            // 全局assert函数
-            globalThis.assert = function(condition, message) {{
-                if (!condition) {{
-                    throw new Error(message || 'Assertion failed');
-                }}
-            }};
-            "#,
-            serde_json::to_string(&response_json).unwrap()
+            "{}\nglobalThis.__httpie_tests = [];\nglobalThis.__httpie_logs = [];\nglobalThis.__httpie_control = null;\nglobalThis.response = {};",
+            self.import_globals_script()?,
+            serde_json::to_string(&response_json)?
         );
 
         self.runtime
@@ -193,6 +622,14 @@ impl ScriptEngine {
         Ok(())
     }
 
+    /// 把已知的全局变量写回运行时，使隔离的运行时和共用的运行时都能读到其他脚本设置的值
+    fn import_globals_script(&self) -> Result<String> {
+        Ok(format!(
+            "Object.assign(globalThis.__httpie_globals, {});",
+            serde_json::to_string(&self.global_variables)?
+        ))
+    }
+
     /// 获取全局变量
     pub fn get_global_variable(&self, key: &str) -> Option<&Value> {
         self.global_variables.get(key)
@@ -204,38 +641,165 @@ impl ScriptEngine {
     }
 }
 
+/// 从启动快照创建运行时
+#[cfg(feature = "scripting")]
+fn new_runtime() -> JsRuntime {
+    JsRuntime::new(RuntimeOptions {
+        startup_snapshot: Some(RUNTIME_SNAPSHOT),
+        ..Default::default()
+    })
+}
+
 impl ResponseObject {
     /// 从reqwest::Response创建ResponseObject
     pub async fn from_response(response: Response) -> Result<Self> {
         let status = response.status().as_u16();
-        let content_type = response
-            .headers()
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await?;
+
+        Ok(Self::from_parts(status, &headers, &bytes))
+    }
+
+    /// 从状态码、响应头和响应体字节创建ResponseObject
+    pub fn from_parts(status: u16, header_map: &HeaderMap, bytes: &[u8]) -> Self {
+        Self::from_bytes(status, header_map, Bytes::copy_from_slice(bytes))
+    }
+
+    /// 同 [`from_parts`](Self::from_parts)，直接保留响应体字节而不复制
+    pub fn from_bytes(status: u16, header_map: &HeaderMap, bytes: Bytes) -> Self {
+        let content_type = header_map
             .get("content-type")
             .and_then(|v| v.to_str().ok())
             .unwrap_or("")
             .to_string();
 
-        let mut headers = HashMap::new();
-        for (name, value) in response.headers() {
+        let mut headers = Headers::new();
+        for (name, value) in header_map {
             headers.insert(name.to_string(), value.to_str().unwrap_or("").to_string());
         }
+        let cookies = SetCookie::from_headers(header_map);
+
+        // 以 `application/octet-stream` 等类型返回的JSON仍按文本处理
+        if binary::is_binary(&content_type, &bytes) && !binary::is_json(&bytes) {
+            return Self {
+                status,
+                headers,
+                body: Value::Null,
+                content_type,
+                binary: Some(BinarySummary::new(&bytes)),
+                http_version: None,
+                truncated: false,
+                encoded: None,
+                raw_body: bytes,
+                remote_addr: None,
+                tls: None,
+                redirects: Vec::new(),
+                cookies,
+            };
+        }
 
-        let body_text = response.text().await?;
+        let body_text = binary::decode_text(&content_type, &bytes).into_owned();
         let body = if content_type.contains("application/json") {
             serde_json::from_str(&body_text).unwrap_or(Value::String(body_text))
         } else {
             Value::String(body_text)
         };
 
-        Ok(Self {
+        Self {
             status,
             headers,
             body,
             content_type,
-        })
+            binary: None,
+            http_version: None,
+            truncated: false,
+            encoded: None,
+            raw_body: bytes,
+            remote_addr: None,
+            tls: None,
+            redirects: Vec::new(),
+            cookies,
+        }
+    }
+
+    /// 状态码是否为2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// 响应体只保留JSONPath选中片段的副本，用于输出（`--filter`、`@filter`）
+    ///
+    /// 路径不存在时响应体为null；响应体不是JSON时返回None。
+    pub fn filtered(&self, path: &str) -> Result<Option<Self>> {
+        let parsed;
+        let body = match &self.body {
+            Value::String(text) => match serde_json::from_str(text) {
+                Ok(value) => {
+                    parsed = value;
+                    &parsed
+                }
+                Err(_) => return Ok(None),
+            },
+            Value::Null if self.binary.is_some() => return Ok(None),
+            body => body,
+        };
+        let body = crate::jsonpath::extract(body, path)?.unwrap_or(Value::Null);
+        Ok(Some(Self {
+            body,
+            ..self.clone()
+        }))
+    }
+
+    /// 响应体的原始字节
+    pub fn bytes(&self) -> &Bytes {
+        &self.raw_body
+    }
+
+    /// 响应体文本（按Content-Type中的charset解码，默认UTF-8，非法字节按替换字符处理）
+    pub fn text(&self) -> Cow<'_, str> {
+        if self.raw_body.is_empty()
+            && let Value::String(text) = &self.body
+        {
+            return Cow::Borrowed(text);
+        }
+        binary::decode_text(&self.content_type, &self.raw_body)
+    }
+
+    /// 把响应体反序列化为指定类型
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        // 手工构建、没有原始字节的响应对象使用已解析的body
+        if self.raw_body.is_empty() && !self.body.is_null() {
+            return Ok(serde_json::from_value(self.body.clone())?);
+        }
+        Ok(serde_json::from_str(&self.text())?)
+    }
+
+    /// 响应头的值（名称不区分大小写）
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 把响应头的值解析为指定类型，响应头不存在时返回None
+    pub fn header_as<T: FromStr>(&self, name: &str) -> Result<Option<T>> {
+        self.header(name)
+            .map(|value| {
+                value.trim().parse().map_err(|_| {
+                    HttpieError::Parse(format!("Invalid value '{value}' for header '{name}'"))
+                })
+            })
+            .transpose()
+    }
+
+    /// `Content-Length` 响应头
+    pub fn content_length(&self) -> Result<Option<u64>> {
+        self.header_as("Content-Length")
     }
 }
 
+#[cfg(feature = "scripting")]
 impl Default for ScriptEngine {
     fn default() -> Self {
         Self::new().expect("Failed to create script engine")
@@ -0,0 +1,145 @@
+//! 密钥模块
+//!
+//! 环境文件中的变量值可以写作 `{"exec": "命令"}` 或 `{"keyring": "服务/账户"}`，
+//! 在请求用到该变量时才执行命令或读取系统钥匙串，密钥本身不写入环境文件。
+//! 钥匙串通过系统工具读取：macOS 使用 `security`，Linux 使用 `secret-tool`。
+//!
+//! 自动查找到的环境文件可能来自不受信任的仓库，`exec` 默认不允许，
+//! 需要显式开启（命令行 `--allow-exec`）。
+
+use crate::error::{HttpieError, Result};
+use serde_json::Value;
+use std::process::Command;
+
+/// 密钥来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretSource {
+    /// 执行shell命令，取标准输出（去除首尾空白）
+    Exec(String),
+    /// 系统钥匙串中的条目
+    Keyring { service: String, account: String },
+}
+
+impl SecretSource {
+    /// 从环境文件中的变量值解析，不是密钥对象时返回 None
+    pub fn from_value(value: &Value) -> Result<Option<Self>> {
+        let Some(object) = value.as_object() else {
+            return Ok(None);
+        };
+        let invalid =
+            |reason: &str| HttpieError::Parse(format!("Invalid secret {value}: {reason}"));
+
+        if let Some(command) = object.get("exec") {
+            let command = command
+                .as_str()
+                .ok_or_else(|| invalid("'exec' must be a string"))?;
+            return Ok(Some(Self::Exec(command.to_string())));
+        }
+        if let Some(entry) = object.get("keyring") {
+            let (service, account) = entry
+                .as_str()
+                .and_then(|entry| entry.split_once('/'))
+                .filter(|(service, account)| !service.is_empty() && !account.is_empty())
+                .ok_or_else(|| invalid("'keyring' must be 'service/account'"))?;
+            return Ok(Some(Self::Keyring {
+                service: service.to_string(),
+                account: account.to_string(),
+            }));
+        }
+        Ok(None)
+    }
+
+    /// 密钥来源的名称，与环境文件中的键一致
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Exec(_) => "exec",
+            Self::Keyring { .. } => "keyring",
+        }
+    }
+
+    /// 读取密钥值
+    pub fn resolve(&self) -> Result<String> {
+        let (program, args, description) = match self {
+            Self::Exec(command) => (SHELL, vec![SHELL_FLAG, command.as_str()], command.clone()),
+            Self::Keyring { service, account } => {
+                let (program, args) = keyring_command(service, account)?;
+                (program, args, format!("keyring entry {service}/{account}"))
+            }
+        };
+
+        let output = Command::new(program)
+            .args(&args)
+            .output()
+            .map_err(|e| HttpieError::InvalidRequest(format!("Failed to run {program}: {e}")))?;
+        if !output.status.success() {
+            return Err(HttpieError::InvalidRequest(format!(
+                "Failed to read secret from {description}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// 环境文件中允许使用的密钥来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecretPolicy {
+    /// 允许执行命令和读取钥匙串
+    AllowExec,
+    /// 只允许读取钥匙串，`exec` 报错（默认）
+    #[default]
+    KeyringOnly,
+    /// 不允许任何密钥来源，用于远程环境文件：下载的内容不能在本机执行命令或读取钥匙串
+    Deny,
+}
+
+impl SecretPolicy {
+    /// 检查 `name` 的密钥来源是否允许使用
+    pub fn check(self, name: &str, secret: &SecretSource) -> Result<()> {
+        match (self, secret) {
+            (Self::AllowExec, _) | (Self::KeyringOnly, SecretSource::Keyring { .. }) => Ok(()),
+            (Self::KeyringOnly, _) => Err(HttpieError::InvalidRequest(format!(
+                "Secret '{name}' uses 'exec', which runs a shell command and requires --allow-exec"
+            ))),
+            (Self::Deny, _) => Err(HttpieError::InvalidRequest(format!(
+                "Secret '{name}' uses '{}', which is not allowed in remote environment files",
+                secret.kind()
+            ))),
+        }
+    }
+}
+
+#[cfg(windows)]
+const SHELL: &str = "cmd";
+#[cfg(windows)]
+const SHELL_FLAG: &str = "/C";
+#[cfg(not(windows))]
+const SHELL: &str = "sh";
+#[cfg(not(windows))]
+const SHELL_FLAG: &str = "-c";
+
+#[cfg(target_os = "macos")]
+fn keyring_command<'a>(service: &'a str, account: &'a str) -> Result<(&'static str, Vec<&'a str>)> {
+    Ok((
+        "security",
+        vec!["find-generic-password", "-s", service, "-a", account, "-w"],
+    ))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn keyring_command<'a>(service: &'a str, account: &'a str) -> Result<(&'static str, Vec<&'a str>)> {
+    Ok((
+        "secret-tool",
+        vec!["lookup", "service", service, "account", account],
+    ))
+}
+
+#[cfg(not(unix))]
+fn keyring_command<'a>(
+    _service: &'a str,
+    _account: &'a str,
+) -> Result<(&'static str, Vec<&'a str>)> {
+    Err(HttpieError::InvalidRequest(
+        "keyring secrets are not supported on this platform, use 'exec' instead".to_string(),
+    ))
+}
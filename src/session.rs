@@ -0,0 +1,253 @@
+//! 会话模块
+//!
+//! 把一次运行中的Cookie和运行期变量（捕获值、脚本设置的全局变量，通常包含登录令牌）
+//! 保存为命名会话文件，后续运行可以重新加载，把多步骤流程拆到多次调用中执行。
+//! 会话文件中是明文凭据，只有当前用户可读写。
+
+use crate::environment::{create_private_dir, open_private};
+use crate::error::{HttpieError, Result};
+use crate::observer::ExecutionObserver;
+use crate::runner::RunReport;
+use crate::variable::VariableContext;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+use url::Url;
+
+/// 会话文件目录（相对当前目录）
+pub const DEFAULT_SESSION_DIR: &str = ".httpie/sessions";
+
+/// 保存的单个Cookie
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// 所属域名
+    pub domain: String,
+    pub path: String,
+    /// 未设置Domain属性时只发送给完全相同的主机
+    #[serde(default)]
+    pub host_only: bool,
+}
+
+impl Cookie {
+    /// 解析 `Set-Cookie` 响应头，`Max-Age=0` 的删除指令返回值为空的Cookie
+    fn parse(header: &str, url: &Url) -> Option<(Self, bool)> {
+        let mut parts = header.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let host = url.host_str()?.to_ascii_lowercase();
+        let mut cookie = Self {
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+            domain: host.clone(),
+            path: default_path(url),
+            host_only: true,
+        };
+        let mut expired = false;
+
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    // 只接受当前主机或其父域名
+                    if host != domain && !host.ends_with(&format!(".{domain}")) {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "max-age" => expired = value.parse::<i64>().is_ok_and(|age| age <= 0),
+                _ => {}
+            }
+        }
+        (!cookie.name.is_empty()).then_some((cookie, expired))
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return false;
+        };
+        let domain_matches = if self.host_only {
+            host == self.domain
+        } else {
+            host == self.domain || host.ends_with(&format!(".{}", self.domain))
+        };
+        let path = url.path();
+        let path_matches = path == self.path
+            || path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/'));
+        domain_matches && path_matches
+    }
+}
+
+/// 未指定Path属性时的默认路径：请求路径最后一个 `/` 之前的部分
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => url.path()[..index].to_string(),
+    }
+}
+
+/// 可保存的Cookie存储，作为reqwest的Cookie提供者使用
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+impl CookieJar {
+    /// 用已保存的Cookie创建存储
+    pub fn new(cookies: Vec<Cookie>) -> Self {
+        Self {
+            cookies: Mutex::new(cookies),
+        }
+    }
+
+    /// 当前所有Cookie的副本
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Cookie>> {
+        self.cookies.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let mut cookies = self.lock();
+        for header in cookie_headers {
+            let Some((cookie, expired)) = header
+                .to_str()
+                .ok()
+                .and_then(|header| Cookie::parse(header, url))
+            else {
+                continue;
+            };
+            cookies.retain(|existing| {
+                existing.name != cookie.name
+                    || existing.domain != cookie.domain
+                    || existing.path != cookie.path
+            });
+            if !expired {
+                cookies.push(cookie);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let header = self
+            .lock()
+            .iter()
+            .filter(|cookie| cookie.matches(url))
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        (!header.is_empty())
+            .then(|| HeaderValue::from_str(&header).ok())
+            .flatten()
+    }
+}
+
+/// 命名会话
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// 运行期变量（捕获值和脚本全局变量）
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+}
+
+impl Session {
+    /// 从运行结束时的变量上下文和Cookie存储生成会话
+    pub fn capture(variables: &VariableContext, cookies: &CookieJar) -> Self {
+        Self {
+            variables: variables.snapshot().into_iter().collect(),
+            cookies: cookies.cookies(),
+        }
+    }
+
+    /// 把会话中的变量写入变量上下文，返回其Cookie存储
+    pub fn restore(self, variables: &VariableContext) -> Arc<CookieJar> {
+        for (name, value) in self.variables {
+            variables.set(name, value);
+        }
+        Arc::new(CookieJar::new(self.cookies))
+    }
+
+    /// 会话文件路径，名称只能包含字母、数字、`-` 和 `_`
+    pub fn path(dir: &Path, name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(HttpieError::InvalidRequest(format!(
+                "Invalid session name '{name}': use letters, digits, '-' and '_'"
+            )));
+        }
+        Ok(dir.join(format!("{name}.json")))
+    }
+
+    /// 加载命名会话
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let path = Self::path(dir, name)?;
+        let content = fs::read_to_string(&path)
+            .map_err(|_| HttpieError::FileNotFound(path.display().to_string()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 保存为命名会话，返回文件路径
+    ///
+    /// 会话文件包含Cookie和登录令牌等凭据，文件和新建的目录仅当前用户可访问。
+    pub fn save(&self, dir: &Path, name: &str) -> Result<PathBuf> {
+        let path = Self::path(dir, name)?;
+        create_private_dir(dir)?;
+        open_private(&path, fs::OpenOptions::new().write(true).truncate(true))?
+            .write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(path)
+    }
+}
+
+/// 运行结束时把变量和Cookie保存为命名会话的观察者
+pub struct SessionRecorder {
+    dir: PathBuf,
+    name: String,
+    variables: VariableContext,
+    cookies: Arc<CookieJar>,
+}
+
+impl SessionRecorder {
+    /// `variables` 和 `cookies` 应与执行请求的客户端共用
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        name: impl Into<String>,
+        variables: VariableContext,
+        cookies: Arc<CookieJar>,
+    ) -> Self {
+        Self {
+            dir: dir.into(),
+            name: name.into(),
+            variables,
+            cookies,
+        }
+    }
+}
+
+impl ExecutionObserver for SessionRecorder {
+    fn on_run_complete(&mut self, _report: &RunReport) {
+        match Session::capture(&self.variables, &self.cookies).save(&self.dir, &self.name) {
+            Ok(path) => info!("Saved session '{}' to {}", self.name, path.display()),
+            Err(e) => warn!("Failed to save session '{}': {}", self.name, e),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! TLS连接信息模块
+//!
+//! reqwest（native-tls后端）只提供对端的叶子证书（DER编码），这里从中解析证书有效期，
+//! 供脚本和 `--verbose` 输出使用。协商的TLS协议版本和密码套件后端不提供，因此不包含。
+
+use serde::Serialize;
+
+/// 对端证书的有效期（ISO 8601格式，UTC）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    pub not_before: String,
+    pub not_after: String,
+}
+
+/// 一次HTTPS请求的TLS信息
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TlsDetails {
+    /// 对端证书，无法解析时为None
+    pub cert: Option<CertificateInfo>,
+}
+
+impl TlsDetails {
+    /// 从reqwest的TLS扩展中读取（需要客户端启用 `tls_info`），非TLS连接返回None
+    pub fn from_tls_info(info: Option<&reqwest::tls::TlsInfo>) -> Option<Self> {
+        info.map(|info| Self {
+            cert: info.peer_certificate().and_then(parse_certificate),
+        })
+    }
+}
+
+/// 解析DER编码的X.509证书中的有效期
+///
+/// 只读取 `tbsCertificate` 的前几个字段：版本、序列号、签名算法、颁发者，然后是有效期。
+pub fn parse_certificate(der: &[u8]) -> Option<CertificateInfo> {
+    let (tag, certificate, _) = read_tlv(der)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    let (tag, tbs, _) = read_tlv(certificate)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+
+    let mut rest = tbs;
+    // 可选的显式版本字段 [0]
+    if rest.first() == Some(&VERSION_TAG) {
+        rest = read_tlv(rest)?.2;
+    }
+    // 序列号、签名算法、颁发者
+    for _ in 0..3 {
+        rest = read_tlv(rest)?.2;
+    }
+
+    let (tag, validity, _) = read_tlv(rest)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    let (before_tag, not_before, rest) = read_tlv(validity)?;
+    let (after_tag, not_after, _) = read_tlv(rest)?;
+    Some(CertificateInfo {
+        not_before: parse_time(before_tag, not_before)?,
+        not_after: parse_time(after_tag, not_after)?,
+    })
+}
+
+const SEQUENCE: u8 = 0x30;
+const VERSION_TAG: u8 = 0xa0;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+
+/// 读取一个DER元素，返回标签、内容和剩余的字节
+fn read_tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, mut input) = input.split_first()?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || input.len() < count {
+            return None;
+        }
+        let (bytes, rest) = input.split_at(count);
+        input = rest;
+        bytes
+            .iter()
+            .fold(0usize, |len, &byte| (len << 8) | byte as usize)
+    };
+    if input.len() < len {
+        return None;
+    }
+    let (content, rest) = input.split_at(len);
+    Some((tag, content, rest))
+}
+
+/// 把 `UTCTime`（YYMMDDHHMMSSZ）或 `GeneralizedTime`（YYYYMMDDHHMMSSZ）转换为ISO 8601
+fn parse_time(tag: u8, value: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(value).ok()?;
+    let digits = text.strip_suffix('Z')?;
+    if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let (year, rest) = match (tag, digits.len()) {
+        (UTC_TIME, 12) => {
+            // RFC 5280：两位年份50及以上表示19xx
+            let year: u32 = digits[..2].parse().ok()?;
+            let century = if year >= 50 { 1900 } else { 2000 };
+            (century + year, &digits[2..])
+        }
+        (GENERALIZED_TIME, 14) => (digits[..4].parse().ok()?, &digits[4..]),
+        _ => return None,
+    };
+    Some(format!(
+        "{year:04}-{}-{}T{}:{}:{}Z",
+        &rest[0..2],
+        &rest[2..4],
+        &rest[4..6],
+        &rest[6..8],
+        &rest[8..10]
+    ))
+}
@@ -0,0 +1,113 @@
+//! 分布式追踪模块
+//!
+//! 为每个请求生成追踪上下文，按需注入W3C `traceparent` 或B3请求头，
+//! 模板中可以用 `{{$traceId}}`、`{{$spanId}}` 引用同一组ID，
+//! 便于把测试流量与Jaeger/Tempo中的后端追踪关联起来。
+
+use crate::error::{HttpieError, Result};
+use crate::models::Headers;
+use rand::Rng;
+use std::str::FromStr;
+
+/// W3C Trace Context 请求头
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+/// B3 多请求头格式
+pub const B3_TRACE_ID_HEADER: &str = "X-B3-TraceId";
+pub const B3_SPAN_ID_HEADER: &str = "X-B3-SpanId";
+pub const B3_SAMPLED_HEADER: &str = "X-B3-Sampled";
+/// 请求ID请求头，值与 `{{$idempotencyKey}}` 相同
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// 一个请求的追踪上下文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// 32位十六进制追踪ID
+    pub trace_id: String,
+    /// 16位十六进制span ID
+    pub span_id: String,
+}
+
+impl TraceContext {
+    /// 生成随机的追踪上下文
+    pub fn generate() -> Self {
+        Self::generate_with(&mut rand::rng())
+    }
+
+    /// 使用指定的随机数生成器生成追踪上下文
+    pub fn generate_with(rng: &mut impl Rng) -> Self {
+        Self {
+            trace_id: format!("{:032x}", rng.random_range(1..=u128::MAX)),
+            span_id: format!("{:016x}", rng.random_range(1..=u64::MAX)),
+        }
+    }
+
+    /// `traceparent` 请求头的值（版本00，已采样）
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id)
+    }
+}
+
+/// 注入的追踪请求头格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// W3C `traceparent`
+    W3c,
+    /// B3 `X-B3-TraceId`、`X-B3-SpanId`、`X-B3-Sampled`
+    B3,
+}
+
+impl FromStr for TraceFormat {
+    type Err = HttpieError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "w3c" => Ok(Self::W3c),
+            "b3" => Ok(Self::B3),
+            other => Err(HttpieError::Parse(format!(
+                "Invalid trace format '{other}', expected w3c or b3"
+            ))),
+        }
+    }
+}
+
+impl TraceFormat {
+    /// 把追踪请求头加入请求，已声明的同名请求头（不区分大小写）保持不变
+    pub fn inject(self, headers: &mut Headers, context: &TraceContext) {
+        let values = match self {
+            Self::W3c => vec![(TRACEPARENT_HEADER, context.traceparent())],
+            Self::B3 => vec![
+                (B3_TRACE_ID_HEADER, context.trace_id.clone()),
+                (B3_SPAN_ID_HEADER, context.span_id.clone()),
+                (B3_SAMPLED_HEADER, "1".to_string()),
+            ],
+        };
+        for (name, value) in values {
+            if !headers.keys().any(|key| key.eq_ignore_ascii_case(name)) {
+                headers.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+/// 添加 `X-Request-Id` 请求头，已声明时（不区分大小写）保持不变
+pub fn inject_request_id(headers: &mut Headers, request_id: &str) {
+    if !headers
+        .keys()
+        .any(|key| key.eq_ignore_ascii_case(REQUEST_ID_HEADER))
+    {
+        headers.insert(REQUEST_ID_HEADER.to_string(), request_id.to_string());
+    }
+}
+
+/// 从请求头中读取追踪ID（`traceparent` 优先，其次是B3）
+pub fn trace_id(headers: &Headers) -> Option<&str> {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+    header(TRACEPARENT_HEADER)
+        .and_then(|value| value.split('-').nth(1))
+        .or_else(|| header(B3_TRACE_ID_HEADER))
+}
@@ -0,0 +1,248 @@
+//! HTTP传输层模块
+//!
+//! [`HttpClient`](crate::HttpClient) 和 [`Runner`](crate::Runner) 通过 [`HttpTransport`]
+//! 发送请求，默认实现 [`ReqwestTransport`] 基于reqwest。替换为其他HTTP库或测试替身时，
+//! 脚本、断言、捕获和报告等处理流程保持不变。[`MockTransport`] 按方法和URL返回预设的
+//! 响应，嵌入执行器的项目可以不启动任何服务器测试自己的.http用例。
+
+use crate::client::{self, HttpProtocol, RawResponse, TransportConfig};
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use crate::raw;
+use crate::script::ResponseObject;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Method, Version};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// 发送HTTP请求的后端
+///
+/// 传入的请求已替换运行期变量；实现负责发送请求并读取（按需截断）响应体，
+/// 解压、脚本和断言由调用方处理。
+pub trait HttpTransport: Send + Sync {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<RawResponse>>;
+}
+
+/// 按请求合并后的配置缓存的reqwest客户端，克隆后共用
+pub(crate) type ClientCache = Arc<Mutex<HashMap<ClientKey, Client>>>;
+
+/// 合并配置中可能与基础配置不同的部分（见 [`TransportConfig::for_request`]）
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ClientKey {
+    protocol: HttpProtocol,
+    insecure: bool,
+    ca_cert: Option<PathBuf>,
+    dns_overrides: BTreeMap<String, SocketAddr>,
+}
+
+impl ClientKey {
+    fn of(config: &TransportConfig) -> Self {
+        Self {
+            protocol: config.protocol,
+            insecure: config.insecure,
+            ca_cert: config.ca_cert.clone(),
+            dns_overrides: config
+                .dns_overrides
+                .iter()
+                .map(|(host, addr)| (host.clone(), *addr))
+                .collect(),
+        }
+    }
+}
+
+/// 基于reqwest的默认传输层
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+    config: TransportConfig,
+    clients: ClientCache,
+}
+
+impl ReqwestTransport {
+    /// 按传输层配置构建reqwest客户端
+    pub fn new(config: TransportConfig) -> Result<Self> {
+        Ok(Self::from_parts(config.build()?, config))
+    }
+
+    /// 使用已构建的客户端（配置需与构建客户端时一致）
+    pub fn from_parts(client: Client, config: TransportConfig) -> Self {
+        Self {
+            client,
+            config,
+            clients: ClientCache::default(),
+        }
+    }
+
+    /// 与其他基于同一配置的传输层共用按请求配置构建的客户端
+    pub(crate) fn with_client_cache(mut self, clients: ClientCache) -> Self {
+        self.clients = clients;
+        self
+    }
+
+    /// 合并配置对应的客户端，配置相同的请求共用一个客户端以复用连接（包括QUIC连接）
+    ///
+    /// 合并配置复制自基础配置，Cookie存储与基础客户端是同一个。
+    fn client_for(&self, config: &TransportConfig) -> Result<Client> {
+        let mut clients = self.clients.lock().unwrap();
+        let key = ClientKey::of(config);
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let client = config.build()?;
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// 底层的reqwest客户端
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn config(&self) -> &TransportConfig {
+        &self.config
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<RawResponse>> {
+        Box::pin(async move {
+            // 带 `@resolve`、`@http3` 指令或主机TLS设置的请求使用合并后的配置
+            let merged = self.config.for_request(request)?;
+            let config = merged.as_ref().unwrap_or(&self.config);
+            // 原始TCP请求不经过reqwest，直接按配置建立连接
+            if let Some(connection) = &request.raw {
+                return raw::send(request, connection, config).await;
+            }
+            let client = match &merged {
+                Some(config) => self.client_for(config)?,
+                None => self.client.clone(),
+            };
+            client::fetch(&client, request, config).await
+        })
+    }
+}
+
+/// 发送请求，取消令牌触发时中止并返回 [`HttpieError::Cancelled`]
+pub async fn send_with_cancellation(
+    transport: &dyn HttpTransport,
+    request: &HttpRequest,
+    token: &CancellationToken,
+) -> Result<RawResponse> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(HttpieError::Cancelled),
+        result = transport.send(request) => result,
+    }
+}
+
+/// 预设响应的内存传输层，不建立任何连接
+///
+/// URL以 `/` 开头时只比较路径（含查询参数时连同查询参数），否则比较完整URL；
+/// 按添加顺序取第一个匹配的响应，没有匹配时请求失败。
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    routes: Vec<(Method, String, ResponseObject)>,
+    received: Mutex<Vec<HttpRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 为方法和URL预设响应
+    pub fn with_response(
+        mut self,
+        method: Method,
+        url: impl Into<String>,
+        response: ResponseObject,
+    ) -> Self {
+        self.routes.push((method, url.into(), response));
+        self
+    }
+
+    /// 已收到的请求（已替换运行期变量），按发送顺序排列
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.received.lock().unwrap().clone()
+    }
+
+    fn find(&self, request: &HttpRequest) -> Result<&ResponseObject> {
+        let url = request.target_url()?;
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        self.routes
+            .iter()
+            .find(|(method, pattern, _)| {
+                let matches = if !pattern.starts_with('/') {
+                    pattern == url.as_str() || pattern == &request.url
+                } else if pattern.contains('?') {
+                    *pattern == path
+                } else {
+                    pattern == url.path()
+                };
+                *method == request.method && matches
+            })
+            .map(|(_, _, response)| response)
+            .ok_or_else(|| {
+                HttpieError::RequestFailed(
+                    request.name.clone(),
+                    format!("no mock response for {} {url}", request.method),
+                )
+            })
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<RawResponse>> {
+        Box::pin(async move {
+            self.received.lock().unwrap().push(request.clone());
+            let response = self.find(request)?;
+            Ok(raw_response(response))
+        })
+    }
+}
+
+/// 把预设的响应对象转换为原始响应（JSON响应体重新序列化）
+fn raw_response(response: &ResponseObject) -> RawResponse {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &response.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    if !response.content_type.is_empty()
+        && !headers.contains_key("content-type")
+        && let Ok(value) = HeaderValue::from_str(&response.content_type)
+    {
+        headers.insert("content-type", value);
+    }
+
+    let bytes = match &response.body {
+        Value::Null => Bytes::new(),
+        Value::String(text) => Bytes::from(text.clone()),
+        body => Bytes::from(body.to_string()),
+    };
+    RawResponse {
+        status: response.status,
+        version: Version::HTTP_11,
+        headers,
+        bytes,
+        duration: Duration::ZERO,
+        truncated: false,
+        remote_addr: None,
+        tls: None,
+        redirects: Vec::new(),
+    }
+}
@@ -0,0 +1,49 @@
+//! 文件请求体模块
+//!
+//! 请求体可以写成单独一行 `< ./file`，发送时才读取文件（相对路径基于.http文件所在目录）。
+//! 超过阈值的文件以已知长度流式上传，不整体读入内存。
+
+use crate::compress::Compression;
+use crate::error::{HttpieError, Result};
+use crate::models::HttpRequest;
+use std::fs;
+use std::path::PathBuf;
+
+/// 超过该大小（字节）的文件请求体流式上传
+pub const STREAM_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+/// 识别文件请求体引用（单独一行的 `< path`），返回路径
+pub fn body_file_reference(body: &str) -> Option<&str> {
+    let body = body.trim();
+    if body.contains('\n') {
+        return None;
+    }
+    let path = body.strip_prefix("< ")?.trim();
+    (!path.is_empty()).then_some(path)
+}
+
+/// 文件请求体的实际路径和长度
+pub fn body_file(request: &HttpRequest) -> Result<Option<(PathBuf, u64)>> {
+    let Some(path) = &request.body_file else {
+        return Ok(None);
+    };
+    let path = request.resolve_path(path);
+    let metadata =
+        fs::metadata(&path).map_err(|_| HttpieError::FileNotFound(path.display().to_string()))?;
+    Ok(Some((path, metadata.len())))
+}
+
+/// 需要流式上传的文件及其长度：超过阈值且没有 `@compress` 指令
+pub fn streamed_file(request: &HttpRequest) -> Result<Option<(PathBuf, u64)>> {
+    if Compression::from_request(request)?.is_some() {
+        return Ok(None);
+    }
+    Ok(body_file(request)?.filter(|(_, len)| *len > STREAM_THRESHOLD))
+}
+
+/// 读取整个文件请求体
+pub fn read_body_file(request: &HttpRequest) -> Result<Option<Vec<u8>>> {
+    body_file(request)?
+        .map(|(path, _)| fs::read(path).map_err(HttpieError::from))
+        .transpose()
+}
@@ -1,22 +1,175 @@
 //! 变量替换模块
 //!
 //! 处理HTTP请求中的各种变量替换，包括动态变量、环境变量和用户自定义变量。
+//!
+//! `{{name}}` 的查找顺序：命令行 `--var` > 文件内 `@var` > 环境文件 > `.env`；
+//! 进程环境变量通过 `{{$env NAME}}`、`{{$processEnv NAME}}` 或 `$processEnv.NAME`
+//! 引用，同名的 `.env` 变量优先。动态变量的取值来自 [`DynamicValues`]，设置种子和冻结时间后
+//! 每次运行结果相同。
 
-use crate::models::Environment;
-use rand::Rng;
+use crate::models::{Environment, HttpRequest};
+use crate::trace::TraceContext;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
-use uuid::Uuid;
+use uuid::Builder;
+
+/// 模板形式的进程环境变量：`{{$env NAME}}` 或 `{{$processEnv NAME}}`
+static PROCESS_ENV_TEMPLATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*\$(?:env|processEnv)\s+([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap()
+});
+
+/// 模板形式的动态变量（如 `{{$uuid}}`），替换时连同花括号一起替换
+static DYNAMIC_TEMPLATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*(\$(?:uuid|timestamp|randomInt|traceId|spanId|idempotencyKey))\s*\}\}")
+        .unwrap()
+});
+
+/// 用户变量的引用：`{{name}}`，花括号内的文本必须与变量名完全一致
+static USER_TEMPLATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\{([^{}]+)\}\}").unwrap());
+
+/// 单次扫描替换文本中的 `{{name}}`，`lookup` 找不到的变量保持原样
+///
+/// 替换结果不再扫描，也与变量的遍历顺序无关。
+pub(crate) fn replace_templates<F>(text: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    USER_TEMPLATE
+        .replace_all(text, |captures: &regex::Captures| {
+            lookup(&captures[1]).unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+/// 文本中以 `{{name}}` 引用的变量名
+pub(crate) fn template_names(text: &str) -> impl Iterator<Item = &str> {
+    USER_TEMPLATE
+        .captures_iter(text)
+        .filter_map(|captures| captures.get(1).map(|name| name.as_str()))
+}
+
+/// 看起来像密钥的变量名片段，展示时隐藏其值
+const SECRET_NAME_PARTS: &[&str] = &[
+    "secret",
+    "token",
+    "password",
+    "passwd",
+    "key",
+    "auth",
+    "credential",
+];
+
+/// 变量名是否像密钥（不区分大小写）
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SECRET_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// 动态变量（`$uuid`、`$randomInt`、`$timestamp`）、追踪ID和请求ID的取值来源
+///
+/// 默认使用系统随机数和当前时间。设置种子后按解析顺序从同一个随机数生成器取值，
+/// 冻结时间后 `$timestamp` 固定，录制、快照和比对结果在多次运行间保持一致。
+/// 克隆后共享同一个随机数生成器。
+#[derive(Debug, Clone, Default)]
+pub struct DynamicValues {
+    rng: Option<Arc<Mutex<StdRng>>>,
+    frozen_time: Option<u64>,
+}
+
+impl DynamicValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用固定种子的随机数生成器
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Some(Arc::new(Mutex::new(StdRng::seed_from_u64(seed))));
+        self
+    }
+
+    /// 冻结 `$timestamp` 的值（Unix秒）
+    pub fn with_frozen_time(mut self, timestamp: u64) -> Self {
+        self.frozen_time = Some(timestamp);
+        self
+    }
+
+    /// 随机UUID（v4）
+    pub fn uuid(&self) -> String {
+        let bytes = self.with_rng(|rng| rng.random());
+        Builder::from_random_bytes(bytes).into_uuid().to_string()
+    }
+
+    /// 1到1000000之间的随机整数
+    pub fn random_int(&self) -> u32 {
+        self.with_rng(|rng| rng.random_range(1..=1000000))
+    }
+
+    /// 当前的Unix时间戳（秒），冻结时返回固定值
+    pub fn timestamp(&self) -> u64 {
+        self.frozen_time.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        })
+    }
+
+    /// 随机的追踪上下文
+    pub fn trace(&self) -> TraceContext {
+        self.with_rng(TraceContext::generate_with)
+    }
+
+    fn with_rng<T>(&self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        match &self.rng {
+            Some(rng) => f(&mut rng.lock().unwrap_or_else(|e| e.into_inner())),
+            None => f(&mut StdRng::from_rng(&mut rand::rng())),
+        }
+    }
+}
 
 /// 变量替换器
 #[derive(Debug)]
 pub struct VariableReplacer<'a> {
     environment: &'a Environment,
+    /// 动态变量的取值来源
+    values: DynamicValues,
+    /// `$traceId` 和 `$spanId` 使用的追踪上下文
+    trace: Option<TraceContext>,
+    /// `$idempotencyKey` 使用的请求ID
+    request_id: Option<String>,
 }
 
 impl<'a> VariableReplacer<'a> {
     /// 创建新的变量替换器
     pub fn new(environment: &'a Environment) -> Self {
-        Self { environment }
+        Self {
+            environment,
+            values: DynamicValues::default(),
+            trace: None,
+            request_id: None,
+        }
+    }
+
+    /// 设置动态变量的取值来源（种子、冻结时间）
+    pub fn with_dynamic_values(mut self, values: DynamicValues) -> Self {
+        self.values = values;
+        self
+    }
+
+    /// 设置追踪上下文，同一请求中的 `$traceId`、`$spanId` 取相同的值
+    pub fn with_trace(mut self, trace: TraceContext) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// 设置请求ID，同一请求中的 `$idempotencyKey` 取相同的值
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
     }
 
     /// 替换文本中的所有变量
@@ -35,38 +188,54 @@ impl<'a> VariableReplacer<'a> {
         result
     }
 
-    /// 替换动态变量（$uuid, $timestamp, $randomInt）
+    /// 替换动态变量（$uuid, $timestamp, $randomInt, $traceId, $spanId, $idempotencyKey）
     fn replace_dynamic_variables(&self, text: &str) -> String {
-        let mut result = text.to_string();
+        let mut result = DYNAMIC_TEMPLATE.replace_all(text, "$1").into_owned();
+
+        if let Some(trace) = &self.trace {
+            result = result
+                .replace("$traceId", &trace.trace_id)
+                .replace("$spanId", &trace.span_id);
+        }
+
+        if result.contains("$idempotencyKey") {
+            let key = match &self.request_id {
+                Some(request_id) => request_id.clone(),
+                None => self.values.uuid(),
+            };
+            result = result.replace("$idempotencyKey", &key);
+        }
 
         // 替换 $uuid
         if result.contains("$uuid") {
-            let uuid = Uuid::new_v4().to_string();
+            let uuid = self.values.uuid();
             result = result.replace("$uuid", &uuid);
         }
 
         // 替换 $timestamp
         if result.contains("$timestamp") {
-            let timestamp = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs()
-                .to_string();
+            let timestamp = self.values.timestamp().to_string();
             result = result.replace("$timestamp", &timestamp);
         }
 
         // 替换 $randomInt
         if result.contains("$randomInt") {
-            let random_int = rand::rng().random_range(1..=1000000).to_string();
+            let random_int = self.values.random_int().to_string();
             result = result.replace("$randomInt", &random_int);
         }
 
         result
     }
 
-    /// 替换环境变量（$processEnv.VARIABLE_NAME）
+    /// 替换环境变量（{{$env NAME}}、{{$processEnv NAME}} 和 $processEnv.NAME）
     fn replace_env_variables(&self, text: &str) -> String {
-        let mut result = text.to_string();
+        let mut result = PROCESS_ENV_TEMPLATE
+            .replace_all(text, |captures: &regex::Captures| {
+                self.environment
+                    .process_env(&captures[1])
+                    .unwrap_or_default()
+            })
+            .into_owned();
 
         // 查找所有 $processEnv. 模式
         while let Some(start) = result.find("$processEnv.") {
@@ -77,7 +246,7 @@ impl<'a> VariableReplacer<'a> {
                 .unwrap_or(result.len());
 
             let var_name = &result[var_start..var_end];
-            let replacement = std::env::var(var_name).unwrap_or_default();
+            let replacement = self.environment.process_env(var_name).unwrap_or_default();
 
             result.replace_range(start..var_end, &replacement);
         }
@@ -85,28 +254,85 @@ impl<'a> VariableReplacer<'a> {
         result
     }
 
-    /// 替换用户自定义变量（{{variable_name}}）
+    /// 替换用户自定义变量（{{variable_name}}），变量值中引用的变量同样替换
     fn replace_user_variables(&self, text: &str) -> String {
         let mut result = text.to_string();
-        let mut changed = true;
-        let mut iterations = 0;
         const MAX_ITERATIONS: usize = 100; // 防止无限递归
 
         // 持续替换直到没有更多变化或达到最大迭代次数
-        while changed && iterations < MAX_ITERATIONS {
-            changed = false;
-            iterations += 1;
-
-            for (key, value) in self.environment.variables() {
-                let pattern = format!("{{{{{}}}}}", key);
-                let new_result = result.replace(&pattern, value);
-                if new_result != result {
-                    result = new_result;
-                    changed = true;
-                }
+        for _ in 0..MAX_ITERATIONS {
+            let replaced =
+                replace_templates(&result, |name| self.environment.lookup(name).cloned());
+            if replaced == result {
+                break;
             }
+            result = replaced;
         }
 
         result
     }
 }
+
+/// 运行期变量上下文
+///
+/// 保存捕获值和脚本设置的全局变量，在发送请求前替换其中的 `{{name}}`。
+/// 克隆后共享同一份数据。
+#[derive(Debug, Clone, Default)]
+pub struct VariableContext {
+    values: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl VariableContext {
+    /// 创建空的变量上下文
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取变量值
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.lock().get(name).cloned()
+    }
+
+    /// 设置变量值
+    pub fn set(&self, name: impl Into<String>, value: impl Into<String>) {
+        self.lock().insert(name.into(), value.into());
+    }
+
+    /// 获取所有变量的副本
+    pub fn snapshot(&self) -> HashMap<String, String> {
+        self.lock().clone()
+    }
+
+    /// 替换文本中已知的 `{{name}}`，未知变量保持原样；替换进来的值不再展开
+    pub fn replace(&self, text: &str) -> String {
+        let values = self.lock();
+        replace_templates(text, |name| values.get(name).cloned())
+    }
+
+    /// 替换请求URL、查询和路径参数、请求头、请求体和内联断言中的变量
+    pub fn resolve(&self, request: &HttpRequest) -> HttpRequest {
+        let mut resolved = request.clone();
+        if self.lock().is_empty() {
+            return resolved;
+        }
+
+        resolved.url = self.replace(&request.url);
+        for (_, value) in resolved.query.iter_mut().chain(&mut resolved.path_params) {
+            *value = self.replace(value);
+        }
+        for value in resolved.headers.values_mut() {
+            *value = self.replace(value);
+        }
+        if let Some(body) = &mut resolved.body {
+            *body = self.replace(body);
+        }
+        for assertion in &mut resolved.assertions {
+            *assertion = self.replace(assertion);
+        }
+        resolved
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        self.values.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -2,11 +2,50 @@
 //!
 //! 包含所有模块的单元测试和集成测试
 
+pub mod test_adhoc;
+pub mod test_aggregate;
+pub mod test_archive;
+pub mod test_assertion;
+pub mod test_bench;
+pub mod test_binary;
+pub mod test_blocking;
+pub mod test_capture;
 pub mod test_client;
+pub mod test_compat;
+pub mod test_compress;
+pub mod test_condition;
+pub mod test_config;
+pub mod test_cookie;
+pub mod test_curl;
+pub mod test_environment;
 pub mod test_error;
+pub mod test_expect;
+pub mod test_format;
+pub mod test_grpc;
+pub mod test_handler;
+pub mod test_history;
+pub mod test_hooks;
+pub mod test_jsonpath;
+pub mod test_listen;
+pub mod test_metrics;
 pub mod test_models;
+pub mod test_modules;
+pub mod test_monitor;
+pub mod test_netrc;
+pub mod test_notify;
+pub mod test_output;
+pub mod test_pager;
 pub mod test_parser;
+pub mod test_progress;
+pub mod test_raw;
+pub mod test_runner;
 pub mod test_script;
+pub mod test_secret;
+pub mod test_session;
+pub mod test_tls;
+pub mod test_trace;
+pub mod test_transport;
+pub mod test_upload;
 pub mod test_variable;
 
 // 测试辅助函数和常量
@@ -0,0 +1,103 @@
+//! adhoc模块的单元测试
+
+use httpie::adhoc::{RequestItem, from_args};
+use reqwest::Method;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_request_items() {
+        assert_eq!(
+            RequestItem::parse("Authorization:Bearer x").unwrap(),
+            RequestItem::Header("Authorization".to_string(), "Bearer x".to_string())
+        );
+        assert_eq!(
+            RequestItem::parse("page==2").unwrap(),
+            RequestItem::Query("page".to_string(), "2".to_string())
+        );
+        assert_eq!(
+            RequestItem::parse("name=test").unwrap(),
+            RequestItem::Field("name".to_string(), "test".to_string())
+        );
+        assert_eq!(
+            RequestItem::parse("tags:=[1, 2]").unwrap(),
+            RequestItem::JsonField("tags".to_string(), json!([1, 2]))
+        );
+
+        // 最先出现的分隔符决定类型
+        assert_eq!(
+            RequestItem::parse("url=http://example.com").unwrap(),
+            RequestItem::Field("url".to_string(), "http://example.com".to_string())
+        );
+        assert_eq!(
+            RequestItem::parse("X-Filter:a=b").unwrap(),
+            RequestItem::Header("X-Filter".to_string(), "a=b".to_string())
+        );
+
+        assert!(RequestItem::parse("bogus").is_err());
+        assert!(RequestItem::parse("=value").is_err());
+        assert!(RequestItem::parse("count:=not json").is_err());
+    }
+
+    #[test]
+    fn test_from_args() {
+        let request = from_args(
+            "post",
+            "https://api.example.com/users",
+            &items(&[
+                "Authorization:Bearer x",
+                "name=test",
+                "admin:=true",
+                "dry_run==1",
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.name, "POST https://api.example.com/users");
+        assert_eq!(request.headers["Authorization"], "Bearer x");
+        assert_eq!(request.headers["Content-Type"], "application/json");
+        assert_eq!(
+            request.query,
+            vec![("dry_run".to_string(), "1".to_string())]
+        );
+        let body: serde_json::Value =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body, json!({"name": "test", "admin": true}));
+    }
+
+    #[test]
+    fn test_from_args_without_fields() {
+        let request = from_args("get", "example.com/users", &items(&["page==2"])).unwrap();
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.url, "http://example.com/users");
+        assert!(request.body.is_none());
+        assert!(request.headers.is_empty());
+
+        // 已指定的Content-Type保持不变
+        let request = from_args(
+            "put",
+            "https://example.com",
+            &items(&["content-type:application/vnd.api+json", "a=1"]),
+        )
+        .unwrap();
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(request.headers["content-type"], "application/vnd.api+json");
+    }
+
+    #[test]
+    fn test_from_args_localhost_shorthand() {
+        let url = |url: &str| from_args("get", url, &[]).unwrap().url;
+        assert_eq!(url(":3000/api"), "http://localhost:3000/api");
+        assert_eq!(url(":/api"), "http://localhost/api");
+        assert_eq!(url(":"), "http://localhost");
+        assert_eq!(url("https://example.com"), "https://example.com");
+    }
+}
@@ -0,0 +1,145 @@
+//! aggregate模块的单元测试
+
+use httpie::AggregateAssertion;
+use httpie::BenchReport;
+use httpie::aggregate::{Metric, check_all, has_aggregate};
+use httpie::assertion::Operator;
+use httpie::bench::Sample;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(samples: &[(u64, Option<u16>)]) -> BenchReport {
+        let samples = samples
+            .iter()
+            .map(|&(millis, status)| Sample {
+                latency: Duration::from_millis(millis),
+                status,
+            })
+            .collect();
+        BenchReport::new("search", samples, Duration::from_secs(1))
+    }
+
+    #[test]
+    fn test_parse_aggregate_assertions() {
+        let assertion = AggregateAssertion::parse("?? p95 < 300ms")
+            .unwrap()
+            .unwrap();
+        assert_eq!(assertion.metric, Metric::Percentile(95.0));
+        assert_eq!(assertion.operator, Operator::Lt);
+        assert_eq!(assertion.expected, 300.0);
+        assert_eq!(assertion.source, "p95 < 300ms");
+
+        let assertion = AggregateAssertion::parse("p99.9 <= 2s").unwrap().unwrap();
+        assert_eq!(assertion.metric, Metric::Percentile(99.9));
+        assert_eq!(assertion.expected, 2000.0);
+
+        let assertion = AggregateAssertion::parse("error_rate < 1%")
+            .unwrap()
+            .unwrap();
+        assert_eq!(assertion.metric, Metric::ErrorRate);
+        assert_eq!(assertion.expected, 0.01);
+        let assertion = AggregateAssertion::parse("error_rate == 0")
+            .unwrap()
+            .unwrap();
+        assert_eq!(assertion.expected, 0.0);
+
+        assert_eq!(
+            AggregateAssertion::parse("mean < 100")
+                .unwrap()
+                .unwrap()
+                .metric,
+            Metric::Mean
+        );
+
+        // 普通断言不是聚合断言
+        assert!(
+            AggregateAssertion::parse("status == 200")
+                .unwrap()
+                .is_none()
+        );
+        assert!(AggregateAssertion::parse("p0 < 1s").unwrap().is_none());
+        assert!(AggregateAssertion::is_aggregate("?? max < 1s"));
+        assert!(!AggregateAssertion::is_aggregate("?? duration < 1s"));
+    }
+
+    #[test]
+    fn test_parse_invalid_aggregate_assertions() {
+        let error = AggregateAssertion::parse("p95 contains 300ms").unwrap_err();
+        assert!(error.to_string().contains("expected a comparison operator"));
+
+        let error = AggregateAssertion::parse("p95 <").unwrap_err();
+        assert!(error.to_string().contains("missing expected value"));
+
+        let error = AggregateAssertion::parse("p95 < fast").unwrap_err();
+        assert!(error.to_string().contains("invalid duration"));
+
+        let error = AggregateAssertion::parse("error_rate < few").unwrap_err();
+        assert!(error.to_string().contains("invalid rate"));
+    }
+
+    #[test]
+    fn test_check_aggregate_assertions() {
+        let samples = report(&[
+            (100, Some(200)),
+            (120, Some(200)),
+            (150, Some(500)),
+            (412, Some(200)),
+        ]);
+        let check = |line: &str| {
+            AggregateAssertion::parse(line)
+                .unwrap()
+                .unwrap()
+                .check(&samples)
+        };
+
+        assert!(check("p50 <= 120ms").passed);
+        assert!(check("min == 100").passed);
+        assert!(check("max >= 0.4s").passed);
+        assert!(check("error_rate <= 25%").passed);
+
+        let result = check("p95 < 300ms");
+        assert!(!result.passed);
+        assert_eq!(result.name, "p95 < 300ms");
+        assert_eq!(
+            result.message.as_deref(),
+            Some("p95 was 412.00 ms over 4 request(s)")
+        );
+
+        let result = check("error_rate < 1%");
+        assert_eq!(
+            result.message.as_deref(),
+            Some("error rate was 25.00% over 4 request(s)")
+        );
+
+        let empty = report(&[]);
+        let result = AggregateAssertion::parse("p95 < 1s")
+            .unwrap()
+            .unwrap()
+            .check(&empty);
+        assert_eq!(result.message.as_deref(), Some("no completed requests"));
+    }
+
+    #[test]
+    fn test_check_all_ignores_regular_assertions() {
+        let assertions = vec![
+            "status == 200".to_string(),
+            "mean < 1s".to_string(),
+            "p90 < {{budget}}".to_string(),
+        ];
+        assert!(has_aggregate(&assertions));
+        assert!(!has_aggregate(&assertions[..1]));
+
+        let results = check_all(&assertions, &report(&[(10, Some(200))]));
+        let outcomes: Vec<(&str, bool)> = results
+            .iter()
+            .map(|r| (r.name.as_str(), r.passed))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![("mean < 1s", true), ("p90 < {{budget}}", false)]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! archive模块的单元测试
+
+use httpie::archive::ResponseMetadata;
+use httpie::{HttpRequest, ResponseArchive, ResponseObject};
+use reqwest::Method;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_response(body: &[u8]) -> ResponseObject {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("x-request-id", HeaderValue::from_static("abc"));
+        ResponseObject::from_parts(200, &headers, body)
+    }
+
+    #[test]
+    fn test_archive_saves_body_and_metadata() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("out");
+        let mut archive = ResponseArchive::new(&out);
+        let request = HttpRequest::new(
+            "Get users".to_string(),
+            Method::GET,
+            "https://example.com/users".to_string(),
+        );
+
+        let path = archive
+            .save(
+                &request,
+                &json_response(br#"{"id":1}"#),
+                Duration::from_millis(42),
+            )
+            .unwrap();
+        assert_eq!(path, out.join("Get_users.json"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"id":1}"#);
+
+        let metadata: ResponseMetadata =
+            serde_json::from_str(&fs::read_to_string(out.join("Get_users.meta.json")).unwrap())
+                .unwrap();
+        assert_eq!(metadata.name, "Get users");
+        assert_eq!(metadata.method, "GET");
+        assert_eq!(metadata.url, "https://example.com/users");
+        assert_eq!(metadata.status, 200);
+        assert_eq!(metadata.duration_ms, 42);
+        assert_eq!(metadata.body, "Get_users.json");
+        assert_eq!(metadata.headers["x-request-id"], "abc");
+    }
+
+    #[test]
+    fn test_archive_numbers_duplicate_names() {
+        let dir = TempDir::new().unwrap();
+        let request = HttpRequest::new(
+            "Poll".to_string(),
+            Method::GET,
+            "https://example.com/jobs/1".to_string(),
+        );
+        let text = ResponseObject {
+            body: serde_json::json!("pending"),
+            content_type: "text/plain".to_string(),
+            ..Default::default()
+        };
+
+        let mut archive = ResponseArchive::new(dir.path());
+        let first = archive.save(&request, &text, Duration::ZERO).unwrap();
+        let second = archive.save(&request, &text, Duration::ZERO).unwrap();
+        assert_eq!(first, dir.path().join("Poll.txt"));
+        assert_eq!(second, dir.path().join("Poll-2.txt"));
+        assert!(dir.path().join("Poll-2.meta.json").exists());
+        assert_eq!(fs::read_to_string(second).unwrap(), "pending");
+
+        // 新的运行覆盖之前的文件
+        let mut archive = ResponseArchive::new(dir.path());
+        let again = archive.save(&request, &text, Duration::ZERO).unwrap();
+        assert_eq!(again, first);
+    }
+}
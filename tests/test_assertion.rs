@@ -0,0 +1,128 @@
+//! assertion模块的单元测试
+
+use httpie::ResponseObject;
+use httpie::assertion::{Assertion, Operator, Subject};
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_response() -> ResponseObject {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("application/json; charset=utf-8"),
+        );
+        ResponseObject::from_parts(
+            200,
+            &headers,
+            br#"{"id": 7, "name": "alice", "tags": ["admin", "ops"], "token": null}"#,
+        )
+    }
+
+    fn check(assertion: &str) -> bool {
+        Assertion::parse(assertion)
+            .unwrap()
+            .check(&json_response(), Duration::from_millis(120))
+            .passed
+    }
+
+    #[test]
+    fn test_parse_assertion() {
+        let assertion = Assertion::parse("?? header Content-Type contains json").unwrap();
+        assert_eq!(
+            assertion.subject,
+            Subject::Header("Content-Type".to_string())
+        );
+        assert_eq!(assertion.operator, Operator::Contains);
+        assert_eq!(assertion.expected.as_deref(), Some("json"));
+        assert_eq!(assertion.source, "header Content-Type contains json");
+
+        let assertion = Assertion::parse("$.id exists").unwrap();
+        assert_eq!(assertion.subject, Subject::JsonPath("$.id".to_string()));
+        assert_eq!(assertion.expected, None);
+
+        for invalid in [
+            "status",
+            "status = 200",
+            "status ==",
+            "$.id exists 1",
+            "header",
+            "cookie == 1",
+            "$..id == 1",
+        ] {
+            assert!(
+                Assertion::parse(invalid)
+                    .unwrap_err()
+                    .to_string()
+                    .contains("Invalid assertion"),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_assertions() {
+        assert!(check("status == 200"));
+        assert!(check("status < 300"));
+        assert!(!check("status != 200"));
+        assert!(check("header content-type contains json"));
+        assert!(!check("header X-Missing exists"));
+        assert!(check("$.id == 7"));
+        assert!(check("$.id >= 7"));
+        assert!(check("$.name == alice"));
+        assert!(check(r#"$.name == "alice""#));
+        assert!(check("$.tags contains admin"));
+        assert!(!check("$.tags contains root"));
+        assert!(check("$.token == null"));
+        assert!(check("$.token exists"));
+        assert!(!check("$.missing exists"));
+        assert!(check("body contains alice"));
+        assert!(check("duration < 500"));
+        assert!(!check("duration < 100"));
+        assert!(check("duration < 500ms"));
+        assert!(check("duration <= 1s"));
+        assert!(!check("duration < 0.1s"));
+        assert!(check("redirects == 0"));
+    }
+
+    #[test]
+    fn test_duration_assertion_records_latency() {
+        let assertion = Assertion::parse("?? duration < 2s").unwrap();
+        assert_eq!(assertion.expected.as_deref(), Some("2000"));
+        assert!(Assertion::parse("duration < fast").is_err());
+
+        let result = Assertion::parse("duration < 100ms")
+            .unwrap()
+            .check(&json_response(), Duration::from_millis(734));
+        assert!(!result.passed);
+        assert_eq!(result.name, "duration < 100ms");
+        assert_eq!(result.message.as_deref(), Some("request took 734 ms"));
+        assert_eq!(result.duration_ms, 734);
+
+        let result = Assertion::parse("duration < 1s")
+            .unwrap()
+            .check(&json_response(), Duration::from_millis(734));
+        assert!(result.passed);
+        assert_eq!(result.duration_ms, 734);
+    }
+
+    #[test]
+    fn test_failed_assertion_reports_actual_value() {
+        let result = Assertion::parse("$.name == bob")
+            .unwrap()
+            .check(&json_response(), Duration::ZERO);
+
+        assert!(!result.passed);
+        assert_eq!(result.name, "$.name == bob");
+        assert_eq!(result.message.as_deref(), Some("actual value was 'alice'"));
+        assert_eq!(result.error_kind.as_deref(), Some("AssertionError"));
+
+        let result = Assertion::parse("$.name > 3")
+            .unwrap()
+            .check(&json_response(), Duration::ZERO);
+        assert!(result.message.unwrap().contains("numerically"));
+    }
+}
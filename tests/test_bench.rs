@@ -0,0 +1,276 @@
+//! bench模块的单元测试
+
+use httpie::bench::{Sample, parse_duration, parse_ratio, select_request};
+use httpie::{
+    Bench, BenchLimit, BenchReport, BenchSummary, BenchThresholds, CancellationToken, CaseSelector,
+    HttpRequest,
+};
+use mockito::Server;
+use reqwest::Method;
+use std::fs;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bench_runs_fixed_iterations() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/ping")
+            .with_status(200)
+            .expect(20)
+            .create_async()
+            .await;
+
+        let report = Bench::new(create_request(&format!("{}/ping", server.url())))
+            .with_concurrency(4)
+            .with_limit(BenchLimit::Iterations(20))
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(report.total(), 20);
+        assert_eq!(report.errors, 0);
+        assert_eq!(report.status_codes.get(&200), Some(&20));
+        assert!(report.percentile(50.0) <= report.percentile(99.0));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_bench_runs_for_duration() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/ping")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let report = Bench::new(create_request(&format!("{}/ping", server.url())))
+            .with_concurrency(2)
+            .with_limit(BenchLimit::Duration(Duration::from_millis(200)))
+            .run()
+            .await
+            .unwrap();
+
+        assert!(report.total() > 0);
+        assert!(report.elapsed < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_bench_counts_errors() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/fail")
+            .with_status(503)
+            .create_async()
+            .await;
+
+        let report = Bench::new(create_request(&format!("{}/fail", server.url())))
+            .with_limit(BenchLimit::Iterations(5))
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(report.errors, 5);
+        assert_eq!(report.error_rate(), 1.0);
+        assert_eq!(report.summary().status_codes.get(&503), Some(&5));
+    }
+
+    #[tokio::test]
+    async fn test_bench_stops_when_cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let report = Bench::new(create_request("http://127.0.0.1:1/never"))
+            .with_limit(BenchLimit::Iterations(10))
+            .with_cancellation(token)
+            .run()
+            .await
+            .unwrap();
+
+        assert!(report.cancelled);
+        assert_eq!(report.total(), 0);
+    }
+
+    #[test]
+    fn test_bench_report_percentiles() {
+        let samples = (1..=100)
+            .map(|ms| Sample {
+                latency: Duration::from_millis(ms),
+                status: Some(200),
+            })
+            .collect();
+        let report = BenchReport::new("p", samples, Duration::from_secs(2));
+
+        assert_eq!(report.percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(report.percentile(95.0), Some(Duration::from_millis(95)));
+        assert_eq!(report.percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(report.throughput(), 50.0);
+
+        let summary = report.summary();
+        assert_eq!(summary.latency_ms.min, 1.0);
+        assert_eq!(summary.latency_ms.max, 100.0);
+        assert!(report.to_string().contains("Latency p95"));
+    }
+
+    #[test]
+    fn test_bench_report_empty() {
+        let report = BenchReport::new("empty", Vec::new(), Duration::ZERO);
+
+        assert_eq!(report.percentile(50.0), None);
+        assert_eq!(report.error_rate(), 0.0);
+        assert_eq!(report.throughput(), 0.0);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("10s").unwrap(), Duration::from_secs(10));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse_duration("3").unwrap(), Duration::from_secs(3));
+        assert!(parse_duration("ten").is_err());
+        assert!(parse_duration("5w").is_err());
+    }
+
+    #[test]
+    fn test_parse_ratio() {
+        assert_eq!(parse_ratio("10%").unwrap(), 0.1);
+        assert_eq!(parse_ratio("0.5 %").unwrap(), 0.005);
+        assert_eq!(parse_ratio("0.2").unwrap(), 0.2);
+        assert!(parse_ratio("ten").is_err());
+    }
+
+    #[test]
+    fn test_compare_with_baseline() {
+        let baseline = summary(&[(100, 200), (100, 200), (100, 200), (100, 200)]);
+        // 延迟增加 20%，错误率增加 25 个百分点
+        let current = summary(&[(120, 200), (120, 200), (120, 200), (120, 503)]);
+
+        let comparison = current.compare(&baseline, BenchThresholds::default());
+        let regressed: Vec<&str> = comparison.regressions().iter().map(|m| m.metric).collect();
+        assert_eq!(regressed, vec!["mean", "p50", "p95", "p99", "error_rate"]);
+        assert!((comparison.metrics[0].change.unwrap() - 0.2).abs() < 1e-9);
+
+        let lenient = BenchThresholds {
+            latency: 0.25,
+            error_rate: 0.3,
+        };
+        assert!(current.compare(&baseline, lenient).regressions().is_empty());
+
+        let output = comparison.to_string();
+        assert!(output.contains("=== Baseline comparison: bench ==="));
+        assert!(output.contains("100.00ms ->   120.00ms (+20.0%)  REGRESSED"));
+        assert!(output.contains("0.00% ->     25.00% (+25.00pp)  REGRESSED"));
+        assert!(output.contains("5 metric(s) regressed"));
+
+        // 基线延迟为0时不计算相对变化
+        let empty = BenchReport::new("bench", Vec::new(), Duration::ZERO).summary();
+        let comparison = current.compare(&empty, lenient);
+        assert_eq!(comparison.metrics[0].change, None);
+        assert!(comparison.to_string().contains("(n/a)"));
+    }
+
+    #[tokio::test]
+    async fn test_bench_evaluate_against_baseline() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/ping")
+            .with_status(503)
+            .create_async()
+            .await;
+        let mut request = create_request(&format!("{}/ping", server.url()));
+        request.assertions.push("?? error_rate < 50%".to_string());
+        let bench = Bench::new(request).with_limit(BenchLimit::Iterations(4));
+
+        // 没有基线时只对聚合断言求值
+        let outcome = bench.evaluate().await.unwrap();
+        assert!(outcome.comparison.is_none());
+        assert_eq!(outcome.assertions.len(), 1);
+        let error = outcome.check().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("1 aggregate assertion(s) failed")
+        );
+
+        // 全部失败时错误率相对基线增长100个百分点
+        let baseline = summary(&[(100, 200), (100, 200)]);
+        let outcome = Bench::new(create_request(&format!("{}/ping", server.url())))
+            .with_limit(BenchLimit::Iterations(4))
+            .with_baseline(Some(baseline))
+            .with_thresholds(BenchThresholds::default())
+            .evaluate()
+            .await
+            .unwrap();
+        let comparison = outcome.comparison.as_ref().unwrap();
+        assert!(
+            comparison
+                .regressions()
+                .iter()
+                .any(|m| m.metric == "error_rate")
+        );
+        let error = outcome.check().unwrap_err();
+        assert!(error.to_string().contains("regressed against the baseline"));
+    }
+
+    #[test]
+    fn test_select_request() {
+        let requests = vec![create_request("https://a.test"), {
+            let mut request = create_request("https://b.test");
+            request.name = "second".to_string();
+            request
+        }];
+
+        let selected = select_request(&requests, Some(&CaseSelector::parse("second"))).unwrap();
+        assert_eq!(selected.url, "https://b.test");
+        assert!(select_request(&requests, Some(&CaseSelector::parse("missing"))).is_err());
+        let error = select_request(&requests, None).unwrap_err();
+        assert!(error.to_string().contains("bench requires --case"));
+        assert_eq!(
+            select_request(&requests[..1], None).unwrap().url,
+            "https://a.test"
+        );
+    }
+
+    #[test]
+    fn test_load_baseline() {
+        let baseline = summary(&[(100, 200), (150, 404)]);
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), serde_json::to_string(&baseline).unwrap()).unwrap();
+
+        let loaded = BenchSummary::load(file.path()).unwrap();
+        assert_eq!(loaded.requests, 2);
+        assert_eq!(loaded.latency_ms.p95, 150.0);
+        assert_eq!(loaded.status_codes.get(&404), Some(&1));
+
+        fs::write(file.path(), "not json").unwrap();
+        let error = BenchSummary::load(file.path()).unwrap_err();
+        assert!(error.to_string().contains("Invalid baseline"));
+        let error = BenchSummary::load("missing-baseline.json").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Failed to read baseline missing-baseline.json")
+        );
+    }
+
+    // 辅助函数：由（延迟毫秒, 状态码）生成压测汇总
+    fn summary(samples: &[(u64, u16)]) -> BenchSummary {
+        let samples = samples
+            .iter()
+            .map(|&(ms, status)| Sample {
+                latency: Duration::from_millis(ms),
+                status: Some(status),
+            })
+            .collect();
+        BenchReport::new("bench", samples, Duration::from_secs(1)).summary()
+    }
+
+    // 辅助函数：创建压测请求
+    fn create_request(url: &str) -> HttpRequest {
+        HttpRequest::new("bench".to_string(), Method::GET, url.to_string())
+    }
+}
@@ -0,0 +1,233 @@
+//! binary模块的单元测试
+
+use httpie::binary::{
+    BinarySummary, ContentRange, DownloadProgress, ResponseRedirect, ResumeOutcome, charset,
+    decode_text, extension_for, is_binary, is_json, parse_range, resume_body, sanitize_file_name,
+    save_body,
+};
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_HEADER: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00];
+
+    #[test]
+    fn test_is_binary_by_content_type() {
+        assert!(is_binary("image/png", b"anything"));
+        assert!(is_binary("application/octet-stream", b"text"));
+        assert!(!is_binary("application/json; charset=utf-8", b"{}"));
+        assert!(!is_binary("text/plain", b"hello"));
+        assert!(!is_binary("application/problem+json", b"{}"));
+    }
+
+    #[test]
+    fn test_is_binary_by_sniffing() {
+        assert!(is_binary("", PNG_HEADER));
+        assert!(is_binary("application/x-custom", &[0xff, 0xfe, 0xfd, 0x41]));
+        assert!(!is_binary("", "纯文本内容".as_bytes()));
+        assert!(!is_binary("", b""));
+    }
+
+    #[test]
+    fn test_is_json() {
+        assert!(is_json(br#"{"id": 1}"#));
+        assert!(is_json(b"  \n[1, 2]"));
+        assert!(!is_json(b"42"));
+        assert!(!is_json(b"{not json"));
+        assert!(!is_json(PNG_HEADER));
+    }
+
+    #[test]
+    fn test_decode_text_by_charset() {
+        assert_eq!(
+            charset("text/html; Charset=\"Shift_JIS\""),
+            Some("Shift_JIS")
+        );
+        assert_eq!(charset("application/json"), None);
+
+        assert_eq!(
+            decode_text("text/plain; charset=latin1", &[0x63, 0xe9]),
+            "cé"
+        );
+        assert_eq!(
+            decode_text("text/plain; charset=shift_jis", &[0x82, 0xa0]),
+            "あ"
+        );
+        // 未声明或无法识别的charset按UTF-8解码，非法字节被替换
+        assert_eq!(decode_text("text/plain", "中文".as_bytes()), "中文");
+        assert_eq!(
+            decode_text("text/plain; charset=bogus", &[0x61, 0xff]),
+            "a\u{fffd}"
+        );
+        // 字节顺序标记优先于声明的charset
+        assert_eq!(
+            decode_text(
+                "text/plain; charset=latin1",
+                &[0xef, 0xbb, 0xbf, 0xe4, 0xb8, 0xad]
+            ),
+            "中"
+        );
+    }
+
+    #[test]
+    fn test_binary_summary() {
+        let summary = BinarySummary::new(b"abc");
+        assert_eq!(summary.size, 3);
+        assert_eq!(
+            summary.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(summary.preview, "61 62 63");
+
+        let display = summary.to_string();
+        assert!(display.contains("<binary body: 3 bytes>"));
+        assert!(!display.contains("..."));
+
+        let large = BinarySummary::new(&[0u8; 100]);
+        assert!(large.to_string().ends_with("..."));
+    }
+
+    #[test]
+    fn test_response_redirect_parse() {
+        assert_eq!(
+            ResponseRedirect::parse(">> ./out/image.png"),
+            Some(ResponseRedirect {
+                path: "./out/image.png".to_string(),
+                overwrite: false,
+                resume: false,
+            })
+        );
+        assert_eq!(
+            ResponseRedirect::parse("  >>! out.json"),
+            Some(ResponseRedirect {
+                path: "out.json".to_string(),
+                overwrite: true,
+                resume: false,
+            })
+        );
+        assert_eq!(
+            ResponseRedirect::parse(">>+ ./downloads/large.iso"),
+            Some(ResponseRedirect {
+                path: "./downloads/large.iso".to_string(),
+                overwrite: false,
+                resume: true,
+            })
+        );
+        assert!(ResponseRedirect::parse(">>").is_none());
+        assert!(ResponseRedirect::parse("> {%").is_none());
+    }
+
+    #[test]
+    fn test_extension_and_file_name() {
+        assert_eq!(extension_for("image/png"), "png");
+        assert_eq!(extension_for("application/json; charset=utf-8"), "json");
+        assert_eq!(extension_for("application/vnd.api+json"), "json");
+        assert_eq!(extension_for("application/x-unknown"), "bin");
+
+        assert_eq!(
+            sanitize_file_name("Download avatar / v2"),
+            "Download_avatar___v2"
+        );
+        assert_eq!(sanitize_file_name("///"), "response");
+    }
+
+    #[test]
+    fn test_save_body_with_suffix_and_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("nested/file.bin");
+
+        let first = save_body(&target, b"one", false).unwrap();
+        assert_eq!(first, target);
+
+        let second = save_body(&target, b"two", false).unwrap();
+        assert_eq!(second, dir.path().join("nested/file-1.bin"));
+        assert_eq!(fs::read(&target).unwrap(), b"one");
+
+        let third = save_body(&target, b"three", true).unwrap();
+        assert_eq!(third, target);
+        assert_eq!(fs::read(&target).unwrap(), b"three");
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-1023").unwrap(), "bytes=0-1023");
+        assert!(parse_range("bytes=1024-").is_ok());
+        assert!(parse_range("bytes=-500").is_ok());
+        assert!(parse_range(" bytes=0-99, 200-299 ").is_ok());
+
+        assert!(parse_range("0-1023").is_err());
+        assert!(parse_range("bytes=-").is_err());
+        assert!(parse_range("bytes=10-5").is_err());
+        assert!(parse_range("bytes=a-b").is_err());
+    }
+
+    #[test]
+    fn test_content_range_parse() {
+        assert_eq!(
+            ContentRange::parse("bytes 100-199/1000"),
+            Some(ContentRange {
+                range: Some((100, 199)),
+                total: Some(1000),
+            })
+        );
+        assert_eq!(
+            ContentRange::parse("bytes 0-9/*"),
+            Some(ContentRange {
+                range: Some((0, 9)),
+                total: None,
+            })
+        );
+        assert_eq!(
+            ContentRange::parse("bytes */1000"),
+            Some(ContentRange {
+                range: None,
+                total: Some(1000),
+            })
+        );
+        assert_eq!(ContentRange::parse("items 0-9/10"), None);
+        assert_eq!(ContentRange::parse("bytes 0-9"), None);
+    }
+
+    #[test]
+    fn test_resume_body() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("nested/file.bin");
+
+        // 没有部分文件时写入完整响应体
+        let outcome = resume_body(&target, 200, None, b"abc").unwrap();
+        assert_eq!(outcome, ResumeOutcome::Downloaded);
+
+        let outcome = resume_body(&target, 206, Some("bytes 3-5/6"), b"def").unwrap();
+        assert_eq!(outcome, ResumeOutcome::Resumed { offset: 3 });
+        assert_eq!(fs::read(&target).unwrap(), b"abcdef");
+
+        let outcome = resume_body(&target, 416, Some("bytes */6"), b"").unwrap();
+        assert_eq!(outcome, ResumeOutcome::Complete);
+
+        // 范围不衔接或状态异常时报错且不修改文件
+        assert!(resume_body(&target, 206, Some("bytes 0-2/6"), b"abc").is_err());
+        assert!(resume_body(&target, 206, None, b"ghi").is_err());
+        assert!(resume_body(&target, 416, Some("bytes */10"), b"").is_err());
+        assert!(resume_body(&target, 500, None, b"error").is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"abcdef");
+
+        // 服务器忽略Range时用完整响应体替换部分文件
+        let outcome = resume_body(&target, 200, None, b"fresh").unwrap();
+        assert_eq!(outcome, ResumeOutcome::Downloaded);
+        assert_eq!(fs::read(&target).unwrap(), b"fresh");
+    }
+
+    #[test]
+    fn test_download_progress_render() {
+        let mut progress = DownloadProgress::new(1024 * 1024, Some(4 * 1024 * 1024));
+        progress.advance(1024 * 1024);
+        assert_eq!(progress.render(), "Downloaded 2.0 MiB / 4.0 MiB (50%)");
+
+        let mut progress = DownloadProgress::new(0, None);
+        progress.advance(512);
+        assert_eq!(progress.render(), "Downloaded 512 B");
+    }
+}
@@ -0,0 +1,164 @@
+//! blocking模块的单元测试
+
+#![cfg(feature = "blocking")]
+
+use httpie::HttpRequest;
+use httpie::blocking::HttpClient;
+use mockito::Server;
+use reqwest::Method;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_execute_simple_get_request() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/test")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"message": "success"}"#)
+            .create();
+
+        let request = HttpRequest::new(
+            "blocking_get".to_string(),
+            Method::GET,
+            format!("{}/test", server.url()),
+        );
+
+        let mut client = HttpClient::new().unwrap().with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).unwrap();
+
+        assert_eq!(outcome.response.status, 200);
+        assert_eq!(outcome.response.body["message"], "success");
+        mock.assert();
+    }
+
+    #[test]
+    fn test_blocking_execute_post_request_with_body() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("POST", "/items")
+            .match_header("content-type", "application/json")
+            .match_body(r#"{"name":"a"}"#)
+            .with_status(201)
+            .create();
+
+        let mut headers = std::collections::HashMap::new();
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        let request = HttpRequest::new(
+            "blocking_post".to_string(),
+            Method::POST,
+            format!("{}/items", server.url()),
+        )
+        .with_headers(headers)
+        .with_body(Some(r#"{"name":"a"}"#.to_string()));
+
+        let mut client = HttpClient::new().unwrap().with_print_response(false);
+
+        assert!(client.execute(&request).is_ok());
+        mock.assert();
+    }
+
+    #[cfg(feature = "scripting")]
+    #[test]
+    fn test_blocking_execute_runs_response_handler() {
+        let mut server = Server::new();
+        let _mock = server
+            .mock("GET", "/scripted")
+            .with_status(200)
+            .with_body("ok")
+            .create();
+
+        let request = HttpRequest::new(
+            "blocking_script".to_string(),
+            Method::GET,
+            format!("{}/scripted", server.url()),
+        )
+        .with_response_handler(Some(
+            r#"client.test("status", function() { client.assert(response.status === 200); });"#
+                .to_string(),
+        ));
+
+        let mut client = HttpClient::new()
+            .unwrap()
+            .with_script_engine()
+            .unwrap()
+            .with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).unwrap();
+
+        assert_eq!(outcome.test_results.len(), 1);
+        assert!(outcome.test_results[0].passed);
+    }
+
+    #[test]
+    fn test_blocking_execute_truncates_body_over_limit() {
+        let mut server = Server::new();
+        let _mock = server
+            .mock("GET", "/large")
+            .with_status(200)
+            .with_body("y".repeat(64))
+            .create();
+
+        let request = HttpRequest::new(
+            "blocking_large".to_string(),
+            Method::GET,
+            format!("{}/large", server.url()),
+        );
+
+        let mut client = HttpClient::new()
+            .unwrap()
+            .with_max_body_bytes(Some(16))
+            .with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).unwrap();
+
+        assert!(outcome.response.truncated);
+        assert_eq!(outcome.response.body, "y".repeat(16));
+    }
+
+    #[test]
+    fn test_blocking_execute_records_redirects() {
+        let mut server = Server::new();
+        let _old = server
+            .mock("GET", "/old")
+            .with_status(301)
+            .with_header("location", "/new")
+            .create();
+        let _new = server
+            .mock("GET", "/new")
+            .with_status(200)
+            .with_body("moved")
+            .create();
+
+        let request = HttpRequest::new(
+            "blocking_redirect".to_string(),
+            Method::GET,
+            format!("{}/old", server.url()),
+        );
+
+        let mut client = HttpClient::new().unwrap().with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).unwrap();
+
+        assert_eq!(outcome.response.status, 200);
+        assert_eq!(outcome.response.redirects.len(), 1);
+        assert_eq!(outcome.response.redirects[0].status, 301);
+        assert_eq!(
+            outcome.response.redirects[0].location,
+            format!("{}/new", server.url())
+        );
+    }
+
+    #[test]
+    fn test_blocking_execute_network_error() {
+        let request = HttpRequest::new(
+            "blocking_error".to_string(),
+            Method::GET,
+            "http://127.0.0.1:1/unreachable".to_string(),
+        );
+
+        let mut client = HttpClient::new().unwrap().with_print_response(false);
+
+        assert!(client.execute(&request).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! capture模块的单元测试
+
+use httpie::ResponseObject;
+use httpie::capture::{Capture, CaptureSource};
+use reqwest::header::{HeaderMap, HeaderValue};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_capture() {
+        assert_eq!(
+            Capture::parse("token = $.access_token").unwrap(),
+            Capture {
+                name: "token".to_string(),
+                source: CaptureSource::JsonPath("$.access_token".to_string()),
+            }
+        );
+        assert_eq!(
+            Capture::parse("session=header Set-Cookie").unwrap().source,
+            CaptureSource::Header("Set-Cookie".to_string())
+        );
+        assert_eq!(
+            Capture::parse("code = status").unwrap().source,
+            CaptureSource::Status
+        );
+
+        for invalid in [
+            "token",
+            "= $.a",
+            "bad name = $.a",
+            "token = body",
+            "token = $..",
+        ] {
+            assert!(
+                Capture::parse(invalid)
+                    .unwrap_err()
+                    .to_string()
+                    .contains("Invalid @capture"),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_capture() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("x-request-id", HeaderValue::from_static("req-1"));
+        let response = ResponseObject::from_parts(
+            201,
+            &headers,
+            br#"{"access_token": "abc", "user": {"id": 7}}"#,
+        );
+
+        let extract = |rule: &str| Capture::parse(rule).unwrap().extract(&response);
+        assert_eq!(extract("t = $.access_token").as_deref(), Some("abc"));
+        assert_eq!(extract("id = $.user.id").as_deref(), Some("7"));
+        assert_eq!(extract("user = $.user").as_deref(), Some(r#"{"id":7}"#));
+        assert_eq!(
+            extract("rid = header X-Request-Id").as_deref(),
+            Some("req-1")
+        );
+        assert_eq!(extract("code = status").as_deref(), Some("201"));
+        assert_eq!(extract("missing = $.nope"), None);
+    }
+
+    #[test]
+    fn test_extract_json_path_from_untyped_body() {
+        let response = ResponseObject::from_parts(200, &HeaderMap::new(), br#"{"id": "x"}"#);
+        let capture = Capture::parse("id = $.id").unwrap();
+        assert_eq!(capture.extract(&response).as_deref(), Some("x"));
+    }
+}
@@ -1,6 +1,10 @@
 //! client模块的单元测试
 
-use httpie::{HttpClient, HttpRequest, ResponseFormatter};
+use httpie::client::parse_resolve;
+use httpie::{
+    CancellationToken, Directive, HeaderFilter, HttpClient, HttpProtocol, HttpRequest, HttpVersion,
+    HttpieError, OutputOptions, ResponseFormatter, ResponseObject, TransportConfig,
+};
 use mockito::{Matcher, Server};
 use reqwest::Method;
 use std::collections::HashMap;
@@ -32,6 +36,7 @@ mod tests {
         // 测试默认值设置
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_http_client_with_script_engine() {
         let result = HttpClient::new().with_script_engine();
@@ -102,6 +107,36 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_execute_request_encodes_query() {
+        let mut server = Server::new_async().await;
+
+        let mock = server
+            .mock("GET", "/users/7")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("q".into(), "hello world".into()),
+                Matcher::UrlEncoded("name".into(), "张三".into()),
+            ]))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "search".to_string(),
+            Method::GET,
+            format!("{}/users/{{id}}", server.url()),
+        )
+        .with_path_params(vec![("id".to_string(), "7".to_string())])
+        .with_query(vec![
+            ("q".to_string(), "hello world".to_string()),
+            ("name".to_string(), "张三".to_string()),
+        ]);
+
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute(&request).await.unwrap();
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_execute_request_with_headers() {
         let mut server = Server::new_async().await;
@@ -133,6 +168,7 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_request_with_script_engine() {
         let mut server = Server::new_async().await;
@@ -177,6 +213,7 @@ client.test("Data should be array", function() {
         mock.assert_async().await;
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_request_script_engine_not_initialized() {
         let mut server = Server::new_async().await;
@@ -223,6 +260,358 @@ client.test("Data should be array", function() {
         assert!(result.is_err(), "Should fail for invalid domain");
     }
 
+    #[tokio::test]
+    async fn test_execute_records_negotiated_http_version() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/version")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "version".to_string(),
+            Method::GET,
+            format!("{}/version", server.url()),
+        )
+        .with_http_version(Some(HttpVersion::Http11));
+        let mut client = HttpClient::new()
+            .with_http_protocol(HttpProtocol::Http1Only)
+            .unwrap()
+            .with_print_response(false);
+
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        assert_eq!(outcome.response.http_version.as_deref(), Some("HTTP/1.1"));
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_for_request_http3_directive() {
+        let request = HttpRequest::new(
+            "quic".to_string(),
+            Method::GET,
+            "https://example.com/".to_string(),
+        )
+        .with_directives(vec![Directive::new("http3", "")]);
+
+        let config = TransportConfig::default().for_request(&request).unwrap();
+        assert_eq!(config.unwrap().protocol, HttpProtocol::Http3);
+
+        // 客户端已经优先使用HTTP/3时无需新建客户端
+        let config = TransportConfig {
+            protocol: HttpProtocol::Http3,
+            ..Default::default()
+        };
+        assert!(config.for_request(&request).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_http3_sends_plain_http_over_tcp() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/version")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        // HTTP/3只用于HTTPS，明文请求照常发送
+        let request = HttpRequest::new(
+            "version".to_string(),
+            Method::GET,
+            format!("{}/version", server.url()),
+        )
+        .with_directives(vec![Directive::new("http3", "")]);
+        let mut client = HttpClient::new()
+            .with_http_protocol(HttpProtocol::Http3)
+            .unwrap()
+            .with_print_response(false);
+
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        assert_eq!(outcome.response.status, 200);
+        assert_eq!(outcome.response.http_version.as_deref(), Some("HTTP/1.1"));
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "http3")]
+    #[tokio::test]
+    async fn test_execute_http3_falls_back_only_for_idempotent_methods() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // 本地端口上没有QUIC服务，HTTP/3请求总是失败；TCP连接被接受后立即关闭
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("https://{}/items", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        tokio::spawn(async move {
+            while listener.accept().await.is_ok() {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        let mut client = HttpClient::new()
+            .with_http_protocol(HttpProtocol::Http3)
+            .unwrap()
+            .with_print_response(false);
+
+        // POST可能已经送达，不以TCP重发
+        let create = HttpRequest::new("create".to_string(), Method::POST, url.clone())
+            .with_body(Some("{}".to_string()));
+        assert!(client.execute_with_outcome(&create).await.is_err());
+        assert_eq!(connections.load(Ordering::SeqCst), 0, "POST was re-sent");
+
+        // GET回退到HTTP/1.1或HTTP/2
+        let list = HttpRequest::new("list".to_string(), Method::GET, url);
+        assert!(client.execute_with_outcome(&list).await.is_err());
+        assert_eq!(connections.load(Ordering::SeqCst), 1, "GET was not retried");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_outcome_returns_test_results() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/health")
+            .with_status(503)
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "health".to_string(),
+            Method::GET,
+            format!("{}/health", server.url()),
+        )
+        .with_assertions(vec![
+            "status == 200".to_string(),
+            "status < 600".to_string(),
+        ]);
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_print_test_results(false);
+
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        assert_eq!(outcome.test_results.len(), 2);
+        assert!(!outcome.passed());
+        let failed: Vec<&str> = outcome.failed_tests().map(|t| t.name.as_str()).collect();
+        assert_eq!(failed, vec!["status == 200"]);
+        assert_eq!(
+            outcome.test_results[0].request_name.as_deref(),
+            Some("health")
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_request_headers_on_the_wire() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/signed", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !received.ends_with(b"\r\n\r\n") {
+                let read = socket.read(&mut chunk).await.unwrap();
+                received.extend_from_slice(&chunk[..read]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(received).unwrap()
+        });
+
+        let request = HttpRequest::new("signed".to_string(), Method::GET, url).with_headers([
+            ("X-Zeta".to_string(), "1".to_string()),
+            ("X-Alpha".to_string(), "2".to_string()),
+            ("X-Mid".to_string(), "3".to_string()),
+        ]);
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute_with_outcome(&request).await.unwrap();
+
+        // 按书写顺序发送，名称统一为小写
+        let received = server.await.unwrap();
+        let names: Vec<&str> = received
+            .lines()
+            .filter_map(|line| line.split_once(':').map(|(name, _)| name))
+            .filter(|name| name.to_ascii_lowercase().starts_with("x-"))
+            .collect();
+        assert_eq!(names, ["x-zeta", "x-alpha", "x-mid"]);
+    }
+
+    #[tokio::test]
+    async fn test_response_headers_keep_wire_order() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/ordered")
+            .with_status(200)
+            .with_header("x-zeta", "1")
+            .with_header("x-alpha", "2")
+            .with_header("x-mid", "3")
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "ordered".to_string(),
+            Method::GET,
+            format!("{}/ordered", server.url()),
+        );
+        let mut client = HttpClient::new().with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        let names: Vec<&str> = outcome
+            .response
+            .headers
+            .keys()
+            .map(String::as_str)
+            .filter(|name| name.starts_with("x-"))
+            .collect();
+        assert_eq!(names, ["x-zeta", "x-alpha", "x-mid"]);
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_parse_resolve() {
+        let (host, addr) = parse_resolve("api.example.com:443:10.0.0.5").unwrap();
+        assert_eq!(host, "api.example.com");
+        assert_eq!(addr.to_string(), "10.0.0.5:443");
+
+        let (_, addr) = parse_resolve("api.example.com:8443:[::1]").unwrap();
+        assert_eq!(addr.to_string(), "[::1]:8443");
+
+        for invalid in [
+            "api.example.com",
+            "api.example.com:x:10.0.0.5",
+            ":80:10.0.0.5",
+        ] {
+            assert!(
+                parse_resolve(invalid).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_resolve_directive() {
+        let mut server = Server::new_async().await;
+        let port = server
+            .host_with_port()
+            .rsplit(':')
+            .next()
+            .unwrap()
+            .to_string();
+        let mock = server
+            .mock("GET", "/health")
+            .match_header("host", format!("staging.example.test:{port}").as_str())
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "resolved".to_string(),
+            Method::GET,
+            format!("http://staging.example.test:{port}/health"),
+        )
+        .with_directives(vec![Directive::new(
+            "resolve",
+            format!("staging.example.test:{port}:127.0.0.1"),
+        )]);
+        let mut client = HttpClient::new().with_print_response(false);
+
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        assert_eq!(outcome.response.status, 200);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_truncates_body_over_limit() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/large")
+            .with_status(200)
+            .with_header("content-type", "text/plain")
+            .with_body("x".repeat(100))
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "large".to_string(),
+            Method::GET,
+            format!("{}/large", server.url()),
+        );
+        let mut client = HttpClient::new()
+            .with_max_body_bytes(Some(10))
+            .with_print_response(false);
+
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        assert!(outcome.response.truncated);
+        assert_eq!(outcome.response.body, "x".repeat(10));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_body_within_limit_is_not_truncated() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/small")
+            .with_status(200)
+            .with_body("small")
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "small".to_string(),
+            Method::GET,
+            format!("{}/small", server.url()),
+        );
+        let mut client = HttpClient::new()
+            .with_max_body_bytes(Some(5))
+            .with_print_response(false);
+
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        assert!(!outcome.response.truncated);
+        assert_eq!(outcome.response.body, "small");
+    }
+
+    #[test]
+    fn test_response_formatter_limits_printed_body() {
+        let formatter = ResponseFormatter::new();
+        let response = httpie::ResponseObject {
+            status: 200,
+            body: serde_json::Value::String("多字节字符".repeat(10)),
+            truncated: true,
+            ..Default::default()
+        };
+
+        // 截断位置落在多字节字符中间时不应panic
+        let rendered = formatter.render_response_limited("limited", &response, Some(4));
+        assert!(rendered.starts_with("=== limited ==="));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_cancelled_token() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let request = HttpRequest::new(
+            "cancelled".to_string(),
+            Method::GET,
+            "http://127.0.0.1:1/cancelled".to_string(),
+        );
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_cancellation(token);
+
+        let result = client.execute(&request).await;
+
+        assert!(matches!(result, Err(HttpieError::Cancelled)));
+    }
+
     #[test]
     fn test_response_formatter_creation() {
         let _formatter = ResponseFormatter::new();
@@ -274,16 +663,28 @@ client.test("Data should be array", function() {
                 name: "Test 1".to_string(),
                 passed: true,
                 message: None,
+                duration_ms: 0,
+                error_kind: None,
+                request_name: None,
+                xfail: false,
             },
             TestResult {
                 name: "Test 2".to_string(),
                 passed: false,
                 message: Some("Assertion failed".to_string()),
+                duration_ms: 0,
+                error_kind: None,
+                request_name: None,
+                xfail: false,
             },
             TestResult {
                 name: "Test 3".to_string(),
                 passed: true,
                 message: Some("Custom message".to_string()),
+                duration_ms: 0,
+                error_kind: None,
+                request_name: None,
+                xfail: false,
             },
         ];
 
@@ -291,6 +692,126 @@ client.test("Data should be array", function() {
         formatter.format_test_results("test_request", &test_results);
     }
 
+    #[test]
+    fn test_response_formatter_render_options() {
+        let formatter = ResponseFormatter::new();
+        let mut request = HttpRequest::new(
+            "Create user".to_string(),
+            Method::POST,
+            "https://example.com/users".to_string(),
+        );
+        request
+            .headers
+            .insert("Content-Type".to_string(), "application/json".to_string());
+        request.body = Some(r#"{"name":"alice"}"#.to_string());
+        let mut response = ResponseObject {
+            status: 201,
+            body: serde_json::json!({"id": 1}),
+            ..Default::default()
+        };
+        for (name, value) in [
+            ("content-type", "application/json"),
+            ("date", "Mon, 01 Jan 2024 00:00:00 GMT"),
+            ("server", "cdn"),
+        ] {
+            response.headers.insert(name.to_string(), value.to_string());
+        }
+
+        let default = formatter.render_request_response(&request, &response, &Default::default());
+        assert!(!default.contains("Request:"));
+        assert!(default.contains("  date: \""));
+        assert!(default.contains("Body:\n{\n  \"id\": 1\n}"));
+
+        let options = OutputOptions {
+            parts: "HBh".parse().unwrap(),
+            headers: HeaderFilter::new().with_hidden("Date, server"),
+            ..Default::default()
+        };
+        let output = formatter.render_request_response(&request, &response, &options);
+        assert!(output.contains("Request: POST https://example.com/users\n"));
+        assert!(output.contains("Request Headers:\n  Content-Type: \"application/json\"\n"));
+        assert!(output.contains("Request Body:\n{\"name\":\"alice\"}\n"));
+        assert!(output.contains("Status: 201 Created"));
+        assert!(output.contains("Headers:\n  content-type: \"application/json\"\n"));
+        assert!(!output.contains("date"));
+        assert!(!output.contains("server"));
+        assert!(!output.contains("\nBody:"));
+
+        // 没有要显示的头时不打印标题
+        let options = OutputOptions {
+            headers: HeaderFilter::new().with_only("x-request-id"),
+            ..Default::default()
+        };
+        let output = formatter.render_request_response(&request, &response, &options);
+        assert!(!output.contains("Headers:"));
+    }
+
+    #[test]
+    fn test_response_formatter_render_test_results() {
+        use httpie::TestResult;
+
+        let formatter = ResponseFormatter::new();
+        let results = vec![
+            TestResult::success("status is 200"),
+            TestResult::failure("has items", "expected 3, got 0", "AssertionError"),
+        ];
+
+        assert_eq!(
+            formatter.render_test_results("List items", &results),
+            "\n=== Test Results for List items ===\n\
+             ✓ PASS status is 200 (0ms)\n\
+             ✗ FAIL has items (0ms)\n  Message: expected 3, got 0\n\n"
+        );
+        assert_eq!(formatter.render_test_results("List items", &[]), "");
+    }
+
+    #[test]
+    fn test_response_formatter_render_expected_failures() {
+        use httpie::TestResult;
+
+        let formatter = ResponseFormatter::new();
+        let mut xfailed = TestResult::failure("known bug", "expected 1, got 2", "AssertionError");
+        xfailed.xfail = true;
+        let mut xpassed = TestResult::success("fixed bug");
+        xpassed.xfail = true;
+
+        let output = formatter.render_test_results("Bugs", &[xfailed, xpassed]);
+        assert!(output.contains("✓ XFAIL known bug (0ms)\n  Message: expected 1, got 2\n"));
+        assert!(output.contains("✗ XPASS fixed bug (0ms)\n"));
+    }
+
+    #[test]
+    fn test_response_formatter_render_test_results_with_logs() {
+        use httpie::{LogLevel, ScriptLog, TestResult};
+
+        let formatter = ResponseFormatter::new();
+        let results = vec![TestResult::failure(
+            "has items",
+            "expected 3, got 0",
+            "AssertionError",
+        )];
+        let log = |level, message: &str, test: Option<&str>| ScriptLog {
+            level,
+            message: message.to_string(),
+            test: test.map(String::from),
+        };
+        let logs = vec![
+            log(LogLevel::Log, "page 1", None),
+            log(LogLevel::Warn, "items: []", Some("has items")),
+        ];
+
+        assert_eq!(
+            formatter.render_test_results_with_logs("List items", &results, &logs),
+            "\n=== Test Results for List items ===\n\
+             [log] page 1\n\
+             ✗ FAIL has items (0ms)\n  Message: expected 3, got 0\n  [warn] items: []\n\n"
+        );
+        assert_eq!(
+            formatter.render_test_results_with_logs("Only logs", &[], &logs[..1]),
+            "\n=== Test Results for Only logs ===\n[log] page 1\n\n"
+        );
+    }
+
     #[test]
     fn test_response_formatter_format_empty_test_results() {
         let formatter = ResponseFormatter::new();
@@ -300,6 +821,7 @@ client.test("Data should be array", function() {
         formatter.format_test_results("empty_test", &empty_results);
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_client_builder_pattern() {
         // 测试链式调用的构建模式
@@ -419,4 +941,230 @@ client.test("Data should be array", function() {
         text_mock.assert_async().await;
         xml_mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_execute_saves_binary_body_to_download_dir() {
+        let mut server = Server::new_async().await;
+        let png: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff];
+
+        let mock = server
+            .mock("GET", "/avatar")
+            .with_status(200)
+            .with_header("content-type", "image/png")
+            .with_body(png)
+            .create_async()
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let request = HttpRequest::new(
+            "Get avatar".to_string(),
+            Method::GET,
+            format!("{}/avatar", server.url()),
+        );
+
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_download_dir(Some(dir.path().to_path_buf()));
+        client.execute(&request).await.unwrap();
+
+        assert_eq!(
+            std::fs::read(dir.path().join("Get_avatar.png")).unwrap(),
+            png
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_writes_response_redirect() {
+        let mut server = Server::new_async().await;
+
+        let mock = server
+            .mock("GET", "/report")
+            .with_status(200)
+            .with_header("content-type", "text/csv")
+            .with_body("a,b\n1,2\n")
+            .create_async()
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let request = HttpRequest::new(
+            "report".to_string(),
+            Method::GET,
+            format!("{}/report", server.url()),
+        )
+        .with_source_file(Some(dir.path().join("suite.http")))
+        .with_response_redirect(Some(httpie::binary::ResponseRedirect {
+            path: "out/report.csv".to_string(),
+            overwrite: true,
+            resume: false,
+        }));
+
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute(&request).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out/report.csv")).unwrap(),
+            "a,b\n1,2\n"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_resumes_partial_download() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/large.bin")
+            .match_header("range", "bytes=3-")
+            .with_status(206)
+            .with_header("content-type", "application/octet-stream")
+            .with_header("content-range", "bytes 3-5/6")
+            .with_body("def")
+            .create_async()
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("large.bin"), "abc").unwrap();
+        let request = HttpRequest::new(
+            "download".to_string(),
+            Method::GET,
+            format!("{}/large.bin", server.url()),
+        )
+        .with_source_file(Some(dir.path().join("suite.http")))
+        .with_response_redirect(Some(httpie::binary::ResponseRedirect {
+            path: "large.bin".to_string(),
+            overwrite: false,
+            resume: true,
+        }));
+
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute(&request).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("large.bin")).unwrap(),
+            "abcdef"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_sends_range_directive() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/file")
+            .match_header("range", "bytes=0-1023")
+            .with_status(206)
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "range".to_string(),
+            Method::GET,
+            format!("{}/file", server.url()),
+        )
+        .with_directives(vec![Directive::new("range", "bytes=0-1023")]);
+
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute(&request).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_transport_config_redirect_policy() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/old")
+            .with_status(302)
+            .with_header("location", "/new")
+            .create_async()
+            .await;
+        let target = server
+            .mock("GET", "/new")
+            .with_body("moved")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "redirect".to_string(),
+            Method::GET,
+            format!("{}/old", server.url()),
+        );
+
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport_config(TransportConfig {
+                max_redirects: Some(0),
+                ..TransportConfig::default()
+            })
+            .unwrap();
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+        assert_eq!(outcome.response.status, 302);
+
+        let mut client = HttpClient::new().with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+        assert_eq!(outcome.response.status, 200);
+        target.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_records_redirect_chain() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/start")
+            .with_status(301)
+            .with_header("location", "/middle")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/middle")
+            .with_status(302)
+            .with_header("location", "/end")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/end")
+            .with_body("done")
+            .create_async()
+            .await;
+
+        let request = HttpRequest::new(
+            "chain".to_string(),
+            Method::POST,
+            format!("{}/start", server.url()),
+        )
+        .with_assertions(vec!["redirects == 2".to_string()]);
+
+        let mut client = HttpClient::new().with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+        assert_eq!(outcome.response.status, 200);
+        let redirects = &outcome.response.redirects;
+        assert_eq!(redirects.len(), 2);
+        assert_eq!(redirects[0].url, format!("{}/start", server.url()));
+        assert_eq!(redirects[0].status, 301);
+        assert_eq!(redirects[0].location, format!("{}/middle", server.url()));
+        assert_eq!(redirects[1].status, 302);
+        assert_eq!(redirects[1].location, format!("{}/end", server.url()));
+        assert!(outcome.test_results[0].passed, "{:?}", outcome.test_results);
+
+        // 超过上限时请求失败，不跟随重定向时链为空
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport_config(TransportConfig {
+                max_redirects: Some(1),
+                ..TransportConfig::default()
+            })
+            .unwrap();
+        assert!(client.execute_with_outcome(&request).await.is_err());
+
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport_config(TransportConfig {
+                max_redirects: Some(0),
+                ..TransportConfig::default()
+            })
+            .unwrap();
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+        assert_eq!(outcome.response.status, 301);
+        assert!(outcome.response.redirects.is_empty());
+    }
 }
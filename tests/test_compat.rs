@@ -0,0 +1,125 @@
+//! 方言兼容性语料测试
+//!
+//! `tests/compat/<方言>/` 下的每个 `.http` 文件按该方言解析，结果与同名 `.json`
+//! 中的期望比较：请求的名称、方法、URL，以及列出的请求头、请求体、响应处理器、
+//! 断言数量和请求体文件；警告按顺序逐条比较。
+
+use httpie::{Environment, HttpParser, HttpRequest, ParserDialect};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(dialect: &str) -> Vec<PathBuf> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/compat")
+            .join(dialect);
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "http"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn check_request(file: &Path, request: &HttpRequest, expected: &Value) {
+        let context = format!("{} / {}", file.display(), request.name);
+        assert_eq!(request.name, expected["name"], "{context}");
+        assert_eq!(request.method.as_str(), expected["method"], "{context}");
+        assert_eq!(request.url, expected["url"], "{context}");
+        if let Some(headers) = expected.get("headers").and_then(Value::as_object) {
+            for (name, value) in headers {
+                assert_eq!(
+                    request.headers.get(name),
+                    value.as_str().map(String::from).as_ref(),
+                    "{context}"
+                );
+            }
+        }
+        if let Some(body) = expected.get("body") {
+            assert_eq!(request.body.as_deref(), body.as_str(), "{context}");
+        }
+        if let Some(handler) = expected.get("handler") {
+            assert_eq!(
+                request.response_handler.is_some(),
+                handler.as_bool().unwrap(),
+                "{context}"
+            );
+        }
+        if let Some(assertions) = expected.get("assertions") {
+            assert_eq!(
+                request.assertions.len() as u64,
+                assertions.as_u64().unwrap(),
+                "{context}"
+            );
+        }
+        if let Some(body_file) = expected.get("bodyFile") {
+            assert_eq!(
+                request.body_file.as_deref(),
+                body_file.as_str(),
+                "{context}"
+            );
+        }
+    }
+
+    fn check_dialect(name: &str, dialect: ParserDialect) {
+        let files = corpus(name);
+        assert!(!files.is_empty());
+        for file in files {
+            let expected: Value =
+                serde_json::from_str(&fs::read_to_string(file.with_extension("json")).unwrap())
+                    .unwrap();
+            let mut parser = HttpParser::new(Environment::new()).with_dialect(dialect);
+            let output = parser
+                .parse_file_with_warnings(&file.to_string_lossy())
+                .unwrap();
+
+            let requests = expected["requests"].as_array().unwrap();
+            assert_eq!(output.requests.len(), requests.len(), "{}", file.display());
+            for (request, expected) in output.requests.iter().zip(requests) {
+                check_request(&file, request, expected);
+            }
+            let warnings: Vec<&str> = output.warnings.iter().map(|w| w.message.as_str()).collect();
+            let expected: Vec<&str> = expected["warnings"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|w| w.as_str().unwrap())
+                .collect();
+            assert_eq!(warnings, expected, "{}", file.display());
+        }
+    }
+
+    #[test]
+    fn test_intellij_corpus() {
+        check_dialect("intellij", ParserDialect::IntelliJ);
+    }
+
+    #[test]
+    fn test_httpyac_corpus() {
+        check_dialect("httpyac", ParserDialect::Httpyac);
+    }
+
+    #[test]
+    fn test_rest_client_corpus() {
+        check_dialect("rest-client", ParserDialect::RestClient);
+    }
+
+    #[test]
+    fn test_dialect_from_str() {
+        assert_eq!(
+            "intellij".parse::<ParserDialect>().unwrap(),
+            ParserDialect::IntelliJ
+        );
+        assert_eq!(
+            "rest-client".parse::<ParserDialect>().unwrap(),
+            ParserDialect::RestClient
+        );
+        assert!("postman".parse::<ParserDialect>().is_err());
+        assert_eq!(ParserDialect::default(), ParserDialect::Native);
+    }
+}
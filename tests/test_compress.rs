@@ -0,0 +1,198 @@
+//! compress模块的单元测试
+
+use bytes::Bytes;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use httpie::compress::{ACCEPT_ENCODING, Compression, EncodedBody, decode_response, encode_body};
+use httpie::{Directive, HttpClient, HttpRequest, TransportConfig};
+use mockito::Server;
+use reqwest::Method;
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde_json::json;
+use std::io::{Read, Write};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(body: &str, compression: &str) -> HttpRequest {
+        HttpRequest::new(
+            "upload".to_string(),
+            Method::POST,
+            "https://api.example.com/upload".to_string(),
+        )
+        .with_body(Some(body.to_string()))
+        .with_directives(vec![Directive::new("compress", compression)])
+    }
+
+    #[test]
+    fn test_parse_compression() {
+        assert_eq!("gzip".parse::<Compression>().unwrap(), Compression::Gzip);
+        assert_eq!(
+            " Deflate ".parse::<Compression>().unwrap(),
+            Compression::Deflate
+        );
+        assert!("br".parse::<Compression>().is_err());
+    }
+
+    #[test]
+    fn test_compress_round_trip() {
+        let data = "{\"items\": [1, 2, 3]}".repeat(100);
+
+        let gzip = Compression::Gzip.compress(data.as_bytes()).unwrap();
+        assert!(gzip.len() < data.len());
+        let mut decoded = String::new();
+        GzDecoder::new(&gzip[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+
+        let deflate = Compression::Deflate.compress(data.as_bytes()).unwrap();
+        let mut decoded = String::new();
+        ZlibDecoder::new(&deflate[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_encode_body() {
+        let (body, encoding) = encode_body(&request("hello", "gzip")).unwrap().unwrap();
+        assert_eq!(encoding, Some("gzip"));
+        assert_ne!(body, b"hello");
+
+        // 已声明的Content-Encoding不重复添加
+        let declared = request("hello", "deflate")
+            .with_headers([("content-encoding".to_string(), "deflate".to_string())]);
+        let (_, encoding) = encode_body(&declared).unwrap().unwrap();
+        assert_eq!(encoding, None);
+
+        // 没有指令时原样发送
+        let plain = request("hello", "gzip").with_directives(Vec::new());
+        assert_eq!(
+            encode_body(&plain).unwrap(),
+            Some((b"hello".to_vec(), None))
+        );
+        assert!(encode_body(&request("hello", "zip")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_sends_compressed_body() {
+        let mut server = Server::new_async().await;
+        let body = r#"{"name": "test"}"#;
+        let mock = server
+            .mock("POST", "/upload")
+            .match_header("content-encoding", "gzip")
+            .match_body(Compression::Gzip.compress(body.as_bytes()).unwrap())
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let mut request = request(body, "gzip");
+        request.url = format!("{}/upload", server.url());
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute(&request).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    fn encoded_headers(encoding: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-encoding", HeaderValue::from_str(encoding).unwrap());
+        headers
+    }
+
+    fn brotli(data: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
+        writer.write_all(data).unwrap();
+        drop(writer);
+        compressed
+    }
+
+    #[test]
+    fn test_decode_response() {
+        let data = b"hello hello hello hello".repeat(10);
+        let gzip = Bytes::from(Compression::Gzip.compress(&data).unwrap());
+
+        let (body, encoded) = decode_response(&encoded_headers("gzip"), gzip.clone(), true);
+        assert_eq!(body, data);
+        assert_eq!(
+            encoded,
+            Some(EncodedBody {
+                encoding: "gzip".to_string(),
+                encoded_size: gzip.len(),
+                decoded_size: Some(data.len()),
+            })
+        );
+
+        // raw模式保留原始字节，但仍报告解压后的大小
+        let (body, encoded) = decode_response(&encoded_headers("gzip"), gzip.clone(), false);
+        assert_eq!(body, gzip);
+        assert_eq!(encoded.unwrap().decoded_size, Some(data.len()));
+
+        let br = Bytes::from(brotli(&data));
+        let (body, _) = decode_response(&encoded_headers("br"), br, true);
+        assert_eq!(body, data);
+
+        // 多层编码按逆序解码
+        let layered = Bytes::from(brotli(&Compression::Deflate.compress(&data).unwrap()));
+        let (body, _) = decode_response(&encoded_headers("deflate, br"), layered, true);
+        assert_eq!(body, data);
+    }
+
+    #[test]
+    fn test_decode_response_passthrough() {
+        let data = Bytes::from_static(b"plain");
+        let (body, encoded) = decode_response(&HeaderMap::new(), data.clone(), true);
+        assert_eq!(body, data);
+        assert_eq!(encoded, None);
+
+        let (_, encoded) = decode_response(&encoded_headers("identity"), data.clone(), true);
+        assert_eq!(encoded, None);
+
+        // 不支持的编码和损坏的数据保留原始字节
+        let (body, encoded) = decode_response(&encoded_headers("zstd"), data.clone(), true);
+        assert_eq!(body, data);
+        assert_eq!(encoded.unwrap().decoded_size, None);
+        let (body, _) = decode_response(&encoded_headers("gzip"), data.clone(), true);
+        assert_eq!(body, data);
+    }
+
+    #[tokio::test]
+    async fn test_execute_decodes_response() {
+        let mut server = Server::new_async().await;
+        let body = Compression::Gzip
+            .compress(br#"{"message": "compressed"}"#)
+            .unwrap();
+        let mock = server
+            .mock("GET", "/data")
+            .match_header("accept-encoding", ACCEPT_ENCODING)
+            .with_header("content-type", "application/json")
+            .with_header("content-encoding", "gzip")
+            .with_body(&body)
+            .expect(2)
+            .create_async()
+            .await;
+        let request = HttpRequest::new(
+            "data".to_string(),
+            Method::GET,
+            format!("{}/data", server.url()),
+        );
+
+        let mut client = HttpClient::new().with_print_response(false);
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+        assert_eq!(outcome.response.body, json!({"message": "compressed"}));
+        assert_eq!(outcome.response.encoded.unwrap().encoded_size, body.len());
+
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport_config(TransportConfig {
+                raw: true,
+                ..TransportConfig::default()
+            })
+            .unwrap();
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+        assert!(outcome.response.binary.is_some());
+        assert_eq!(outcome.response.headers["content-encoding"], "gzip");
+        mock.assert_async().await;
+    }
+}
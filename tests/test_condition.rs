@@ -0,0 +1,58 @@
+//! condition模块的单元测试
+
+use httpie::Condition;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holds(text: &str) -> bool {
+        Condition::parse(text).unwrap().evaluate()
+    }
+
+    #[test]
+    fn test_condition_comparisons() {
+        assert!(holds(r#"staging == "staging""#));
+        assert!(holds(r#""prod" != 'staging'"#));
+        assert!(holds("10 > 9"));
+        assert!(holds("2.5 <= 2.50"));
+        assert!(holds("b > a"));
+        assert!(holds(r#""api.example.com" contains example"#));
+        assert!(!holds("1==2"));
+    }
+
+    #[test]
+    fn test_condition_truthiness_and_logic() {
+        assert!(holds("yes"));
+        assert!(!holds("false"));
+        assert!(!holds("0"));
+        assert!(!holds(r#""""#));
+        assert!(holds("!0"));
+        assert!(holds("0 || 1 && 1"));
+        assert!(!holds("1 && 0 || 0"));
+        // 未定义的变量视为空字符串
+        assert!(!holds("{{feature}}"));
+        assert!(holds(r#"{{env}} == """#));
+    }
+
+    #[test]
+    fn test_condition_map_operands_and_display() {
+        let condition = Condition::parse(r#"{{env}} == "staging" && !{{dry_run}}"#)
+            .unwrap()
+            .map_operands(|operand| operand.replace("{{env}}", "my \"staging\""));
+        assert_eq!(
+            condition.to_string(),
+            r#""my \"staging\"" == "staging" && !"{{dry_run}}""#
+        );
+        assert!(!condition.evaluate());
+        assert_eq!(Condition::parse(&condition.to_string()).unwrap(), condition);
+    }
+
+    #[test]
+    fn test_condition_parse_errors() {
+        for text in ["", "a ==", "a b", "&& a", "a === b", "\"open"] {
+            let error = Condition::parse(text).unwrap_err().to_string();
+            assert!(error.contains("Invalid condition"), "{text}: {error}");
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! config模块的单元测试
+
+use httpie::{
+    Config, Environment, FailurePolicy, HttpParser, HttpRequest, ScriptIsolation, TransportConfig,
+};
+use reqwest::Method;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_config(content: &str) -> (TempDir, std::path::PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("httpie.config.toml");
+        fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_load_config() {
+        let (dir, path) = write_config(
+            r#"
+[client]
+timeout = 30
+max_redirects = 3
+proxy = "http://proxy.local:8080"
+insecure = true
+ca_cert = "certs/ca.pem"
+
+[headers]
+User-Agent = "httpie-tests"
+X-Team = "{{team}}"
+
+[output]
+quiet = true
+max_body_print = 0
+
+[report]
+failure_policy = "fail-fast"
+order = "random"
+seed = 7
+
+[notify]
+webhook = "https://hooks.slack.com/services/T000/B000/XXX"
+on_failure = true
+
+[scripts]
+hooks = "ci/hooks.js"
+isolation = "shared"
+"#,
+        );
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.client.timeout, Some(30));
+        assert_eq!(config.client.ca_cert, Some(dir.path().join("certs/ca.pem")));
+        assert_eq!(
+            config.headers.keys().collect::<Vec<_>>(),
+            vec!["User-Agent", "X-Team"]
+        );
+        assert_eq!(config.output.quiet, Some(true));
+        assert_eq!(config.output.max_body_print, Some(0));
+        assert_eq!(
+            config.report.failure_policy().unwrap(),
+            Some(FailurePolicy::FailFast)
+        );
+        assert_eq!(config.report.seed, Some(7));
+        assert_eq!(
+            config.notify.webhook.as_deref(),
+            Some("https://hooks.slack.com/services/T000/B000/XXX")
+        );
+        assert_eq!(config.notify.on_failure, Some(true));
+        assert_eq!(config.notify.template, None);
+        assert_eq!(config.scripts.hooks, Some(dir.path().join("ci/hooks.js")));
+        assert_eq!(
+            config.scripts.isolation().unwrap(),
+            Some(ScriptIsolation::Shared)
+        );
+
+        let mut transport = TransportConfig::default();
+        config.apply(&mut transport);
+        assert_eq!(transport.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(transport.max_redirects, Some(3));
+        assert_eq!(transport.proxy.as_deref(), Some("http://proxy.local:8080"));
+        assert!(transport.insecure);
+    }
+
+    #[test]
+    fn test_config_defaults_and_redirects() {
+        let config = Config::load(Path::new("/nonexistent/httpie.config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+
+        let (_dir, path) = write_config("[client]\nfollow_redirects = false\nmax_redirects = 5\n");
+        let mut transport = TransportConfig::default();
+        Config::load(&path).unwrap().apply(&mut transport);
+        assert_eq!(transport.max_redirects, Some(0));
+    }
+
+    const HOSTS: &str = r#"
+[headers]
+X-Team = "core"
+X-Tenant = "default"
+
+[hosts."api.example.com"]
+authorization = "Bearer {{token}}"
+headers = { X-Tenant = "acme" }
+
+[hosts."*.internal.test"]
+insecure = true
+ca_cert = "certs/internal.pem"
+
+[hosts."*.test"]
+headers = { X-Env = "test" }
+"#;
+
+    #[test]
+    fn test_host_profiles() {
+        let (dir, path) = write_config(HOSTS);
+        let config = Config::load(&path).unwrap();
+
+        let profile = config.hosts.find("API.example.com").unwrap();
+        assert_eq!(profile.authorization.as_deref(), Some("Bearer {{token}}"));
+        assert_eq!(profile.request_headers().len(), 2);
+        assert!(!profile.has_tls());
+
+        // 精确匹配优先，其次是最长的通配符后缀
+        let profile = config.hosts.find("db.internal.test").unwrap();
+        assert_eq!(profile.ca_cert, Some(dir.path().join("certs/internal.pem")));
+        assert!(config.hosts.find("other.test").unwrap().ca_cert.is_none());
+        assert!(
+            config
+                .hosts
+                .find("internal.test")
+                .unwrap()
+                .ca_cert
+                .is_none()
+        );
+        assert!(config.hosts.find("example.com").is_none());
+        assert!(
+            config
+                .hosts
+                .for_url("https://db.internal.test:8443/x")
+                .is_some()
+        );
+        assert!(config.hosts.for_url("{{host}}/x").is_none());
+
+        // TLS设置只合并到匹配主机的请求
+        let mut transport = TransportConfig::default();
+        config.apply(&mut transport);
+        let request = |url: &str| HttpRequest::new("r".to_string(), Method::GET, url.to_string());
+        let merged = transport
+            .for_request(&request("https://db.internal.test/health"))
+            .unwrap()
+            .unwrap();
+        assert!(merged.insecure);
+        assert_eq!(merged.ca_cert, Some(dir.path().join("certs/internal.pem")));
+        assert!(
+            transport
+                .for_request(&request("https://api.example.com/users"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parser_applies_host_profiles() {
+        let (dir, path) = write_config(HOSTS);
+        let config = Config::load(&path).unwrap();
+        let http_path = dir.path().join("api.http");
+        fs::write(
+            &http_path,
+            "@token = abc\n\n### Users\nGET https://api.example.com/users\n\n### Explicit\nGET https://api.example.com/me\nAuthorization: Basic xyz\n\n### Other\nGET https://example.com\n",
+        )
+        .unwrap();
+
+        let requests = HttpParser::new(Environment::new())
+            .with_default_headers(config.headers.clone())
+            .with_host_profiles(config.hosts.clone())
+            .parse_file(&http_path.to_string_lossy())
+            .unwrap();
+
+        let headers = &requests[0].headers;
+        assert_eq!(headers["Authorization"], "Bearer abc");
+        assert_eq!(headers["X-Tenant"], "acme");
+        assert_eq!(headers["X-Team"], "core");
+        assert_eq!(requests[1].headers["Authorization"], "Basic xyz");
+        assert!(!requests[2].headers.contains_key("Authorization"));
+        assert_eq!(requests[2].headers["X-Tenant"], "default");
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let (_dir, path) = write_config("[client]\ntimeuot = 5\n");
+        let error = Config::load(&path).unwrap_err().to_string();
+        assert!(error.contains("Invalid config"));
+        assert!(error.contains("timeuot"));
+
+        let (_dir, path) = write_config("[report]\nfailure_policy = \"never\"\n");
+        assert!(Config::load(&path).is_err());
+
+        let (_dir, path) = write_config("[report]\norder = \"size\"\n");
+        assert!(Config::load(&path).is_err());
+
+        let (_dir, path) = write_config("[hosts.\"api.example.com\"]\ntoken = \"x\"\n");
+        assert!(Config::load(&path).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! cookie模块的单元测试
+
+use httpie::cookie::SetCookie;
+use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_set_cookie_attributes() {
+        let cookie = SetCookie::parse(
+            "session=abc123; Domain=example.com; Path=/api; Expires=Wed, 21 Oct 2026 07:28:00 GMT; \
+             Max-Age=3600; Secure; HttpOnly; SameSite=Strict",
+        )
+        .unwrap();
+        assert_eq!(
+            cookie,
+            SetCookie {
+                name: "session".to_string(),
+                value: "abc123".to_string(),
+                domain: Some("example.com".to_string()),
+                path: Some("/api".to_string()),
+                expires: Some("Wed, 21 Oct 2026 07:28:00 GMT".to_string()),
+                max_age: Some(3600),
+                secure: true,
+                http_only: true,
+                same_site: Some("Strict".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_set_cookie_minimal() {
+        let cookie = SetCookie::parse(r#"theme="dark"; secure; samesite=lax"#).unwrap();
+        assert_eq!(cookie.name, "theme");
+        assert_eq!(cookie.value, "dark");
+        assert!(cookie.secure);
+        assert!(!cookie.http_only);
+        assert_eq!(cookie.same_site.as_deref(), Some("lax"));
+        assert_eq!(cookie.domain, None);
+        assert_eq!(cookie.max_age, None);
+
+        assert_eq!(SetCookie::parse("novalue"), None);
+        assert_eq!(SetCookie::parse("=orphan"), None);
+    }
+
+    #[test]
+    fn test_from_headers_keeps_every_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("a=1; HttpOnly"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("b=2; Max-Age=0"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("invalid"));
+
+        let cookies = SetCookie::from_headers(&headers);
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].name, "a");
+        assert!(cookies[0].http_only);
+        assert_eq!(cookies[1].max_age, Some(0));
+    }
+}
@@ -0,0 +1,245 @@
+//! curl模块的单元测试
+
+use httpie::curl::{export, from_curl, is_curl_command, shell_quote, to_curl, to_http};
+use httpie::{CaseSelector, Headers, HttpRequest, HttpVersion};
+use reqwest::Method;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_selected_requests() {
+        let request = |name: &str, path: &str| {
+            HttpRequest::new(
+                name.to_string(),
+                Method::GET,
+                format!("https://api.example.com/{path}"),
+            )
+        };
+        let requests = vec![
+            request("list users", "users"),
+            request("get user", "users/1"),
+            request("list orders", "orders"),
+        ];
+
+        let all = export(&requests, &[]).unwrap();
+        assert!(all.starts_with("# list users\ncurl https://api.example.com/users\n\n"));
+        assert_eq!(all.matches("curl ").count(), 3);
+
+        // 不带 `#N` 的名称导出所有名称包含该文本的请求，保持文件顺序
+        let selected = export(&requests, &[CaseSelector::parse("list")]).unwrap();
+        assert_eq!(
+            selected,
+            "# list users\ncurl https://api.example.com/users\n\n\
+             # list orders\ncurl https://api.example.com/orders\n\n"
+        );
+
+        let error = export(&requests, &[CaseSelector::parse("missing")]).unwrap_err();
+        assert!(error.to_string().contains("Case 'missing' not found"));
+    }
+
+    #[test]
+    fn test_to_curl_simple_get() {
+        let request = HttpRequest::new(
+            "get".to_string(),
+            Method::GET,
+            "https://example.com/users".to_string(),
+        );
+
+        assert_eq!(to_curl(&request), "curl https://example.com/users");
+    }
+
+    #[test]
+    fn test_to_curl_post_with_headers_and_body() {
+        let mut headers = Headers::new();
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        headers.insert("Authorization".to_string(), "Bearer abc".to_string());
+
+        let request = HttpRequest::new(
+            "post".to_string(),
+            Method::POST,
+            "https://example.com/users?a=1&b=2".to_string(),
+        )
+        .with_headers(headers)
+        .with_body(Some(r#"{"name": "O'Brien"}"#.to_string()));
+
+        let expected = [
+            "curl -X POST 'https://example.com/users?a=1&b=2'",
+            "-H 'Content-Type: application/json'",
+            "-H 'Authorization: Bearer abc'",
+            r#"--data-raw '{"name": "O'\''Brien"}'"#,
+        ]
+        .join(" \\\n  ");
+        assert_eq!(to_curl(&request), expected);
+    }
+
+    #[test]
+    fn test_to_curl_head_and_delete() {
+        let head = HttpRequest::new(
+            "head".to_string(),
+            Method::HEAD,
+            "https://example.com".to_string(),
+        );
+        assert!(to_curl(&head).contains("--head"));
+
+        let delete = HttpRequest::new(
+            "delete".to_string(),
+            Method::DELETE,
+            "https://example.com/1".to_string(),
+        );
+        assert!(to_curl(&delete).contains("-X DELETE"));
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain-value"), "plain-value");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn test_from_curl_devtools_command() {
+        let command = r#"curl 'https://api.example.com/users' \
+  -H 'accept: application/json' \
+  -H "authorization: Bearer \"quoted\"" \
+  --data-raw $'{"name":"it\'s"}' \
+  --compressed"#;
+
+        let request = from_curl(command).unwrap();
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.url, "https://api.example.com/users");
+        assert_eq!(request.headers.get("accept").unwrap(), "application/json");
+        assert_eq!(
+            request.headers.get("authorization").unwrap(),
+            r#"Bearer "quoted""#
+        );
+        assert_eq!(request.body.as_deref(), Some(r#"{"name":"it's"}"#));
+
+        let names: Vec<&str> = request.headers.keys().map(String::as_str).collect();
+        assert_eq!(names, ["accept", "authorization"]);
+    }
+
+    #[test]
+    fn test_from_curl_method_and_options() {
+        let request =
+            from_curl("curl -X put --url https://example.com/1 -u user:pass -o out.json -s")
+                .unwrap();
+        assert_eq!(request.method, Method::PUT);
+        assert_eq!(request.url, "https://example.com/1");
+        assert_eq!(
+            request.headers.get("Authorization").unwrap(),
+            "Basic dXNlcjpwYXNz"
+        );
+        assert!(request.body.is_none());
+
+        let head = from_curl("curl -I https://example.com").unwrap();
+        assert_eq!(head.method, Method::HEAD);
+    }
+
+    #[test]
+    fn test_from_curl_get_with_data_as_query() {
+        let request =
+            from_curl("curl -G https://example.com/search?x=1 -d q=rust -d page=2").unwrap();
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.url, "https://example.com/search?x=1&q=rust&page=2");
+        assert!(request.body.is_none());
+    }
+
+    #[test]
+    fn test_from_curl_attached_short_options() {
+        let request =
+            from_curl("curl -XPUT -HAccept:text/plain -dfoo=1 -uuser:pass -sSL https://x/1")
+                .unwrap();
+        assert_eq!(request.method, Method::PUT);
+        assert_eq!(request.url, "https://x/1");
+        assert_eq!(request.headers.get("Accept").unwrap(), "text/plain");
+        assert_eq!(request.body.as_deref(), Some("foo=1"));
+        assert_eq!(
+            request.headers.get("Authorization").unwrap(),
+            "Basic dXNlcjpwYXNz"
+        );
+
+        let request = from_curl("curl -XDELETE https://x/1").unwrap();
+        assert_eq!(request.method, Method::DELETE);
+        // 合并的短开关中的 -I 同样生效
+        let request = from_curl("curl -sI https://x/1").unwrap();
+        assert_eq!(request.method, Method::HEAD);
+    }
+
+    #[test]
+    fn test_from_curl_consumes_option_values() {
+        let request = from_curl(
+            "curl https://x/1 --max-redirs 5 -c jar.txt --resolve x:443:127.0.0.1 \
+             --connect-timeout 3 -A agent/1.0 -D headers.txt",
+        )
+        .unwrap();
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.url, "https://x/1");
+        assert_eq!(request.headers.get("User-Agent").unwrap(), "agent/1.0");
+    }
+
+    #[test]
+    fn test_from_curl_rejects_unsupported_options() {
+        for command in [
+            "curl https://x/upload -F file=@a.txt",
+            "curl https://x/upload --form file=@a.txt",
+            "curl https://x/upload -Ffile=@a.txt",
+        ] {
+            let error = from_curl(command).unwrap_err();
+            assert!(error.to_string().contains("multipart form"), "{command}");
+        }
+
+        let error = from_curl("curl https://x/1 --unknown-option value").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Unsupported curl option '--unknown-option'")
+        );
+        // 未知选项的参数不会被当作URL
+        let error = from_curl("curl -Z https://x/1").unwrap_err();
+        assert!(error.to_string().contains("'-Z'"), "{error}");
+        let error = from_curl("curl https://x/1 https://x/2").unwrap_err();
+        assert!(error.to_string().contains("more than one URL"));
+    }
+
+    #[test]
+    fn test_from_curl_errors() {
+        assert!(from_curl("wget https://example.com").is_err());
+        assert!(from_curl("curl -H 'X: 1'").is_err());
+        assert!(from_curl("curl 'https://example.com").is_err());
+        assert!(from_curl("curl https://example.com -H").is_err());
+    }
+
+    #[test]
+    fn test_to_http_round_trip() {
+        let request =
+            from_curl(r#"curl https://example.com/items -H 'Content-Type: text/plain' -d hello"#)
+                .unwrap();
+
+        assert_eq!(
+            to_http(&request),
+            "### POST https://example.com/items\nPOST https://example.com/items\nContent-Type: text/plain\n\nhello\n"
+        );
+    }
+
+    #[test]
+    fn test_is_curl_command() {
+        assert!(is_curl_command("curl https://example.com"));
+        assert!(!is_curl_command("curling https://example.com"));
+        assert!(!is_curl_command("GET https://example.com"));
+    }
+
+    #[test]
+    fn test_curl_http_version_round_trip() {
+        let request = from_curl("curl --http2 https://example.com/").unwrap();
+        assert_eq!(request.http_version, Some(HttpVersion::Http2));
+        assert_eq!(to_curl(&request), "curl --http2 https://example.com/");
+        assert!(to_http(&request).contains("GET https://example.com/ HTTP/2\n"));
+
+        let request = from_curl("curl --http1.1 https://example.com/").unwrap();
+        assert_eq!(request.http_version, Some(HttpVersion::Http11));
+    }
+}
@@ -0,0 +1,300 @@
+//! environment模块的单元测试
+
+use httpie::client::dns_overrides;
+use httpie::{Environment, EnvironmentLoader, RemoteEnvironment, SecretPolicy};
+use std::fs;
+use std::net::TcpListener;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENV_JSON: &str = r#"{"development": {"host": "https://dev.example.com"}}"#;
+
+    /// 本地监听但不处理的地址，用于确认没有发起下载
+    fn unused_listener() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let url = format!("https://{}/envs.json", listener.local_addr().unwrap());
+        (listener, url)
+    }
+
+    #[test]
+    fn test_remote_environment_is_cached() {
+        let (listener, url) = unused_listener();
+        let cache_dir = TempDir::new().unwrap();
+        let remote = RemoteEnvironment::new(url).with_cache_dir(cache_dir.path());
+        fs::write(remote.cache_path(), ENV_JSON).unwrap();
+
+        // 缓存未过期，不再请求
+        let path = remote.fetch().unwrap();
+        assert_eq!(path, remote.cache_path());
+        assert!(listener.accept().is_err());
+
+        let environment = Environment::from_file(&path.to_string_lossy()).unwrap();
+        assert_eq!(
+            environment.get("host"),
+            Some(&"https://dev.example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_remote_environment_falls_back_to_stale_cache() {
+        let url = {
+            let (_listener, url) = unused_listener();
+            url
+        };
+        let cache_dir = TempDir::new().unwrap();
+        let remote = RemoteEnvironment::new(url)
+            .with_cache_dir(cache_dir.path())
+            .with_cache_ttl(Duration::ZERO);
+        fs::write(remote.cache_path(), ENV_JSON).unwrap();
+
+        let path = remote.fetch().unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), ENV_JSON);
+    }
+
+    #[test]
+    fn test_remote_environment_errors() {
+        let cache_dir = TempDir::new().unwrap();
+
+        // 只接受 https:// 地址
+        let error = RemoteEnvironment::new("http://config.example.com/envs.json")
+            .with_cache_dir(cache_dir.path())
+            .fetch()
+            .unwrap_err();
+        assert!(error.to_string().contains("must be an https:// URL"));
+        assert!(EnvironmentLoader::load_from_path("http://config.example.com/envs.json").is_err());
+
+        // 下载失败且没有缓存
+        let url = {
+            let (_listener, url) = unused_listener();
+            url
+        };
+        let remote = RemoteEnvironment::new(url).with_cache_dir(cache_dir.path());
+        assert!(remote.fetch().is_err());
+        assert!(!remote.cache_path().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_remote_environment_refuses_shared_cache() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_listener, url) = unused_listener();
+        let cache_dir = TempDir::new().unwrap();
+        let remote = RemoteEnvironment::new(url).with_cache_dir(cache_dir.path());
+        fs::write(remote.cache_path(), ENV_JSON).unwrap();
+        fs::set_permissions(remote.cache_path(), fs::Permissions::from_mode(0o666)).unwrap();
+
+        let error = remote.fetch().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Refusing to use environment cache")
+        );
+    }
+
+    #[test]
+    fn test_remote_environment_refuses_secrets() {
+        let temp_dir = TempDir::new().unwrap();
+        let env_file = temp_dir.path().join("envs.json");
+        fs::write(
+            &env_file,
+            r#"{
+                "development": {"host": "https://dev.example.com"},
+                "staging": {"token": {"exec": "echo pwned"}},
+                "production": {"token": {"keyring": "myservice/token"}}
+            }"#,
+        )
+        .unwrap();
+        let env_files = [env_file];
+
+        let environment =
+            Environment::from_files_with_policy(&env_files, None, SecretPolicy::Deny).unwrap();
+        assert_eq!(
+            environment.get("host"),
+            Some(&"https://dev.example.com".to_string())
+        );
+
+        for (name, source) in [("staging", "exec"), ("production", "keyring")] {
+            let error =
+                Environment::from_files_with_policy(&env_files, Some(name), SecretPolicy::Deny)
+                    .unwrap_err();
+            assert!(
+                error.to_string().contains(&format!(
+                    "Secret 'token' uses '{source}', which is not allowed in remote environment files"
+                )),
+                "{error}"
+            );
+            // 按名称加载其他环境时沿用限制
+            assert!(environment.load_named(name).is_err());
+        }
+
+        // 本地环境文件默认只允许钥匙串
+        let environment = Environment::from_files_named(&env_files, "production").unwrap();
+        assert!(environment.secrets().contains_key("token"));
+        let error = Environment::from_files_named(&env_files, "staging").unwrap_err();
+        assert!(
+            error.to_string().contains("requires --allow-exec"),
+            "{error}"
+        );
+
+        let environment = Environment::from_files_with_policy(
+            &env_files,
+            Some("staging"),
+            SecretPolicy::AllowExec,
+        )
+        .unwrap();
+        assert!(environment.secrets().contains_key("token"));
+    }
+
+    #[test]
+    fn test_resolve_files() {
+        let repo = TempDir::new().unwrap();
+        fs::create_dir(repo.path().join(".git")).unwrap();
+        fs::write(repo.path().join("httpie.env.json"), ENV_JSON).unwrap();
+        let http_file = repo.path().join("api.http");
+
+        // 未指定环境文件时向上查找
+        let (files, policy) =
+            EnvironmentLoader::resolve_files(&http_file, None, false, |remote| remote).unwrap();
+        assert_eq!(
+            files,
+            vec![repo.path().canonicalize().unwrap().join("httpie.env.json")]
+        );
+        assert_eq!(policy, SecretPolicy::KeyringOnly);
+
+        let (files, policy) =
+            EnvironmentLoader::resolve_files(&http_file, Some("custom.json"), true, |remote| {
+                remote
+            })
+            .unwrap();
+        assert_eq!(files, vec![std::path::PathBuf::from("custom.json")]);
+        assert_eq!(policy, SecretPolicy::AllowExec);
+
+        // 远程环境文件使用缓存，不允许密钥
+        let (_listener, url) = unused_listener();
+        let cache_dir = TempDir::new().unwrap();
+        let remote = RemoteEnvironment::new(url.as_str()).with_cache_dir(cache_dir.path());
+        fs::write(remote.cache_path(), ENV_JSON).unwrap();
+        let (files, policy) = EnvironmentLoader::resolve_files(&http_file, Some(&url), true, |r| {
+            r.with_cache_dir(cache_dir.path())
+        })
+        .unwrap();
+        assert_eq!(files, vec![remote.cache_path()]);
+        assert_eq!(policy, SecretPolicy::Deny);
+
+        let error = EnvironmentLoader::resolve_files(
+            &http_file,
+            Some("http://example.com/envs.json"),
+            false,
+            |remote| remote,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("https://"), "{error}");
+    }
+
+    #[test]
+    fn test_load_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = [temp_dir.path().join("httpie.env.json")];
+        let environment =
+            EnvironmentLoader::load_files(&missing, None, SecretPolicy::KeyringOnly).unwrap();
+        assert!(environment.variables().is_empty());
+        // 显式指定环境时文件必须存在
+        assert!(
+            EnvironmentLoader::load_files(&missing, Some("staging"), SecretPolicy::KeyringOnly)
+                .is_err()
+        );
+
+        let env_file = temp_dir.path().join("envs.json");
+        fs::write(
+            &env_file,
+            r#"{"development": {"dns": {"api.test": "10.0.0.1:443", "web.test": "10.0.0.2:443"}}}"#,
+        )
+        .unwrap();
+        let env_files = [env_file];
+        let environment =
+            EnvironmentLoader::load_files(&env_files, None, SecretPolicy::KeyringOnly).unwrap();
+        assert!(
+            EnvironmentLoader::load_files(&env_files, Some("staging"), SecretPolicy::KeyringOnly)
+                .is_err()
+        );
+
+        // 未指定环境时无法解析的文件退回空环境，不允许的密钥来源仍然报错
+        let invalid = temp_dir.path().join("invalid.env.json");
+        fs::write(&invalid, "{not json").unwrap();
+        let fallback =
+            EnvironmentLoader::load_files(&[invalid], None, SecretPolicy::KeyringOnly).unwrap();
+        assert!(fallback.variables().is_empty());
+        let exec_file = temp_dir.path().join("exec.env.json");
+        fs::write(
+            &exec_file,
+            r#"{"development": {"token": {"exec": "echo secret"}}}"#,
+        )
+        .unwrap();
+        let error = EnvironmentLoader::load_files(&[exec_file], None, SecretPolicy::KeyringOnly)
+            .unwrap_err();
+        assert!(error.to_string().contains("--allow-exec"), "{error}");
+
+        // 命令行的解析覆盖优先于环境文件中的 dns 配置
+        let overrides = dns_overrides(&environment, ["api.test:8443:127.0.0.1"]).unwrap();
+        assert_eq!(overrides["api.test"].to_string(), "127.0.0.1:8443");
+        assert_eq!(overrides["web.test"].to_string(), "10.0.0.2:443");
+        assert!(dns_overrides(&environment, ["api.test"]).is_err());
+    }
+
+    #[test]
+    fn test_load_from_path_missing_file() {
+        let environment =
+            EnvironmentLoader::load_from_path("/nonexistent/httpie.env.json").unwrap();
+        assert!(environment.variables().is_empty());
+    }
+
+    #[test]
+    fn test_discover_walks_up_to_repo_root() {
+        let root = TempDir::new().unwrap();
+        let repo = root.path().join("repo");
+        let api = repo.join("services").join("api");
+        fs::create_dir_all(&api).unwrap();
+        fs::create_dir(repo.join(".git")).unwrap();
+        // 仓库之外的环境文件不参与合并
+        fs::write(root.path().join("httpie.env.json"), "{}").unwrap();
+        fs::write(
+            repo.join("httpie.env.json"),
+            r#"{"development": {"host": "https://repo.example.com", "token": "repo"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            api.join("httpie.env.json"),
+            r#"{"development": {"host": "https://api.example.com"}, "staging": {"host": "s"}}"#,
+        )
+        .unwrap();
+
+        let found = EnvironmentLoader::discover(&api.join("users.http"), "httpie.env.json");
+        let repo = repo.canonicalize().unwrap();
+        assert_eq!(
+            found,
+            vec![
+                repo.join("httpie.env.json"),
+                repo.join("services/api/httpie.env.json")
+            ]
+        );
+
+        // 近处文件覆盖远处同一环境的同名变量
+        let environment = Environment::from_files(&found).unwrap();
+        assert_eq!(
+            environment.get("host"),
+            Some(&"https://api.example.com".to_string())
+        );
+        assert_eq!(environment.get("token"), Some(&"repo".to_string()));
+        assert_eq!(environment.sources(), found.as_slice());
+
+        let staging = Environment::from_files_named(&found, "staging").unwrap();
+        assert_eq!(staging.get("token"), None);
+        assert!(Environment::from_files_named(&found, "production").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! error模块的单元测试
 
 use httpie::HttpieError;
+use httpie::error::SourceLocation;
 use std::io;
 
 #[cfg(test)]
@@ -159,6 +160,15 @@ mod tests {
             HttpieError::InvalidRequest("bad request".to_string()),
             HttpieError::ScriptError("script error".to_string()),
             HttpieError::ScriptParsingError("parsing error".to_string()),
+            HttpieError::RequestFailed("login".to_string(), "timed out".to_string()),
+            HttpieError::RunFailed(2),
+            HttpieError::Unformatted(1),
+            HttpieError::Syntax {
+                location: SourceLocation::default(),
+                message: "bad".to_string(),
+                source_line: String::new(),
+            },
+            HttpieError::Cancelled,
         ];
 
         for error in errors {
@@ -201,4 +211,25 @@ mod tests {
             assert!(e.to_string().contains("Parse error"));
         }
     }
+
+    #[test]
+    fn test_syntax_error_with_snippet() {
+        let location = SourceLocation {
+            file: None,
+            line: 12,
+            column: 5,
+        };
+        let error = HttpieError::InvalidRequest("expected URL".to_string())
+            .at(location, "GET")
+            .in_file("api.http");
+
+        assert_eq!(
+            error.to_string(),
+            "api.http:12:5: expected URL\n12 | GET\n   |     ^"
+        );
+
+        // 非解析类错误保持不变
+        let error = HttpieError::Cancelled.at(SourceLocation::default(), "GET");
+        assert!(matches!(error, HttpieError::Cancelled));
+    }
 }
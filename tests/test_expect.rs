@@ -0,0 +1,195 @@
+//! expect模块的单元测试
+
+use httpie::expect::diff_json;
+use httpie::{Directive, Headers, HttpRequest, ResponseExpectation, ResponseObject};
+use reqwest::Method;
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expectation_absent_without_directive() {
+        let request = create_request(vec![]);
+        assert!(
+            ResponseExpectation::from_request(&request)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_expectation_resolves_fixture_relative_to_source_file() {
+        let dir = TempDir::new().unwrap();
+        let request = create_request(vec![Directive::new(
+            "expect-response",
+            "./expected/login.json",
+        )])
+        .with_source_file(Some(dir.path().join("api.http")));
+
+        let expectation = ResponseExpectation::from_request(&request)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            expectation.fixture,
+            dir.path().join("./expected/login.json")
+        );
+    }
+
+    #[test]
+    fn test_expectation_matches_with_ignored_keys_and_masks() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("login.json"),
+            r#"{"token": "abc", "issued_at": "2024-01-01T00:00:00Z", "request_id": 1}"#,
+        )
+        .unwrap();
+
+        let request = create_request(vec![
+            Directive::new("expect-response", "login.json"),
+            Directive::new("expect-ignore", "request_id"),
+            Directive::new("expect-mask", r"\d{4}-\d{2}-\d{2}T[\d:]+Z"),
+        ])
+        .with_source_file(Some(dir.path().join("api.http")));
+
+        let response = create_response(json!({
+            "token": "abc",
+            "issued_at": "2025-06-30T12:34:56Z",
+            "request_id": 99
+        }));
+
+        let expectation = ResponseExpectation::from_request(&request)
+            .unwrap()
+            .unwrap();
+        let result = expectation.check(&response).unwrap();
+        assert!(result.passed, "{:?}", result.message);
+    }
+
+    #[test]
+    fn test_expectation_reports_structured_diff() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("user.json"),
+            r#"{"name": "alice", "roles": ["admin"], "active": true}"#,
+        )
+        .unwrap();
+
+        let request = create_request(vec![Directive::new("expect-response", "user.json")])
+            .with_source_file(Some(dir.path().join("api.http")));
+        let response = create_response(json!({"name": "bob", "roles": ["admin", "dev"]}));
+
+        let expectation = ResponseExpectation::from_request(&request)
+            .unwrap()
+            .unwrap();
+        let result = expectation.check(&response).unwrap();
+
+        assert!(!result.passed);
+        assert_eq!(result.error_kind.as_deref(), Some("ResponseMismatch"));
+        let message = result.message.unwrap();
+        assert!(message.contains("3 difference(s)"));
+        assert!(message.contains(r#"$.name: expected "alice", got "bob""#));
+        assert!(message.contains("$.active: missing"));
+        assert!(message.contains(r#"$.roles[1]: unexpected "dev""#));
+    }
+
+    #[test]
+    fn test_expectation_compares_text_fixtures_line_by_line() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("hello.txt"), "hello\nworld\n").unwrap();
+
+        let request = create_request(vec![Directive::new("expect-response", "hello.txt")])
+            .with_source_file(Some(dir.path().join("api.http")));
+        let response = create_response(json!("hello\nthere"));
+
+        let result = ResponseExpectation::from_request(&request)
+            .unwrap()
+            .unwrap()
+            .check(&response)
+            .unwrap();
+
+        assert!(!result.passed);
+        assert!(result.message.unwrap().contains("line 2"));
+    }
+
+    #[test]
+    fn test_expectation_compares_binary_bodies_exactly() {
+        let dir = TempDir::new().unwrap();
+        let image = [0x89, b'P', b'N', b'G', 0x00, 0xff, 0xfe];
+        fs::write(dir.path().join("logo.png"), image).unwrap();
+
+        let request = create_request(vec![Directive::new("expect-response", "logo.png")])
+            .with_source_file(Some(dir.path().join("api.http")));
+        let expectation = ResponseExpectation::from_request(&request)
+            .unwrap()
+            .unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("image/png"));
+
+        let response = ResponseObject::from_parts(200, &headers, &image);
+        assert!(expectation.check(&response).unwrap().passed);
+
+        let response = ResponseObject::from_parts(200, &headers, &image[..6]);
+        let result = expectation.check(&response).unwrap();
+        assert!(!result.passed);
+        assert!(
+            result
+                .message
+                .unwrap()
+                .contains("expected 7 bytes (sha256 ")
+        );
+    }
+
+    #[test]
+    fn test_expectation_missing_fixture_is_error() {
+        let request = create_request(vec![Directive::new(
+            "expect-response",
+            "/nonexistent/fixture.json",
+        )]);
+        let expectation = ResponseExpectation::from_request(&request)
+            .unwrap()
+            .unwrap();
+        assert!(expectation.check(&create_response(json!({}))).is_err());
+    }
+
+    #[test]
+    fn test_expectation_invalid_mask_is_error() {
+        let request = create_request(vec![
+            Directive::new("expect-response", "a.json"),
+            Directive::new("expect-mask", "(unclosed"),
+        ]);
+        assert!(ResponseExpectation::from_request(&request).is_err());
+    }
+
+    #[test]
+    fn test_diff_json_identical_values() {
+        let value = json!({"a": [1, 2, {"b": null}]});
+        let mut differences = Vec::new();
+        diff_json(&value, &value, "$", &mut differences);
+        assert!(differences.is_empty());
+    }
+
+    // 辅助函数：创建带指令的请求
+    fn create_request(directives: Vec<Directive>) -> HttpRequest {
+        HttpRequest::new(
+            "expect_test".to_string(),
+            Method::GET,
+            "https://example.com".to_string(),
+        )
+        .with_directives(directives)
+    }
+
+    // 辅助函数：创建测试用的ResponseObject
+    fn create_response(body: serde_json::Value) -> ResponseObject {
+        ResponseObject {
+            status: 200,
+            headers: Headers::new(),
+            body,
+            content_type: "application/json".to_string(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! format模块的单元测试
+
+use httpie::format::{self, HttpDocument, Node};
+use httpie::{Environment, HttpParser, HttpRequest};
+use std::fs;
+use tempfile::NamedTempFile;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = "\
+# 用户接口
+
+
+@host=http://localhost:8080
+@token =  abc
+### 登录
+# @name login
+POST   {{host}}/login   HTTP/1.1
+content-type:application/json
+x-request-id :  42
+
+{
+  \"user\": \"admin\"
+}
+
+
+> {%
+        client.test(\"ok\", function() {
+            client.assert(response.status === 200);
+        });
+%}
+###   获取用户
+GET {{host}}/users
+authorization: Bearer {{token}}
+??   status == 200
+";
+
+    const FORMATTED: &str = "\
+# 用户接口
+
+@host = http://localhost:8080
+@token = abc
+
+### 登录
+# @name login
+POST {{host}}/login HTTP/1.1
+Content-Type: application/json
+X-Request-Id: 42
+
+{
+  \"user\": \"admin\"
+}
+
+> {%
+    client.test(\"ok\", function() {
+        client.assert(response.status === 200);
+    });
+%}
+
+### 获取用户
+GET {{host}}/users
+Authorization: Bearer {{token}}
+?? status == 200
+";
+
+    #[test]
+    fn test_format_normalizes_document() {
+        assert_eq!(format::format(MESSY), FORMATTED);
+    }
+
+    #[test]
+    fn test_format_normalizes_handler_markers() {
+        let content = "### Split\nGET http://localhost/\n\n>\n{%\nclient.log(1);\n%\n}\n";
+        assert_eq!(
+            format::format(content),
+            "### Split\nGET http://localhost/\n\n> {%\n    client.log(1);\n%}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        assert_eq!(format::format(FORMATTED), FORMATTED);
+    }
+
+    #[test]
+    fn test_document_preserves_comments() {
+        let document = HttpDocument::parse(MESSY);
+        assert_eq!(document.preamble[0], Node::Text("# 用户接口".to_string()));
+        assert_eq!(document.requests.len(), 2);
+        assert_eq!(
+            document.requests[0].prelude,
+            vec![Node::Text("# @name login".to_string())]
+        );
+        assert_eq!(document.requests[1].title, "获取用户");
+    }
+
+    #[test]
+    fn test_format_keeps_curl_and_header_acronyms() {
+        let content = "### 导入\ncurl https://example.com \\\n  -H 'X-API-Key: 1'\n";
+        let formatted = format::format(content);
+        assert_eq!(
+            formatted,
+            "### 导入\ncurl https://example.com \\\n    -H 'X-API-Key: 1'\n"
+        );
+
+        let headers = format::format("### a\nGET /x\nX-API-Key: 1\n");
+        assert!(headers.contains("X-API-Key: 1"));
+
+        let defaults = format::format("### @defaults\n# 公共请求头\naccept:  */*\n");
+        assert_eq!(defaults, "### @defaults\n# 公共请求头\nAccept: */*\n");
+
+        let query = format::format("### a\nGET /x\n?a=1\n  &b=2\naccept: */*\n");
+        assert_eq!(query, "### a\nGET /x\n    ?a=1\n    &b=2\nAccept: */*\n");
+    }
+
+    #[test]
+    fn test_formatted_file_parses_the_same() {
+        let parse = |content: &str| {
+            let temp_file = NamedTempFile::new().unwrap();
+            fs::write(temp_file.path(), content).unwrap();
+            HttpParser::new(Environment::new())
+                .parse_file(&temp_file.path().to_string_lossy())
+                .unwrap()
+        };
+
+        let original = parse(MESSY);
+        let formatted = parse(FORMATTED);
+        assert_eq!(original.len(), formatted.len());
+        for (original, formatted) in original.iter().zip(&formatted) {
+            assert_eq!(original.name, formatted.name);
+            assert_eq!(original.url, formatted.url);
+            assert_eq!(original.body, formatted.body);
+            assert_eq!(original.assertions, formatted.assertions);
+            let script = |request: &HttpRequest| {
+                request
+                    .response_handler
+                    .as_deref()
+                    .map(|script| script.lines().map(str::trim).collect::<Vec<_>>().join("\n"))
+            };
+            assert_eq!(script(original), script(formatted));
+        }
+    }
+}
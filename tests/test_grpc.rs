@@ -0,0 +1,309 @@
+//! grpc模块的单元测试
+
+use httpie::grpc::{GrpcCall, PROTO_DIRECTIVE};
+#[cfg(feature = "grpc")]
+use httpie::grpc::{decode_messages, encode_message, load_method};
+use httpie::{Environment, HttpParser, HttpRequest};
+#[cfg(feature = "grpc")]
+use httpie::{HttpClient, Runner};
+#[cfg(feature = "grpc")]
+use prost_reflect::prost::Message;
+#[cfg(feature = "grpc")]
+use prost_reflect::prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    MethodDescriptorProto, ServiceDescriptorProto, field_descriptor_proto::Type,
+};
+#[cfg(feature = "grpc")]
+use serde_json::{Value, json};
+use std::fs;
+#[cfg(feature = "grpc")]
+use std::path::Path;
+use tempfile::NamedTempFile;
+#[cfg(feature = "grpc")]
+use tempfile::TempDir;
+#[cfg(feature = "grpc")]
+use tokio::net::TcpListener;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> httpie::Result<Vec<HttpRequest>> {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        HttpParser::new(Environment::new()).parse_file(&temp_file.path().to_string_lossy())
+    }
+
+    #[test]
+    fn test_parse_grpc_target() {
+        let (url, call) = GrpcCall::parse("localhost:50051/users.v1.UserService/GetUser").unwrap();
+        assert_eq!(url, "http://localhost:50051/users.v1.UserService/GetUser");
+        assert_eq!(call.service, "users.v1.UserService");
+        assert_eq!(call.method, "GetUser");
+        assert_eq!(call.to_string(), "users.v1.UserService/GetUser");
+
+        let (url, _) = GrpcCall::parse("grpcs://api.example.com/pkg.Svc/Call").unwrap();
+        assert_eq!(url, "https://api.example.com/pkg.Svc/Call");
+        let (url, _) = GrpcCall::parse("grpc://127.0.0.1:9000/Svc/Call").unwrap();
+        assert_eq!(url, "http://127.0.0.1:9000/Svc/Call");
+
+        for target in [
+            "localhost:50051",
+            "localhost/Svc",
+            "host/Svc/a/b",
+            "ftp://host/S/M",
+        ] {
+            let error = GrpcCall::parse(target).unwrap_err();
+            assert!(
+                error.to_string().contains("Invalid GRPC target"),
+                "{target}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_grpc_request() {
+        let requests = parse(
+            "### Get user\n# @proto ./api.desc\nGRPC localhost:50051/users.v1.UserService/GetUser\nAuthorization: Bearer t\n\n{\"id\": 1}\n\n?? $.name == alice\n",
+        )
+        .unwrap();
+
+        let request = &requests[0];
+        assert_eq!(request.method.as_str(), "POST");
+        assert_eq!(
+            request.url,
+            "http://localhost:50051/users.v1.UserService/GetUser"
+        );
+        assert_eq!(request.grpc.as_ref().unwrap().method, "GetUser");
+        assert_eq!(request.directive(PROTO_DIRECTIVE), Some("./api.desc"));
+        assert_eq!(request.body.as_deref(), Some("{\"id\": 1}"));
+        // 请求体发送前转码，不推断JSON的Content-Type
+        assert!(!request.headers.contains_key("Content-Type"));
+
+        let error = parse("### Missing proto\nGRPC localhost:50051/pkg.Svc/Call\n").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("GRPC request requires a '# @proto <descriptor set>' directive")
+        );
+    }
+
+    // 辅助函数：写入 `users.v1` 包的描述符集
+    #[cfg(feature = "grpc")]
+    fn write_descriptor(dir: &Path) -> std::path::PathBuf {
+        let field = |name: &str, number: i32, kind: Type| FieldDescriptorProto {
+            name: Some(name.to_string()),
+            json_name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(kind as i32),
+            label: Some(1),
+            ..Default::default()
+        };
+        let method = |name: &str, output: &str, streaming: bool| MethodDescriptorProto {
+            name: Some(name.to_string()),
+            input_type: Some(".users.v1.GetUserRequest".to_string()),
+            output_type: Some(output.to_string()),
+            server_streaming: Some(streaming),
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            name: Some("users.proto".to_string()),
+            package: Some("users.v1".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("GetUserRequest".to_string()),
+                    field: vec![field("id", 1, Type::Int32)],
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("User".to_string()),
+                    field: vec![field("id", 1, Type::Int32), field("name", 2, Type::String)],
+                    ..Default::default()
+                },
+            ],
+            service: vec![ServiceDescriptorProto {
+                name: Some("UserService".to_string()),
+                method: vec![
+                    method("GetUser", ".users.v1.User", false),
+                    method("WatchUser", ".users.v1.User", true),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let path = dir.join("api.desc");
+        let set = FileDescriptorSet { file: vec![file] };
+        fs::write(&path, set.encode_to_vec()).unwrap();
+        path
+    }
+
+    #[cfg(feature = "grpc")]
+    fn call(method: &str) -> GrpcCall {
+        GrpcCall {
+            service: "users.v1.UserService".to_string(),
+            method: method.to_string(),
+        }
+    }
+
+    #[cfg(feature = "grpc")]
+    #[test]
+    fn test_transcode_messages() {
+        let dir = TempDir::new().unwrap();
+        let method = load_method(&write_descriptor(dir.path()), &call("GetUser")).unwrap();
+
+        let framed = encode_message(method.input(), "{\"id\": 7}").unwrap();
+        assert_eq!(&framed[..5], &[0, 0, 0, 0, 2]);
+        let decoded = decode_messages(method.input(), &framed).unwrap();
+        assert_eq!(decoded, vec![json!({"id": 7})]);
+
+        // 空请求体视为空消息
+        let empty = encode_message(method.input(), "").unwrap();
+        assert_eq!(&empty[..], &[0, 0, 0, 0, 0]);
+
+        let error = encode_message(method.input(), "{\"nickname\": 1}").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid users.v1.GetUserRequest message")
+        );
+        let error = decode_messages(method.output(), &framed[..3]).unwrap_err();
+        assert!(error.to_string().contains("truncated message prefix"));
+    }
+
+    #[cfg(feature = "grpc")]
+    #[test]
+    fn test_load_method_errors() {
+        let dir = TempDir::new().unwrap();
+        let path = write_descriptor(dir.path());
+
+        let error = load_method(&path, &call("DeleteUser")).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Method 'DeleteUser' not found in service 'users.v1.UserService'")
+        );
+        let other = GrpcCall {
+            service: "orders.v1.OrderService".to_string(),
+            method: "Get".to_string(),
+        };
+        let error = load_method(&path, &other).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Service 'orders.v1.OrderService' not found")
+        );
+
+        fs::write(&path, b"not a descriptor").unwrap();
+        let error = load_method(&path, &call("GetUser")).unwrap_err();
+        assert!(error.to_string().contains("Invalid descriptor set"));
+    }
+
+    /// 启动一个明文HTTP/2的GRPC测试服务：`GetUser` 对 id 1 返回用户，其余返回 NOT_FOUND；
+    /// `WatchUser` 返回两条消息
+    #[cfg(feature = "grpc")]
+    async fn start_server(dir: &Path) -> String {
+        let method = load_method(&dir.join("api.desc"), &call("GetUser")).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                let method = method.clone();
+                tokio::spawn(async move {
+                    let mut connection = h2::server::handshake(socket).await.unwrap();
+                    while let Some(Ok((request, mut respond))) = connection.accept().await {
+                        let path = request.uri().path().to_string();
+                        let metadata = request
+                            .headers()
+                            .get("authorization")
+                            .map(|v| v.to_str().unwrap().to_string());
+                        let mut body = request.into_body();
+                        let mut data = Vec::new();
+                        while let Some(chunk) = body.data().await {
+                            let chunk = chunk.unwrap();
+                            let _ = body.flow_control().release_capacity(chunk.len());
+                            data.extend_from_slice(&chunk);
+                        }
+                        let input = decode_messages(method.input(), &data).unwrap();
+                        let id = input[0]["id"].as_i64().unwrap_or_default();
+
+                        let ok = http::Response::builder()
+                            .status(200)
+                            .header("content-type", "application/grpc")
+                            .body(())
+                            .unwrap();
+                        let user = |name: &str| {
+                            let json = json!({"id": id, "name": name}).to_string();
+                            encode_message(method.output(), &json).unwrap()
+                        };
+                        let messages = match (path.as_str(), id) {
+                            ("/users.v1.UserService/WatchUser", _) => {
+                                vec![user("alice"), user("alice (renamed)")]
+                            }
+                            (_, 1) => vec![user(metadata.as_deref().unwrap_or("alice"))],
+                            _ => {
+                                // 只有尾部的错误响应
+                                let error = http::Response::builder()
+                                    .status(200)
+                                    .header("content-type", "application/grpc")
+                                    .header("grpc-status", "5")
+                                    .header("grpc-message", "user not found")
+                                    .body(())
+                                    .unwrap();
+                                respond.send_response(error, true).unwrap();
+                                continue;
+                            }
+                        };
+                        let mut stream = respond.send_response(ok, false).unwrap();
+                        for message in messages {
+                            stream.send_data(message, false).unwrap();
+                        }
+                        let mut trailers = http::HeaderMap::new();
+                        trailers.insert("grpc-status", "0".parse().unwrap());
+                        stream.send_trailers(trailers).unwrap();
+                    }
+                });
+            }
+        });
+        format!("127.0.0.1:{}", addr.port())
+    }
+
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn test_grpc_request_end_to_end() {
+        let dir = TempDir::new().unwrap();
+        write_descriptor(dir.path());
+        let host = start_server(dir.path()).await;
+        let http_file = dir.path().join("users.http");
+        fs::write(
+            &http_file,
+            format!(
+                "### Get user\n# @proto ./api.desc\nGRPC {host}/users.v1.UserService/GetUser\nAuthorization: alice\n\n{{\"id\": 1}}\n\n?? $.name == alice\n?? header grpc-status == 0\n\n\
+                 ### Missing user\n# @proto ./api.desc\nGRPC {host}/users.v1.UserService/GetUser\n\n{{\"id\": 2}}\n\n?? $.code == 5\n?? header grpc-message == user not found\n\n\
+                 ### Watch user\n# @proto ./api.desc\nGRPC {host}/users.v1.UserService/WatchUser\n\n{{\"id\": 1}}\n\n?? $[1].name == alice (renamed)\n"
+            ),
+        )
+        .unwrap();
+        let requests = HttpParser::new(Environment::new())
+            .parse_file(&http_file.to_string_lossy())
+            .unwrap();
+
+        let mut client = HttpClient::new().with_print_response(false);
+        let outcome = client.execute_with_outcome(&requests[0]).await.unwrap();
+        assert_eq!(outcome.response.status, 200);
+        assert_eq!(outcome.response.body, json!({"id": 1, "name": "alice"}));
+        assert!(outcome.passed(), "{:?}", outcome.test_results);
+
+        let mut runner = Runner::new(HttpClient::new().with_print_response(false));
+        let report = runner.run(&requests).await.unwrap();
+        let failures: Vec<Value> = report
+            .requests
+            .iter()
+            .flat_map(|r| r.test_results.iter().filter(|t| !t.passed))
+            .map(|t| json!([t.name, t.message]))
+            .collect();
+        assert!(report.success(), "{failures:?}");
+        assert_eq!(report.requests.len(), 3);
+    }
+}
@@ -0,0 +1,59 @@
+//! handler模块的单元测试
+
+use httpie::handler::{HandlerBlock, find};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_standard_block() {
+        let lines = ["{}", "", "> {%  ", "client.log(1);", "%}", "trailer"];
+        assert_eq!(
+            find(&lines),
+            Some(HandlerBlock {
+                start: 2,
+                end: Some(5),
+                script: vec!["client.log(1);"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_find_split_markers() {
+        for lines in [
+            vec![">", "{%", "client.log(1);", "%", "}"],
+            vec!["> {", "%", "client.log(1);", "% }"],
+            vec![">{%", "client.log(1); %}"],
+        ] {
+            let block = find(&lines).unwrap();
+            assert_eq!(block.start, 0);
+            assert_eq!(block.end, Some(lines.len()));
+            assert_eq!(block.script(), "client.log(1);");
+        }
+    }
+
+    #[test]
+    fn test_find_inline_block() {
+        let block = find(&["> {% client.log(1) %}"]).unwrap();
+        assert_eq!(block.end, Some(1));
+        assert_eq!(block.script(), "client.log(1)");
+    }
+
+    #[test]
+    fn test_find_not_a_block() {
+        // 单独的 `>` 后面不是 `{%` 时不是处理器
+        assert_eq!(find(&[">", "text", ">> out.json"]), None);
+        // 脚本中单独的 `%` 不是结束标记
+        let block = find(&["> {%", "%", "x", "%}"]).unwrap();
+        assert_eq!(block.script, vec!["%", "x"]);
+    }
+
+    #[test]
+    fn test_find_unterminated() {
+        let block = find(&["body", "> {%", "client.log(1);"]).unwrap();
+        assert_eq!(block.start, 1);
+        assert_eq!(block.end, None);
+        assert_eq!(block.script, vec!["client.log(1);"]);
+    }
+}
@@ -0,0 +1,241 @@
+//! history模块的单元测试
+
+use httpie::history::{MASKED_VALUE, format_age};
+use httpie::{
+    Environment, History, HistoryEntry, HistoryRecorder, HttpClient, HttpRequest, Runner,
+    SecretPolicy,
+};
+use mockito::Server;
+use reqwest::Method;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            timestamp: 1_700_000_000,
+            name: name.to_string(),
+            method: "POST".to_string(),
+            url: "https://api.example.com/users?page=2".to_string(),
+            fingerprint: None,
+            request_headers: [("Content-Type".to_string(), "application/json".to_string())]
+                .into_iter()
+                .collect(),
+            request_body: Some(r#"{"name": "alice"}"#.to_string()),
+            body_file: None,
+            status: Some(201),
+            duration_ms: 12,
+            response_headers: Default::default(),
+            response_body: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_history_append_and_get() {
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("nested/history.jsonl"));
+        assert!(history.entries().unwrap().is_empty());
+
+        assert_eq!(history.append(entry("first")).unwrap(), 1);
+        assert_eq!(history.append(entry("second")).unwrap(), 2);
+
+        let entries = history.entries().unwrap();
+        assert_eq!(
+            entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+        assert_eq!(history.get(2).unwrap().name, "second");
+        assert!(history.get(3).is_err());
+    }
+
+    #[test]
+    fn test_history_append_skips_invalid_lines() {
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("history.jsonl"));
+        history.append(entry("first")).unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(history.path())
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"{not json\n").unwrap();
+
+        // 损坏的行不影响编号和后续追加
+        assert_eq!(history.append(entry("second")).unwrap(), 2);
+        let names: Vec<String> = history
+            .entries()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, ["first", "second"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_history_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("nested/history.jsonl"));
+        history.append(entry("first")).unwrap();
+        let mode =
+            |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(history.path()), 0o600);
+        assert_eq!(mode(&dir.path().join("nested")), 0o700);
+    }
+
+    #[test]
+    fn test_history_entry_to_request() {
+        let request = entry("create").to_request().unwrap();
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.url, "https://api.example.com/users?page=2");
+        assert_eq!(request.headers["Content-Type"], "application/json");
+        assert_eq!(request.body.as_deref(), Some(r#"{"name": "alice"}"#));
+
+        // 请求体文件重新读取，已屏蔽的请求头不发送
+        let mut entry = entry("upload");
+        entry.request_body = None;
+        entry.body_file = Some("/tmp/payload.json".to_string());
+        entry
+            .request_headers
+            .insert("Authorization".to_string(), MASKED_VALUE.to_string());
+        let request = entry.to_request().unwrap();
+        assert_eq!(request.body_file.as_deref(), Some("/tmp/payload.json"));
+        assert!(!request.headers.contains_key("Authorization"));
+        assert_eq!(request.headers["Content-Type"], "application/json");
+    }
+
+    #[test]
+    fn test_history_entry_output() {
+        let mut entry = entry("create");
+        entry.id = 7;
+        assert_eq!(
+            entry.summary_line(1_700_000_090),
+            "#7       1m ago  201  POST https://api.example.com/users?page=2 (12 ms)  create"
+        );
+        assert_eq!(
+            entry.to_string(),
+            "#7 create\nPOST https://api.example.com/users?page=2\n\
+             Content-Type: application/json\n\n{\"name\": \"alice\"}\n\nStatus: 201 (12 ms)\n"
+        );
+
+        entry.status = None;
+        entry.error = Some("connection refused".to_string());
+        assert!(entry.summary_line(1_700_000_000).contains("0s ago  ERR "));
+        assert!(entry.to_string().ends_with("\nError: connection refused\n"));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(59), "59s ago");
+        assert_eq!(format_age(3599), "59m ago");
+        assert_eq!(format_age(7200), "2h ago");
+        assert_eq!(format_age(3 * 86400), "3d ago");
+    }
+
+    #[tokio::test]
+    async fn test_history_recorder() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/users?page=2")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"users": []}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("history.jsonl"));
+        let request = HttpRequest::new(
+            "users".to_string(),
+            Method::GET,
+            format!("{}/users", server.url()),
+        )
+        .with_query(vec![("page".to_string(), "2".to_string())]);
+
+        let client = HttpClient::new().with_print_response(false);
+        let mut runner = Runner::new(client)
+            .with_observer(HistoryRecorder::new(history.clone()).with_bodies(true));
+        runner.run(&[request]).await.unwrap();
+
+        // 未请求响应体时只记录摘要
+        let client = HttpClient::new().with_print_response(false);
+        let mut runner = Runner::new(client).with_observer(HistoryRecorder::new(history.clone()));
+        runner
+            .run(&[history.get(1).unwrap().to_request().unwrap()])
+            .await
+            .unwrap();
+
+        let entries = history.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        let first = &entries[0];
+        assert_eq!(first.url, format!("{}/users?page=2", server.url()));
+        assert_eq!(first.status, Some(200));
+        assert_eq!(first.response_headers["content-type"], "application/json");
+        assert_eq!(first.response_body.as_deref(), Some(r#"{"users":[]}"#));
+        assert_eq!(entries[1].url, first.url);
+        // 重新执行的请求与原请求指纹相同
+        assert_eq!(first.fingerprint.as_ref().map(String::len), Some(16));
+        assert_eq!(entries[1].fingerprint, first.fingerprint);
+        assert_eq!(entries[1].status, Some(200));
+        assert_eq!(entries[1].response_body, None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_history_recorder_masks_sensitive_headers() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/me")
+            .with_header("set-cookie", "sid=abc123")
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let env_file = dir.path().join("httpie.env.json");
+        std::fs::write(
+            &env_file,
+            r#"{"development": {"api_key": {"exec": "echo s3cr3t"}}}"#,
+        )
+        .unwrap();
+        let mut environment =
+            Environment::from_files_with_policy(&[env_file], None, SecretPolicy::AllowExec)
+                .unwrap();
+        environment.resolve_secrets("{{api_key}}").unwrap();
+
+        let history = History::new(dir.path().join("history.jsonl"));
+        let request = HttpRequest::new(
+            "me".to_string(),
+            Method::GET,
+            format!("{}/me", server.url()),
+        )
+        .with_headers(
+            [
+                ("Authorization", "Bearer token"),
+                ("Cookie", "sid=abc123"),
+                ("X-Api-Key", "s3cr3t"),
+                ("Accept", "application/json"),
+            ]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<Vec<_>>(),
+        );
+
+        let client = HttpClient::new().with_print_response(false);
+        let mut runner = Runner::new(client)
+            .with_observer(HistoryRecorder::new(history.clone()).with_environment(&environment));
+        runner.run(&[request]).await.unwrap();
+
+        let entry = history.get(1).unwrap();
+        for name in ["Authorization", "Cookie", "X-Api-Key"] {
+            assert_eq!(entry.request_headers[name], MASKED_VALUE);
+        }
+        assert_eq!(entry.request_headers["Accept"], "application/json");
+        assert_eq!(entry.response_headers["set-cookie"], MASKED_VALUE);
+        let content = std::fs::read_to_string(history.path()).unwrap();
+        assert!(!content.contains("s3cr3t") && !content.contains("abc123"));
+    }
+}
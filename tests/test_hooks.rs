@@ -0,0 +1,52 @@
+//! hooks模块的单元测试
+
+use httpie::{ScriptHook, ScriptHooks};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_function_names() {
+        let names: Vec<String> = ScriptHook::ALL.iter().map(|h| h.to_string()).collect();
+        assert_eq!(
+            names,
+            vec!["beforeAll", "beforeEach", "afterEach", "afterAll"]
+        );
+    }
+
+    #[test]
+    fn test_program_strips_export_keywords() {
+        let hooks = ScriptHooks::new(
+            "httpie.hooks.js",
+            "export function beforeAll() {}\n  export const afterAll = () => {};\nconst label = \"export default\";",
+        );
+        let program = hooks.program();
+
+        assert!(program.contains("\nfunction beforeAll() {}\n"));
+        assert!(program.contains("\nconst afterAll = () => {};\n"));
+        assert!(program.contains("const label = \"export default\";"));
+        assert!(program.starts_with("globalThis.__httpie_hooks = (function () {"));
+    }
+
+    #[test]
+    fn test_load_hooks_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("httpie.hooks.js");
+        fs::write(&path, "function beforeAll() {}").unwrap();
+
+        let hooks = ScriptHooks::load(&path).unwrap();
+        assert_eq!(hooks.path(), path.as_path());
+        assert!(hooks.program().contains("function beforeAll() {}"));
+
+        let error = ScriptHooks::load(Path::new("missing.hooks.js")).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Failed to read hooks missing.hooks.js")
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! jsonpath模块的单元测试
+
+use httpie::jsonpath::{self, Segment};
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_segments() {
+        assert_eq!(
+            jsonpath::parse("$.data['user-name'][0].id").unwrap(),
+            vec![
+                Segment::Key("data".to_string()),
+                Segment::Key("user-name".to_string()),
+                Segment::Index(0),
+                Segment::Key("id".to_string()),
+            ]
+        );
+        assert!(jsonpath::parse("$").unwrap().is_empty());
+        assert!(jsonpath::parse("data.id").is_err());
+        assert!(jsonpath::parse("$..id").is_err());
+        assert!(jsonpath::parse("$[abc]").is_err());
+    }
+
+    #[test]
+    fn test_select_values() {
+        let value = json!({
+            "access_token": "abc",
+            "items": [{"id": 1}, {"id": 2}, {"id": 3}],
+        });
+
+        assert_eq!(
+            jsonpath::select(&value, "$.access_token").unwrap(),
+            Some(&json!("abc"))
+        );
+        assert_eq!(
+            jsonpath::select(&value, "$.items[1].id").unwrap(),
+            Some(&json!(2))
+        );
+        assert_eq!(
+            jsonpath::select(&value, "$.items[-1].id").unwrap(),
+            Some(&json!(3))
+        );
+        assert_eq!(jsonpath::select(&value, "$.items[5]").unwrap(), None);
+        assert_eq!(jsonpath::select(&value, "$.missing.id").unwrap(), None);
+        assert_eq!(jsonpath::select(&value, "$").unwrap(), Some(&value));
+    }
+
+    #[test]
+    fn test_wildcard_selects_all() {
+        assert_eq!(
+            jsonpath::parse("$.items[*].id").unwrap(),
+            vec![
+                Segment::Key("items".to_string()),
+                Segment::Wildcard,
+                Segment::Key("id".to_string()),
+            ]
+        );
+        assert_eq!(jsonpath::parse("$.user.*").unwrap()[1], Segment::Wildcard);
+
+        let value = json!({
+            "items": [{"id": 1}, {"name": "no id"}, {"id": 3}],
+            "user": {"id": 7, "name": "alice"},
+        });
+        assert_eq!(
+            jsonpath::select_all(&value, "$.items[*].id").unwrap(),
+            vec![&json!(1), &json!(3)]
+        );
+        assert_eq!(
+            jsonpath::select_all(&value, "$.user.*").unwrap(),
+            vec![&json!(7), &json!("alice")]
+        );
+        assert_eq!(
+            jsonpath::select(&value, "$.items[*].id").unwrap(),
+            Some(&json!(1))
+        );
+    }
+
+    #[test]
+    fn test_extract_fragment() {
+        let value = json!({"data": {"items": [{"id": 1}, {"id": 2}]}});
+        assert_eq!(
+            jsonpath::extract(&value, "$.data.items[*].id").unwrap(),
+            Some(json!([1, 2]))
+        );
+        assert_eq!(
+            jsonpath::extract(&value, "$.data.items[0]").unwrap(),
+            Some(json!({"id": 1}))
+        );
+        assert_eq!(jsonpath::extract(&value, "$.data.total").unwrap(), None);
+        assert_eq!(
+            jsonpath::extract(&value, "$.missing[*]").unwrap(),
+            Some(json!([]))
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! listen模块的单元测试
+
+use httpie::listen::{DEFAULT_LISTEN_TIMEOUT, LISTEN_METHOD, Listen};
+use httpie::{Environment, HttpClient, HttpParser, HttpRequest, Runner};
+use std::fs;
+use std::net::TcpListener;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn free_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    fn parse(content: &str) -> Vec<HttpRequest> {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        HttpParser::new(Environment::new())
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_listen_args() {
+        let listen = Listen::parse(&[":8089", "/callback", "timeout=5s"]).unwrap();
+        assert_eq!(listen.port, 8089);
+        assert_eq!(listen.path.as_deref(), Some("/callback"));
+        assert_eq!(listen.timeout(), Duration::from_secs(5));
+        assert_eq!(listen.url(), "http://localhost:8089/callback");
+
+        let listen = Listen::parse(&["localhost:8089/hooks/paid"]).unwrap();
+        assert_eq!(listen.path.as_deref(), Some("/hooks/paid"));
+        assert_eq!(listen.timeout(), DEFAULT_LISTEN_TIMEOUT);
+
+        let listen = Listen::parse(&["8089"]).unwrap();
+        assert_eq!(listen.path, None);
+        assert_eq!(listen.url(), "http://localhost:8089/");
+
+        assert!(Listen::parse(&[]).is_err());
+        assert!(Listen::parse(&[":abc"]).is_err());
+        assert!(Listen::parse(&[":0"]).is_err());
+        assert!(Listen::parse(&[":8089", "/callback", "extra"]).is_err());
+        assert!(Listen::parse(&[":8089", "timeout=soon"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_listen_step() {
+        let requests = parse(
+            r#"
+### Wait for webhook
+LISTEN :8089 /callback timeout=10s
+Content-Type: application/json
+
+{"received": true}
+
+?? $.event == paid
+"#,
+        );
+
+        let request = &requests[0];
+        assert_eq!(request.method.as_str(), LISTEN_METHOD);
+        assert_eq!(request.url, "http://localhost:8089/callback");
+        assert_eq!(request.http_version, None);
+        let listen = request.listen.as_ref().unwrap();
+        assert_eq!(listen.timeout(), Duration::from_secs(10));
+        assert_eq!(request.body.as_deref(), Some(r#"{"received": true}"#));
+        assert_eq!(request.assertions, ["$.event == paid"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_receives_callback() {
+        let port = free_port();
+        let requests = parse(&format!(
+            r#"
+### Wait for webhook
+LISTEN :{port} /callback timeout=10s
+Content-Type: application/json
+
+{{"received": true}}
+
+?? $.event == paid
+?? header x-signature == abc
+"#
+        ));
+
+        // 路径不匹配的请求得到404，不结束等待
+        let sender = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let send = |path: &'static str| {
+                client
+                    .post(format!("http://127.0.0.1:{port}{path}"))
+                    .header("X-Signature", "abc")
+                    .json(&serde_json::json!({"event": "paid"}))
+                    .send()
+            };
+            loop {
+                match send("/other").await {
+                    Ok(response) => {
+                        assert_eq!(response.status(), 404);
+                        break;
+                    }
+                    Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+                }
+            }
+            let response = send("/callback?attempt=1").await.unwrap();
+            assert_eq!(response.status(), 200);
+            response.text().await.unwrap()
+        });
+
+        let client = HttpClient::new().with_print_response(false);
+        let report = Runner::new(client).run(&requests).await.unwrap();
+
+        assert!(report.success(), "{:?}", report.requests[0]);
+        assert_eq!(report.requests[0].status, Some(200));
+        assert_eq!(report.requests[0].test_results.len(), 2);
+        assert_eq!(sender.await.unwrap(), r#"{"received": true}"#);
+    }
+
+    #[tokio::test]
+    async fn test_run_listen_timeout() {
+        let port = free_port();
+        let requests = parse(&format!(
+            "### Wait for webhook\nLISTEN :{port} /callback timeout=100ms\n"
+        ));
+
+        let client = HttpClient::new().with_print_response(false);
+        let report = Runner::new(client).run(&requests).await.unwrap();
+
+        let error = report.requests[0].error.as_deref().unwrap();
+        assert!(error.contains("no request received"), "{error}");
+    }
+}
@@ -0,0 +1,93 @@
+//! metrics模块的单元测试
+
+use httpie::metrics::{prometheus_text, push};
+use httpie::{RequestReport, RunReport, TestResult};
+use mockito::{Matcher, Server};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(name: &str, status: Option<u16>, millis: u64) -> RequestReport {
+        RequestReport {
+            name: name.to_string(),
+            method: "GET".to_string(),
+            url: "https://api.example.com".to_string(),
+            status,
+            duration: Duration::from_millis(millis),
+            test_results: Vec::new(),
+            error: status.is_none().then(|| "connection refused".to_string()),
+            iteration: None,
+            hook: None,
+            skipped: None,
+            logs: Vec::new(),
+            xfail: None,
+        }
+    }
+
+    fn report() -> RunReport {
+        let mut health = request("health \"check\"", Some(200), 20);
+        health.test_results = vec![
+            TestResult::success("status"),
+            TestResult::failure("body", "mismatch", "AssertionError"),
+        ];
+        RunReport {
+            requests: vec![
+                health,
+                request("health \"check\"", Some(503), 300),
+                request("orders", None, 0),
+            ],
+            duration: Duration::from_millis(1500),
+            cancelled: false,
+            aborted: None,
+        }
+    }
+
+    #[test]
+    fn test_prometheus_text() {
+        let text = prometheus_text(&report());
+        let lines: Vec<&str> = text.lines().collect();
+
+        for expected in [
+            r#"httpie_requests_total{request="health \"check\"",method="GET",status_class="2xx"} 1"#,
+            r#"httpie_requests_total{request="health \"check\"",method="GET",status_class="5xx"} 1"#,
+            r#"httpie_requests_total{request="orders",method="GET",status_class="error"} 1"#,
+            r#"httpie_request_duration_seconds_bucket{request="health \"check\"",le="0.025"} 1"#,
+            r#"httpie_request_duration_seconds_bucket{request="health \"check\"",le="0.5"} 2"#,
+            r#"httpie_request_duration_seconds_bucket{request="health \"check\"",le="+Inf"} 2"#,
+            r#"httpie_request_duration_seconds_count{request="health \"check\""} 2"#,
+            r#"httpie_tests_total{request="health \"check\"",result="failed"} 1"#,
+            r#"httpie_tests_total{request="health \"check\"",result="passed"} 1"#,
+            "httpie_run_duration_seconds 1.5",
+            "httpie_run_success 0",
+            "# TYPE httpie_request_duration_seconds histogram",
+        ] {
+            assert!(lines.contains(&expected), "missing line: {expected}");
+        }
+        // 传输错误没有耗时样本
+        assert!(!text.contains(r#"httpie_request_duration_seconds_count{request="orders"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_push_to_pushgateway() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/metrics/job/httpie")
+            .match_body(Matcher::Regex("httpie_run_success 0".to_string()))
+            .with_status(200)
+            .create_async()
+            .await;
+        let url = format!("{}/metrics/job/httpie", server.url());
+        push(&url, &report()).await.unwrap();
+        mock.assert_async().await;
+
+        server
+            .mock("PUT", "/metrics/job/broken")
+            .with_status(400)
+            .create_async()
+            .await;
+        let url = format!("{}/metrics/job/broken", server.url());
+        assert!(push(&url, &report()).await.is_err());
+    }
+}
@@ -1,9 +1,10 @@
 //! models模块的单元测试
 
-use httpie::{Environment, HttpRequest};
+use httpie::{Directive, Environment, Headers, HttpRequest, RequestSuite};
 use reqwest::Method;
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 
 use tempfile::NamedTempFile;
 
@@ -29,7 +30,7 @@ mod tests {
 
     #[test]
     fn test_http_request_with_headers() {
-        let mut headers = HashMap::new();
+        let mut headers = Headers::new();
         headers.insert("Content-Type".to_string(), "application/json".to_string());
         headers.insert("Authorization".to_string(), "Bearer token".to_string());
 
@@ -80,7 +81,7 @@ mod tests {
 
     #[test]
     fn test_http_request_builder_pattern() {
-        let mut headers = HashMap::new();
+        let mut headers = Headers::new();
         headers.insert("Content-Type".to_string(), "application/json".to_string());
 
         let body = "{\"test\": true}".to_string();
@@ -105,6 +106,35 @@ mod tests {
         assert_eq!(request.response_handler, Some(script));
     }
 
+    #[test]
+    fn test_http_request_directives_and_paths() {
+        let request = HttpRequest::new(
+            "directive_request".to_string(),
+            Method::GET,
+            "https://example.com".to_string(),
+        )
+        .with_directives(vec![
+            Directive::new("expect-mask", "a"),
+            Directive::new("expect-mask", "b"),
+        ])
+        .with_source_file(Some(PathBuf::from("/suites/api.http")));
+
+        assert_eq!(request.directive("expect-mask"), Some("a"));
+        assert_eq!(
+            request.directives_named("expect-mask").collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert!(request.directive("missing").is_none());
+        assert_eq!(
+            request.resolve_path("data/body.json"),
+            PathBuf::from("/suites/data/body.json")
+        );
+        assert_eq!(
+            request.resolve_path("/abs/body.json"),
+            PathBuf::from("/abs/body.json")
+        );
+    }
+
     #[test]
     fn test_environment_creation() {
         let env = Environment::new();
@@ -177,6 +207,35 @@ mod tests {
         assert_eq!(env.variables().len(), 3);
     }
 
+    #[test]
+    fn test_environment_from_file_named() {
+        let env_content = r#"{
+  "development": {"BASE_URL": "https://dev.example.com"},
+  "staging": {"BASE_URL": "https://staging.example.com"}
+}"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), env_content).unwrap();
+        let path = temp_file.path().to_string_lossy();
+
+        let env = Environment::from_file_named(&path, "staging").unwrap();
+        assert_eq!(
+            env.get("BASE_URL"),
+            Some(&"https://staging.example.com".to_string())
+        );
+        assert_eq!(env.name(), Some("staging"));
+        assert_eq!(env.source(), Some(temp_file.path()));
+
+        let env = Environment::from_file(&path).unwrap();
+        assert_eq!(env.name(), Some("development"));
+
+        let err = Environment::from_file_named(&path, "production").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Environment 'production' not found")
+        );
+    }
+
     #[test]
     fn test_environment_from_file_with_dns_overrides() {
         let env_content = r#"{
@@ -207,6 +266,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_environment_from_file_merges_shared() {
+        let env_content = r#"{
+  "$shared": {
+    "BASE_URL": "https://api.example.com",
+    "ACCEPT": "application/json",
+    "dns": {"api.example.com": "127.0.0.1:8000"}
+  },
+  "development": {"BASE_URL": "http://localhost:8080"},
+  "production": {"TOKEN": "prod"}
+}"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), env_content).unwrap();
+        let path = temp_file.path().to_string_lossy();
+
+        // 具体环境覆盖共享值
+        let env = Environment::from_file(&path).unwrap();
+        assert_eq!(
+            env.get("BASE_URL"),
+            Some(&"http://localhost:8080".to_string())
+        );
+        assert_eq!(env.get("ACCEPT"), Some(&"application/json".to_string()));
+        assert_eq!(env.dns_overrides().len(), 1);
+
+        let env = Environment::from_file_named(&path, "production").unwrap();
+        assert_eq!(
+            env.get("BASE_URL"),
+            Some(&"https://api.example.com".to_string())
+        );
+        assert_eq!(env.get("TOKEN"), Some(&"prod".to_string()));
+
+        // 共享配置不能代替缺失的环境
+        assert!(Environment::from_file_named(&path, "staging").is_err());
+    }
+
+    #[test]
+    fn test_environment_from_file_extends() {
+        let env_content = r#"{
+  "$shared": {"ACCEPT": "application/json", "REGION": "shared"},
+  "development": {"BASE_URL": "http://localhost:8080", "DEBUG": true, "REGION": "local"},
+  "staging": {"extends": "development", "BASE_URL": "https://staging.example.com"},
+  "staging-eu": {"extends": "staging", "REGION": "eu"},
+  "broken": {"extends": "missing"},
+  "loop-a": {"extends": "loop-b"},
+  "loop-b": {"extends": "loop-a"}
+}"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), env_content).unwrap();
+        let path = temp_file.path().to_string_lossy();
+
+        let env = Environment::from_file_named(&path, "staging-eu").unwrap();
+        assert_eq!(
+            env.get("BASE_URL"),
+            Some(&"https://staging.example.com".to_string())
+        );
+        assert_eq!(env.get("DEBUG"), Some(&"true".to_string()));
+        assert_eq!(env.get("REGION"), Some(&"eu".to_string()));
+        assert_eq!(env.get("ACCEPT"), Some(&"application/json".to_string()));
+        assert_eq!(env.get("extends"), None);
+
+        let err = Environment::from_file_named(&path, "broken").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("extends unknown environment 'missing'")
+        );
+
+        let err = Environment::from_file_named(&path, "loop-a").unwrap_err();
+        assert!(err.to_string().contains("inheritance cycle"));
+    }
+
+    #[test]
+    fn test_environment_names() {
+        let env_content = r#"{
+  "$shared": {"ACCEPT": "application/json"},
+  "staging": {},
+  "development": {},
+  "version": 2
+}"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), env_content).unwrap();
+
+        let names = Environment::names(&temp_file.path().to_string_lossy()).unwrap();
+        assert_eq!(names, vec!["development", "staging"]);
+        assert!(Environment::names("/non/existent/file.json").is_err());
+    }
+
     #[test]
     fn test_environment_from_file_not_found() {
         let result = Environment::from_file("/non/existent/file.json");
@@ -283,4 +431,127 @@ mod tests {
         assert_eq!(env1.get("TEST_KEY"), env2.get("TEST_KEY"));
         assert_eq!(env1.variables().len(), env2.variables().len());
     }
+
+    fn sample_request() -> HttpRequest {
+        let mut headers = Headers::new();
+        headers.insert("X-Trace".to_string(), "1".to_string());
+        headers.insert("Accept".to_string(), "application/json".to_string());
+        HttpRequest::new(
+            "create user".to_string(),
+            Method::POST,
+            "https://example.com/users".to_string(),
+        )
+        .with_headers(headers)
+        .with_body(Some(r#"{"name":"a"}"#.to_string()))
+        .with_directives(vec![Directive::new("expect-response", "user.json")])
+    }
+
+    #[test]
+    fn test_target_url_encodes_query_and_path_params() {
+        let request = HttpRequest::new(
+            "search".to_string(),
+            Method::GET,
+            "https://example.com/users/:id/posts/{slug}?page=1".to_string(),
+        )
+        .with_path_params(vec![
+            ("id".to_string(), "a b".to_string()),
+            ("slug".to_string(), "你好/x".to_string()),
+        ])
+        .with_query(vec![
+            ("q".to_string(), "hello world".to_string()),
+            ("tag".to_string(), "a&b".to_string()),
+        ]);
+
+        assert_eq!(
+            request.target_url().unwrap().as_str(),
+            "https://example.com/users/a%20b/posts/%E4%BD%A0%E5%A5%BD%2Fx?page=1&q=hello+world&tag=a%26b"
+        );
+    }
+
+    #[test]
+    fn test_request_fingerprint() {
+        let request = |name: &str, url: &str, body: Option<&str>| {
+            HttpRequest::new(name.to_string(), Method::POST, url.to_string())
+                .with_body(body.map(String::from))
+        };
+        let base = request("a", "https://example.com/users?page=1", Some("{}"));
+        let fingerprint = base.fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+        assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // 名称和请求头不影响指纹，查询参数的写法不影响目标URL
+        let same = request("b", "https://example.com/users", Some("{}"))
+            .with_query(vec![("page".to_string(), "1".to_string())])
+            .with_headers(Headers::from([("X-Debug".to_string(), "1".to_string())]));
+        assert_eq!(same.fingerprint(), fingerprint);
+
+        assert_ne!(
+            request("a", "https://example.com/users?page=2", Some("{}")).fingerprint(),
+            fingerprint
+        );
+        assert_ne!(
+            request("a", "https://example.com/users?page=1", None).fingerprint(),
+            fingerprint
+        );
+        let mut get = base.clone();
+        get.method = Method::GET;
+        assert_ne!(get.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_target_url_missing_path_param() {
+        let request = HttpRequest::new(
+            "user".to_string(),
+            Method::GET,
+            "https://example.com/users/{id}".to_string(),
+        );
+        let error = request.target_url().unwrap_err().to_string();
+        assert!(error.contains("missing path parameter 'id'"), "{error}");
+    }
+
+    #[test]
+    fn test_request_suite_json_round_trip() {
+        let suite = RequestSuite::new(vec![sample_request()]);
+        let json = suite.to_json().unwrap();
+
+        assert!(json.contains(r#""method": "POST""#));
+        assert!(!json.contains("response_handler"));
+        // 请求头按书写顺序输出
+        assert!(json.find("X-Trace").unwrap() < json.find("Accept").unwrap());
+
+        let parsed = RequestSuite::from_json(&json).unwrap();
+        assert_eq!(parsed.version, 1);
+        let request = &parsed.requests[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.headers.len(), 2);
+        assert_eq!(request.body.as_deref(), Some(r#"{"name":"a"}"#));
+        assert_eq!(request.directive("expect-response"), Some("user.json"));
+        assert_eq!(parsed.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn test_request_suite_yaml_round_trip() {
+        let suite = RequestSuite::new(vec![sample_request()]);
+        let yaml = suite.to_yaml().unwrap();
+
+        let parsed = RequestSuite::from_yaml(&yaml).unwrap();
+        assert_eq!(parsed.requests[0].name, "create user");
+        assert_eq!(parsed.to_yaml().unwrap(), yaml);
+    }
+
+    #[test]
+    fn test_request_suite_minimal_yaml() {
+        let yaml = "version: 1\nrequests:\n  - name: ping\n    method: get\n    url: https://example.com/ping\n";
+        let suite = RequestSuite::from_yaml(yaml).unwrap();
+
+        assert_eq!(suite.requests[0].method, Method::GET);
+        assert!(suite.requests[0].headers.is_empty());
+        assert!(suite.requests[0].body.is_none());
+    }
+
+    #[test]
+    fn test_request_suite_rejects_newer_version() {
+        let result = RequestSuite::from_json(r#"{"version": 99, "requests": []}"#);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,175 @@
+//! modules模块的单元测试
+
+#[cfg(feature = "scripting")]
+use httpie::ScriptEngine;
+use httpie::modules::link;
+#[cfg(feature = "scripting")]
+use httpie::{HttpClient, HttpRequest};
+#[cfg(feature = "scripting")]
+use mockito::Server;
+#[cfg(feature = "scripting")]
+use reqwest::Method;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_modules(files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_link_without_imports_is_unchanged() {
+        let script = "client.log(importance);\nconst x = import(\"./lazy.mjs\");";
+        assert_eq!(link(script, Path::new(".")).unwrap(), script);
+    }
+
+    #[test]
+    fn test_link_binds_imports_and_keeps_line_numbers() {
+        let dir = write_modules(&[(
+            "lib/helpers.mjs",
+            "export function validate(x) { return x > 0; }\nexport const LIMIT = 3;\nexport default \"helpers\";\n",
+        )]);
+        let script = "import name, { validate, LIMIT as limit } from \"./lib/helpers.mjs\";\nimport * as helpers from './lib/helpers.mjs';\nclient.log(validate(limit));";
+
+        let linked = link(script, dir.path()).unwrap();
+
+        // 同一个模块只定义一次
+        assert_eq!(linked.matches("var exports = {};").count(), 1);
+        assert!(linked.contains("exports.validate = validate;"));
+        assert!(linked.contains("exports.LIMIT = LIMIT;"));
+        assert!(linked.contains("exports.default = \"helpers\";"));
+        assert!(linked.contains("var name = globalThis.__httpie_modules["));
+        assert!(linked.contains("].LIMIT;"));
+        assert!(!linked.contains("import "));
+        assert!(linked.ends_with("\n\nclient.log(validate(limit));\n"));
+    }
+
+    #[test]
+    fn test_link_multiline_and_nested_imports() {
+        let dir = write_modules(&[
+            (
+                "checks.mjs",
+                "import { ok } from \"./shared/status.mjs\";\nexport { ok as isOk };\n",
+            ),
+            (
+                "shared/status.mjs",
+                "function ok(s) { return s < 400; }\nexport { ok };\n",
+            ),
+        ]);
+        let script =
+            "import {\n    isOk,\n} from \"./checks.mjs\";\nclient.assert(isOk(response.status));";
+
+        let linked = link(script, dir.path()).unwrap();
+
+        let status = linked.find("exports.ok = ok;").unwrap();
+        let checks = linked.find("exports.isOk = ok;").unwrap();
+        assert!(status < checks, "dependencies are defined first");
+        assert!(linked.ends_with("\n\n\nclient.assert(isOk(response.status));\n"));
+    }
+
+    #[test]
+    fn test_link_errors() {
+        let dir = write_modules(&[
+            ("a.mjs", "import \"./b.mjs\";\nexport const a = 1;\n"),
+            ("b.mjs", "import \"./a.mjs\";\n"),
+            ("reexport.mjs", "export { a } from \"./a.mjs\";\n"),
+        ]);
+
+        let error = link("import { a } from \"./a.mjs\";", dir.path()).unwrap_err();
+        assert!(error.to_string().contains("Circular import of"));
+
+        let error = link("import lodash from \"lodash\";", dir.path()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("only relative or absolute file paths are supported")
+        );
+
+        let error = link("import { a } from \"./missing.mjs\";", dir.path()).unwrap_err();
+        assert!(error.to_string().contains("Failed to import"));
+
+        let error = link("import { a } from \"./reexport.mjs\";", dir.path()).unwrap_err();
+        assert!(error.to_string().contains("re-exports are not supported"));
+
+        let error = link("import { 1a } from \"./a.mjs\";", dir.path()).unwrap_err();
+        assert!(error.to_string().contains("invalid name '1a'"));
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_linked_script_runs_in_engine() {
+        let dir = write_modules(&[(
+            "helpers.mjs",
+            "export function expectStatus(expected) {\n    client.test(\"status \" + expected, function () {\n        client.assert(response.status === expected);\n    });\n}\n",
+        )]);
+        let script = link(
+            "import { expectStatus } from \"./helpers.mjs\";\nexpectStatus(200);\nexpectStatus(404);",
+            dir.path(),
+        )
+        .unwrap();
+
+        let mut engine = ScriptEngine::new().unwrap();
+        let response = httpie::ResponseObject {
+            status: 200,
+            ..Default::default()
+        };
+        let results = engine
+            .execute_response_script(script, response)
+            .await
+            .unwrap();
+
+        let outcomes: Vec<(&str, bool)> = results
+            .iter()
+            .map(|r| (r.name.as_str(), r.passed))
+            .collect();
+        assert_eq!(outcomes, vec![("status 200", true), ("status 404", false)]);
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_handler_imports_resolve_relative_to_http_file() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/users")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id": 1}]"#)
+            .create_async()
+            .await;
+        let dir = write_modules(&[(
+            "api/helpers.mjs",
+            "export default function nonEmpty(list) { return list.length > 0; }\n",
+        )]);
+
+        let request = HttpRequest::new(
+            "users".to_string(),
+            Method::GET,
+            format!("{}/users", server.url()),
+        )
+        .with_source_file(Some(dir.path().join("api/users.http")))
+        .with_response_handler(Some(
+            "import nonEmpty from \"./helpers.mjs\";\nclient.test(\"users\", function () {\n    client.assert(nonEmpty(response.body));\n});"
+                .to_string(),
+        ));
+        let mut client = HttpClient::new()
+            .with_script_engine()
+            .unwrap()
+            .with_print_response(false);
+
+        let outcome = client.execute_with_outcome(&request).await.unwrap();
+
+        assert_eq!(outcome.test_results.len(), 1);
+        assert!(outcome.passed());
+        mock.assert_async().await;
+    }
+}
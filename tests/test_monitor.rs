@@ -0,0 +1,255 @@
+//! monitor模块的单元测试
+
+use httpie::monitor::{Alert, Monitor, MonitorLoop, notify};
+use httpie::{
+    CancellationToken, Environment, HttpClient, HttpParser, HttpRequest, MockTransport,
+    RequestReport, ResponseObject, RunReport, Runner, TestResult,
+};
+use mockito::{Matcher, Server};
+use reqwest::Method;
+use serde_json::json;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(name: &str, passed: bool, millis: u64) -> RequestReport {
+        RequestReport {
+            name: name.to_string(),
+            method: "GET".to_string(),
+            url: "https://api.example.com".to_string(),
+            status: Some(200),
+            duration: Duration::from_millis(millis),
+            test_results: vec![if passed {
+                TestResult::success("status")
+            } else {
+                TestResult::failure("status", "expected 200", "AssertionError")
+            }],
+            error: None,
+            iteration: None,
+            hook: None,
+            skipped: None,
+            logs: Vec::new(),
+            xfail: None,
+        }
+    }
+
+    fn report(requests: Vec<RequestReport>) -> RunReport {
+        RunReport {
+            requests,
+            duration: Duration::from_millis(100),
+            cancelled: false,
+            aborted: None,
+        }
+    }
+
+    #[test]
+    fn test_record_alerts_on_transitions() {
+        let mut monitor = Monitor::new();
+
+        assert!(
+            monitor
+                .record(&report(vec![request("health", true, 10)]))
+                .is_empty()
+        );
+
+        let alerts = monitor.record(&report(vec![request("health", false, 10)]));
+        assert_eq!(
+            alerts,
+            vec![Alert::Failing {
+                case: "health".to_string(),
+                failures: 1,
+                reason: "test 'status' failed: expected 200".to_string(),
+            }]
+        );
+        // 持续失败不重复告警
+        assert!(
+            monitor
+                .record(&report(vec![request("health", false, 10)]))
+                .is_empty()
+        );
+
+        let alerts = monitor.record(&report(vec![request("health", true, 10)]));
+        assert_eq!(
+            alerts,
+            vec![Alert::Recovered {
+                case: "health".to_string(),
+                failures: 2,
+            }]
+        );
+        assert_eq!(monitor.cycles(), 4);
+        assert_eq!(
+            alerts[0].to_string(),
+            "RECOVERED: 'health' passed after 2 failure(s)"
+        );
+    }
+
+    #[test]
+    fn test_failure_threshold() {
+        let mut monitor = Monitor::new().with_failure_threshold(3);
+        let mut transport_error = request("orders", false, 0);
+        transport_error.status = None;
+        transport_error.test_results.clear();
+        transport_error.error = Some("connection refused".to_string());
+
+        for _ in 0..2 {
+            assert!(
+                monitor
+                    .record(&report(vec![transport_error.clone()]))
+                    .is_empty()
+            );
+        }
+        let alerts = monitor.record(&report(vec![transport_error.clone()]));
+        assert_eq!(
+            alerts[0].to_string(),
+            "FAILING: 'orders' failed 3 time(s) in a row: connection refused"
+        );
+
+        // 未达到阈值的失败恢复时不告警
+        let mut monitor = Monitor::new().with_failure_threshold(3);
+        monitor.record(&report(vec![transport_error]));
+        assert!(
+            monitor
+                .record(&report(vec![request("orders", true, 5)]))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_rolling_statistics() {
+        let mut monitor = Monitor::new().with_window(4);
+        for (passed, millis) in [
+            (false, 1000),
+            (true, 10),
+            (true, 20),
+            (false, 30),
+            (true, 40),
+        ] {
+            monitor.record(&report(vec![request("health", passed, millis)]));
+        }
+
+        let stats = &monitor.cases()["health"];
+        assert_eq!(stats.runs, 5);
+        assert_eq!(stats.failures, 2);
+        assert_eq!(stats.consecutive_failures, 0);
+        // 只统计最近4次
+        assert_eq!(stats.success_rate(), 0.75);
+        assert_eq!(stats.average_duration(), Duration::from_millis(25));
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut monitor = Monitor::new();
+        monitor.record(&report(vec![request("health", true, 10)]));
+        monitor.record(&report(vec![request("health", false, 30)]));
+
+        assert_eq!(
+            monitor.summary().to_string(),
+            "Stopped after 2 cycle(s):\n  \
+             health: 2 run(s), 1 failure(s), 50.0% success (last 2), avg 20 ms, \
+             failing 1 time(s) in a row\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_monitor_loop_skips_cycles_that_fail_to_parse() {
+        const URL: &str = "https://api.test/health";
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("health.http");
+
+        let path = file.to_string_lossy().to_string();
+
+        let run = |transport: Arc<MockTransport>, cycles: Arc<Mutex<Vec<String>>>| {
+            let files = vec![path.clone()];
+            async move {
+                let cancellation = CancellationToken::new();
+                let client = HttpClient::new()
+                    .with_print_response(false)
+                    .with_transport(transport);
+                let runner = Runner::new(client).with_cancellation(cancellation.clone());
+                let parser = HttpParser::new(Environment::new());
+                let stop = tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    cancellation.cancel();
+                });
+                let request = HttpRequest::new("health".to_string(), Method::GET, URL.to_string());
+                let monitor = MonitorLoop::new(runner, parser, files, Duration::from_millis(10))
+                    .with_on_cycle(move |cycle| cycles.lock().unwrap().push(cycle.to_string()))
+                    .run(vec![request])
+                    .await
+                    .unwrap();
+                stop.await.unwrap();
+                monitor
+            }
+        };
+        let transport = || {
+            Arc::new(MockTransport::new().with_response(
+                Method::GET,
+                URL,
+                ResponseObject {
+                    status: 200,
+                    ..Default::default()
+                },
+            ))
+        };
+
+        // 文件无法读取时只运行最初的请求，之后的每一轮都被跳过
+        let missing = transport();
+        let cycles = Arc::new(Mutex::new(Vec::new()));
+        let monitor = run(missing.clone(), cycles.clone()).await;
+        assert_eq!(monitor.cycles(), 1);
+        assert_eq!(missing.requests().len(), 1);
+        // 每轮的结果交给回调输出
+        let cycles = cycles.lock().unwrap().clone();
+        assert_eq!(cycles.len(), 1);
+        assert!(
+            cycles[0].starts_with("Cycle 1: 1 of 1 request(s) passed in "),
+            "{}",
+            cycles[0]
+        );
+
+        // 文件可以解析时每轮重新解析并运行
+        fs::write(&file, format!("GET {URL}\n")).unwrap();
+        let monitor = run(transport(), Arc::default()).await;
+        assert!(monitor.cycles() > 1, "{}", monitor.cycles());
+    }
+
+    #[tokio::test]
+    async fn test_notify_webhook() {
+        let mut server = Server::new_async().await;
+        let alert = Alert::Failing {
+            case: "health".to_string(),
+            failures: 1,
+            reason: "timeout".to_string(),
+        };
+        let mock = server
+            .mock("POST", "/hook")
+            .match_body(Matcher::Json(json!({
+                "text": "FAILING: 'health' failed 1 time(s) in a row: timeout",
+                "case": "health",
+                "status": "failing",
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+        notify(&format!("{}/hook", server.url()), &alert)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+
+        server
+            .mock("POST", "/broken")
+            .with_status(500)
+            .create_async()
+            .await;
+        assert!(
+            notify(&format!("{}/broken", server.url()), &alert)
+                .await
+                .is_err()
+        );
+    }
+}
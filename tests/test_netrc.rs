@@ -0,0 +1,106 @@
+//! netrc模块的单元测试
+
+use httpie::netrc::{Netrc, NetrcEntry};
+use httpie::{Environment, HttpParser, HttpRequest};
+use reqwest::Method;
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETRC: &str = "\
+machine api.example.com
+  login alice
+  password s3cret
+
+macdef init
+machine evil.example.com login mallory password x
+
+machine API.example.com login bob password other
+default login anonymous password guest
+";
+
+    fn entry(login: &str, password: &str) -> NetrcEntry {
+        NetrcEntry {
+            login: login.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_netrc() {
+        let netrc = Netrc::parse(NETRC);
+        // 同一主机以第一条为准，主机名不区分大小写
+        assert_eq!(
+            netrc.credentials("Api.Example.com"),
+            Some(&entry("alice", "s3cret"))
+        );
+        // 宏定义中的内容被忽略
+        assert_eq!(
+            netrc.credentials("evil.example.com"),
+            Some(&entry("anonymous", "guest"))
+        );
+
+        let netrc = Netrc::parse("machine a.test login me password pw");
+        assert_eq!(netrc.credentials("a.test"), Some(&entry("me", "pw")));
+        assert_eq!(netrc.credentials("b.test"), None);
+        assert_eq!(Netrc::parse(""), Netrc::default());
+    }
+
+    #[test]
+    fn test_authorize_request() {
+        let netrc = Netrc::parse("machine api.example.com login alice password s3cret");
+
+        let mut request = HttpRequest::new(
+            "users".to_string(),
+            Method::GET,
+            "https://api.example.com/users".to_string(),
+        );
+        netrc.authorize(&mut request);
+        assert_eq!(request.headers["Authorization"], "Basic YWxpY2U6czNjcmV0");
+
+        // 已声明的Authorization保持不变
+        let mut request =
+            request.with_headers([("authorization".to_string(), "Bearer token".to_string())]);
+        netrc.authorize(&mut request);
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(request.headers["authorization"], "Bearer token");
+
+        // 主机不匹配时不添加
+        let mut request = HttpRequest::new(
+            "other".to_string(),
+            Method::GET,
+            "https://other.example.com".to_string(),
+        );
+        netrc.authorize(&mut request);
+        assert!(request.headers.is_empty());
+    }
+
+    #[test]
+    fn test_parser_applies_netrc() {
+        let dir = TempDir::new().unwrap();
+        let netrc_path = dir.path().join(".netrc");
+        fs::write(&netrc_path, NETRC).unwrap();
+        let http_path = dir.path().join("api.http");
+        fs::write(
+            &http_path,
+            "### Users\nGET https://api.example.com/users\n\n### Listen\nLISTEN :8089\n",
+        )
+        .unwrap();
+
+        let netrc = Netrc::load_from(&netrc_path).unwrap();
+        let requests = HttpParser::new(Environment::new())
+            .with_netrc(Some(netrc))
+            .parse_file(&http_path.to_string_lossy())
+            .unwrap();
+        assert_eq!(
+            requests[0].headers["Authorization"],
+            "Basic YWxpY2U6czNjcmV0"
+        );
+        assert!(requests[1].headers.is_empty());
+
+        assert!(Netrc::load_from(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! notify模块的单元测试
+
+use httpie::{
+    HttpClient, HttpRequest, LogLevel, MockTransport, Notifier, RequestReport, ResponseObject,
+    RunReport, Runner, ScriptLog, TestResult,
+};
+use reqwest::Method;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEBHOOK: &str = "https://hooks.example.com/notify";
+
+    fn request(name: &str, status: Option<u16>, test_results: Vec<TestResult>) -> RequestReport {
+        RequestReport {
+            name: name.to_string(),
+            method: "GET".to_string(),
+            url: format!("https://example.com/{name}"),
+            status,
+            duration: Duration::from_millis(10),
+            test_results,
+            error: status.is_none().then(|| "connection refused".to_string()),
+            iteration: None,
+            hook: None,
+            skipped: None,
+            logs: Vec::new(),
+            xfail: None,
+        }
+    }
+
+    fn failing_report() -> RunReport {
+        RunReport {
+            requests: vec![
+                request("health", Some(200), Vec::new()),
+                request("users", None, Vec::new()),
+                request(
+                    "orders",
+                    Some(500),
+                    vec![TestResult::failure(
+                        "status is 200",
+                        "expected 200, got 500",
+                        "AssertionError",
+                    )],
+                ),
+            ],
+            duration: Duration::from_millis(1234),
+            cancelled: false,
+            aborted: None,
+        }
+    }
+
+    #[test]
+    fn test_notifier_message() {
+        let notifier = Notifier::new(WEBHOOK).with_source("api.http");
+        assert_eq!(
+            notifier.message(&failing_report()),
+            "httpie: 2 of 3 request(s) failed in api.http\n\
+             • users: connection refused\n\
+             • orders: test 'status is 200' failed: expected 200, got 500"
+        );
+
+        let notifier = notifier.with_template("{{passed}}/{{total}} passed in {{duration_ms}} ms");
+        assert_eq!(notifier.message(&failing_report()), "1/3 passed in 1234 ms");
+    }
+
+    #[test]
+    fn test_notifier_request_body() {
+        let request = Notifier::new(WEBHOOK).request(&failing_report());
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.url, WEBHOOK);
+        assert_eq!(request.headers["Content-Type"], "application/json");
+
+        let body: Value = serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["failed"], 2);
+        assert_eq!(body["durationMs"], 1234);
+        assert_eq!(
+            body["failures"][1],
+            json!({
+                "name": "orders",
+                "status": 500,
+                "reason": "test 'status is 200' failed: expected 200, got 500",
+            })
+        );
+        assert!(body["text"].as_str().unwrap().starts_with("httpie: 2 of 3"));
+    }
+
+    #[test]
+    fn test_notifier_request_body_includes_script_logs() {
+        let mut report = failing_report();
+        report.requests[2].logs.push(ScriptLog {
+            level: LogLevel::Warn,
+            message: "order 42 missing".to_string(),
+            test: Some("status is 200".to_string()),
+        });
+
+        let request = Notifier::new(WEBHOOK).request(&report);
+
+        let body: Value = serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            body["failures"][1]["logs"],
+            json!([{"level": "warn", "message": "order 42 missing", "test": "status is 200"}])
+        );
+        assert!(body["failures"][0].get("logs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_notify_only_on_failure() {
+        let transport = MockTransport::new().with_response(
+            Method::POST,
+            WEBHOOK,
+            ResponseObject {
+                status: 200,
+                ..Default::default()
+            },
+        );
+        let notifier = Notifier::new(WEBHOOK);
+
+        let passing = RunReport {
+            requests: vec![request("health", Some(200), Vec::new())],
+            ..Default::default()
+        };
+        assert!(
+            !notifier
+                .notify_on_failure(&transport, &passing)
+                .await
+                .unwrap()
+        );
+        assert!(transport.requests().is_empty());
+
+        assert!(
+            notifier
+                .notify_on_failure(&transport, &failing_report())
+                .await
+                .unwrap()
+        );
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_runner_sends_notification_through_client_transport() {
+        let transport = Arc::new(MockTransport::new().with_response(
+            Method::POST,
+            WEBHOOK,
+            ResponseObject {
+                status: 200,
+                ..Default::default()
+            },
+        ));
+        let client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport(transport.clone());
+        let mut runner = Runner::new(client).with_notifier(Some(Notifier::new(WEBHOOK)));
+
+        // 没有预设响应的请求以传输错误失败
+        let request = HttpRequest::new(
+            "orders".to_string(),
+            Method::GET,
+            "https://api.test/orders".to_string(),
+        );
+        let report = runner.run(&[request]).await.unwrap();
+
+        assert_eq!(report.failed(), 1);
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].url, WEBHOOK);
+        let body: Value = serde_json::from_str(requests[1].body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["failures"][0]["name"], "orders");
+    }
+
+    #[tokio::test]
+    async fn test_notify_rejected_by_webhook() {
+        let transport = MockTransport::new().with_response(
+            Method::POST,
+            WEBHOOK,
+            ResponseObject {
+                status: 404,
+                ..Default::default()
+            },
+        );
+        let err = Notifier::new(WEBHOOK)
+            .notify_on_failure(&transport, &failing_report())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP 404"));
+    }
+}
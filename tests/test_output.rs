@@ -0,0 +1,77 @@
+//! output模块的单元测试
+
+use httpie::{HeaderFilter, JsonFormat, PrintParts};
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_parts_parse() {
+        let parts: PrintParts = "HBhb".parse().unwrap();
+        assert!(parts.request_headers && parts.request_body);
+        assert!(parts.response_headers && parts.response_body);
+
+        let parts: PrintParts = "b".parse().unwrap();
+        assert!(!parts.request_headers && !parts.request_body && !parts.response_headers);
+        assert!(parts.response_body);
+
+        assert_eq!(PrintParts::default(), "hb".parse().unwrap());
+        let err = "hx".parse::<PrintParts>().unwrap_err();
+        assert!(err.to_string().contains("Invalid --print value 'hx'"));
+    }
+
+    #[test]
+    fn test_header_filter() {
+        let filter = HeaderFilter::new();
+        assert!(filter.allows("Date"));
+
+        let filter = HeaderFilter::new().with_hidden("date, Server,");
+        assert!(!filter.allows("date"));
+        assert!(!filter.allows("server"));
+        assert!(filter.allows("content-type"));
+
+        let filter = HeaderFilter::new()
+            .with_only("Content-Type,x-request-id")
+            .with_hidden("x-request-id");
+        assert!(filter.allows("content-type"));
+        assert!(!filter.allows("X-Request-Id"));
+        assert!(!filter.allows("date"));
+    }
+
+    #[test]
+    fn test_json_format_render() {
+        let value: serde_json::Value =
+            serde_json::from_str(r#"{"name": "alice", "id": 1, "tags": [{"z": 1, "a": 2}]}"#)
+                .unwrap();
+
+        // 默认保留服务器返回的键顺序
+        assert_eq!(
+            JsonFormat::default().render(&json!({"b": 1, "a": [2]})),
+            "{\n  \"b\": 1,\n  \"a\": [\n    2\n  ]\n}"
+        );
+
+        let sorted = JsonFormat {
+            sort_keys: true,
+            compact: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted.render(&value),
+            r#"{"id":1,"name":"alice","tags":[{"a":2,"z":1}]}"#
+        );
+
+        let indented = JsonFormat {
+            indent: 4,
+            ..Default::default()
+        };
+        assert_eq!(indented.render(&json!({"id": 1})), "{\n    \"id\": 1\n}");
+
+        assert_eq!(
+            JsonFormat::default().render_text("[1]"),
+            Some("[\n  1\n]".to_string())
+        );
+        assert_eq!(JsonFormat::default().render_text("plain text"), None);
+    }
+}
@@ -0,0 +1,46 @@
+//! pager模块的单元测试
+
+use httpie::Pager;
+use httpie::pager::DEFAULT_PAGER_THRESHOLD;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_from_command() {
+        assert_eq!(Pager::from_command(None).unwrap().command(), "less -R");
+        assert_eq!(
+            Pager::from_command(Some("most -s")).unwrap().command(),
+            "most -s"
+        );
+        assert_eq!(Pager::from_command(Some("")), None);
+        assert_eq!(Pager::from_command(Some("cat")), None);
+    }
+
+    #[test]
+    fn test_pager_threshold() {
+        let pager = Pager::new("less -R");
+        assert!(!pager.should_page(&"x".repeat(DEFAULT_PAGER_THRESHOLD)));
+        assert!(pager.should_page(&"x".repeat(DEFAULT_PAGER_THRESHOLD + 1)));
+
+        let pager = pager.with_threshold(10);
+        assert!(!pager.should_page("short"));
+        assert!(pager.should_page("more than ten bytes"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pager_pipes_output() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("paged.txt");
+        let pager = Pager::new(format!("cat > '{}'", path.display()));
+
+        pager.page("=== Get users ===\nStatus: 200 OK\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "=== Get users ===\nStatus: 200 OK\n"
+        );
+    }
+}
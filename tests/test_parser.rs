@@ -1,8 +1,14 @@
 //! parser模块的单元测试
 
-use httpie::{Environment, HttpParser};
+use httpie::{
+    DynamicValues, Environment, Headers, Hook, HttpParser, HttpVersion, HttpieError, Iteration,
+    SecretPolicy, TraceFormat, duplicate_requests, required_variables,
+};
 use reqwest::Method;
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
 use tempfile::NamedTempFile;
 
 #[cfg(test)]
@@ -47,6 +53,67 @@ User-Agent: httpie-test
         assert!(request.response_handler.is_none());
     }
 
+    #[test]
+    fn test_parse_query_continuation_and_path_params() {
+        let content = r#"
+@keyword = hello world
+
+### Search
+# @path id = 42
+GET https://example.com/users/:id/search
+    ?q={{keyword}}
+    &page=2&sort=
+Accept: application/json
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let request = &requests[0];
+        let pair = |key: &str, value: &str| (key.to_string(), value.to_string());
+        assert_eq!(
+            request.query,
+            [
+                pair("q", "hello world"),
+                pair("page", "2"),
+                pair("sort", "")
+            ]
+        );
+        assert_eq!(request.path_params, [pair("id", "42")]);
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(
+            request.target_url().unwrap().as_str(),
+            "https://example.com/users/42/search?q=hello+world&page=2&sort="
+        );
+    }
+
+    #[test]
+    fn test_parse_preserves_header_order_and_case() {
+        let content = r#"
+### Signed Request
+GET https://httpbin.org/get
+X-Signature: abc
+date: Mon, 01 Jan 2024 00:00:00 GMT
+Accept: */*
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let names: Vec<&str> = requests[0].headers.keys().map(String::as_str).collect();
+        assert_eq!(names, ["X-Signature", "date", "Accept"]);
+    }
+
     #[test]
     fn test_parse_post_request_with_body() {
         let content = r#"
@@ -157,6 +224,23 @@ client.test("Status should be 200", function() {
         let script = request.response_handler.as_ref().unwrap();
         assert!(script.contains("client.test"));
         assert!(script.contains("response.status === 200"));
+        // 脚本第一行在文件中的行号，用于定位脚本错误
+        assert_eq!(request.handler_line, Some(6));
+    }
+
+    #[test]
+    fn test_parse_handler_line_of_inline_and_curl_handlers() {
+        let content = "### inline\nGET https://example.com/a\n\n> {% client.log(1) %}\n\n### curl\ncurl https://example.com/b\n\n> {%\n\n  client.log(2);\n%}\n";
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        let mut parser = HttpParser::new(Environment::new());
+
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests[0].handler_line, Some(4));
+        assert_eq!(requests[1].handler_line, Some(11));
     }
 
     #[test]
@@ -520,4 +604,1537 @@ client.global.set("userToken", response.body.token);
         assert!(script.contains("User creation successful"));
         assert!(script.contains("client.global.set"));
     }
+
+    #[test]
+    fn test_parse_request_directives() {
+        let content = r#"
+@fixtures = ./expected
+
+### Login
+# @expect-response {{fixtures}}/login.json
+// @expect-ignore id, created_at
+# plain comment is not a directive
+# @flag
+POST https://example.com/login
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let request = &requests[0];
+        assert_eq!(request.directives.len(), 3);
+        assert_eq!(
+            request.directive("expect-response"),
+            Some("./expected/login.json")
+        );
+        assert_eq!(request.directive("expect-ignore"), Some("id, created_at"));
+        assert_eq!(request.directive("flag"), Some(""));
+        assert_eq!(request.source_file.as_deref(), Some(temp_file.path()));
+    }
+
+    #[test]
+    fn test_parse_curl_request_section() {
+        let content = r#"
+@host = https://example.com
+
+### Pasted from devtools
+# @expect-response ./login.json
+curl '{{host}}/login' \
+  -H 'Content-Type: application/json' \
+  --data-raw '{"user":"admin"}'
+
+> {%
+client.test("ok", function() {});
+%}
+
+### Next request
+GET {{host}}/next
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests.len(), 2);
+        let request = &requests[0];
+        assert_eq!(request.name, "Pasted from devtools");
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.url, "https://example.com/login");
+        assert_eq!(
+            request.headers.get("Content-Type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(request.body.as_deref(), Some(r#"{"user":"admin"}"#));
+        assert_eq!(request.directive("expect-response"), Some("./login.json"));
+        assert!(
+            request
+                .response_handler
+                .as_ref()
+                .unwrap()
+                .contains("client.test")
+        );
+        assert_eq!(requests[1].url, "https://example.com/next");
+    }
+
+    #[test]
+    fn test_parse_response_redirect() {
+        let content = r#"
+@out = ./downloads
+
+### Download
+GET https://example.com/file.zip
+Accept: application/zip
+
+>>! {{out}}/file.zip
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let request = &requests[0];
+        let redirect = request.response_redirect.as_ref().unwrap();
+        assert_eq!(redirect.path, "./downloads/file.zip");
+        assert!(redirect.overwrite);
+        assert!(
+            request.body.is_none(),
+            "redirect line must not become the body"
+        );
+    }
+
+    #[test]
+    fn test_parse_response_redirect_only_after_body() {
+        let content = r#"
+### Quoted reply
+POST https://example.com/messages
+Content-Type: text/plain
+
+> original message
+>> quoted reply
+thanks
+
+> {%
+    const shifted = 8
+>> 1;
+%}
+
+>> ./out/reply.json
+
+### Quoted only
+POST https://example.com/messages
+Content-Type: text/plain
+
+>> not a redirect
+thanks
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let request = &requests[0];
+        assert_eq!(
+            request.body.as_deref(),
+            Some("> original message\n>> quoted reply\nthanks")
+        );
+        assert!(
+            request
+                .response_handler
+                .as_deref()
+                .unwrap()
+                .contains(">> 1;")
+        );
+        assert_eq!(
+            request.response_redirect.as_ref().unwrap().path,
+            "./out/reply.json"
+        );
+
+        let request = &requests[1];
+        assert!(request.response_redirect.is_none());
+        assert_eq!(request.body.as_deref(), Some(">> not a redirect\nthanks"));
+    }
+
+    #[test]
+    fn test_parse_http_version_token() {
+        let content = r#"
+### Versioned
+GET https://example.com/v2 HTTP/2
+
+### Legacy
+GET https://example.com/v1 HTTP/1.1
+
+### Default
+GET https://example.com/
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests[0].url, "https://example.com/v2");
+        assert_eq!(requests[0].http_version, Some(HttpVersion::Http2));
+        assert_eq!(requests[1].http_version, Some(HttpVersion::Http11));
+        assert_eq!(requests[2].http_version, None);
+    }
+
+    #[test]
+    fn test_parse_unsupported_http_version() {
+        let content = r#"
+### Bad version
+GET https://example.com/ HTTP/9
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let result = parser.parse_file(&temp_file.path().to_string_lossy());
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Unsupported HTTP version 'HTTP/9'")
+        );
+    }
+
+    #[test]
+    fn test_parse_repeat_directive() {
+        let content = r#"
+### Create order
+# @repeat 3
+POST https://example.com/orders
+X-Request-Id: {{$uuid}}
+
+### Ping
+GET https://example.com/ping
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].name, "Create order [1/3]");
+        assert_eq!(requests[2].name, "Create order [3/3]");
+        assert_eq!(
+            requests[1].iteration,
+            Some(Iteration { index: 2, total: 3 })
+        );
+        assert_eq!(requests[3].name, "Ping");
+        assert_eq!(requests[3].iteration, None);
+
+        // 每次迭代的动态变量取新值
+        let ids: Vec<_> = requests[..3]
+            .iter()
+            .map(|r| r.headers["X-Request-Id"].clone())
+            .collect();
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[1], ids[2]);
+    }
+
+    #[test]
+    fn test_parse_invalid_repeat_directive() {
+        let content = r#"
+### Bad repeat
+# @repeat zero
+GET https://example.com/
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let result = parser.parse_file(&temp_file.path().to_string_lossy());
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid @repeat value 'zero'")
+        );
+    }
+
+    #[test]
+    fn test_parse_filter_directive() {
+        let content = r#"
+### Item ids
+# @filter $.data.items[*].id
+GET https://example.com/items
+
+### Bad filter
+# @filter data.items
+GET https://example.com/items
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content.split("### Bad").next().unwrap()).unwrap();
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+        assert_eq!(requests[0].directive("filter"), Some("$.data.items[*].id"));
+
+        fs::write(temp_file.path(), content).unwrap();
+        let result = parser.parse_file(&temp_file.path().to_string_lossy());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid JSONPath 'data.items'")
+        );
+    }
+
+    #[test]
+    fn test_parse_hook_sections() {
+        let content = r#"
+### @setup create user
+POST https://example.com/users
+
+### Get user
+GET https://example.com/users/1
+
+### @teardown
+DELETE https://example.com/users/1
+
+### @setupish is not a hook
+GET https://example.com/
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].hook, Some(Hook::Setup));
+        assert_eq!(requests[1].hook, None);
+        assert_eq!(requests[2].hook, Some(Hook::Teardown));
+        assert_eq!(requests[3].hook, None);
+    }
+
+    #[test]
+    fn test_parse_inline_assertions() {
+        let content = r#"
+@expected = alice
+
+### Get user
+?? status == 200
+GET https://example.com/users/1
+Accept: application/json
+?? header content-type contains json
+
+{"probe": true}
+
+?? $.name == {{expected}}
+> {%
+    const fallback = null
+        ?? "default";
+%}
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let request = &requests[0];
+        assert_eq!(
+            request.assertions,
+            [
+                "status == 200",
+                "header content-type contains json",
+                "$.name == alice"
+            ]
+        );
+        assert_eq!(
+            request.headers.keys().collect::<Vec<_>>(),
+            ["Accept", "Content-Type"]
+        );
+        assert_eq!(request.body.as_deref(), Some(r#"{"probe": true}"#));
+        assert!(
+            request
+                .response_handler
+                .as_ref()
+                .unwrap()
+                .contains("?? \"default\"")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_inline_assertion() {
+        let content = r#"
+### Bad assertion
+GET https://example.com/
+?? status === 200
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let result = parser.parse_file(&temp_file.path().to_string_lossy());
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid assertion 'status === 200'")
+        );
+    }
+
+    #[test]
+    fn test_parse_aggregate_assertions() {
+        let content = r#"
+### Search latency
+# @repeat 2
+GET https://example.com/search
+?? p95 < 300ms
+?? error_rate < 1%
+
+### Bad budget
+GET https://example.com/
+?? p95 < soon
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            &content[..content.find("### Bad").unwrap()],
+        )
+        .unwrap();
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].assertions, ["p95 < 300ms", "error_rate < 1%"]);
+
+        fs::write(temp_file.path(), content).unwrap();
+        let mut parser = HttpParser::new(Environment::new());
+        let error = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid assertion 'p95 < soon': invalid duration")
+        );
+    }
+
+    #[test]
+    fn test_parse_env_directive() {
+        let env_file = NamedTempFile::new().unwrap();
+        fs::write(
+            env_file.path(),
+            r#"{
+  "development": {"host": "https://dev.example.com", "token": "dev"},
+  "staging": {"host": "https://staging.example.com"}
+}"#,
+        )
+        .unwrap();
+        let environment = Environment::from_file(&env_file.path().to_string_lossy()).unwrap();
+
+        let content = r#"
+@path = /cleanup
+
+### Default
+GET {{host}}/items
+
+### Cleanup
+# @env staging
+DELETE {{host}}{{path}}
+Authorization: {{token}}
+
+### Same env
+# @env development
+GET {{host}}/same
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(environment);
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests[0].url, "https://dev.example.com/items");
+        assert_eq!(requests[1].url, "https://staging.example.com/cleanup");
+        // 指定环境中没有的变量不会回落到全局环境
+        assert_eq!(requests[1].headers["Authorization"], "{{token}}");
+        assert_eq!(requests[2].url, "https://dev.example.com/same");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_resolves_secrets_on_use() {
+        let env_file = NamedTempFile::new().unwrap();
+        fs::write(
+            env_file.path(),
+            r#"{
+  "development": {
+    "token": {"exec": "echo from-command"},
+    "unused": {"exec": "exit 1"},
+    "overridden": {"exec": "exit 1"}
+  }
+}"#,
+        )
+        .unwrap();
+        let environment = Environment::from_files_with_policy(
+            &[env_file.path().to_path_buf()],
+            None,
+            SecretPolicy::AllowExec,
+        )
+        .unwrap();
+        assert!(environment.get("token").is_none());
+
+        let content = r#"
+@overridden = from-file
+
+### Secret
+GET https://example.com/{{overridden}}
+Authorization: Bearer {{token}}
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(environment);
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+        assert_eq!(requests[0].url, "https://example.com/from-file");
+        assert_eq!(requests[0].headers["Authorization"], "Bearer from-command");
+
+        fs::write(
+            temp_file.path(),
+            "### Broken\nGET https://example.com/{{unused}}\n",
+        )
+        .unwrap();
+        let error = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap_err();
+        assert!(error.to_string().contains("Failed to resolve 'unused'"));
+    }
+
+    #[test]
+    fn test_parse_env_directive_unknown_environment() {
+        let env_file = NamedTempFile::new().unwrap();
+        fs::write(env_file.path(), r#"{"development": {}}"#).unwrap();
+        let environment = Environment::from_file(&env_file.path().to_string_lossy()).unwrap();
+
+        let content = r#"
+### Cleanup
+# @env staging
+DELETE https://example.com/cleanup
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(environment);
+        let result = parser.parse_file(&temp_file.path().to_string_lossy());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Environment 'staging' not found")
+        );
+
+        // 没有环境文件时无法切换环境
+        let mut parser = HttpParser::new(Environment::new());
+        let result = parser.parse_file(&temp_file.path().to_string_lossy());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("requires an environment file")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_report_location() {
+        let content = r#"@host = https://example.com
+
+### First
+GET {{host}}/
+
+### Malformed
+# some comment
+  GET
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        let path = temp_file.path().to_string_lossy().to_string();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let error = parser.parse_file(&path).unwrap_err();
+
+        match &error {
+            HttpieError::Syntax {
+                location,
+                source_line,
+                ..
+            } => {
+                assert_eq!(location.file.as_deref(), Some(path.as_str()));
+                assert_eq!(location.line, 8);
+                assert_eq!(location.column, 7);
+                assert_eq!(source_line, "  GET");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(error.to_string().starts_with(&format!(
+            "{path}:8:7: Invalid request line format: expected URL after method"
+        )));
+    }
+
+    #[test]
+    fn test_parse_directive_errors_point_at_value() {
+        let content = r#"
+### Capture
+# @capture token = body
+GET https://example.com/
+"#;
+
+        let mut parser = HttpParser::new(Environment::new());
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let error = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap_err();
+        let HttpieError::Syntax { location, .. } = &error else {
+            panic!("unexpected error: {error:?}");
+        };
+        assert_eq!((location.line, location.column), (3, 12));
+        assert!(error.to_string().contains("Invalid @capture"));
+    }
+
+    #[test]
+    fn test_parse_validates_url() {
+        let parse = |content: &str| {
+            let temp_file = NamedTempFile::new().unwrap();
+            fs::write(temp_file.path(), content).unwrap();
+            HttpParser::new(Environment::new()).parse_file(&temp_file.path().to_string_lossy())
+        };
+
+        let error = parse("### No scheme\nGET localhost:8080/api\n").unwrap_err();
+        let HttpieError::Syntax {
+            location, message, ..
+        } = &error
+        else {
+            panic!("unexpected error: {error:?}");
+        };
+        assert_eq!((location.line, location.column), (2, 5));
+        assert!(message.contains("missing scheme"), "{message}");
+
+        let error = parse("### Bad host\nGET http://exa%mple.com/\n").unwrap_err();
+        assert!(error.to_string().contains("malformed host"), "{error}");
+
+        let error = parse("### Bad scheme\nGET ftp://example.com/\n").unwrap_err();
+        assert!(
+            error.to_string().contains("unsupported scheme 'ftp'"),
+            "{error}"
+        );
+
+        // 运行期才能替换的变量留到发送时校验
+        assert!(parse("### Captured\nGET {{next_url}}\n").is_ok());
+    }
+
+    #[test]
+    fn test_parse_infers_json_content_type() {
+        let content = r#"
+### Inferred
+POST https://example.com/users
+
+{"name": "a"}
+
+### Declared
+POST https://example.com/users
+content-type: application/vnd.api+json
+
+{"name": "a"}
+
+### Plain text
+POST https://example.com/echo
+
+hello
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        let path = temp_file.path().to_string_lossy().to_string();
+
+        let requests = HttpParser::new(Environment::new())
+            .parse_file(&path)
+            .unwrap();
+        assert_eq!(
+            requests[0].headers.get("Content-Type").map(String::as_str),
+            Some("application/json")
+        );
+        assert_eq!(requests[1].headers.len(), 1);
+        assert!(requests[2].headers.is_empty());
+
+        let requests = HttpParser::new(Environment::new())
+            .with_infer_content_type(false)
+            .parse_file(&path)
+            .unwrap();
+        assert!(requests[0].headers.is_empty());
+    }
+
+    #[test]
+    fn test_parse_graphql_variables_block() {
+        let content = r#"
+@user_id = 42
+
+### Query with variables
+GRAPHQL https://example.com/graphql
+
+query User($id: ID!) {
+  user(id: $id) { name }
+}
+
+{
+  "id": "{{user_id}}"
+}
+
+### Plain query
+GRAPHQL https://example.com/graphql
+
+{ viewer { login } }
+
+### JSON body
+GRAPHQL https://example.com/graphql
+Content-Type: application/json
+
+{"query": "{ viewer { login } }"}
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let payload = |index: usize| -> serde_json::Value {
+            serde_json::from_str(requests[index].body.as_deref().unwrap()).unwrap()
+        };
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(
+            requests[0].headers.get("Content-Type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            payload(0),
+            serde_json::json!({
+                "query": "query User($id: ID!) {\n  user(id: $id) { name }\n}",
+                "variables": {"id": "42"}
+            })
+        );
+        assert_eq!(
+            payload(1),
+            serde_json::json!({"query": "{ viewer { login } }"})
+        );
+        assert_eq!(
+            requests[2].body.as_deref(),
+            Some(r#"{"query": "{ viewer { login } }"}"#)
+        );
+    }
+
+    #[test]
+    fn test_parse_file_variables_apply_positionally() {
+        let content = r#"
+@token = first
+
+### Before redefinition
+GET https://example.com/a
+Authorization: Bearer {{token}}
+X-Later: {{later}}
+
+@token = second
+
+### After redefinition
+GET https://example.com/b
+Authorization: Bearer {{token}}
+
+### Defined inside section
+@token = third
+GET https://example.com/c
+Authorization: Bearer {{token}}
+
+@later = late
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        let header = |index: usize, name: &str| requests[index].headers.get(name).unwrap().clone();
+        assert_eq!(header(0, "Authorization"), "Bearer first");
+        assert_eq!(header(0, "X-Later"), "{{later}}");
+        assert_eq!(header(1, "Authorization"), "Bearer second");
+        assert_eq!(header(2, "Authorization"), "Bearer third");
+    }
+
+    #[test]
+    fn test_parse_variable_overrides_take_precedence() {
+        let content = r#"
+@token = from-file
+
+### Override
+GET https://example.com/{{path}}
+Authorization: Bearer {{token}}
+X-Dotenv: {{fromDotenv}}
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut environment = Environment::new().with_dotenv(HashMap::from([
+            ("token".to_string(), "from-dotenv".to_string()),
+            ("fromDotenv".to_string(), "dotenv".to_string()),
+        ]));
+        environment.insert("path".to_string(), "users".to_string());
+        let mut parser = HttpParser::new(environment).with_variable_overrides(HashMap::from([(
+            "token".to_string(),
+            "from-cli".to_string(),
+        )]));
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests[0].url, "https://example.com/users");
+        assert_eq!(requests[0].headers["Authorization"], "Bearer from-cli");
+        assert_eq!(requests[0].headers["X-Dotenv"], "dotenv");
+    }
+
+    #[test]
+    fn test_required_variables() {
+        let content = r#"
+@host = https://example.com
+
+### Login
+# @capture token = $.token
+POST {{host}}/login
+
+{"user": "{{ user }}", "password": "{{password}}"}
+
+> {%
+    client.global.set("session", response.body.id);
+%}
+
+### Profile
+GET {{host}}/users/{{userId}}
+Authorization: Bearer {{token}}
+X-Session: {{session}}
+X-Request-Id: {{$uuid}}
+"#;
+
+        let required: Vec<String> = required_variables(content).into_iter().collect();
+        assert_eq!(required, vec!["password", "user", "userId"]);
+    }
+
+    #[test]
+    fn test_parse_default_headers() {
+        let content = r#"
+@team = core
+
+### Plain
+GET https://example.com/a
+user-agent: custom
+
+### Curl
+curl https://example.com/b
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let defaults = Headers::from([
+            ("User-Agent".to_string(), "httpie".to_string()),
+            ("X-Team".to_string(), "{{team}}".to_string()),
+        ]);
+        let mut parser = HttpParser::new(Environment::new()).with_default_headers(defaults);
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests[0].headers["user-agent"], "custom");
+        assert!(!requests[0].headers.contains_key("User-Agent"));
+        assert_eq!(requests[0].headers["X-Team"], "core");
+        assert_eq!(requests[1].headers["User-Agent"], "httpie");
+        assert_eq!(requests[1].headers["X-Team"], "core");
+    }
+
+    #[test]
+    fn test_parse_trace_headers() {
+        let content = r#"
+### Traced
+GET https://example.com/orders?trace={{$traceId}}
+X-Span: {{$spanId}}
+
+### Explicit
+GET https://example.com/b
+traceparent: 00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new())
+            .with_trace_formats(vec![TraceFormat::W3c, TraceFormat::B3]);
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        // 模板中的ID与注入的请求头一致
+        let headers = &requests[0].headers;
+        let trace_id = &headers["X-B3-TraceId"];
+        assert_eq!(
+            requests[0].url,
+            format!("https://example.com/orders?trace={trace_id}")
+        );
+        assert_eq!(
+            headers["traceparent"],
+            format!("00-{trace_id}-{}-01", headers["X-Span"])
+        );
+        assert_eq!(
+            requests[1].headers["traceparent"],
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+        );
+
+        // 未启用时不注入
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+        assert!(!requests[0].headers.contains_key("traceparent"));
+        assert_eq!(requests[0].headers["X-Span"].len(), 16);
+    }
+
+    #[test]
+    fn test_parse_request_id() {
+        let content = r#"
+### Pay
+POST https://example.com/payments
+Idempotency-Key: {{$idempotencyKey}}
+
+{"key": "{{$idempotencyKey}}"}
+
+### Explicit
+GET https://example.com/b
+x-request-id: fixed
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let mut parser = HttpParser::new(Environment::new()).with_request_id(true);
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        // 同一请求中的幂等键与请求ID一致，不同请求各不相同
+        let key = &requests[0].headers["Idempotency-Key"];
+        assert_eq!(key.len(), 36);
+        assert_eq!(&requests[0].headers["X-Request-Id"], key);
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some(format!(r#"{{"key": "{key}"}}"#).as_str())
+        );
+        assert_eq!(requests[1].headers["x-request-id"], "fixed");
+        assert!(!requests[1].headers.contains_key("X-Request-Id"));
+
+        // 未启用时只替换变量
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+        assert!(!requests[0].headers.contains_key("X-Request-Id"));
+        assert_eq!(requests[0].headers["Idempotency-Key"].len(), 36);
+    }
+
+    #[test]
+    fn test_parse_with_seeded_dynamic_values() {
+        let content = r#"
+### 创建订单
+POST https://example.com/orders?t={{$timestamp}}
+
+{"id": "{{$uuid}}", "n": {{$randomInt}}}
+
+### 查询订单
+GET https://example.com/orders/{{$uuid}}
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        let parse = |seed| {
+            HttpParser::new(Environment::new())
+                .with_trace_formats(vec![TraceFormat::W3c])
+                .with_request_id(true)
+                .with_dynamic_values(DynamicValues::new().with_seed(seed).with_frozen_time(0))
+                .parse_file(&temp_file.path().to_string_lossy())
+                .unwrap()
+        };
+
+        let first = parse(1);
+        let second = parse(1);
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.url, b.url);
+            assert_eq!(a.body, b.body);
+            assert_eq!(a.headers, b.headers);
+        }
+        assert_eq!(first[0].url, "https://example.com/orders?t=0");
+        assert_ne!(
+            first[0].headers["X-Request-Id"],
+            first[1].headers["X-Request-Id"]
+        );
+        assert_ne!(parse(2)[1].url, first[1].url);
+    }
+
+    #[test]
+    fn test_duplicate_requests() {
+        let content = r#"
+### 列出用户
+GET https://example.com/users
+
+### 再次列出用户
+# @repeat 2
+GET https://example.com/users
+
+### 创建用户
+POST https://example.com/users
+
+{"name": "a"}
+
+### 创建另一个用户
+POST https://example.com/users
+
+{"name": "b"}
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        let requests = HttpParser::new(Environment::new())
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        // 重复执行的迭代不算重复
+        assert_eq!(requests.len(), 5);
+        assert_eq!(duplicate_requests(&requests), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_parse_defaults_section() {
+        let content = r#"
+@tenant = acme
+
+### @defaults
+# 所有请求共用
+Accept: application/json
+X-Tenant: {{tenant}}
+user-agent: from-file
+
+### First
+GET https://example.com/a
+Accept: text/plain
+
+### Second
+POST https://example.com/b
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let config_defaults = Headers::from([
+            ("User-Agent".to_string(), "from-config".to_string()),
+            ("X-Config".to_string(), "1".to_string()),
+        ]);
+        let mut parser = HttpParser::new(Environment::new()).with_default_headers(config_defaults);
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].headers["Accept"], "text/plain");
+        assert_eq!(requests[0].headers["X-Tenant"], "acme");
+        assert_eq!(requests[1].headers["Accept"], "application/json");
+        // 文件内默认值覆盖配置中的同名请求头
+        assert_eq!(requests[1].headers["user-agent"], "from-file");
+        assert!(!requests[1].headers.contains_key("User-Agent"));
+        assert_eq!(requests[1].headers["X-Config"], "1");
+
+        fs::write(temp_file.path(), "### @defaults\nnot a header\n").unwrap();
+        let error = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains(":2:1"));
+        assert!(error.contains("expected 'Name: value'"));
+    }
+
+    #[test]
+    fn test_parse_large_file() {
+        let mut content = String::new();
+        for index in 0..5000 {
+            content.push_str(&format!(
+                "@id = {index}\n### Request {index}\nPOST https://example.com/items/{{{{id}}}}\nContent-Type: application/json\n\n{{\"index\": {index}}}\n\n"
+            ));
+        }
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), &content).unwrap();
+        let mut parser = HttpParser::new(Environment::new());
+        let requests = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(requests.len(), 5000);
+        assert_eq!(requests[4999].name, "Request 4999");
+        assert_eq!(requests[4999].url, "https://example.com/items/4999");
+        assert_eq!(requests[4999].body.as_deref(), Some("{\"index\": 4999}"));
+
+        // 错误仍定位到原文件中的行（跳过的变量定义行计入行号）
+        content.push_str("@id = last\n### Broken\nGET\n");
+        fs::write(temp_file.path(), &content).unwrap();
+        let error = parser
+            .parse_file(&temp_file.path().to_string_lossy())
+            .unwrap_err();
+        let HttpieError::Syntax { location, .. } = &error else {
+            panic!("expected a syntax error, got {error:?}");
+        };
+        assert_eq!(location.line, 5000 * 7 + 3);
+    }
+
+    #[test]
+    fn test_iter_requests() {
+        let content = r#"
+### @defaults
+Accept: application/json
+
+### First
+# @repeat 2
+GET https://example.com/a
+
+### Second
+GET https://example.com/b
+
+### Broken
+  GET
+
+### Never parsed
+GET https://example.com/c
+"#;
+
+        let parser = HttpParser::new(Environment::new());
+        let mut requests = parser.iter_requests(content);
+
+        let first = requests.next().unwrap().unwrap();
+        assert_eq!(first.name, "First [1/2]");
+        assert_eq!(first.headers["Accept"], "application/json");
+        assert_eq!(
+            requests.next().unwrap().unwrap().iteration,
+            Some(Iteration { index: 2, total: 2 })
+        );
+        assert_eq!(requests.next().unwrap().unwrap().name, "Second");
+
+        // 解析出错后结束迭代
+        let error = requests.next().unwrap().unwrap_err();
+        assert!(matches!(error, HttpieError::Syntax { .. }));
+        assert!(requests.next().is_none());
+    }
+
+    #[test]
+    fn test_iter_requests_applies_defaults_to_following_requests() {
+        let content = r#"### Before
+GET https://example.com/a
+
+### @defaults
+Accept: application/json
+
+### After
+GET https://example.com/b
+
+### @defaults
+not a header
+"#;
+
+        let parser = HttpParser::new(Environment::new());
+        let mut requests = parser.iter_requests(content);
+
+        // 第一个请求不必等到读完后面的段落
+        let before = requests.next().unwrap().unwrap();
+        assert!(!before.headers.contains_key("Accept"));
+        let after = requests.next().unwrap().unwrap();
+        assert_eq!(after.headers["Accept"], "application/json");
+
+        let warnings = requests.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].location.line, 4);
+        assert!(
+            warnings[0]
+                .message
+                .contains("applies only to the requests below it")
+        );
+
+        // 后面段落的错误在读到该段落时才返回
+        assert!(requests.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_parse_body_preserves_bytes() {
+        let content = "### Signed\r\nPOST https://example.com/hook\r\n\r\n  indented \r\nline two\t\r\n\r\n### Trimmed\n# @trim-body\nPOST https://example.com/trim\n\n\n  padded  \n\n### With handler\nPOST https://example.com/h\n\n{\"a\": 1}\n\n> {%\n  client.log(1);\n%}\n";
+
+        let parser = HttpParser::new(Environment::new());
+        let requests: Vec<_> = parser
+            .iter_requests(content)
+            .collect::<httpie::Result<_>>()
+            .unwrap();
+
+        // CRLF、缩进和行尾空白原样保留，只去掉与下一个请求之间的空行
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some("  indented \r\nline two\t")
+        );
+        assert_eq!(requests[1].body.as_deref(), Some("padded"));
+        assert_eq!(requests[2].body.as_deref(), Some("{\"a\": 1}"));
+        assert_eq!(
+            requests[2].response_handler.as_deref(),
+            Some("client.log(1);")
+        );
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let content = r#"### Comments
+# note: before the request line
+POST https://example.com/items
+# note: not a header
+Content-Type: text/plain
+// @repeat 2
+
+# kept: inside the body
+// also kept
+
+> {%
+  // handler comment
+  client.log("ok");
+%}
+"#;
+
+        let parser = HttpParser::new(Environment::new());
+        let requests: Vec<_> = parser
+            .iter_requests(content)
+            .collect::<httpie::Result<_>>()
+            .unwrap();
+
+        // 请求头区域中的指令同样生效
+        assert_eq!(requests.len(), 2);
+        let request = &requests[0];
+        assert_eq!(request.directive("repeat"), Some("2"));
+        assert_eq!(
+            request.headers.keys().collect::<Vec<_>>(),
+            vec!["Content-Type"]
+        );
+        assert_eq!(
+            request.body.as_deref(),
+            Some("# kept: inside the body\n// also kept")
+        );
+        assert_eq!(
+            request.response_handler.as_deref(),
+            Some("// handler comment\n  client.log(\"ok\");")
+        );
+    }
+
+    #[test]
+    fn test_parse_implicit_sections() {
+        let content = r#"# Users API
+
+# @name list-users
+GET https://example.com/users
+Accept: application/json
+
+POST https://example.com/users
+Content-Type: application/json
+
+{"name": "alice"}
+
+> {%
+  client.log("created");
+
+GET is not a request line inside a handler
+%}
+
+### Explicit
+DELETE https://example.com/users/1
+"#;
+
+        let requests = |implicit| {
+            HttpParser::new(Environment::new())
+                .with_implicit_sections(implicit)
+                .iter_requests(content)
+                .collect::<httpie::Result<Vec<_>>>()
+                .unwrap()
+        };
+
+        let implicit = requests(true);
+        let names: Vec<_> = implicit.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["list-users", "POST https://example.com/users", "Explicit"]
+        );
+        assert!(implicit[0].body.is_none());
+        assert_eq!(implicit[1].body.as_deref(), Some(r#"{"name": "alice"}"#));
+        assert!(
+            implicit[1]
+                .response_handler
+                .as_deref()
+                .unwrap()
+                .ends_with("GET is not a request line inside a handler")
+        );
+
+        // 默认只以 `###` 分隔
+        let names: Vec<_> = requests(false).into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["Explicit"]);
+    }
+
+    #[test]
+    fn test_parse_handler_marker_variants() {
+        let content = r#"### Test POST Request
+POST https://httpbin.org/post
+Content-Type: application/json
+
+{
+  "name": "test"
+}
+
+> {
+%
+client.test("Status should be 200", function() {
+    client.assert(response.status === 200, "Expected status 200");
+});
+%
+}
+"#
+        .replace('\n', "\r\n");
+        let parser = HttpParser::new(Environment::new());
+        let requests: Vec<_> = parser
+            .iter_requests(&content)
+            .collect::<httpie::Result<_>>()
+            .unwrap();
+
+        let post = requests
+            .iter()
+            .find(|r| r.name == "Test POST Request")
+            .unwrap();
+        let handler = post.response_handler.as_deref().unwrap();
+        assert!(handler.starts_with("client.test(\"Status should be 200\""));
+        assert!(handler.ends_with("});"));
+        assert!(post.body.as_deref().unwrap().ends_with('}'));
+
+        let error = parser
+            .iter_requests("### Open\nGET https://example.com\n\n> {%\nclient.log(1);\n")
+            .next()
+            .unwrap()
+            .unwrap_err();
+        let HttpieError::Syntax {
+            location, message, ..
+        } = &error
+        else {
+            panic!("expected a syntax error, got {error:?}");
+        };
+        assert_eq!(location.line, 4);
+        assert!(message.contains("Unterminated response handler"));
+    }
+
+    #[test]
+    fn test_parse_warnings() {
+        let content = r#"### First
+# @frobnicate yes
+GET https://example.com/a
+Bad Header: 1
+no colon here
+
+### No method
+FETCH https://example.com/b
+
+### Same as first
+GET https://example.com/a
+
+###
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        let path = temp_file.path().to_string_lossy().to_string();
+        let mut parser = HttpParser::new(Environment::new());
+        let output = parser.parse_file_with_warnings(&path).unwrap();
+
+        assert_eq!(output.requests.len(), 2);
+        let duplicate = format!(
+            "requests 'First' and 'Same as first' are identical (same method, URL and body; fingerprint {})",
+            output.requests[0].fingerprint()
+        );
+        let warnings: Vec<_> = output
+            .warnings
+            .iter()
+            .map(|w| (w.location.line, w.location.column, w.message.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (2, 3, "unknown directive '@frobnicate'"),
+                (4, 1, "suspicious header name 'Bad Header'"),
+                (
+                    5,
+                    1,
+                    "ignored header line 'no colon here': expected 'Name: value'"
+                ),
+                (
+                    8,
+                    1,
+                    "section 'No method' has no request line and was skipped"
+                ),
+                (10, 1, duplicate.as_str()),
+            ]
+        );
+        assert_eq!(
+            output.warnings[0].location.file.as_deref(),
+            Some(path.as_str())
+        );
+
+        // --strict：警告转换为带位置的错误
+        let error = output.warnings[0].clone().into_error().to_string();
+        assert!(error.starts_with(&format!("{path}:2:3: unknown directive")));
+        assert!(error.contains("# @frobnicate yes"));
+
+        // 警告作为带位置字段的日志事件输出
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || LogWriter(writer.clone()))
+            .finish();
+        tracing::subscriber::with_default(subscriber, || output.warnings[0].log());
+        let event: serde_json::Value =
+            serde_json::from_slice(buffer.lock().unwrap().as_slice()).unwrap();
+        assert_eq!(event["level"], "WARN");
+        assert_eq!(event["fields"]["file"], path.as_str());
+        assert_eq!(event["fields"]["line"], 2);
+        assert_eq!(event["fields"]["column"], 3);
+        assert!(
+            event["fields"]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("unknown directive")
+        );
+    }
+
+    /// 收集日志输出的缓冲区
+    struct LogWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for LogWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_request_index_and_duplicate_names() {
+        let content = r#"### Login
+POST https://example.com/login
+
+### List
+# @repeat 2
+GET https://example.com/items
+
+### Login
+POST https://example.com/login?admin=1
+"#;
+
+        let parser = HttpParser::new(Environment::new());
+        let mut iter = parser.iter_requests(content);
+        let requests: Vec<_> = iter.by_ref().collect::<Result<_, _>>().unwrap();
+        let indexes: Vec<_> = requests.iter().map(|r| r.index).collect();
+        assert_eq!(indexes, vec![Some(1), Some(2), Some(2), Some(3)]);
+
+        let warnings = iter.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].location.line, 8);
+        assert_eq!(
+            warnings[0].message,
+            "request name 'Login' is already used by request #1; \
+             select this one with --case 'Login#2' or --index 3"
+        );
+    }
+
+    #[test]
+    fn test_parse_condition_directives() {
+        let content = r#"@stage = my staging
+
+### Conditional
+# @if {{stage}} == "my staging" && {{token}}
+GET https://example.com/a
+
+### Invalid
+# @require {{stage}} ===
+GET https://example.com/b
+"#;
+
+        let parser = HttpParser::new(Environment::new());
+        let mut requests = parser.iter_requests(content);
+        let request = requests.next().unwrap().unwrap();
+        assert_eq!(
+            request.directive("if"),
+            Some(r#""my staging" == "my staging" && "{{token}}""#)
+        );
+
+        let error = requests.next().unwrap().unwrap_err().to_string();
+        assert!(error.starts_with("8:12:"), "{error}");
+        assert!(error.contains("unknown operator '==='"), "{error}");
+    }
 }
@@ -0,0 +1,57 @@
+//! progress模块的单元测试
+
+use httpie::progress::progress_line;
+use httpie::{RequestReport, TestResult};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(test_results: Vec<TestResult>, error: Option<&str>) -> RequestReport {
+        RequestReport {
+            name: "Get users".to_string(),
+            method: "GET".to_string(),
+            url: "https://example.com/users".to_string(),
+            status: error.is_none().then_some(200),
+            duration: Duration::from_millis(120),
+            test_results,
+            error: error.map(String::from),
+            iteration: None,
+            hook: None,
+            skipped: None,
+            logs: Vec::new(),
+            xfail: None,
+        }
+    }
+
+    #[test]
+    fn test_progress_line_passed() {
+        let report = report(vec![TestResult::success("status is 200")], None);
+        assert_eq!(
+            progress_line(1, 5, &report),
+            "[2/5] ✓ GET Get users (120 ms)"
+        );
+    }
+
+    #[test]
+    fn test_progress_line_failure_reason() {
+        let failed = report(
+            vec![
+                TestResult::success("status is 200"),
+                TestResult::failure("has users", "expected 3, got 0", "AssertionError"),
+            ],
+            None,
+        );
+        assert_eq!(
+            progress_line(0, 1, &failed),
+            "[1/1] ✗ GET Get users (120 ms) - has users: expected 3, got 0"
+        );
+
+        let errored = report(Vec::new(), Some("connection refused"));
+        assert_eq!(
+            progress_line(2, 3, &errored),
+            "[3/3] ✗ GET Get users (120 ms) - connection refused"
+        );
+    }
+}
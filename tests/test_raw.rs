@@ -0,0 +1,157 @@
+//! raw模块的单元测试
+
+use httpie::raw::{DEFAULT_RAW_TIMEOUT, RawConnection, encode_body};
+use httpie::{Environment, HttpClient, HttpParser, HttpRequest};
+use std::fs;
+use tempfile::NamedTempFile;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> httpie::Result<Vec<HttpRequest>> {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+        HttpParser::new(Environment::new()).parse_file(&temp_file.path().to_string_lossy())
+    }
+
+    #[test]
+    fn test_parse_raw_args() {
+        let (url, connection) = RawConnection::parse(&["localhost:8080"]).unwrap();
+        assert_eq!(url, "tcp://localhost:8080");
+        assert!(!connection.crlf && !connection.shutdown);
+        assert_eq!(connection.timeout(), DEFAULT_RAW_TIMEOUT);
+
+        let (url, connection) =
+            RawConnection::parse(&["tls://example.com:443", "crlf", "shutdown", "timeout=2s"])
+                .unwrap();
+        assert_eq!(url, "tls://example.com:443");
+        assert!(connection.crlf && connection.shutdown);
+        assert_eq!(connection.timeout_ms, 2000);
+
+        // 端口可以引用运行期变量
+        let (url, _) = RawConnection::parse(&["{{host}}:{{port}}"]).unwrap();
+        assert_eq!(url, "tcp://{{host}}:{{port}}");
+
+        for args in [
+            &["localhost"][..],
+            &["localhost:0"],
+            &[":8080"],
+            &["localhost:8080/path"],
+            &["udp://localhost:53"],
+            &["localhost:8080", "keepalive"],
+        ] {
+            let error = RawConnection::parse(args).unwrap_err();
+            assert!(
+                error.to_string().contains("Invalid RAW request"),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_body() {
+        assert_eq!(
+            encode_body("GET / HTTP/1.1\nHost: x\n\\r\\n", true),
+            b"GET / HTTP/1.1\r\nHost: x\r\n\r\n"
+        );
+        assert_eq!(encode_body("a\\x00\\xffb\\t\\\\", false), b"a\0\xffb\t\\");
+        // 无法识别的转义按原样发送
+        assert_eq!(encode_body("\\q\\x4", false), b"\\q\\x4");
+        assert_eq!(encode_body("line\nline", false), b"line\nline");
+    }
+
+    #[test]
+    fn test_parse_raw_request() {
+        let requests = parse(
+            "### Malformed\nRAW localhost:8080 crlf timeout=1s\n\nGET / HTTP/1.1\nHost: {{undefined}}\n\\r\\n\n\n?? body contains 400\n",
+        )
+        .unwrap();
+
+        let request = &requests[0];
+        assert_eq!(request.method.as_str(), "RAW");
+        assert_eq!(request.url, "tcp://localhost:8080");
+        assert_eq!(request.raw.as_ref().unwrap().timeout_ms, 1000);
+        assert!(request.http_version.is_none());
+        // 不添加默认请求头
+        assert!(request.headers.is_empty());
+        assert_eq!(
+            request.body.as_deref(),
+            Some("GET / HTTP/1.1\nHost: {{undefined}}\n\\r\\n")
+        );
+
+        let error = parse("### Bad\nRAW localhost\n").unwrap_err();
+        assert!(error.to_string().contains("expected host:port"));
+    }
+
+    #[tokio::test]
+    async fn test_raw_request_reads_until_close() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = vec![0u8; 64];
+            let read = socket.read(&mut received).await.unwrap();
+            received.truncate(read);
+            socket
+                .write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n")
+                .await
+                .unwrap();
+            received
+        });
+
+        let requests = parse(&format!(
+            "### Malformed\nRAW 127.0.0.1:{port} crlf\n\nGET / HTTP/1.1\n\\r\\n\n\n?? body contains 400 Bad Request\n"
+        ))
+        .unwrap();
+        let mut client = HttpClient::new().with_print_response(false);
+        let outcome = client.execute_with_outcome(&requests[0]).await.unwrap();
+
+        assert_eq!(server.await.unwrap(), b"GET / HTTP/1.1\r\n\r\n");
+        assert_eq!(outcome.response.status, 0);
+        assert_eq!(outcome.response.body, "HTTP/1.1 400 Bad Request\r\n\r\n");
+        assert!(outcome.response.http_version.is_none());
+        assert!(outcome.passed(), "{:?}", outcome.test_results);
+    }
+
+    #[tokio::test]
+    async fn test_raw_request_stops_at_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"+PONG\r\n").await.unwrap();
+            // 保持连接不关闭
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        });
+
+        let requests = parse(&format!(
+            "### Ping\nRAW 127.0.0.1:{port} timeout=200ms\n\nPING\\r\\n\n"
+        ))
+        .unwrap();
+        let mut client = HttpClient::new().with_print_response(false);
+        let outcome = client.execute_with_outcome(&requests[0]).await.unwrap();
+
+        assert_eq!(outcome.response.body, "+PONG\r\n");
+        assert!(outcome.duration >= std::time::Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_raw_request_connection_refused() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let requests = parse(&format!("### Closed\nRAW 127.0.0.1:{port}\n\nhello\n")).unwrap();
+
+        let mut client = HttpClient::new().with_print_response(false);
+        let error = client.execute_with_outcome(&requests[0]).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("failed to connect to 127.0.0.1:{port}"))
+        );
+    }
+}
@@ -0,0 +1,1584 @@
+//! runner模块的单元测试
+
+use httpie::Directive;
+use httpie::{
+    Abort, CancellationToken, CaseSelector, FailurePolicy, Hook, HttpClient, HttpRequest,
+    HttpieError, Iteration, RequestReport, RunOrder, RunReport, Runner,
+};
+#[cfg(feature = "scripting")]
+use httpie::{ExecutionObserver, ResponseObject, ScriptHooks, TestResult};
+use mockito::Server;
+use reqwest::Method;
+use serde_json::Value;
+#[cfg(feature = "scripting")]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+#[cfg(feature = "scripting")]
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_collects_report() {
+        let mut server = Server::new_async().await;
+        let ok_mock = server
+            .mock("GET", "/ok")
+            .with_status(200)
+            .with_body("ok")
+            .create_async()
+            .await;
+        let created_mock = server
+            .mock("POST", "/items")
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 1}"#)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("ok", Method::GET, &format!("{}/ok", server.url())),
+            create_request("create", Method::POST, &format!("{}/items", server.url()))
+                .with_response_handler(Some(
+                    r#"
+client.test("created", function() {
+    client.assert(response.status === 201);
+});
+client.test("wrong id", function() {
+    client.assert(response.body.id === 2, "id mismatch");
+});
+"#
+                    .to_string(),
+                )),
+        ];
+
+        let mut runner = Runner::new(create_script_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.passed(), 1);
+        assert_eq!(report.failed(), 1);
+        assert_eq!(report.tests_passed(), 1);
+        assert_eq!(report.tests_failed(), 1);
+        assert!(!report.success());
+        assert!(report.first_error().is_none());
+
+        assert_eq!(report.requests[0].status, Some(200));
+        assert_eq!(report.requests[1].status, Some(201));
+        assert_eq!(report.requests[1].method, "POST");
+        assert_eq!(
+            report.requests[1].test_results[1].request_name.as_deref(),
+            Some("create")
+        );
+
+        ok_mock.assert_async().await;
+        created_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_with_case_filter() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/second")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request(
+                "first case",
+                Method::GET,
+                &format!("{}/first", server.url()),
+            ),
+            create_request(
+                "second case",
+                Method::GET,
+                &format!("{}/second", server.url()),
+            ),
+        ];
+
+        let mut runner = Runner::new(create_client()).with_case_filter(Some("second".to_string()));
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 1);
+        assert_eq!(report.requests[0].name, "second case");
+        mock.assert_async().await;
+
+        let mut runner = Runner::new(create_client()).with_case_filter(Some("missing".to_string()));
+        let result = runner.run(&requests).await;
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Case 'missing' not found")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_case_filter_selects_all_iterations() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repeat")
+            .with_status(200)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let url = format!("{}/repeat", server.url());
+        let mut requests: Vec<HttpRequest> = (1..=3)
+            .map(|index| {
+                create_request(&format!("repeat [{index}/3]"), Method::GET, &url)
+                    .with_iteration(Some(Iteration { index, total: 3 }))
+            })
+            .collect();
+        requests.push(create_request(
+            "repeat later",
+            Method::GET,
+            &format!("{}/later", server.url()),
+        ));
+
+        let mut runner = Runner::new(create_client()).with_case_filter(Some("repeat".to_string()));
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 3);
+        assert_eq!(
+            report.requests[2].iteration,
+            Some(Iteration { index: 3, total: 3 })
+        );
+        assert!(report.success());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_evaluates_aggregate_assertions_after_last_iteration() {
+        let mut server = Server::new_async().await;
+        let ok = server
+            .mock("GET", "/search")
+            .with_status(200)
+            .expect(4)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("GET", "/flaky")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let assertions = vec![
+            "status == 200".to_string(),
+            "p95 < 10s".to_string(),
+            "error_rate < 1%".to_string(),
+        ];
+        let search = format!("{}/search", server.url());
+        let flaky = |index: usize| format!("{}/flaky?i={index}", server.url());
+        let group = |name: &str, urls: &[String]| -> Vec<HttpRequest> {
+            let total = urls.len();
+            urls.iter()
+                .enumerate()
+                .map(|(i, url)| {
+                    let index = i + 1;
+                    create_request(&format!("{name} [{index}/{total}]"), Method::GET, url)
+                        .with_iteration(Some(Iteration { index, total }))
+                        .with_assertions(assertions.clone())
+                })
+                .collect()
+        };
+        let mut requests = group("search", &[search.clone(), search.clone()]);
+        requests.extend(group(
+            "mixed",
+            &[search.clone(), flaky(2), search.clone(), flaky(4)],
+        ));
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        let names = |report: &RequestReport| -> Vec<String> {
+            report.test_results.iter().map(|r| r.name.clone()).collect()
+        };
+        // 只有最后一次迭代带有聚合断言的结果
+        assert_eq!(names(&report.requests[0]), vec!["status == 200"]);
+        assert_eq!(
+            names(&report.requests[1]),
+            vec!["status == 200", "p95 < 10s", "error_rate < 1%"]
+        );
+        assert!(report.requests[1].passed());
+
+        let last = &report.requests[5];
+        assert_eq!(last.name, "mixed [4/4]");
+        let error_rate = last
+            .test_results
+            .iter()
+            .find(|r| r.name == "error_rate < 1%")
+            .unwrap();
+        assert!(!error_rate.passed);
+        assert_eq!(
+            error_rate.message.as_deref(),
+            Some("error rate was 50.00% over 4 request(s)")
+        );
+        assert!(
+            last.test_results
+                .iter()
+                .any(|r| r.name == "p95 < 10s" && r.passed)
+        );
+        ok.assert_async().await;
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_evaluates_aggregate_assertions_when_group_ends_early() {
+        let mut server = Server::new_async().await;
+        let ok = server
+            .mock("GET", "/search")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("GET", "/flaky")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let assertions = vec!["status == 200".to_string(), "error_rate < 1%".to_string()];
+        let iteration = |name: &str, path: &str, index: usize| {
+            create_request(
+                &format!("{name} [{index}/3]"),
+                Method::GET,
+                &format!("{}{path}", server.url()),
+            )
+            .with_iteration(Some(Iteration { index, total: 3 }))
+            .with_assertions(assertions.clone())
+        };
+        let aggregate_names = |report: &RequestReport| -> Vec<String> {
+            report
+                .test_results
+                .iter()
+                .filter(|r| r.name != "status == 200")
+                .map(|r| r.name.clone())
+                .collect()
+        };
+
+        // 最后一次迭代被 `@if` 跳过时，聚合断言附在最后一次发送的迭代上
+        let requests = vec![
+            iteration("search", "/search", 1),
+            iteration("search", "/search", 2),
+            iteration("search", "/search", 3)
+                .with_directives(vec![Directive::new("if", r#""a" == "b""#)]),
+        ];
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+        assert_eq!(report.skipped(), 1);
+        assert!(aggregate_names(&report.requests[0]).is_empty());
+        assert_eq!(aggregate_names(&report.requests[1]), ["error_rate < 1%"]);
+        assert!(report.requests[1].passed());
+
+        // 失败处理策略中途停止时，已采集的样本照常求值
+        let requests = vec![
+            iteration("flaky", "/flaky", 1),
+            iteration("flaky", "/flaky", 2),
+            iteration("flaky", "/flaky", 3),
+        ];
+        let mut runner = Runner::new(create_client()).with_failure_policy(FailurePolicy::FailFast);
+        let report = runner.run(&requests).await.unwrap();
+        assert_eq!(report.requests.len(), 1);
+        let error_rate = report.requests[0]
+            .test_results
+            .iter()
+            .find(|r| r.name == "error_rate < 1%")
+            .unwrap();
+        assert!(!error_rate.passed);
+        assert_eq!(
+            error_rate.message.as_deref(),
+            Some("error rate was 100.00% over 1 request(s)")
+        );
+        ok.assert_async().await;
+        failing.assert_async().await;
+    }
+
+    #[test]
+    fn test_case_selector_duplicate_names() {
+        let requests: Vec<HttpRequest> = [
+            ("login", "/a"),
+            ("login", "/b"),
+            ("login admin", "/c"),
+            ("issue #2", "/d"),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(index, (name, path))| {
+            create_request(name, Method::GET, &format!("http://localhost{path}"))
+                .with_index(Some(index + 1))
+        })
+        .collect();
+        let requests: Vec<&HttpRequest> = requests.iter().collect();
+        let position = |selector: CaseSelector| selector.position(&requests);
+
+        assert_eq!(position(CaseSelector::Name("login".to_string())), Some(0));
+        assert_eq!(position(CaseSelector::Name("login#2".to_string())), Some(1));
+        assert_eq!(position(CaseSelector::Name("login#3".to_string())), None);
+        assert_eq!(
+            position(CaseSelector::Name("issue #2".to_string())),
+            Some(3)
+        );
+        assert_eq!(position(CaseSelector::Index(3)), Some(2));
+        assert_eq!(position(CaseSelector::Index(5)), None);
+        assert!(CaseSelector::Name("login".to_string()).is_name_filter());
+        assert!(!CaseSelector::Name("login#2".to_string()).is_name_filter());
+        assert_eq!(CaseSelector::Index(3).to_string(), "#3");
+    }
+
+    #[test]
+    fn test_case_selector_across_files() {
+        let request = |file: &str, name: &str, index: usize| {
+            create_request(name, Method::GET, "http://localhost/")
+                .with_source_file(Some(PathBuf::from(file)))
+                .with_index(Some(index))
+        };
+        let requests = [
+            request("api/users.http", "list users", 1),
+            request("api/users.http", "create user", 2),
+            request("api/orders.http", "list orders", 1),
+            request("admin/users.http", "list users", 1),
+        ];
+        let requests: Vec<&HttpRequest> = requests.iter().collect();
+        let positions = |text: &str| CaseSelector::parse(text).positions(&requests);
+
+        assert_eq!(positions("list users"), vec![0]);
+        assert_eq!(positions("list*"), vec![0, 2, 3]);
+        assert_eq!(positions("api/users.http::list users"), vec![0]);
+        assert_eq!(positions("admin/users.http::list"), vec![3]);
+        assert_eq!(positions("users.http::list users"), vec![0]);
+        assert_eq!(positions("users.http::list users#2"), vec![3]);
+        assert_eq!(positions("*/users.http::*user*"), vec![0, 1, 3]);
+        assert_eq!(positions("orders.http::"), vec![2]);
+        assert_eq!(positions("api/*::#2"), vec![1]);
+        assert_eq!(positions("billing.http::*"), Vec::<usize>::new());
+        assert_eq!(
+            CaseSelector::parse("api/*::#2").to_string(),
+            "#2 in 'api/*'"
+        );
+        assert_eq!(requests[3].qualified_name(), "admin/users.http::list users");
+    }
+
+    #[tokio::test]
+    async fn test_run_with_multiple_selectors_and_qualified_names() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let request = |file: &str, name: &str| {
+            create_request(name, Method::GET, &format!("{}/{name}", server.url()))
+                .with_source_file(Some(PathBuf::from(file)))
+        };
+        let requests = vec![
+            request("a.http", "login"),
+            request("a.http", "profile"),
+            request("b.http", "login"),
+        ];
+
+        let mut runner = Runner::new(create_client())
+            .with_case_selectors(vec![
+                CaseSelector::parse("b.http::login"),
+                CaseSelector::parse("profile"),
+            ])
+            .with_qualified_names(true);
+        let report = runner.run(&requests).await.unwrap();
+        let names: Vec<&str> = report.requests.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["a.http::profile", "b.http::login"]);
+        mock.assert_async().await;
+
+        let mut runner = Runner::new(create_client()).with_case_selectors(vec![
+            CaseSelector::parse("login"),
+            CaseSelector::parse("c.http::*"),
+        ]);
+        let error = runner.run(&requests).await.unwrap_err();
+        assert!(error.to_string().contains("Case '*' in 'c.http' not found"));
+    }
+
+    #[tokio::test]
+    async fn test_run_with_case_selector_index() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/second")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("same", Method::GET, &format!("{}/first", server.url()))
+                .with_index(Some(1)),
+            create_request("same", Method::GET, &format!("{}/second", server.url()))
+                .with_index(Some(2)),
+        ];
+
+        let mut runner =
+            Runner::new(create_client()).with_case_selector(Some(CaseSelector::Index(2)));
+        let report = runner.run(&requests).await.unwrap();
+        assert_eq!(report.total(), 1);
+        mock.assert_async().await;
+
+        let mut runner = Runner::new(create_client()).with_case_filter(Some("same#2".to_string()));
+        let report = runner.run(&requests).await.unwrap();
+        assert_eq!(report.requests[0].url, format!("{}/second", server.url()));
+    }
+
+    #[tokio::test]
+    async fn test_run_skips_requests_marked_skip() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/kept")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("flaky", Method::GET, &format!("{}/flaky", server.url()))
+                .with_directives(vec![Directive::new("skip", "waiting for #123")]),
+            create_request("kept", Method::GET, &format!("{}/kept", server.url())),
+            create_request("todo", Method::GET, &format!("{}/todo", server.url()))
+                .with_directives(vec![Directive::new("skip", "")]),
+        ];
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(report.total(), 3);
+        assert_eq!(report.passed(), 1);
+        assert_eq!(report.failed(), 0);
+        assert_eq!(report.skipped(), 2);
+        assert!(report.success());
+        assert_eq!(
+            report.requests[0].skipped.as_deref(),
+            Some("waiting for #123")
+        );
+        assert_eq!(report.status_classes().get("skipped"), Some(&2));
+
+        let summary = report.summary(5).to_string();
+        assert!(summary.contains("3 (1 passed, 0 failed, 2 skipped)"));
+        assert!(summary.contains("Skipped requests:\n  - flaky: waiting for #123\n  - todo\n"));
+        assert!(!summary.contains("ms  ERR"));
+    }
+
+    #[tokio::test]
+    async fn test_run_conditional_requests() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/staging")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let request = |name: &str, directive: Directive| {
+            create_request(name, Method::GET, &format!("{}/{name}", server.url()))
+                .with_directives(vec![directive])
+        };
+        let requests = vec![
+            request(
+                "staging",
+                Directive::new("if", r#""{{stage}}" == "staging""#),
+            ),
+            request(
+                "production",
+                Directive::new("if", r#""{{stage}}" == "production""#),
+            ),
+            request("needs-token", Directive::new("require", r#""{{token}}""#)),
+        ];
+
+        let mut runner =
+            Runner::new(create_client()).with_failure_policy(FailurePolicy::ContinueOnError);
+        runner.client_mut().variables().set("stage", "staging");
+        let report = runner.run(&requests).await.unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(report.passed(), 1);
+        assert_eq!(
+            report.requests[1].skipped.as_deref(),
+            Some(r#"@if "staging" == "production" is false"#)
+        );
+        assert_eq!(
+            report.requests[2].error.as_deref(),
+            Some(r#"Invalid request format: Requirement not met: @require "{{token}}""#)
+        );
+        assert_eq!(report.failed(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_only_requests_marked_only() {
+        let mut server = Server::new_async().await;
+        let focused = server
+            .mock("GET", "/focused")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request(
+                "@setup seed",
+                Method::GET,
+                &format!("{}/focused", server.url()),
+            )
+            .with_hook(Some(Hook::Setup)),
+            create_request("other", Method::GET, &format!("{}/other", server.url())),
+            create_request("focused", Method::GET, &format!("{}/focused", server.url()))
+                .with_directives(vec![Directive::new("only", "")]),
+        ];
+
+        let runner = Runner::new(create_client());
+        let selected: Vec<&str> = runner
+            .select(&requests)
+            .unwrap()
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(selected, vec!["@setup seed", "focused"]);
+
+        // 指定 --case 时忽略 @only
+        let runner = Runner::new(create_client()).with_case_filter(Some("other".to_string()));
+        assert_eq!(runner.select(&requests).unwrap()[1].name, "other");
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+        assert_eq!(report.total(), 2);
+        focused.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_on_transport_error() {
+        let requests = vec![
+            create_request("unreachable", Method::GET, "http://127.0.0.1:1/unreachable"),
+            create_request("never", Method::GET, "http://127.0.0.1:1/never"),
+        ];
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 1);
+        assert!(report.first_error().is_some());
+        assert!(report.requests[0].status.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_hooks_wrap_filtered_case() {
+        let mut server = Server::new_async().await;
+        let setup_mock = server
+            .mock("POST", "/users")
+            .with_status(201)
+            .create_async()
+            .await;
+        let case_mock = server
+            .mock("GET", "/second")
+            .with_status(200)
+            .create_async()
+            .await;
+        let teardown_mock = server
+            .mock("DELETE", "/users")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request(
+                "first case",
+                Method::GET,
+                &format!("{}/first", server.url()),
+            ),
+            create_request(
+                "@teardown delete user",
+                Method::DELETE,
+                &format!("{}/users", server.url()),
+            )
+            .with_hook(Some(Hook::Teardown)),
+            create_request(
+                "second case",
+                Method::GET,
+                &format!("{}/second", server.url()),
+            ),
+            create_request(
+                "@setup create user",
+                Method::POST,
+                &format!("{}/users", server.url()),
+            )
+            .with_hook(Some(Hook::Setup)),
+        ];
+
+        let mut runner = Runner::new(create_client()).with_case_filter(Some("second".to_string()));
+        let report = runner.run(&requests).await.unwrap();
+
+        let names: Vec<_> = report.requests.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["@setup create user", "second case", "@teardown delete user"]
+        );
+        assert_eq!(report.requests[0].hook, Some(Hook::Setup));
+        assert!(report.success());
+        setup_mock.assert_async().await;
+        case_mock.assert_async().await;
+        teardown_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_failed_setup_skips_cases_but_runs_teardown() {
+        let mut server = Server::new_async().await;
+        let case_mock = server.mock("GET", "/case").expect(0).create_async().await;
+        let teardown_mock = server
+            .mock("DELETE", "/users")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("@setup", Method::POST, "http://127.0.0.1:1/users")
+                .with_hook(Some(Hook::Setup)),
+            create_request("case", Method::GET, &format!("{}/case", server.url())),
+            create_request(
+                "@teardown",
+                Method::DELETE,
+                &format!("{}/users", server.url()),
+            )
+            .with_hook(Some(Hook::Teardown)),
+        ];
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 2);
+        assert!(report.requests[0].error.is_some());
+        assert_eq!(report.requests[1].name, "@teardown");
+        assert_eq!(report.requests[1].status, Some(204));
+        assert!(!report.success());
+        case_mock.assert_async().await;
+        teardown_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_continue_on_error_runs_every_request() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/after")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("unreachable", Method::GET, "http://127.0.0.1:1/unreachable"),
+            create_request("after", Method::GET, &format!("{}/after", server.url())),
+        ];
+
+        let mut runner =
+            Runner::new(create_client()).with_failure_policy(FailurePolicy::ContinueOnError);
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.failed(), 1);
+        let failures: Vec<_> = report.failures().map(|r| r.name.as_str()).collect();
+        assert_eq!(failures, ["unreachable"]);
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_fail_fast_stops_on_failed_test() {
+        let mut server = Server::new_async().await;
+        let first_mock = server
+            .mock("GET", "/first")
+            .with_status(500)
+            .create_async()
+            .await;
+        let second_mock = server.mock("GET", "/second").expect(0).create_async().await;
+
+        let requests = vec![
+            create_request("first", Method::GET, &format!("{}/first", server.url()))
+                .with_response_handler(Some(
+                    r#"
+client.test("ok", function() {
+    client.assert(response.status === 200, "unexpected status");
+});
+"#
+                    .to_string(),
+                )),
+            create_request("second", Method::GET, &format!("{}/second", server.url())),
+        ];
+
+        let mut runner =
+            Runner::new(create_script_client()).with_failure_policy(FailurePolicy::FailFast);
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 1);
+        assert_eq!(report.tests_failed(), 1);
+        assert!(report.first_error().is_none());
+        first_mock.assert_async().await;
+        second_mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_run_report_summary() {
+        let request = |name: &str, status: Option<u16>, millis: u64| RequestReport {
+            name: name.to_string(),
+            method: "GET".to_string(),
+            url: format!("https://example.com/{name}"),
+            status,
+            duration: Duration::from_millis(millis),
+            test_results: Vec::new(),
+            error: status.is_none().then(|| "connection refused".to_string()),
+            iteration: None,
+            hook: None,
+            skipped: None,
+            logs: Vec::new(),
+            xfail: None,
+        };
+        let report = RunReport {
+            requests: vec![
+                request("fast", Some(200), 12),
+                request("slow", Some(204), 950),
+                request("missing", Some(404), 40),
+                request("down", None, 3000),
+            ],
+            duration: Duration::from_millis(4002),
+            cancelled: false,
+            aborted: None,
+        };
+
+        let classes = report.status_classes();
+        assert_eq!(classes["2xx"], 2);
+        assert_eq!(classes["4xx"], 1);
+        assert_eq!(classes["error"], 1);
+
+        let slowest: Vec<&str> = report.slowest(2).iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(slowest, ["down", "slow"]);
+        assert_eq!(report.slowest(10).len(), 4);
+
+        let summary = report.summary(2).to_string();
+        assert!(
+            summary.contains("Requests     4 (3 passed, 1 failed)"),
+            "{summary}"
+        );
+        assert!(
+            summary.contains("Status       2xx: 2, 4xx: 1, error: 1"),
+            "{summary}"
+        );
+        assert!(summary.contains("Duration     4.00s"), "{summary}");
+        assert!(summary.contains("1.     3000 ms  ERR  GET https://example.com/down  down"));
+        assert!(summary.contains("2.      950 ms  204  GET https://example.com/slow  slow"));
+        assert!(!summary.contains("fast"));
+        assert!(!report.summary(0).to_string().contains("Slowest"));
+    }
+
+    #[test]
+    fn test_run_report_failure_details_and_check() {
+        let request = |name: &str| RequestReport {
+            name: name.to_string(),
+            method: "GET".to_string(),
+            url: format!("https://example.com/{name}"),
+            status: Some(200),
+            duration: Duration::from_millis(10),
+            test_results: Vec::new(),
+            error: None,
+            iteration: None,
+            hook: None,
+            skipped: None,
+            logs: Vec::new(),
+            xfail: None,
+        };
+        let mut report = RunReport {
+            requests: vec![request("ok")],
+            ..Default::default()
+        };
+        assert_eq!(report.failure_details().to_string(), "");
+        assert!(report.check().is_ok());
+
+        let mut failing = request("orders");
+        failing.test_results.push(httpie::TestResult::failure(
+            "status is 200",
+            "expected 200 but got 500",
+            "AssertionError",
+        ));
+        report.requests.push(failing);
+        assert_eq!(
+            report.failure_details().to_string(),
+            "\n1 of 2 request(s) failed:\n  \
+             - orders: test 'status is 200' failed: expected 200 but got 500\n"
+        );
+        assert!(matches!(report.check(), Err(HttpieError::RunFailed(1))));
+
+        let mut down = request("down");
+        down.status = None;
+        down.error = Some("connection refused".to_string());
+        report.requests.push(down);
+        assert!(
+            report
+                .failure_details()
+                .to_string()
+                .contains("  - down: connection refused\n")
+        );
+        assert!(matches!(
+            report.check(),
+            Err(HttpieError::RequestFailed(name, _)) if name == "down"
+        ));
+
+        report.aborted = Some(Abort {
+            request: "orders".to_string(),
+            reason: "stop".to_string(),
+        });
+        assert!(matches!(report.check(), Err(HttpieError::RunAborted(..))));
+        report.cancelled = true;
+        assert!(matches!(report.check(), Err(HttpieError::Cancelled)));
+    }
+
+    #[test]
+    fn test_select_orders_cases() {
+        let url = "http://example.com/";
+        let mut requests = vec![
+            create_request("@setup", Method::POST, url).with_hook(Some(Hook::Setup)),
+            create_request("charlie", Method::GET, url),
+            create_request("alpha", Method::GET, url),
+        ];
+        requests.extend((1..=3).map(|index| {
+            create_request(&format!("bravo [{index}/3]"), Method::GET, url)
+                .with_iteration(Some(Iteration { index, total: 3 }))
+        }));
+        requests.push(create_request("delta", Method::GET, url));
+
+        let names = |order: RunOrder| -> Vec<String> {
+            Runner::new(create_client())
+                .with_order(order)
+                .select(&requests)
+                .unwrap()
+                .iter()
+                .map(|r| r.name.clone())
+                .collect()
+        };
+
+        assert_eq!(
+            names(RunOrder::File),
+            [
+                "@setup",
+                "charlie",
+                "alpha",
+                "bravo [1/3]",
+                "bravo [2/3]",
+                "bravo [3/3]",
+                "delta"
+            ]
+        );
+        assert_eq!(
+            names(RunOrder::Name),
+            [
+                "@setup",
+                "alpha",
+                "bravo [1/3]",
+                "bravo [2/3]",
+                "bravo [3/3]",
+                "charlie",
+                "delta"
+            ]
+        );
+
+        // 相同种子得到相同顺序，钩子保持在最前，迭代保持连续
+        let shuffled = names(RunOrder::Random { seed: 42 });
+        assert_eq!(shuffled, names(RunOrder::Random { seed: 42 }));
+        assert_eq!(shuffled[0], "@setup");
+        let first = shuffled.iter().position(|n| n == "bravo [1/3]").unwrap();
+        assert_eq!(shuffled[first + 1], "bravo [2/3]");
+        assert_eq!(shuffled[first + 2], "bravo [3/3]");
+        assert_eq!(shuffled.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_run_captures_variables_for_later_requests() {
+        let mut server = Server::new_async().await;
+        let login_mock = server
+            .mock("POST", "/login")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token": "secret-token", "user": {"id": 7}}"#)
+            .create_async()
+            .await;
+        let profile_mock = server
+            .mock("GET", "/users/7")
+            .match_header("authorization", "Bearer secret-token")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("login", Method::POST, &format!("{}/login", server.url()))
+                .with_directives(vec![
+                    Directive::new("capture", "token = $.access_token"),
+                    Directive::new("capture", "user_id = $.user.id"),
+                ]),
+            create_request(
+                "profile",
+                Method::GET,
+                &format!("{}/users/{{{{user_id}}}}", server.url()),
+            )
+            .with_headers(HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer {{token}}".to_string(),
+            )])),
+        ];
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert!(report.success());
+        assert!(report.requests[1].url.ends_with("/users/7"));
+        assert_eq!(
+            runner.client_mut().variables().get("token").as_deref(),
+            Some("secret-token")
+        );
+        login_mock.assert_async().await;
+        profile_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_with_concurrency_waits_for_captures() {
+        let mut server = Server::new_async().await;
+        let login_mock = server
+            .mock("POST", "/login")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token": "secret-token", "role": "admin"}"#)
+            .create_async()
+            .await;
+        let profile_mock = server
+            .mock("GET", "/profile")
+            .match_header("authorization", "Bearer secret-token")
+            .with_status(200)
+            .create_async()
+            .await;
+        let admin_mock = server
+            .mock("GET", "/admin")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("login", Method::POST, &format!("{}/login", server.url()))
+                .with_directives(vec![
+                    Directive::new("capture", "token = $.access_token"),
+                    Directive::new("capture", "role = $.role"),
+                ]),
+            create_request("profile", Method::GET, &format!("{}/profile", server.url()))
+                .with_headers(HashMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer {{token}}".to_string(),
+                )])),
+            create_request("admin", Method::GET, &format!("{}/admin", server.url()))
+                .with_directives(vec![Directive::new("if", r#""{{role}}" == "admin""#)]),
+        ];
+
+        // 并发执行时引用捕获值或带条件的请求仍在之前的请求处理完后才发送
+        let mut runner = Runner::new(create_client()).with_concurrency(3);
+        let report = runner.run(&requests).await.unwrap();
+
+        assert!(report.success(), "{}", report.failure_details());
+        assert_eq!(report.skipped(), 0);
+        login_mock.assert_async().await;
+        profile_mock.assert_async().await;
+        admin_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_evaluates_inline_assertions() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/user")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"name": "alice"}"#)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("user", Method::GET, &format!("{}/user", server.url())).with_assertions(
+                vec!["status == 200".to_string(), "$.name == bob".to_string()],
+            ),
+        ];
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.tests_passed(), 1);
+        assert_eq!(report.tests_failed(), 1);
+        assert_eq!(
+            report.requests[0].test_results[1].request_name.as_deref(),
+            Some("user")
+        );
+        assert!(!report.success());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_expected_failures() {
+        let mut server = Server::new_async().await;
+        let broken = server
+            .mock("GET", "/broken")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+        let fixed = server
+            .mock("GET", "/fixed")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+        let status = |name: &str, path: &str| {
+            create_request(name, Method::GET, &format!("{}/{path}", server.url()))
+                .with_assertions(vec!["status == 200".to_string()])
+        };
+
+        let requests = vec![
+            status("broken", "broken").with_directives(vec![Directive::new("xfail", "bug #42")]),
+            status("tests", "broken")
+                .with_directives(vec![Directive::new("xfail-test", "status == 200")]),
+            status("fixed", "fixed").with_directives(vec![Directive::new("xfail", "")]),
+            status("tests-fixed", "fixed")
+                .with_directives(vec![Directive::new("xfail-test", "status == 200")]),
+        ];
+
+        let mut runner = Runner::new(create_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.passed(), 1);
+        assert_eq!(report.xfailed(), 1);
+        assert_eq!(report.failed(), 2);
+        // `@xfail` 请求中失败的测试同样计为预期失败
+        assert_eq!(report.tests_passed(), 1);
+        assert_eq!(report.tests_xfailed(), 2);
+        assert_eq!(report.tests_failed(), 1);
+        assert!(report.requests[0].is_xfailed());
+        assert!(report.requests[1].passed());
+        assert!(report.requests[2].is_xpassed());
+        assert_eq!(
+            report.requests[3].test_results[0].message.as_deref(),
+            Some("expected to fail (@xfail-test) but passed")
+        );
+        assert_eq!(
+            report.unexpected_passes(),
+            vec![
+                "fixed".to_string(),
+                "tests-fixed: test 'status == 200'".to_string()
+            ]
+        );
+        assert!(!report.success());
+
+        let summary = report.summary(0).to_string();
+        assert!(
+            summary.contains("Requests     4 (1 passed, 2 failed, 1 xfailed)"),
+            "{summary}"
+        );
+        assert!(summary.contains("Tests        1 passed, 1 failed, 2 xfailed"));
+        assert!(summary.contains("Expected failures:\n  - broken: bug #42\n"));
+        assert!(summary.contains(
+            "Unexpected passes (XPASS, remove @xfail):\n  - fixed\n  - tests-fixed: test 'status == 200'\n"
+        ));
+        broken.assert_async().await;
+        fixed.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_logs_request_span_and_test_events() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/user")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let buffer = LogBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_max_level(tracing::Level::INFO)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let requests = vec![
+            create_request("user", Method::GET, &format!("{}/user", server.url()))
+                .with_assertions(vec!["status == 201".to_string()]),
+        ];
+        Runner::new(create_client()).run(&requests).await.unwrap();
+
+        let events: Vec<Value> = String::from_utf8(buffer.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let test_event = events
+            .iter()
+            .find(|event| event["fields"]["message"] == "test result")
+            .unwrap();
+        assert_eq!(test_event["fields"]["test"], "status == 201");
+        assert_eq!(test_event["fields"]["passed"], false);
+        assert_eq!(test_event["span"]["request"], "user");
+        assert_eq!(test_event["span"]["method"], "GET");
+        assert_eq!(test_event["span"]["status"], 200);
+        // 发送前的事件同样在请求的span中
+        let start_event = events
+            .iter()
+            .find(|event| event["fields"]["message"] == "Executing request 1/1: user")
+            .unwrap();
+        assert_eq!(start_event["span"]["request"], "user");
+        assert_eq!(start_event["span"]["url"], format!("{}/user", server.url()));
+        assert!(
+            events
+                .iter()
+                .any(|event| event["fields"]["message"] == "request completed")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_with_cancelled_token_sends_nothing() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("GET", "/never").expect(0).create_async().await;
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let requests = vec![create_request(
+            "never",
+            Method::GET,
+            &format!("{}/never", server.url()),
+        )];
+
+        let mut runner = Runner::new(create_client()).with_cancellation(token);
+        let report = runner.run(&requests).await.unwrap();
+
+        assert!(report.cancelled);
+        assert_eq!(report.total(), 0);
+        assert!(!report.success());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_cancellation_aborts_in_flight_request() {
+        // 接受连接但从不响应的服务器
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                sockets.push(socket);
+            }
+        });
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+
+        let requests = vec![
+            create_request("hang", Method::GET, &format!("http://{addr}/hang")),
+            create_request("next", Method::GET, &format!("http://{addr}/next")),
+        ];
+
+        let mut runner = Runner::new(create_client()).with_cancellation(token);
+        let report = tokio::time::timeout(std::time::Duration::from_secs(5), runner.run(&requests))
+            .await
+            .expect("run should stop once cancelled")
+            .unwrap();
+
+        assert!(report.cancelled);
+        assert_eq!(report.total(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_concurrency_preserves_order() {
+        let mut server = Server::new_async().await;
+        let mut mocks = Vec::new();
+        let mut requests = Vec::new();
+        for i in 0..5 {
+            let path = format!("/item/{i}");
+            mocks.push(
+                server
+                    .mock("GET", path.as_str())
+                    .with_status(200 + i)
+                    .create_async()
+                    .await,
+            );
+            requests.push(create_request(
+                &format!("item {i}"),
+                Method::GET,
+                &format!("{}{}", server.url(), path),
+            ));
+        }
+
+        let mut runner = Runner::new(create_client()).with_concurrency(3);
+        let report = runner.run(&requests).await.unwrap();
+
+        let statuses: Vec<u16> = report.requests.iter().filter_map(|r| r.status).collect();
+        assert_eq!(statuses, vec![200, 201, 202, 203, 204]);
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_notifies_observers() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/observed")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request(
+                "observed",
+                Method::GET,
+                &format!("{}/observed", server.url()),
+            )
+            .with_response_handler(Some(r#"client.test("t", function() {});"#.to_string())),
+        ];
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut runner = Runner::new(create_script_client()).with_observer(RecordingObserver {
+            events: events.clone(),
+        });
+        runner.run(&requests).await.unwrap();
+
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                "start observed 0/1",
+                "response observed 204",
+                "test observed t",
+                "complete observed true",
+                "run 1",
+            ]
+        );
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_script_skips_request() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/feature")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("feature", Method::GET, &format!("{}/feature", server.url()))
+                .with_response_handler(Some(
+                    r#"
+client.test("before skip", function() {});
+if (response.status === 404) {
+    client.skip("feature disabled");
+}
+client.test("after skip", function() {});
+"#
+                    .to_string(),
+                )),
+        ];
+
+        let mut runner = Runner::new(create_script_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert!(report.success());
+        assert_eq!(report.skipped(), 1);
+        assert_eq!(report.passed(), 0);
+        let request = &report.requests[0];
+        assert_eq!(request.skipped.as_deref(), Some("feature disabled"));
+        assert!(request.test_results.is_empty());
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_script_skip_excluded_from_status_classes() {
+        let mut server = Server::new_async().await;
+        let feature_mock = server
+            .mock("GET", "/feature")
+            .with_status(200)
+            .create_async()
+            .await;
+        let health_mock = server
+            .mock("GET", "/health")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("feature", Method::GET, &format!("{}/feature", server.url()))
+                .with_response_handler(Some(r#"client.skip("not rolled out");"#.to_string())),
+            create_request("health", Method::GET, &format!("{}/health", server.url())),
+        ];
+
+        let mut runner = Runner::new(create_script_client());
+        let report = runner.run(&requests).await.unwrap();
+        feature_mock.assert_async().await;
+        health_mock.assert_async().await;
+
+        assert_eq!(report.requests[0].status, Some(200));
+        let classes = report.status_classes();
+        assert_eq!(classes["2xx"], 1);
+        assert_eq!(classes["skipped"], 1);
+
+        let summary = report.summary(5).to_string();
+        assert!(
+            summary.contains("Requests     2 (1 passed, 0 failed, 1 skipped)"),
+            "{summary}"
+        );
+        assert!(
+            summary.contains("Status       2xx: 1, skipped: 1"),
+            "{summary}"
+        );
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_script_exit_aborts_run() {
+        let mut server = Server::new_async().await;
+        let login_mock = server
+            .mock("POST", "/login")
+            .with_status(401)
+            .create_async()
+            .await;
+        let orders_mock = server.mock("GET", "/orders").expect(0).create_async().await;
+        let logout_mock = server
+            .mock("POST", "/logout")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request("login", Method::POST, &format!("{}/login", server.url()))
+                .with_response_handler(Some(
+                    r#"
+client.test("logged in", function() {
+    if (response.status !== 200) {
+        client.exit("login failed with " + response.status);
+    }
+});
+"#
+                    .to_string(),
+                )),
+            create_request("orders", Method::GET, &format!("{}/orders", server.url())),
+            create_request("logout", Method::POST, &format!("{}/logout", server.url()))
+                .with_hook(Some(Hook::Teardown)),
+        ];
+
+        let mut runner = Runner::new(create_script_client());
+        let report = runner.run(&requests).await.unwrap();
+
+        assert!(!report.success());
+        let abort = report.aborted.as_ref().unwrap();
+        assert_eq!(abort.request, "login");
+        assert_eq!(abort.reason, "login failed with 401");
+        let names: Vec<&str> = report.requests.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["login", "logout"]);
+        assert!(report.requests[0].test_results.is_empty());
+        assert!(
+            report
+                .summary(0)
+                .to_string()
+                .contains("Aborted      by 'login': login failed with 401")
+        );
+        login_mock.assert_async().await;
+        orders_mock.assert_async().await;
+        logout_mock.assert_async().await;
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_calls_script_hooks() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/items/42")
+            .with_status(200)
+            .create_async()
+            .await;
+        let missing_mock = server
+            .mock("GET", "/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let hooks = ScriptHooks::new(
+            "httpie.hooks.js",
+            r#"
+var events = [];
+
+export function beforeAll(context) {
+    client.global.set("item_id", "42");
+}
+
+exports.beforeEach = function (context) {
+    events.push("before " + context.request.name + " " + context.index + "/" + context.total);
+};
+
+function afterEach(context) {
+    events.push("after " + context.result.name + " " + context.result.status + " " + context.result.passed);
+}
+
+module.exports.afterEach = afterEach;
+
+export function afterAll(context) {
+    events.push("all " + context.report.total + " " + context.report.failed + " " + context.variables.item_id);
+    client.global.set("events", events.join("; "));
+}
+"#,
+        );
+        let requests = vec![
+            create_request(
+                "item",
+                Method::GET,
+                &format!("{}/items/{{{{item_id}}}}", server.url()),
+            ),
+            create_request("missing", Method::GET, &format!("{}/missing", server.url()))
+                .with_assertions(vec!["status == 200".to_string()]),
+        ];
+
+        let mut runner = Runner::new(create_script_client()).with_hooks(Some(hooks));
+        let report = runner.run(&requests).await.unwrap();
+
+        assert_eq!(report.total(), 2);
+        assert_eq!(
+            runner.client_mut().variables().get("events").as_deref(),
+            Some(
+                "before item 0/2; after item 200 true; before missing 1/2; \
+                 after missing 404 false; all 2 1 42"
+            )
+        );
+        mock.assert_async().await;
+        missing_mock.assert_async().await;
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_run_fails_when_before_all_throws() {
+        let hooks = ScriptHooks::new(
+            "httpie.hooks.js",
+            r#"function beforeAll() { throw new Error("seed failed"); }"#,
+        );
+        let requests = vec![create_request("never", Method::GET, "http://127.0.0.1:9")];
+
+        let mut runner = Runner::new(create_script_client()).with_hooks(Some(hooks));
+        let error = runner.run(&requests).await.unwrap_err();
+
+        assert!(error.to_string().contains("Hook beforeAll failed"));
+    }
+
+    // 辅助类型：记录所有回调的观察者
+    #[cfg(feature = "scripting")]
+    struct RecordingObserver {
+        events: Rc<RefCell<Vec<String>>>,
+    }
+
+    #[cfg(feature = "scripting")]
+    impl ExecutionObserver for RecordingObserver {
+        fn on_request_start(&mut self, request: &HttpRequest, index: usize, total: usize) {
+            self.events
+                .borrow_mut()
+                .push(format!("start {} {}/{}", request.name, index, total));
+        }
+
+        fn on_response(
+            &mut self,
+            request: &HttpRequest,
+            response: &ResponseObject,
+            _duration: Duration,
+        ) {
+            self.events
+                .borrow_mut()
+                .push(format!("response {} {}", request.name, response.status));
+        }
+
+        fn on_test_result(&mut self, request: &HttpRequest, result: &TestResult) {
+            self.events
+                .borrow_mut()
+                .push(format!("test {} {}", request.name, result.name));
+        }
+
+        fn on_request_complete(&mut self, request: &HttpRequest, report: &RequestReport) {
+            self.events
+                .borrow_mut()
+                .push(format!("complete {} {}", request.name, report.passed()));
+        }
+
+        fn on_run_complete(&mut self, report: &RunReport) {
+            self.events
+                .borrow_mut()
+                .push(format!("run {}", report.total()));
+        }
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    #[tokio::test]
+    async fn test_run_rejects_handlers_without_scripting() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/scripted")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let requests = vec![
+            create_request(
+                "scripted",
+                Method::GET,
+                &format!("{}/scripted", server.url()),
+            )
+            .with_response_handler(Some(r#"client.test("t", function() {});"#.to_string())),
+        ];
+
+        let mut runner = Runner::new(create_client());
+        let err = runner.run(&requests).await.unwrap_err();
+
+        assert!(err.to_string().contains("'scripting' feature"));
+        mock.assert_async().await;
+    }
+
+    // 辅助函数：创建关闭输出的客户端
+    fn create_client() -> HttpClient {
+        HttpClient::new().with_print_response(false)
+    }
+
+    // 辅助函数：创建启用脚本引擎的客户端
+    #[cfg(feature = "scripting")]
+    fn create_script_client() -> HttpClient {
+        create_client().with_script_engine().unwrap()
+    }
+
+    // 辅助函数：创建请求
+    fn create_request(name: &str, method: Method, url: &str) -> HttpRequest {
+        HttpRequest::new(name.to_string(), method, url.to_string())
+    }
+
+    /// 收集日志输出的缓冲区
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -1,27 +1,37 @@
 //! script模块的单元测试
 
-use httpie::{ResponseObject, ScriptEngine, TestResult};
+#[cfg(feature = "scripting")]
+use httpie::ScriptEngine;
+#[cfg(feature = "scripting")]
+use httpie::tls::{CertificateInfo, TlsDetails};
+use httpie::{
+    Headers, LogLevel, ResponseObject, ScriptFailure, ScriptIsolation, ScriptLog, ScriptOrigin,
+    TestOutcome, TestResult,
+};
 use mockito::Server;
-
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::Deserialize;
 use serde_json::{Value, json};
-use std::collections::HashMap;
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "scripting")]
     #[test]
     fn test_script_engine_creation() {
         let result = ScriptEngine::new();
         assert!(result.is_ok(), "ScriptEngine creation should succeed");
     }
 
+    #[cfg(feature = "scripting")]
     #[test]
     fn test_script_engine_default() {
         let _engine = ScriptEngine::default();
         // 测试Default trait实现
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_simple_test_script() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -46,6 +56,7 @@ client.test("Simple test", function() {
         assert!(test_results[0].message.is_none());
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_failing_test_script() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -80,6 +91,37 @@ client.test("Failing test", function() {
         );
     }
 
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_failing_test_records_error_kind_and_duration() {
+        let mut engine = ScriptEngine::new().unwrap();
+
+        let script = r#"
+client.test("Assertion failure", function() {
+    client.assert(false, "boom");
+});
+
+client.test("Runtime failure", function() {
+    response.missing.value;
+});
+"#;
+
+        let test_results = engine
+            .execute_response_script(script.to_string(), create_test_response_object())
+            .await
+            .unwrap();
+
+        assert_eq!(test_results.len(), 2);
+        assert_eq!(
+            test_results[0].error_kind.as_deref(),
+            Some("AssertionError")
+        );
+        assert_eq!(test_results[1].error_kind.as_deref(), Some("TypeError"));
+        // 引擎本身不知道请求名称，由调用方补充
+        assert!(test_results[0].request_name.is_none());
+    }
+
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_multiple_tests() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -113,6 +155,7 @@ client.test("Test 3", function() {
         }
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_script_with_global_variables() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -148,6 +191,92 @@ client.test("Global variables test", function() {
         assert!(engine.get_global_variable("timestamp").is_some());
     }
 
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_script_engine_reuses_runtime_across_requests() {
+        let mut engine = ScriptEngine::new().unwrap();
+
+        engine
+            .execute_response_script(
+                r#"client.test("first", function() {}); client.global.set("step", 1);"#.to_string(),
+                create_test_response_object(),
+            )
+            .await
+            .unwrap();
+
+        let test_results = engine
+            .execute_response_script(
+                r#"
+client.test("second", function() {
+    client.assert(client.global.get("step") === 1, "globals should survive");
+});
+"#
+                .to_string(),
+                create_test_response_object(),
+            )
+            .await
+            .unwrap();
+
+        // 每次执行只返回本次脚本的测试结果
+        assert_eq!(test_results.len(), 1);
+        assert_eq!(test_results[0].name, "second");
+        assert!(test_results[0].passed);
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_script_isolation_modes() {
+        for (isolation, leaked) in [
+            (ScriptIsolation::Isolated, false),
+            (ScriptIsolation::Shared, true),
+        ] {
+            let mut engine = ScriptEngine::new().unwrap().with_isolation(isolation);
+            assert_eq!(engine.isolation(), isolation);
+
+            engine
+                .execute_response_script(
+                    r#"var counter = 1; client.global.set("token", "abc");"#.to_string(),
+                    create_test_response_object(),
+                )
+                .await
+                .unwrap();
+            let test_results = engine
+                .execute_response_script(
+                    r#"
+client.test("leaked", function() {
+    client.assert(typeof counter !== "undefined");
+});
+client.test("globals", function() {
+    client.assert(client.global.get("token") === "abc");
+});
+"#
+                    .to_string(),
+                    create_test_response_object(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(test_results[0].passed, leaked, "{isolation}");
+            assert!(test_results[1].passed, "{isolation}");
+        }
+    }
+
+    #[test]
+    fn test_script_isolation_from_str() {
+        assert_eq!(
+            "isolated".parse::<ScriptIsolation>().unwrap(),
+            ScriptIsolation::Isolated
+        );
+        assert_eq!(
+            "shared".parse::<ScriptIsolation>().unwrap(),
+            ScriptIsolation::Shared
+        );
+        assert_eq!(ScriptIsolation::default(), ScriptIsolation::Isolated);
+        let error = "global".parse::<ScriptIsolation>().unwrap_err();
+        assert!(error.to_string().contains("expected isolated or shared"));
+    }
+
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_script_with_response_validation() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -193,6 +322,7 @@ client.test("Content type validation", function() {
         }
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_script_with_syntax_error() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -216,6 +346,158 @@ client.test("Syntax error test", function() {
         }
     }
 
+    #[test]
+    fn test_script_failure_maps_stack_to_source_file() {
+        let error = "Uncaught ReferenceError: token is not defined\n    at helper (<response_handler>:1:5)\n    at check (<response_handler>:4:12)\n    at <response_handler>:6:1";
+        let failure = ScriptFailure::parse(error, "<response_handler>");
+        assert_eq!(
+            failure.message,
+            "Uncaught ReferenceError: token is not defined"
+        );
+        assert_eq!(failure.position, Some((1, 5)));
+        assert_eq!(failure.stack.len(), 3);
+
+        // 前两行是导入模块的定义，响应处理器从api.http第10行开始
+        let script = "var helper = function () {};\nvar x = 1;\nfunction check() {\n    if (!token) {}\n}\ncheck();";
+        let origin = ScriptOrigin {
+            file: Some("api.http".to_string()),
+            line: 10,
+            prelude_lines: 2,
+        };
+        assert_eq!(origin.locate(2, 1), None);
+
+        let failure = ScriptFailure {
+            position: Some((4, 12)),
+            ..failure
+        };
+        assert_eq!(
+            failure.render(script, "<response_handler>", &origin),
+            "api.http:11:12: Uncaught ReferenceError: token is not defined\n\
+             11 |     if (!token) {}\n\
+             \x20  |            ^\n\
+             \x20   at helper (<response_handler>:1:5)\n\
+             \x20   at check (api.http:11:12)\n\
+             \x20   at api.http:13:1"
+        );
+    }
+
+    #[test]
+    fn test_script_failure_without_position() {
+        let failure = ScriptFailure::parse("Uncaught Error: boom", "<response_handler>");
+        assert_eq!(failure.position, None);
+        assert!(failure.stack.is_empty());
+        assert_eq!(
+            failure.render(
+                "throw new Error('boom');",
+                "<response_handler>",
+                &ScriptOrigin::default()
+            ),
+            "Uncaught Error: boom"
+        );
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_script_logs_with_levels() {
+        let mut engine = ScriptEngine::new().unwrap();
+        let script = r#"
+client.log("status", response.status, { ok: true });
+client.test("body", function () {
+    client.warn("checking body");
+    console.error(new Error("boom"));
+});
+console.info("done");
+"#;
+
+        engine
+            .execute_response_script(script.to_string(), create_test_response_object())
+            .await
+            .unwrap();
+        let logs = engine.take_logs();
+
+        let summary: Vec<(String, Option<&str>)> = logs
+            .iter()
+            .map(|log| (log.to_string(), log.test.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("[log] status 200 {\"ok\":true}".to_string(), None),
+                ("[warn] checking body".to_string(), Some("body")),
+                ("[error] Error: boom".to_string(), Some("body")),
+                ("[log] done".to_string(), None),
+            ]
+        );
+        assert!(engine.take_logs().is_empty());
+
+        // 下一个脚本从空日志开始
+        engine
+            .execute_response_script("1;".to_string(), create_test_response_object())
+            .await
+            .unwrap();
+        assert!(engine.take_logs().is_empty());
+    }
+
+    #[test]
+    fn test_test_result_outcome() {
+        let mut failed = TestResult::failure("a", "boom", "Error");
+        let mut passed = TestResult::success("b");
+        assert_eq!(
+            (failed.outcome(), failed.is_failure()),
+            (TestOutcome::Failed, true)
+        );
+        assert_eq!(
+            (passed.outcome(), passed.is_failure()),
+            (TestOutcome::Passed, false)
+        );
+
+        failed.xfail = true;
+        passed.xfail = true;
+        assert_eq!(
+            (failed.outcome(), failed.is_failure()),
+            (TestOutcome::Xfailed, false)
+        );
+        assert_eq!(
+            (passed.outcome(), passed.is_failure()),
+            (TestOutcome::Xpassed, true)
+        );
+        assert_eq!(TestOutcome::Xpassed.to_string(), "xpassed");
+    }
+
+    #[test]
+    fn test_script_log_serialization() {
+        let log: ScriptLog =
+            serde_json::from_value(json!({"level": "warn", "message": "slow", "test": null}))
+                .unwrap();
+        assert_eq!(log.level, LogLevel::Warn);
+        assert_eq!(
+            serde_json::to_value(&log).unwrap(),
+            json!({"level": "warn", "message": "slow"})
+        );
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_script_error_points_to_handler_line() {
+        let mut engine = ScriptEngine::new().unwrap();
+        let origin = ScriptOrigin {
+            file: Some("api.http".to_string()),
+            line: 20,
+            prelude_lines: 0,
+        };
+        let script = "client.log(1);\nclient.assert(response.status === 200;";
+
+        let error = engine
+            .execute_response_script_at(script.to_string(), create_test_response_object(), &origin)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Script execution failed: api.http:21:"));
+        assert!(error.contains("\n21 | client.assert(response.status === 200;\n"));
+    }
+
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_script_with_runtime_error() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -243,6 +525,7 @@ client.test("Runtime error test", function() {
         assert!(test_results[0].message.is_some());
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_empty_script() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -263,6 +546,7 @@ client.test("Runtime error test", function() {
         );
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_execute_script_with_console_log() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -289,6 +573,7 @@ client.test("Console log test", function() {
         assert!(test_results[0].passed);
     }
 
+    #[cfg(feature = "scripting")]
     #[tokio::test]
     async fn test_get_all_global_variables() {
         let mut engine = ScriptEngine::new().unwrap();
@@ -395,6 +680,10 @@ client.global.set("var3", {"nested": "object"});
             name: "Test Name".to_string(),
             passed: true,
             message: Some("Test message".to_string()),
+            duration_ms: 0,
+            error_kind: None,
+            request_name: None,
+            xfail: false,
         };
 
         assert_eq!(test_result.name, "Test Name");
@@ -408,6 +697,10 @@ client.global.set("var3", {"nested": "object"});
             name: "Serialization Test".to_string(),
             passed: false,
             message: Some("Error message".to_string()),
+            duration_ms: 0,
+            error_kind: None,
+            request_name: None,
+            xfail: false,
         };
 
         let json_str = serde_json::to_string(&test_result).unwrap();
@@ -418,6 +711,16 @@ client.global.set("var3", {"nested": "object"});
         assert_eq!(deserialized.message, test_result.message);
     }
 
+    #[test]
+    fn test_test_result_deserialization_defaults_metadata() {
+        let json_str = r#"{"name": "Legacy", "passed": true, "message": null}"#;
+        let deserialized: TestResult = serde_json::from_str(json_str).unwrap();
+
+        assert_eq!(deserialized.duration_ms, 0);
+        assert!(deserialized.error_kind.is_none());
+        assert!(deserialized.request_name.is_none());
+    }
+
     #[test]
     fn test_response_object_clone() {
         let response_obj = create_test_response_object();
@@ -429,6 +732,117 @@ client.global.set("var3", {"nested": "object"});
         assert_eq!(response_obj.body, cloned.body);
     }
 
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Created {
+        id: u64,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_response_object_typed_accessors() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("content-length", HeaderValue::from_static("29"));
+        headers.insert("x-rate-limit", HeaderValue::from_static("abc"));
+        let body = br#"{"id": 7, "tags": ["a", "b"]}"#;
+        let response = ResponseObject::from_parts(201, &headers, body);
+
+        assert!(response.is_success());
+        assert_eq!(response.bytes().as_ref(), body);
+        assert_eq!(response.text(), r#"{"id": 7, "tags": ["a", "b"]}"#);
+        assert_eq!(
+            response.json::<Created>().unwrap(),
+            Created {
+                id: 7,
+                tags: vec!["a".to_string(), "b".to_string()],
+            }
+        );
+        assert!(response.json::<Vec<u64>>().is_err());
+
+        assert_eq!(response.header("Content-Type"), Some("application/json"));
+        assert_eq!(response.content_length().unwrap(), Some(29));
+        assert_eq!(response.header_as::<u32>("X-Missing").unwrap(), None);
+        assert!(response.header_as::<u32>("X-Rate-Limit").is_err());
+    }
+
+    #[test]
+    fn test_response_object_decodes_declared_charset() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("text/plain; charset=ISO-8859-1"),
+        );
+        // "café" 的Latin-1编码，不是合法的UTF-8
+        let bytes = [b'c', b'a', b'f', 0xe9];
+        let response = ResponseObject::from_parts(200, &headers, &bytes);
+
+        assert_eq!(response.body, json!("café"));
+        assert_eq!(response.text(), "café");
+        assert_eq!(response.bytes().as_ref(), bytes);
+    }
+
+    #[test]
+    fn test_response_object_accessors_without_raw_body() {
+        // 手工构建的响应对象使用已解析的body
+        let response = create_test_response_object();
+        assert_eq!(response.json::<Value>().unwrap()["id"], 123);
+        assert_eq!(response.header("X-Custom-Header"), Some("custom-value"));
+
+        let response = ResponseObject {
+            status: 500,
+            body: Value::String("oops".to_string()),
+            ..Default::default()
+        };
+        assert!(!response.is_success());
+        assert_eq!(response.text(), "oops");
+    }
+
+    #[test]
+    fn test_response_object_json_with_generic_content_type() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        let response = ResponseObject::from_parts(200, &headers, br#"{"b": 1, "a": 2}"#);
+        assert!(response.binary.is_none());
+        assert_eq!(response.body, json!(r#"{"b": 1, "a": 2}"#));
+
+        let response = ResponseObject::from_parts(200, &headers, b"\x00\x01{");
+        assert!(response.binary.is_some());
+    }
+
+    #[test]
+    fn test_response_object_filtered() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        let body = br#"{"data": {"items": [{"id": 1}, {"id": 2}], "total": 2}}"#;
+        let response = ResponseObject::from_parts(200, &headers, body);
+
+        let filtered = response.filtered("$.data.items[*].id").unwrap().unwrap();
+        assert_eq!(filtered.body, json!([1, 2]));
+        assert_eq!(filtered.status, 200);
+        assert_eq!(filtered.headers, response.headers);
+
+        let filtered = response.filtered("$.data.total").unwrap().unwrap();
+        assert_eq!(filtered.body, json!(2));
+        let filtered = response.filtered("$.data.missing").unwrap().unwrap();
+        assert_eq!(filtered.body, Value::Null);
+        assert!(response.filtered("data").is_err());
+
+        // JSON文本（未声明JSON类型）同样可以过滤，非JSON响应体不过滤
+        let text = ResponseObject {
+            body: json!(r#"{"id": 7}"#),
+            ..Default::default()
+        };
+        assert_eq!(text.filtered("$.id").unwrap().unwrap().body, json!(7));
+        let html = ResponseObject {
+            body: json!("<html></html>"),
+            ..Default::default()
+        };
+        assert!(html.filtered("$.id").unwrap().is_none());
+    }
+
     #[test]
     fn test_response_object_debug() {
         let response_obj = create_test_response_object();
@@ -439,9 +853,134 @@ client.global.set("var3", {"nested": "object"});
         assert!(debug_str.contains("application/json"));
     }
 
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_script_reads_connection_details() {
+        let mut engine = ScriptEngine::new().unwrap();
+        let response_obj = ResponseObject {
+            http_version: Some("HTTP/2.0".to_string()),
+            remote_addr: Some("127.0.0.1:8443".parse().unwrap()),
+            tls: Some(TlsDetails {
+                cert: Some(CertificateInfo {
+                    not_before: "2025-01-01T00:00:00Z".to_string(),
+                    not_after: "2026-01-01T00:00:00Z".to_string(),
+                }),
+            }),
+            ..create_test_response_object()
+        };
+
+        let script = r#"
+client.test("connection", function() {
+    client.assert(response.httpVersion === "HTTP/2.0", "version");
+    client.assert(response.remoteAddress === "127.0.0.1:8443", "remote address");
+    client.assert(response.tls.cert.notBefore === "2025-01-01T00:00:00Z", "not before");
+    client.assert(response.tls.cert.notAfter > "2025-06-01", "not after");
+});
+"#;
+        let results = engine
+            .execute_response_script(script.to_string(), response_obj)
+            .await
+            .unwrap();
+        assert!(results.iter().all(|r| r.passed), "{results:?}");
+
+        let script = r#"
+client.test("no tls", function() {
+    client.assert(response.tls === null && response.remoteAddress === null, "null");
+});
+"#;
+        let results = engine
+            .execute_response_script(script.to_string(), create_test_response_object())
+            .await
+            .unwrap();
+        assert!(results[0].passed, "{results:?}");
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_script_reads_response_cookies() {
+        let mut engine = ScriptEngine::new().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "set-cookie",
+            HeaderValue::from_static("session=abc; Path=/; Secure; HttpOnly; SameSite=Strict"),
+        );
+        headers.append("set-cookie", HeaderValue::from_static("theme=dark"));
+        let response_obj = ResponseObject::from_parts(200, &headers, b"");
+        assert_eq!(response_obj.cookies.len(), 2);
+
+        let script = r#"
+client.test("cookies", function() {
+    const session = response.cookies.find(c => c.name === "session");
+    client.assert(response.cookies.length === 2, "count");
+    client.assert(session.value === "abc", "value");
+    client.assert(session.secure && session.httpOnly, "flags");
+    client.assert(session.sameSite === "Strict", "sameSite");
+    client.assert(session.path === "/" && session.domain === null, "path");
+    client.assert(!response.cookies[1].secure, "theme");
+});
+"#;
+        let results = engine
+            .execute_response_script(script.to_string(), response_obj)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].passed, "{results:?}");
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_script_css_helpers_extract_from_html() {
+        let mut engine = ScriptEngine::new().unwrap();
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Login &amp; more</title>
+<script>if (a < b) { document.write("<a class='link' href='/fake'>") }</script></head>
+<body>
+  <form id="login" action="/session">
+    <input type="hidden" name="csrf_token" value="tok&#x2F;123">
+    <input name="user"><br>
+  </form>
+  <ul class="nav main">
+    <li><a class="link" href="/docs">Docs</a>
+    <li><a class="link external" href="https://example.com/">Example <b>site</b></a>
+  </ul>
+  <p>Outside <a href="/plain">plain</a></p>
+</body></html>"#;
+        let response_obj = ResponseObject {
+            body: Value::String(html.to_string()),
+            content_type: "text/html; charset=utf-8".to_string(),
+            ..Default::default()
+        };
+
+        let script = r##"
+client.test("html", function() {
+    client.assert(css(response.body, "input[name=csrf_token]@value") === "tok/123", "csrf");
+    client.assert(css(response.body, "#login@action") === "/session", "action");
+    client.assert(css(response.body, "a.link@href") === "/docs", "first link");
+    client.assert(css(response.body, "ul.nav > li > a.external") === "Example site", "text");
+    client.assert(cssAll(response.body, "ul a@href").length === 2, "descendant");
+    client.assert(cssAll(response.body, "body > a").length === 0, "child");
+    client.assert(css(response.body, "a[href^=https]@href") === "https://example.com/", "prefix");
+    client.assert(css(response.body, "title") === "Login & more", "title");
+    client.assert(querySelector(response.body, "p a") === "plain", "alias");
+    client.assert(css(response.body, "table") === null, "missing");
+});
+client.test("invalid selector", function() {
+    css(response.body, "a[");
+});
+"##;
+        let results = engine
+            .execute_response_script(script.to_string(), response_obj)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].passed, "{results:?}");
+        assert!(!results[1].passed);
+        assert_eq!(results[1].error_kind.as_deref(), Some("SyntaxError"));
+    }
+
     // 辅助函数：创建测试用的ResponseObject
     fn create_test_response_object() -> ResponseObject {
-        let mut headers = HashMap::new();
+        let mut headers = Headers::new();
         headers.insert("content-type".to_string(), "application/json".to_string());
         headers.insert("x-custom-header".to_string(), "custom-value".to_string());
 
@@ -458,6 +997,7 @@ client.global.set("var3", {"nested": "object"});
                 }
             }),
             content_type: "application/json".to_string(),
+            ..Default::default()
         }
     }
 }
@@ -0,0 +1,45 @@
+//! secret模块的单元测试
+
+use httpie::SecretSource;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_from_value() {
+        assert_eq!(
+            SecretSource::from_value(&json!({"exec": "vault read -field=key secret/api"})).unwrap(),
+            Some(SecretSource::Exec(
+                "vault read -field=key secret/api".to_string()
+            ))
+        );
+        assert_eq!(
+            SecretSource::from_value(&json!({"keyring": "myservice/api_key"})).unwrap(),
+            Some(SecretSource::Keyring {
+                service: "myservice".to_string(),
+                account: "api_key".to_string(),
+            })
+        );
+        assert_eq!(SecretSource::from_value(&json!("plain")).unwrap(), None);
+        assert_eq!(
+            SecretSource::from_value(&json!({"other": 1})).unwrap(),
+            None
+        );
+
+        assert!(SecretSource::from_value(&json!({"keyring": "no-account"})).is_err());
+        assert!(SecretSource::from_value(&json!({"exec": 42})).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_secret_exec_resolve() {
+        let secret = SecretSource::Exec("printf ' s3cret \\n'".to_string());
+        assert_eq!(secret.resolve().unwrap(), "s3cret");
+
+        let failing = SecretSource::Exec("echo denied >&2; exit 1".to_string());
+        let error = failing.resolve().unwrap_err().to_string();
+        assert!(error.contains("denied"));
+    }
+}
@@ -0,0 +1,208 @@
+//! session模块的单元测试
+
+use httpie::session::Cookie;
+use httpie::{
+    CookieJar, ExecutionObserver, HttpClient, HttpRequest, RunReport, Session, SessionRecorder,
+    TransportConfig, VariableContext,
+};
+use mockito::{Matcher, Server};
+use reqwest::Method;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use std::sync::Arc;
+use tempfile::TempDir;
+use url::Url;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_cookie(jar: &CookieJar, url: &str, header: &'static str) {
+        let header = HeaderValue::from_static(header);
+        jar.set_cookies(&mut std::iter::once(&header), &Url::parse(url).unwrap());
+    }
+
+    fn cookie_header(jar: &CookieJar, url: &str) -> Option<String> {
+        CookieStore::cookies(jar, &Url::parse(url).unwrap())
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_cookie_jar_domain_and_path() {
+        let jar = CookieJar::default();
+        set_cookie(
+            &jar,
+            "https://api.example.com/login",
+            "sid=abc; Path=/; HttpOnly",
+        );
+        set_cookie(
+            &jar,
+            "https://api.example.com/",
+            "pref=dark; Domain=example.com",
+        );
+        set_cookie(
+            &jar,
+            "https://api.example.com/admin/",
+            "admin=1; Path=/admin",
+        );
+
+        assert_eq!(
+            cookie_header(&jar, "https://api.example.com/users").as_deref(),
+            Some("sid=abc; pref=dark")
+        );
+        assert_eq!(
+            cookie_header(&jar, "https://api.example.com/admin/users").as_deref(),
+            Some("sid=abc; pref=dark; admin=1")
+        );
+        assert_eq!(
+            cookie_header(&jar, "https://www.example.com/").as_deref(),
+            Some("pref=dark")
+        );
+        assert_eq!(cookie_header(&jar, "https://other.com/"), None);
+    }
+
+    #[test]
+    fn test_cookie_jar_replace_and_delete() {
+        let jar = CookieJar::default();
+        set_cookie(&jar, "https://example.com/", "sid=old");
+        set_cookie(&jar, "https://example.com/", "sid=new");
+        assert_eq!(jar.cookies().len(), 1);
+        assert_eq!(
+            cookie_header(&jar, "https://example.com/").as_deref(),
+            Some("sid=new")
+        );
+
+        set_cookie(&jar, "https://example.com/", "sid=; Max-Age=0");
+        assert!(jar.cookies().is_empty());
+
+        // 不接受其他域名的Cookie
+        set_cookie(&jar, "https://example.com/", "evil=1; Domain=attacker.com");
+        assert!(jar.cookies().is_empty());
+    }
+
+    #[test]
+    fn test_session_save_and_load() {
+        let dir = TempDir::new().unwrap();
+        let variables = VariableContext::new();
+        variables.set("token", "abc123");
+        let jar = CookieJar::default();
+        set_cookie(&jar, "https://example.com/", "sid=xyz");
+
+        let session = Session::capture(&variables, &jar);
+        let path = session.save(dir.path(), "login").unwrap();
+        assert_eq!(path, dir.path().join("login.json"));
+
+        let loaded = Session::load(dir.path(), "login").unwrap();
+        assert_eq!(loaded, session);
+        assert_eq!(
+            loaded.cookies,
+            vec![Cookie {
+                name: "sid".to_string(),
+                value: "xyz".to_string(),
+                domain: "example.com".to_string(),
+                path: "/".to_string(),
+                host_only: true,
+            }]
+        );
+
+        let restored = VariableContext::new();
+        let jar = loaded.restore(&restored);
+        assert_eq!(restored.get("token").as_deref(), Some("abc123"));
+        assert_eq!(
+            cookie_header(&jar, "https://example.com/").as_deref(),
+            Some("sid=xyz")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_session_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let sessions = dir.path().join(".httpie/sessions");
+        let path = Session::default().save(&sessions, "login").unwrap();
+        let mode =
+            |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(&sessions), 0o700);
+
+        // 以前用默认权限保存的会话文件在再次保存时收紧
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        Session::default().save(&sessions, "login").unwrap();
+        assert_eq!(mode(&path), 0o600);
+    }
+
+    #[test]
+    fn test_session_recorder_saves_on_run_complete() {
+        let dir = TempDir::new().unwrap();
+        let variables = VariableContext::new();
+        let jar = Arc::new(CookieJar::default());
+        let mut recorder = SessionRecorder::new(dir.path(), "flow", variables.clone(), jar.clone());
+
+        // 运行中设置的变量和Cookie在运行结束时保存
+        variables.set("token", "abc123");
+        set_cookie(&jar, "https://example.com/", "sid=xyz");
+        recorder.on_run_complete(&RunReport::default());
+
+        let session = Session::load(dir.path(), "flow").unwrap();
+        assert_eq!(session.variables["token"], "abc123");
+        assert_eq!(session.cookies.len(), 1);
+    }
+
+    #[test]
+    fn test_session_invalid_name() {
+        let dir = TempDir::new().unwrap();
+        assert!(Session::default().save(dir.path(), "../escape").is_err());
+        assert!(Session::load(dir.path(), "").is_err());
+        assert!(Session::load(dir.path(), "missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_uses_cookie_jar() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/login")
+            .with_header("set-cookie", "sid=s3cret; Path=/")
+            .create_async()
+            .await;
+        let profile = server
+            .mock("GET", "/profile")
+            .match_header("cookie", Matcher::Exact("sid=s3cret".to_string()))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let jar = Arc::new(CookieJar::default());
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport_config(TransportConfig {
+                cookies: Some(jar.clone()),
+                ..TransportConfig::default()
+            })
+            .unwrap();
+        let login = HttpRequest::new(
+            "login".to_string(),
+            Method::POST,
+            format!("{}/login", server.url()),
+        );
+        client.execute_with_outcome(&login).await.unwrap();
+        assert_eq!(jar.cookies().len(), 1);
+
+        // 新客户端从保存的Cookie继续
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport_config(TransportConfig {
+                cookies: Some(Arc::new(CookieJar::new(jar.cookies()))),
+                ..TransportConfig::default()
+            })
+            .unwrap();
+        let request = HttpRequest::new(
+            "profile".to_string(),
+            Method::GET,
+            format!("{}/profile", server.url()),
+        );
+        client.execute_with_outcome(&request).await.unwrap();
+        profile.assert_async().await;
+    }
+}
@@ -0,0 +1,73 @@
+//! tls模块的单元测试
+
+use httpie::tls::{CertificateInfo, parse_certificate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 编码一个DER元素（长度超过127时使用两字节长度）
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        if content.len() < 0x80 {
+            out.push(content.len() as u8);
+        } else {
+            out.extend([0x82, (content.len() >> 8) as u8, content.len() as u8]);
+        }
+        out.extend_from_slice(content);
+        out
+    }
+
+    /// 构造只含到有效期为止字段的证书
+    fn certificate(not_before: Vec<u8>, not_after: Vec<u8>, with_version: bool) -> Vec<u8> {
+        let mut tbs = Vec::new();
+        if with_version {
+            tbs.extend(tlv(0xa0, &tlv(0x02, &[2])));
+        }
+        tbs.extend(tlv(0x02, &[0x01, 0x23]));
+        tbs.extend(tlv(0x30, &tlv(0x06, &[0x2a, 0x86, 0x48])));
+        // 足够长的颁发者，覆盖多字节长度
+        tbs.extend(tlv(0x30, &tlv(0x0c, &[b'x'; 200])));
+        tbs.extend(tlv(0x30, &[not_before, not_after].concat()));
+        tbs.extend(tlv(0x30, &[]));
+        let cert = [tlv(0x30, &tbs), tlv(0x30, &[]), tlv(0x03, &[0])].concat();
+        tlv(0x30, &cert)
+    }
+
+    #[test]
+    fn test_parse_certificate_validity() {
+        let der = certificate(
+            tlv(0x17, b"250101000000Z"),
+            tlv(0x18, b"20510630235959Z"),
+            true,
+        );
+        assert_eq!(
+            parse_certificate(&der),
+            Some(CertificateInfo {
+                not_before: "2025-01-01T00:00:00Z".to_string(),
+                not_after: "2051-06-30T23:59:59Z".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_certificate_utc_time_century() {
+        let der = certificate(
+            tlv(0x17, b"991231120000Z"),
+            tlv(0x17, b"491231120000Z"),
+            false,
+        );
+        let cert = parse_certificate(&der).unwrap();
+        assert_eq!(cert.not_before, "1999-12-31T12:00:00Z");
+        assert_eq!(cert.not_after, "2049-12-31T12:00:00Z");
+    }
+
+    #[test]
+    fn test_parse_certificate_rejects_malformed() {
+        assert_eq!(parse_certificate(&[]), None);
+        assert_eq!(parse_certificate(&[0x30, 0x05, 0x30]), None);
+
+        let der = certificate(tlv(0x17, b"2501010000Z"), tlv(0x17, b"250101000000Z"), true);
+        assert_eq!(parse_certificate(&der), None);
+    }
+}
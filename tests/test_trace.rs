@@ -0,0 +1,70 @@
+//! trace模块的单元测试
+
+use httpie::Headers;
+use httpie::trace::{TraceContext, TraceFormat, inject_request_id, trace_id};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TraceContext {
+        TraceContext {
+            trace_id: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+            span_id: "00f067aa0ba902b7".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_generate_trace_context() {
+        let context = TraceContext::generate();
+        assert_eq!(context.trace_id.len(), 32);
+        assert_eq!(context.span_id.len(), 16);
+        assert!(context.trace_id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(context.trace_id, TraceContext::generate().trace_id);
+        assert_eq!(
+            context.traceparent(),
+            format!("00-{}-{}-01", context.trace_id, context.span_id)
+        );
+    }
+
+    #[test]
+    fn test_inject_trace_headers() {
+        let mut headers = Headers::new();
+        TraceFormat::W3c.inject(&mut headers, &context());
+        TraceFormat::B3.inject(&mut headers, &context());
+
+        assert_eq!(
+            headers["traceparent"],
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+        assert_eq!(headers["X-B3-TraceId"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(headers["X-B3-SpanId"], "00f067aa0ba902b7");
+        assert_eq!(headers["X-B3-Sampled"], "1");
+        assert_eq!(trace_id(&headers), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+
+        // 已声明的追踪请求头不被覆盖
+        let mut headers = Headers::new();
+        headers.insert("TraceParent".to_string(), "00-abc-def-00".to_string());
+        TraceFormat::W3c.inject(&mut headers, &context());
+        assert_eq!(headers.len(), 1);
+        assert_eq!(trace_id(&headers), Some("abc"));
+
+        assert_eq!(trace_id(&Headers::new()), None);
+    }
+
+    #[test]
+    fn test_inject_request_id() {
+        let mut headers = Headers::new();
+        inject_request_id(&mut headers, "req-1");
+        inject_request_id(&mut headers, "req-2");
+        assert_eq!(headers["X-Request-Id"], "req-1");
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn test_trace_format_from_str() {
+        assert_eq!("w3c".parse::<TraceFormat>().unwrap(), TraceFormat::W3c);
+        assert_eq!("b3".parse::<TraceFormat>().unwrap(), TraceFormat::B3);
+        assert!("zipkin".parse::<TraceFormat>().is_err());
+    }
+}
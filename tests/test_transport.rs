@@ -0,0 +1,338 @@
+//! transport模块的单元测试
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use httpie::client::RawResponse;
+use httpie::transport::send_with_cancellation;
+use httpie::{
+    CancellationToken, CookieJar, Directive, ExecutionObserver, HttpClient, HttpRequest,
+    HttpTransport, HttpieError, MockTransport, RequestReport, ResponseObject, Runner,
+    TransportConfig,
+};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Method, Version};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 不建立连接的测试替身：记录收到的请求，按路径返回固定响应
+    #[derive(Default)]
+    struct FakeTransport {
+        sent: Mutex<Vec<(String, Option<String>)>>,
+    }
+
+    impl HttpTransport for FakeTransport {
+        fn send<'a>(
+            &'a self,
+            request: &'a HttpRequest,
+        ) -> BoxFuture<'a, httpie::Result<RawResponse>> {
+            Box::pin(async move {
+                self.sent.lock().unwrap().push((
+                    request.url.clone(),
+                    request.headers.get("Authorization").cloned(),
+                ));
+                let (status, body) = if request.url.ends_with("/login") {
+                    (200, r#"{"token": "abc"}"#)
+                } else {
+                    (404, "")
+                };
+                let mut headers = HeaderMap::new();
+                headers.insert("content-type", HeaderValue::from_static("application/json"));
+                Ok(RawResponse {
+                    status,
+                    version: Version::HTTP_11,
+                    headers,
+                    bytes: Bytes::from(body),
+                    duration: Duration::from_millis(5),
+                    truncated: false,
+                    remote_addr: None,
+                    tls: None,
+                    redirects: Vec::new(),
+                })
+            })
+        }
+    }
+
+    /// 永不返回的传输层，用于验证取消
+    struct PendingTransport;
+
+    impl HttpTransport for PendingTransport {
+        fn send<'a>(&'a self, _: &'a HttpRequest) -> BoxFuture<'a, httpie::Result<RawResponse>> {
+            Box::pin(futures::future::pending())
+        }
+    }
+
+    /// 把发送和观察者回调记录到同一个事件列表
+    #[derive(Clone, Default)]
+    struct EventLog(Arc<Mutex<Vec<String>>>);
+
+    impl EventLog {
+        fn push(&self, event: String) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    impl HttpTransport for EventLog {
+        fn send<'a>(
+            &'a self,
+            request: &'a HttpRequest,
+        ) -> BoxFuture<'a, httpie::Result<RawResponse>> {
+            Box::pin(async move {
+                self.push(format!("send {}", request.name));
+                // 让同一批的其他请求在响应返回前开始发送
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(RawResponse {
+                    status: 204,
+                    version: Version::HTTP_11,
+                    headers: HeaderMap::new(),
+                    bytes: Bytes::new(),
+                    duration: Duration::from_millis(1),
+                    truncated: false,
+                    remote_addr: None,
+                    tls: None,
+                    redirects: Vec::new(),
+                })
+            })
+        }
+    }
+
+    impl ExecutionObserver for EventLog {
+        fn on_request_start(&mut self, request: &HttpRequest, _index: usize, _total: usize) {
+            self.push(format!("start {}", request.name));
+        }
+
+        fn on_request_complete(&mut self, request: &HttpRequest, _report: &RequestReport) {
+            self.push(format!("complete {}", request.name));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runner_notifies_start_before_sending_concurrently() {
+        let events = EventLog::default();
+        let client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport(Arc::new(events.clone()));
+        let requests: Vec<HttpRequest> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                HttpRequest::new(
+                    name.to_string(),
+                    Method::GET,
+                    format!("https://api.test/{name}"),
+                )
+            })
+            .collect();
+
+        let mut runner = Runner::new(client)
+            .with_concurrency(3)
+            .with_observer(events.clone());
+        runner.run(&requests).await.unwrap();
+
+        // 并发发送时每个请求的开始事件仍在其发送之前
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            vec![
+                "start a",
+                "send a",
+                "start b",
+                "send b",
+                "start c",
+                "send c",
+                "complete a",
+                "complete b",
+                "complete c",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runner_uses_custom_transport() {
+        let transport = Arc::new(FakeTransport::default());
+        let client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport(transport.clone());
+
+        let requests = vec![
+            HttpRequest::new(
+                "login".to_string(),
+                Method::POST,
+                "https://api.test/login".to_string(),
+            )
+            .with_directives(vec![Directive::new("capture", "token = $.token")])
+            .with_assertions(vec!["status == 200".to_string()]),
+            HttpRequest::new(
+                "profile".to_string(),
+                Method::GET,
+                "https://api.test/profile".to_string(),
+            )
+            .with_headers([("Authorization".to_string(), "Bearer {{token}}".to_string())])
+            .with_assertions(vec!["status == 200".to_string()]),
+        ];
+
+        let report = Runner::new(client).run(&requests).await.unwrap();
+        assert_eq!(report.requests.len(), 2);
+        assert_eq!(report.requests[0].status, Some(200));
+        assert!(report.requests[0].passed());
+        assert_eq!(report.requests[1].status, Some(404));
+        assert!(!report.requests[1].passed());
+
+        // 捕获的变量在交给传输层之前已替换
+        let sent = transport.sent.lock().unwrap();
+        assert_eq!(
+            *sent,
+            vec![
+                ("https://api.test/login".to_string(), None),
+                (
+                    "https://api.test/profile".to_string(),
+                    Some("Bearer abc".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_requests_reuse_client_and_cookies() {
+        // 记录连接数和收到的请求头，每个响应都设置Cookie并保持连接
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (counter, heads) = (connections.clone(), received.clone());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let heads = heads.clone();
+                tokio::spawn(async move {
+                    let mut buffer = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    loop {
+                        let read = socket.read(&mut chunk).await.unwrap_or(0);
+                        if read == 0 {
+                            break;
+                        }
+                        buffer.extend_from_slice(&chunk[..read]);
+                        if !buffer.ends_with(b"\r\n\r\n") {
+                            continue;
+                        }
+                        heads
+                            .lock()
+                            .unwrap()
+                            .push(String::from_utf8_lossy(&buffer).to_lowercase());
+                        buffer.clear();
+                        let response = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\
+                                        Set-Cookie: sid=abc; Path=/\r\n\r\n";
+                        if socket.write_all(response.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport_config(TransportConfig {
+                cookies: Some(Arc::new(CookieJar::default())),
+                ..TransportConfig::default()
+            })
+            .unwrap();
+        for name in ["login", "profile"] {
+            let request = HttpRequest::new(
+                name.to_string(),
+                Method::GET,
+                format!("http://api.test:{port}/{name}"),
+            )
+            .with_directives(vec![Directive::new(
+                "resolve",
+                format!("api.test:{port}:127.0.0.1"),
+            )]);
+            client.execute_with_outcome(&request).await.unwrap();
+        }
+
+        // 两个请求使用同一个缓存的客户端：复用连接，第二个请求带上第一个响应设置的Cookie
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(!received[0].contains("cookie:"));
+        assert!(received[1].contains("cookie: sid=abc"), "{}", received[1]);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_cancellation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let request = HttpRequest::new(
+            "slow".to_string(),
+            Method::GET,
+            "https://api.test/slow".to_string(),
+        );
+        let result = send_with_cancellation(&PendingTransport, &request, &token).await;
+        assert!(matches!(result, Err(HttpieError::Cancelled)));
+    }
+
+    fn json_response(status: u16, body: &str) -> ResponseObject {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        ResponseObject::from_parts(status, &headers, body.as_bytes())
+    }
+
+    #[tokio::test]
+    async fn test_mock_transport() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    Method::GET,
+                    "/users?page=2",
+                    json_response(200, r#"{"page": 2}"#),
+                )
+                .with_response(Method::GET, "/users", json_response(200, r#"{"page": 1}"#))
+                .with_response(
+                    Method::POST,
+                    "https://api.test/users",
+                    json_response(201, r#"{"id": 7}"#),
+                ),
+        );
+        let mut client = HttpClient::new()
+            .with_print_response(false)
+            .with_transport(transport.clone());
+
+        let request = |method: Method, url: &str| {
+            HttpRequest::new("users".to_string(), method, url.to_string())
+        };
+        let outcome = client
+            .execute_with_outcome(&request(Method::GET, "https://api.test/users"))
+            .await
+            .unwrap();
+        assert_eq!(outcome.response.body["page"], 1);
+
+        let outcome = client
+            .execute_with_outcome(
+                &request(Method::GET, "https://api.test/users")
+                    .with_query(vec![("page".to_string(), "2".to_string())]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.response.body["page"], 2);
+
+        let outcome = client
+            .execute_with_outcome(&request(Method::POST, "https://api.test/users"))
+            .await
+            .unwrap();
+        assert_eq!(outcome.response.status, 201);
+        assert_eq!(outcome.response.content_type, "application/json");
+
+        // 没有匹配的响应时请求失败，但仍被记录
+        let error = client
+            .execute_with_outcome(&request(Method::DELETE, "https://api.test/users"))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("no mock response for DELETE"));
+        assert_eq!(transport.requests().len(), 4);
+        assert_eq!(transport.requests()[3].method, Method::DELETE);
+    }
+}
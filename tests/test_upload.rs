@@ -0,0 +1,108 @@
+//! upload模块的单元测试
+
+use httpie::upload::{STREAM_THRESHOLD, body_file_reference, read_body_file, streamed_file};
+use httpie::{Directive, Environment, HttpClient, HttpParser, HttpRequest};
+use mockito::{Matcher, Server};
+use reqwest::Method;
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upload(dir: &TempDir, url: &str) -> HttpRequest {
+        HttpRequest::new("upload".to_string(), Method::PUT, url.to_string())
+            .with_body_file(Some("payload.bin".to_string()))
+            .with_source_file(Some(dir.path().join("api.http")))
+    }
+
+    #[test]
+    fn test_body_file_reference() {
+        assert_eq!(body_file_reference("< ./data.json"), Some("./data.json"));
+        assert_eq!(body_file_reference("\n<  big.bin \n"), Some("big.bin"));
+        // XML请求体和多行内容不是文件引用
+        assert_eq!(body_file_reference("<root/>"), None);
+        assert_eq!(body_file_reference("< a.json\n< b.json"), None);
+        assert_eq!(body_file_reference("<"), None);
+    }
+
+    #[test]
+    fn test_parse_body_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("api.http");
+        fs::write(
+            &path,
+            "@name = payload\n\n### Upload\nPOST https://api.example.com/upload\n\n< ./{{name}}.json\n",
+        )
+        .unwrap();
+
+        let requests = HttpParser::new(Environment::new())
+            .parse_file(&path.to_string_lossy())
+            .unwrap();
+        assert_eq!(requests[0].body, None);
+        assert_eq!(requests[0].body_file.as_deref(), Some("./payload.json"));
+    }
+
+    #[test]
+    fn test_streamed_file() {
+        let dir = TempDir::new().unwrap();
+        let request = upload(&dir, "https://api.example.com/upload");
+        assert!(streamed_file(&request).is_err());
+
+        fs::write(dir.path().join("payload.bin"), b"small").unwrap();
+        assert_eq!(streamed_file(&request).unwrap(), None);
+        assert_eq!(read_body_file(&request).unwrap(), Some(b"small".to_vec()));
+
+        let file = fs::File::create(dir.path().join("payload.bin")).unwrap();
+        file.set_len(STREAM_THRESHOLD + 1).unwrap();
+        let (path, len) = streamed_file(&request).unwrap().unwrap();
+        assert_eq!(path, dir.path().join("payload.bin"));
+        assert_eq!(len, STREAM_THRESHOLD + 1);
+
+        // 需要压缩的文件整体读入
+        let compressed = request.with_directives(vec![Directive::new("compress", "gzip")]);
+        assert_eq!(streamed_file(&compressed).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_uploads_small_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("payload.bin"), b"hello upload").unwrap();
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/upload")
+            .match_body("hello upload")
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let request = upload(&dir, &format!("{}/upload", server.url()));
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute(&request).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_streams_large_file() {
+        let dir = TempDir::new().unwrap();
+        let data = vec![b'x'; STREAM_THRESHOLD as usize + 10];
+        fs::write(dir.path().join("payload.bin"), &data).unwrap();
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/upload")
+            .match_header("content-length", data.len().to_string().as_str())
+            .match_header("transfer-encoding", Matcher::Missing)
+            .match_body(data)
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let request = upload(&dir, &format!("{}/upload", server.url()));
+        let mut client = HttpClient::new().with_print_response(false);
+        client.execute(&request).await.unwrap();
+        mock.assert_async().await;
+    }
+}
@@ -1,6 +1,14 @@
 //! variable模块的单元测试
 
-use httpie::{Environment, VariableReplacer};
+use httpie::{
+    DynamicValues, Environment, EnvironmentLoader, HttpRequest, TraceContext, VariableContext,
+    VariableReplacer, is_secret_name,
+};
+use reqwest::Method;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tempfile::NamedTempFile;
 
 #[cfg(test)]
 mod tests {
@@ -270,4 +278,168 @@ mod tests {
         assert!(!result.contains("{{"));
         assert!(!result.contains("}}"));
     }
+
+    #[test]
+    fn test_variable_context_resolves_request() {
+        let context = VariableContext::new();
+        let shared = context.clone();
+        shared.set("token", "abc");
+        shared.set("id", "42");
+
+        let request = HttpRequest::new(
+            "profile".to_string(),
+            Method::POST,
+            "https://example.com/users/{{id}}".to_string(),
+        )
+        .with_headers(HashMap::from([(
+            "Authorization".to_string(),
+            "Bearer {{token}}".to_string(),
+        )]))
+        .with_body(Some(
+            r#"{"id": {{id}}, "other": "{{unknown}}"}"#.to_string(),
+        ));
+
+        let resolved = context.resolve(&request);
+        assert_eq!(resolved.url, "https://example.com/users/42");
+        assert_eq!(resolved.headers["Authorization"], "Bearer abc");
+        assert_eq!(
+            resolved.body.as_deref(),
+            Some(r#"{"id": 42, "other": "{{unknown}}"}"#)
+        );
+        assert_eq!(context.get("token").as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_variable_context_replaces_in_single_pass() {
+        let context = VariableContext::new();
+        context.set("a", "{{b}}");
+        context.set("b", "x");
+        context.set("c", "$1");
+
+        // 替换进来的值不再展开，结果与变量的遍历顺序无关
+        assert_eq!(
+            context.replace("{{a}} {{b}} {{c}} {{ b }} {{unknown}}"),
+            "{{b}} x $1 {{ b }} {{unknown}}"
+        );
+    }
+
+    #[test]
+    fn test_dynamic_variable_templates() {
+        let env = Environment::new();
+        let trace = TraceContext::generate();
+        let replacer = VariableReplacer::new(&env).with_trace(trace.clone());
+
+        let uuid = replacer.replace("{{$uuid}}");
+        assert_eq!(uuid.len(), 36);
+        assert!(!uuid.contains('{'));
+        assert!(
+            replacer
+                .replace("{{ $timestamp }}")
+                .chars()
+                .all(|c| c.is_ascii_digit())
+        );
+        assert_eq!(
+            replacer.replace("{{$traceId}}-{{$spanId}}-{{$traceId}}"),
+            format!("{0}-{1}-{0}", trace.trace_id, trace.span_id)
+        );
+    }
+
+    #[test]
+    fn test_seeded_dynamic_values_are_reproducible() {
+        let env = Environment::new();
+        let template = "{{$uuid}} {{$randomInt}} {{$timestamp}} {{$idempotencyKey}}";
+        let render = || {
+            let values = DynamicValues::new()
+                .with_seed(42)
+                .with_frozen_time(1_700_000_000);
+            let trace = values.trace();
+            let replacer = VariableReplacer::new(&env).with_dynamic_values(values.clone());
+            (
+                replacer.replace(template),
+                replacer.replace(template),
+                trace,
+            )
+        };
+
+        let (first, second, trace) = render();
+        assert_eq!(render(), (first.clone(), second.clone(), trace));
+        // 同一次运行中的后续取值不同，时间保持冻结
+        assert_ne!(first, second);
+        let parts: Vec<&str> = first.split(' ').collect();
+        assert_eq!(parts[2], "1700000000");
+        assert_eq!(parts[0].len(), 36);
+        assert_eq!(&parts[0][14..15], "4");
+        let random_int: u32 = parts[1].parse().unwrap();
+        assert!((1..=1000000).contains(&random_int));
+
+        // 不同种子产生不同的值
+        let other = VariableReplacer::new(&env)
+            .with_dynamic_values(DynamicValues::new().with_seed(7))
+            .replace("{{$uuid}}");
+        assert_ne!(other, parts[0]);
+    }
+
+    #[test]
+    fn test_process_env_template_forms() {
+        let env = Environment::new();
+        let replacer = VariableReplacer::new(&env);
+
+        assert_eq!(replacer.replace("{{$env CARGO_PKG_NAME}}"), "httpie");
+        assert_eq!(
+            replacer.replace("{{ $processEnv CARGO_PKG_NAME }}"),
+            "httpie"
+        );
+        assert_eq!(replacer.replace("$processEnv.CARGO_PKG_NAME"), "httpie");
+        assert_eq!(replacer.replace("[{{$env HTTPIE_UNSET_VARIABLE}}]"), "[]");
+    }
+
+    #[test]
+    fn test_dotenv_precedence() {
+        let mut env = Environment::new().with_dotenv(HashMap::from([
+            ("host".to_string(), "dotenv-host".to_string()),
+            ("token".to_string(), "dotenv-token".to_string()),
+            ("CARGO_PKG_NAME".to_string(), "from-dotenv".to_string()),
+        ]));
+        env.insert("host".to_string(), "env-host".to_string());
+
+        let replacer = VariableReplacer::new(&env);
+        assert_eq!(
+            replacer.replace("{{host}} {{token}}"),
+            "env-host dotenv-token"
+        );
+        // .env 中的同名变量优先于进程环境变量
+        assert_eq!(replacer.replace("{{$env CARGO_PKG_NAME}}"), "from-dotenv");
+    }
+
+    #[test]
+    fn test_load_dotenv() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            "# comment\n\nAPI_KEY=secret\nexport HOST = \"localhost\"\nEMPTY=\nQUOTED='a b'\n",
+        )
+        .unwrap();
+
+        let dotenv = EnvironmentLoader::load_dotenv(temp_file.path()).unwrap();
+        assert_eq!(dotenv["API_KEY"], "secret");
+        assert_eq!(dotenv["HOST"], "localhost");
+        assert_eq!(dotenv["EMPTY"], "");
+        assert_eq!(dotenv["QUOTED"], "a b");
+
+        fs::write(temp_file.path(), "API_KEY=1\nbroken\n").unwrap();
+        let error = EnvironmentLoader::load_dotenv(temp_file.path()).unwrap_err();
+        assert!(error.to_string().contains("Invalid line 2"));
+
+        let missing = EnvironmentLoader::load_dotenv(Path::new("/nonexistent/.env")).unwrap();
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_is_secret_name() {
+        assert!(is_secret_name("api_key"));
+        assert!(is_secret_name("AUTH_HEADER"));
+        assert!(is_secret_name("accessToken"));
+        assert!(!is_secret_name("host"));
+        assert!(!is_secret_name("version"));
+    }
 }